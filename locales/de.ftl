@@ -0,0 +1,483 @@
+# Playback
+play = Abspielen
+pause = Pause
+stopped = Gestoppt
+previous = Zurück
+next = Weiter
+shuffle = Zufällig
+volume = Lautstärke
+nothing-playing = Es wird nichts abgespielt
+no-cover = Kein Cover
+no-lyrics = Kein Liedtext verfügbar
+
+# Playlist
+add-track = Titel hinzufügen
+load-playlist = Playlist laden
+export-playlist = Playlist exportieren
+recent = Zuletzt verwendet
+recent-track = Titel: { $name }
+recent-playlist = Playlist: { $name }
+recent-folder = Ordner: { $name }
+sort-by = Sortieren nach
+deduplicate = Duplikate entfernen
+clear-playlist = Playlist leeren
+now-playing = Aktueller Titel
+follow = Folgen
+tracks-filter = Titel
+add-url = URL hinzufügen
+playlists-filter = Playlists
+
+# Track fields
+title = Titel
+artist = Interpret
+album = Album
+year = Jahr
+duration = Dauer
+path = Pfad
+unknown = Unbekannt
+no-title = Kein Titel
+no-artist = Kein Interpret
+no-album = Kein Album
+no-year = Kein Jahr
+unknown-year = Unbekanntes Jahr
+
+# Sorting
+sort-artist = Interpret
+sort-album = Album und Titelnummer
+sort-title = Titel
+sort-duration = Dauer
+sort-date-added = Hinzugefügt am
+sort-random = Zufällig
+
+# Selection
+tracks-selected = { $count } ausgewählt
+move-up = Nach oben
+move-down = Nach unten
+save-as-playlist = Als Playlist speichern
+save-selection-as-playlist = Auswahl als Playlist speichern
+remove = Entfernen
+clear-selection = Auswahl aufheben
+
+# Saved queue
+restore-queue-prompt =
+    { $count ->
+        [one] Vorherige Warteschlange wiederherstellen (1 Titel)?
+       *[other] Vorherige Warteschlange wiederherstellen ({ $count } Titel)?
+    }
+restore = Wiederherstellen
+discard = Verwerfen
+
+# Context menu and track info
+play-now = Jetzt abspielen
+play-next = Als Nächstes abspielen
+show-in-file-manager = Im Dateimanager anzeigen
+track-info = Titelinformationen
+add-to-playlist = Zu { $name } hinzufügen
+close = Schließen
+
+# Named playlists
+playlists = Playlists
+new-playlist = Neue Playlist
+create = Erstellen
+save = Speichern
+cancel = Abbrechen
+rename = Umbenennen
+delete = Löschen
+
+# Command palette
+command-palette-placeholder = Befehl oder Titelname eingeben
+next-track = Nächster Titel
+previous-track = Vorheriger Titel
+turn-shuffle-on = Zufallswiedergabe einschalten
+turn-shuffle-off = Zufallswiedergabe ausschalten
+deduplicate-playlist = Duplikate aus der Playlist entfernen
+scroll-to-now-playing = Zum aktuellen Titel scrollen
+toggle-now-playing-view = Vollbildansicht umschalten
+show-current-track-in-file-manager = Aktuellen Titel im Dateimanager anzeigen
+sort-playlist-by = Playlist sortieren nach { $sort }
+load-named-playlist = Playlist „{ $name }“ laden
+jump-to-track = Zu „{ $title }“ springen
+jump-to-track-by-artist = Zu „{ $title }“ von { $artist } springen
+switch-language = Sprache: { $language }
+use-system-language = Sprache: Systemstandard
+
+# Settings
+settings = Einstellungen
+language = Sprache
+text-size = Schriftgröße
+ui-scale = Skalierung
+album-colors = Farben an das Album-Cover anpassen
+open-log-folder = Protokollordner öffnen
+
+# Crash reports
+crash-title = Kanta ist abgestürzt
+crash-description = Kanta ist beim letzten Mal auf ein Problem gestoßen und musste beendet werden. Ein Absturzbericht wurde unter { $path } gespeichert. Möchtest du ihn öffnen?
+
+    Möchtest du ihn öffnen?
+
+# File associations
+file-associations-prompt = Audiodateien und Playlists mit Kanta öffnen?
+register = Registrieren
+not-now = Nicht jetzt
+register-file-types = Dateitypen registrieren
+
+# Profiles
+switch-profile = Profil wechseln
+choose-profile = Profil auswählen
+default-profile = Standard
+new-profile = Neues Profil
+
+# Backups
+export-backup = Sicherung exportieren
+import-backup = Sicherung importieren
+backups-filter = Kanta-Sicherungen
+
+# Statistics
+statistics = Statistik
+tracks = Titel
+albums = Alben
+artists = Interpreten
+total-duration = Gesamtdauer
+size-on-disk = Größe auf der Festplatte
+top-artists = Häufigste Interpreten
+
+# Tempo
+tempo = Tempo
+bpm = { $bpm } BPM
+sort-tempo = Tempo
+
+# Cue points
+add-cue = Cue-Punkt hinzufügen
+cue-name = Name des Cue-Punkts
+default-cue-name = Cue { $number }
+
+# Folder browser
+folders = Ordner
+music-folders = Musikordner
+add-music-folder = Musikordner hinzufügen
+enqueue = Einreihen
+up = Nach oben
+
+# Device sync
+sync-to-device = Mit Gerät synchronisieren
+sync-destination = Ziel
+no-folder-chosen = Kein Ordner gewählt
+choose = Auswählen…
+transcode-lossless = Verlustfreie Dateien umwandeln
+kbps = kbit/s
+sync = Synchronisieren
+syncing = Kopiere Titel { $done } von { $total }…
+sync-finished = Synchronisierung abgeschlossen
+
+# Export
+export-as = Exportieren als…
+export = Exportieren
+exporting = Kodiere Titel { $done } von { $total }…
+export-finished = { $count ->
+    [one] Ein Titel
+   *[other] { $count } Titel
+} exportiert{ $failed ->
+    [0] {""}
+   *[other] , { $failed } fehlgeschlagen
+}
+
+# Loudness
+loudness = Lautheit
+loudness-value = { $integrated } LUFS, Spitze { $peak } dBTP
+replay-gain = ReplayGain { $gain } dB
+normalize-volume = Lautstärke angleichen (ReplayGain oder Lautheitsanalyse)
+
+# Radio
+show-stream-titles = Songtitel von Radiostreams anzeigen
+radio = Radio
+station-name = Sendername
+station-url = Stream-URL
+add = Hinzufügen
+import-stations = Sender importieren…
+stations-filter = Senderlisten
+search-stations = RadioBrowser durchsuchen…
+play-station = Sender „{ $name }“ abspielen
+
+# Scrobbling
+scrobbling = Scrobbling (ListenBrainz)
+scrobble-server = Server-URL (Standard: https://api.listenbrainz.org)
+scrobble-token = Benutzertoken
+scrobble-server-note = Leer lassen, um an ListenBrainz zu scrobbeln, oder die URL eines selbst gehosteten ListenBrainz-kompatiblen Servers eingeben, z. B. http://localhost:42010/apis/listenbrainz.
+scrobble-percent = Scrobbeln nach
+scrobble-min-duration = Titel überspringen, kürzer als
+scrobble-ignored-folders = Titel in diesen Ordnern nie scrobbeln
+add-ignored-folder = Ignorierten Ordner hinzufügen
+love = ♡ Lieben
+loved = ♥ Geliebt
+love-track = Aktuellen Titel lieben
+unlove-track = Aktuellen Titel nicht mehr lieben
+pending-scrobbles = { $count ->
+    [0] Keine Scrobbles warten auf das Senden
+    [one] Ein Scrobble wartet auf das Senden
+   *[other] { $count } Scrobbles warten auf das Senden
+}
+retry-now = Jetzt erneut versuchen
+
+# Import
+import-from-other-player = Aus anderen Playern importieren…
+import-filter = Mediatheken und Playlists
+import-report = { $playlists ->
+    [one] Eine Playlist
+   *[other] { $playlists } Playlists
+} mit { $tracks ->
+    [one] einem Titel
+   *[other] { $tracks } Titeln
+} importiert; { $unmatched ->
+    [0] alle Titel wurden gefunden
+    [one] ein Titel wurde auf diesem Computer nicht gefunden
+   *[other] { $unmatched } Titel wurden auf diesem Computer nicht gefunden
+}
+rating = Bewertung
+plays = Wiedergaben
+
+# History
+export-history = Hörverlauf exportieren…
+listening-history = Hörverlauf
+library = Mediathek (Bewertungen und Wiedergaben)
+history-file-name = hoerverlauf
+from = Von
+until = bis
+history-range-note = Die Daten sind inklusiv und in UTC; leer lassen, um alles zu exportieren.
+plays-recorded = { $count ->
+    [one] Eine Wiedergabe aufgezeichnet
+   *[other] { $count } Wiedergaben aufgezeichnet
+}
+history-exported = { $count ->
+    [one] Ein Eintrag exportiert
+   *[other] { $count } Einträge exportiert
+}
+
+# Shuffle
+shuffle-weighting = Zufallswiedergabe
+shuffle-even = Gleichmäßig
+shuffle-by-rating = Gut bewertete Titel bevorzugen
+shuffle-least-played = Selten gespielte Titel bevorzugen
+shuffle-avoid-recent = Titel überspringen, gespielt in den letzten
+shuffle-hours = { $count ->
+    [one] 1 Stunde
+   *[other] { $count } Stunden
+}
+off = Aus
+
+# Auto-DJ
+auto-dj = Am Ende der Warteschlange
+auto-dj-similar = Ähnliche Titel hinzufügen
+auto-dj-top-rated = Gut bewertete, ungespielte Titel hinzufügen
+auto-dj-note = Titel werden aus den Musikordnern gewählt. Wirkt nicht bei Zufallswiedergabe.
+
+# Party mode
+party-mode = Partymodus
+party-mode-locked = Partymodus: Gäste können Titel hinzufügen
+unlock = Entsperren
+party-pin = Partymodus-PIN
+party-pin-placeholder = Keine PIN
+
+# Album gain
+replay-gain-with-album = ReplayGain { $gain } dB (Album { $album_gain } dB)
+
+# Pitch
+pitch = Tonhöhe { $semitones }
+
+# Sound
+sound = Klang…
+speed = Tempo
+bass = Bass
+mids = Mitten
+treble = Höhen
+save-for-track = Für diesen Titel speichern
+reset = Zurücksetzen
+sound-saved-for-track = Diese Einstellungen werden bei jedem Start dieses Titels angewendet.
+sound-not-saved = Nicht gespeicherte Einstellungen gelten nur bis zum nächsten Titel.
+
+# Level meters
+show-level-meters = Pegelanzeige einblenden
+
+# Spectrogram
+show-spectrogram = Spektrogramm anzeigen
+show-lyrics = Liedtext anzeigen
+frequency-khz = { $khz } kHz
+
+# Lyrics editor
+edit-lyrics = Liedtext bearbeiten
+sync-timestamps = Zeitstempel setzen
+done-syncing = Fertig
+lyrics-save-note = Liedtexte werden als .lrc-Datei neben dem Titel gespeichert. Das Einbetten in Tags wird nicht unterstützt.
+lyrics-sync-help = Spiele den Titel ab und drücke Enter, sobald eine Zeile beginnt. Rücktaste entfernt einen Zeitstempel, Hoch/Runter wechseln die Zeile, Leertaste spielt ab oder pausiert, Links/Rechts spulen.
+
+# Lyrics offset
+lyrics-offset = Liedtext-Versatz: { $offset } s
+
+# Lyrics search
+copy = Kopieren
+find = Suchen
+find-in-lyrics = Im Liedtext suchen…
+no-matches = Keine Treffer
+
+# Karaoke
+karaoke = Karaoke
+toggle-karaoke = Karaoke-Modus umschalten
+exit-karaoke = Karaoke beenden
+reduce-vocals = Gesang abschwächen
+karaoke-needs-synced-lyrics = Der Karaoke-Modus braucht synchronisierte Liedtexte
+
+# Calls
+yield-to-calls = Musik bei Anrufen pausieren oder leiser stellen
+yield-to-calls-note = Kennzeichnet Kantas Audio als Musik, damit PulseAudio oder PipeWire es während eines Anrufs pausieren oder absenken kann. Was genau passiert, hängt von der Einrichtung des Soundservers ab. Wird nach einem Neustart von Kanta wirksam.
+
+# Window title
+window-title = { $title } – Titel { $position } von { $count } – Kanta
+
+# Close to background
+close-to-background = Beim Schließen des Fensters im Hintergrund weiterspielen
+close-to-background-explanation = Kanta blendet sein Fenster aus und spielt weiter. Um es zurückzuholen, starte Kanta erneut oder nutze die Mediensteuerung deines Desktops. Du kannst das in den Einstellungen ändern.
+show-window = Kanta anzeigen
+close-to-background-tray-explanation = Kanta spielt weiter. Über das Symbol im Infobereich kannst du das Fenster wieder öffnen oder Kanta beenden.
+keep-playing = Weiterspielen
+quit = Beenden
+
+# Autostart
+autostart = Kanta bei der Anmeldung starten
+autostart-minimized = Beim Start nach der Anmeldung minimiert starten und die vorherige Warteschlange pausiert laden
+
+# Resume position
+resume-at = Fortsetzen bei { $position }
+
+# Volume on launch
+remember-volume = Lautstärke zwischen Starts merken
+launch-volume = Lautstärke beim Start
+
+# Track tooltip
+format = Format
+stream = Stream
+
+# Queue columns
+length = Länge
+
+# Library search
+search-library = Bibliothek durchsuchen
+
+# Library rescan
+rescan = Neu einlesen
+scanning-library = Bibliothek wird eingelesen…
+library-scan-report = { $added } hinzugefügt, { $updated } aktualisiert, { $removed } entfernt
+library-up-to-date = Die Bibliothek ist aktuell
+
+# Library excludes
+library-excludes = Von der Bibliothek ausgeschlossen
+exclude-folder = Ordner ausschließen
+library-excludes-note = Muster ohne Schrägstrich passen auf Datei- und Ordnernamen (z. B. *.wav); Muster mit Schrägstrich auf Pfade innerhalb der Musikordner (z. B. **/ringtones/**).
+
+# Network shares
+unavailable-folder = { $folder } (nicht verfügbar)
+folder-unreachable = { $folder } ist nicht erreichbar. Falls der Ordner auf einer Netzwerkfreigabe liegt, prüfe, ob die Freigabe eingebunden ist.
+
+# Background jobs
+jobs = Aufgaben ({ $count })
+background-jobs = Hintergrundaufgaben
+job-library-scan = Bibliothek einlesen
+job-loudness = Lautheitsanalyse
+job-tempo = Tempoanalyse
+job-idle = Inaktiv
+job-progress = { $done } / { $total }
+job-paused = Pausiert bei { $done } / { $total }
+resume = Fortsetzen
+job-concurrency = Parallele Aufgaben
+job-concurrency-note = Wie viele Titel gleichzeitig analysiert werden, je Analyseart. Abgebrochene Analysen werden bis zum Neustart von Kanta übersprungen.
+
+# Caches
+caches = Zwischenspeicher
+cache-limit = Speichergrenze
+cache-usage = Zwischenspeicher belegen { $size }
+measuring-cache = Größe wird ermittelt…
+clear-caches = Zwischenspeicher leeren
+caches-note = Heruntergeladene Audiodaten und Cover werden bei Überschreitung der Grenze, älteste zuerst, entfernt. Beim Leeren gehen auch zwischengespeicherte Tempo- und Lautheitsanalysen verloren.
+
+# Playlist changes
+playlist-changed-prompt = Die geladene Playlist wurde auf der Festplatte geändert ({ $added } hinzugefügt, { $removed } entfernt). Änderungen in die Warteschlange übernehmen?
+merge = Übernehmen
+ignore = Ignorieren
+
+# Saving playlists
+save-playlist = Playlist speichern
+overwrite-playlist = Playlist überschreiben?
+overwrite-playlist-explanation = „{ $name }“ existiert bereits oder wurde von einem anderen Programm geändert. Beim Speichern wird der Inhalt durch die aktuelle Warteschlange ersetzt.
+overwrite = Überschreiben
+
+# Appending playlists
+append-playlist = Playlist anhängen
+append = Anhängen
+
+# Compilations
+various-artists = Verschiedene Interpreten
+
+# Cue sheets
+cue-sheet-track = Titel { $number }
+hidden-track = Versteckter Track
+hidden-tracks = Versteckte Tracks vor dem ersten Titel eines Cue-Sheets zur Warteschlange hinzufügen
+
+# Hooks
+hooks = Hook-Befehl
+hooks-note = Wird über die Shell ausgeführt, wenn der Track wechselt oder die Wiedergabe startet, pausiert oder stoppt. Die Track-Details werden in KANTA_EVENT, KANTA_STATE, KANTA_TITLE, KANTA_ARTIST, KANTA_ALBUM, KANTA_PATH, KANTA_DURATION und KANTA_POSITION sowie als JSON über die Standardeingabe übergeben.
+
+# Plugins
+plugins = Plugins
+no-plugins = Keine Plugins geladen
+plugins-note = Native Plugins (.so, .dylib oder .dll) in { $dir } werden beim Start geladen. Sie erhalten dieselben Ereignisse wie der Hook-Befehl sowie während der Wiedergabe jede Sekunde die aktuelle Position und können hier ein Statusfeld anzeigen.
+
+# Scripting
+script-console = Skriptkonsole
+script-console-note = Lua mit einer `kanta`-Tabelle: play, pause, next, previous, seek, set_volume, volume, state, position, track und on(event, handler) für die Ereignisse track-changed, play, pause, stop und position. { $path } wird beim Start ausgeführt.
+run-script = Ausführen
+clear-output = Ausgabe leeren
+
+# Event stream
+event-stream = Player-Ereignisse über WebSocket senden
+port = Port
+event-stream-note = Dashboards und Stream-Overlays können sich mit { $url } verbinden und erhalten Trackwechsel, Wiedergabe, Pause und Stopp, Lautstärkeänderungen sowie jede Sekunde die aktuelle Position als JSON. Nur dieser Computer kann sich verbinden, Seiten anderer Websites werden abgelehnt.
+
+# Web remote
+web-remote = Web-Fernbedienung für Handys in diesem Netzwerk
+web-remote-note = Öffne { $url } auf einem Handy oder Tablet im selben Netzwerk. Jeder mit diesem Link kann die Wiedergabe steuern; erstelle einen neuen Link, um alte zu sperren.
+web-remote-disabled-note = Stellt eine kleine Seite mit Wiedergabetasten, Lautstärke und der kommenden Warteschlange bereit, sodass jeder Browser im Netzwerk als Fernbedienung dienen kann.
+new-web-remote-link = Neuen Link erstellen
+up-next = Als Nächstes
+
+# Now playing file
+now-playing-file = Datei „Aktueller Titel“
+now-playing-file-note = Hält eine Text- oder JSON-Datei mit dem aktuellen Track auf dem neuesten Stand, etwa für Textquellen in OBS. Das Cover wird als -cover-Bild daneben geschrieben.
+text-filter = Textdateien
+
+# Snapcast
+snapcast = An Snapcast streamen
+snapcast-note = Sendet die Wiedergabe als 48-kHz-16-Bit-Stereo-PCM an einen Snapcast-Server statt an die Lautsprecher dieses Computers, für synchrone Wiedergabe in mehreren Räumen. Füge in snapserver.conf eine Quelle wie tcp://0.0.0.0:4953?name=Kanta&mode=server hinzu und gib hier die Adresse des Servers ein. Drücke nach dem Ändern der Adresse Enter, um neu zu verbinden.
+
+# AirPlay
+airplay-speaker = An einen AirPlay-Lautsprecher streamen…
+airplay-searching = Suche nach AirPlay-Lautsprechern…
+find-airplay-speakers = Lautsprecher suchen
+airplay-note = Sendet die Wiedergabe als Apple Lossless an einen AirPlay-(RAOP-)Lautsprecher im lokalen Netzwerk statt an die Lautsprecher dieses Computers. Es werden nur Empfänger angezeigt, die unverschlüsselte Streams annehmen, etwa shairport-sync und viele ältere AirPlay-Lautsprecher. Die Positionsanzeige wird um den Puffer des Lautsprechers verzögert, etwa zwei Sekunden.
+
+# Volume
+muted = Stumm
+volume-level = Lautstärke { $percent } %
+
+# Resume rewind
+resume-rewind = Gesprochenes beim Fortsetzen zurückspulen
+seconds = Sekunden
+resume-rewind-note = Beim Fortsetzen eines Hörbuchs oder Podcasts nach einer Pause von mehr als einer Minute so viele Sekunden zurückspringen, um den Zusammenhang wiederzufinden. Als gesprochenes Wort gelten .m4b-Dateien, Titel mit Kapiteln und Titel mit einem Genre wie Audiobook, Spoken Word oder Podcast.
+
+# Alarm
+alarm = Wecker um
+alarm-current-queue = Aktuelle Warteschlange abspielen
+alarm-note = Zu dieser Uhrzeit (HH:MM) lädt Kanta die gewählte Wiedergabeliste, startet die Wiedergabe und blendet die Lautstärke über eine Minute von null ein. Kanta muss dafür laufen, z. B. im Hintergrund nach dem Schließen des Fensters.
+alarm-invalid-time = Gib die Weckzeit als HH:MM ein, z. B. 07:30.
+
+# Pinned playlists
+pin = Anheften
+unpin = Lösen
+append-pinned-playlists = Strg+1–9 hängt angeheftete Wiedergabelisten an, statt die Warteschlange zu ersetzen
@@ -0,0 +1,483 @@
+# Playback
+play = Play
+pause = Pause
+stopped = Stopped
+previous = Prev
+next = Next
+shuffle = Shuffle
+volume = Volume
+nothing-playing = Nothing playing
+no-cover = No cover
+no-lyrics = No lyrics available
+
+# Playlist
+add-track = Add track
+load-playlist = Load playlist
+export-playlist = Export playlist
+recent = Recent
+recent-track = Track: { $name }
+recent-playlist = Playlist: { $name }
+recent-folder = Folder: { $name }
+sort-by = Sort by
+deduplicate = Deduplicate
+clear-playlist = Clear playlist
+now-playing = Now playing
+follow = Follow
+tracks-filter = Tracks
+add-url = Add URL
+playlists-filter = Playlists
+
+# Track fields
+title = Title
+artist = Artist
+album = Album
+year = Year
+duration = Duration
+path = Path
+unknown = Unknown
+no-title = No title
+no-artist = No artist
+no-album = No album
+no-year = No year
+unknown-year = Unknown year
+
+# Sorting
+sort-artist = Artist
+sort-album = Album and track number
+sort-title = Title
+sort-duration = Duration
+sort-date-added = Date added
+sort-random = Random
+
+# Selection
+tracks-selected = { $count } selected
+move-up = Move up
+move-down = Move down
+save-as-playlist = Save as playlist
+save-selection-as-playlist = Save selection as playlist
+remove = Remove
+clear-selection = Clear selection
+
+# Saved queue
+restore-queue-prompt =
+    { $count ->
+        [one] Restore the previous queue (1 track)?
+       *[other] Restore the previous queue ({ $count } tracks)?
+    }
+restore = Restore
+discard = Discard
+
+# Context menu and track info
+play-now = Play now
+play-next = Play next
+show-in-file-manager = Show in file manager
+track-info = Track info
+add-to-playlist = Add to { $name }
+close = Close
+
+# Named playlists
+playlists = Playlists
+new-playlist = New playlist
+create = Create
+save = Save
+cancel = Cancel
+rename = Rename
+delete = Delete
+
+# Command palette
+command-palette-placeholder = Type a command or track name
+next-track = Next track
+previous-track = Previous track
+turn-shuffle-on = Turn shuffle on
+turn-shuffle-off = Turn shuffle off
+deduplicate-playlist = Deduplicate playlist
+scroll-to-now-playing = Scroll to now playing
+toggle-now-playing-view = Toggle Now Playing view
+show-current-track-in-file-manager = Show current track in file manager
+sort-playlist-by = Sort playlist by { $sort }
+load-named-playlist = Load playlist "{ $name }"
+jump-to-track = Jump to "{ $title }"
+jump-to-track-by-artist = Jump to "{ $title }" by { $artist }
+switch-language = Language: { $language }
+use-system-language = Language: System default
+
+# Settings
+settings = Settings
+language = Language
+text-size = Text size
+ui-scale = UI scale
+album-colors = Tint colors from the album art
+open-log-folder = Open log folder
+
+# Crash reports
+crash-title = Kanta crashed
+crash-description = Kanta ran into a problem and had to close last time. A crash report was saved to { $path }. Do you want to open it?
+
+    Do you want to open it?
+
+# File associations
+file-associations-prompt = Open audio files and playlists with Kanta?
+register = Register
+not-now = Not now
+register-file-types = Register file types
+
+# Profiles
+switch-profile = Switch profile
+choose-profile = Choose a profile
+default-profile = Default
+new-profile = New profile
+
+# Backups
+export-backup = Export backup
+import-backup = Import backup
+backups-filter = Kanta backups
+
+# Statistics
+statistics = Statistics
+tracks = Tracks
+albums = Albums
+artists = Artists
+total-duration = Total duration
+size-on-disk = Size on disk
+top-artists = Top artists
+
+# Tempo
+tempo = Tempo
+bpm = { $bpm } BPM
+sort-tempo = Tempo
+
+# Cue points
+add-cue = Add cue point
+cue-name = Cue name
+default-cue-name = Cue { $number }
+
+# Folder browser
+folders = Folders
+music-folders = Music folders
+add-music-folder = Add music folder
+enqueue = Enqueue
+up = Up
+
+# Device sync
+sync-to-device = Sync to device
+sync-destination = Destination
+no-folder-chosen = No folder chosen
+choose = Choose…
+transcode-lossless = Transcode lossless files
+kbps = kbps
+sync = Sync
+syncing = Copying track { $done } of { $total }…
+sync-finished = Sync finished
+
+# Export
+export-as = Export as…
+export = Export
+exporting = Encoding track { $done } of { $total }…
+export-finished = Exported { $count ->
+    [one] one track
+   *[other] { $count } tracks
+}{ $failed ->
+    [0] {""}
+   *[other] , { $failed } failed
+}
+
+# Loudness
+loudness = Loudness
+loudness-value = { $integrated } LUFS, peak { $peak } dBTP
+replay-gain = ReplayGain { $gain } dB
+normalize-volume = Normalize volume (ReplayGain or loudness analysis)
+
+# Radio
+show-stream-titles = Show song titles from radio streams
+radio = Radio
+station-name = Station name
+station-url = Stream URL
+add = Add
+import-stations = Import stations…
+stations-filter = Station lists
+search-stations = Search RadioBrowser…
+play-station = Play station "{ $name }"
+
+# Scrobbling
+scrobbling = Scrobbling (ListenBrainz)
+scrobble-server = Server URL (default: https://api.listenbrainz.org)
+scrobble-token = User token
+scrobble-server-note = Leave empty to scrobble to ListenBrainz, or enter the URL of a self-hosted ListenBrainz-compatible server, e.g. http://localhost:42010/apis/listenbrainz.
+scrobble-percent = Scrobble after
+scrobble-min-duration = Skip tracks shorter than
+scrobble-ignored-folders = Never scrobble tracks in
+add-ignored-folder = Add ignored folder
+love = ♡ Love
+loved = ♥ Loved
+love-track = Love current track
+unlove-track = Unlove current track
+pending-scrobbles = { $count ->
+    [0] No scrobbles waiting to be sent
+    [one] One scrobble waiting to be sent
+   *[other] { $count } scrobbles waiting to be sent
+}
+retry-now = Retry now
+
+# Import
+import-from-other-player = Import from other players…
+import-filter = Libraries and playlists
+import-report = Imported { $playlists ->
+    [one] one playlist
+   *[other] { $playlists } playlists
+} with { $tracks ->
+    [one] one track
+   *[other] { $tracks } tracks
+}; { $unmatched ->
+    [0] every track was found
+    [one] one track was not found on this computer
+   *[other] { $unmatched } tracks were not found on this computer
+}
+rating = Rating
+plays = Plays
+
+# History
+export-history = Export listening history…
+listening-history = Listening history
+library = Library (ratings and play counts)
+history-file-name = listening-history
+from = From
+until = until
+history-range-note = Dates are inclusive and in UTC; leave them empty to export everything.
+plays-recorded = { $count ->
+    [one] One play recorded
+   *[other] { $count } plays recorded
+}
+history-exported = { $count ->
+    [one] Exported one entry
+   *[other] Exported { $count } entries
+}
+
+# Shuffle
+shuffle-weighting = Shuffle
+shuffle-even = Evenly
+shuffle-by-rating = Favor highly rated tracks
+shuffle-least-played = Favor rarely played tracks
+shuffle-avoid-recent = Skip tracks played in the last
+shuffle-hours = { $count ->
+    [one] 1 hour
+   *[other] { $count } hours
+}
+off = Off
+
+# Auto-DJ
+auto-dj = When the queue ends
+auto-dj-similar = Add similar tracks
+auto-dj-top-rated = Add top rated unplayed tracks
+auto-dj-note = Tracks are picked from the music folders. Has no effect while shuffling.
+
+# Party mode
+party-mode = Party mode
+party-mode-locked = Party mode: guests can add tracks
+unlock = Unlock
+party-pin = Party mode PIN
+party-pin-placeholder = No PIN
+
+# Album gain
+replay-gain-with-album = ReplayGain { $gain } dB (album { $album_gain } dB)
+
+# Pitch
+pitch = Pitch { $semitones }
+
+# Sound
+sound = Sound…
+speed = Speed
+bass = Bass
+mids = Mids
+treble = Treble
+save-for-track = Save for this track
+reset = Reset
+sound-saved-for-track = These settings are applied whenever this track starts.
+sound-not-saved = Unsaved settings only last until the next track.
+
+# Level meters
+show-level-meters = Show level meters
+
+# Spectrogram
+show-spectrogram = Show spectrogram
+show-lyrics = Show lyrics
+frequency-khz = { $khz } kHz
+
+# Lyrics editor
+edit-lyrics = Edit lyrics
+sync-timestamps = Sync timestamps
+done-syncing = Done
+lyrics-save-note = Lyrics are saved as an .lrc file next to the track. Embedding them into tags isn't supported.
+lyrics-sync-help = Play the track and press Enter as each line starts. Backspace clears a timestamp, Up/Down move between lines, Space plays or pauses, Left/Right seek.
+
+# Lyrics offset
+lyrics-offset = Lyrics offset: { $offset } s
+
+# Lyrics search
+copy = Copy
+find = Find
+find-in-lyrics = Find in lyrics…
+no-matches = No matches
+
+# Karaoke
+karaoke = Karaoke
+toggle-karaoke = Toggle karaoke mode
+exit-karaoke = Exit karaoke
+reduce-vocals = Reduce vocals
+karaoke-needs-synced-lyrics = Karaoke mode needs synced lyrics
+
+# Calls
+yield-to-calls = Pause or lower music during calls
+yield-to-calls-note = Marks Kanta's audio as music so PulseAudio or PipeWire can pause or duck it while a call plays. What happens depends on the sound server's setup. Takes effect after restarting Kanta.
+
+# Window title
+window-title = { $title } – Track { $position } of { $count } – Kanta
+
+# Close to background
+close-to-background = Keep playing in the background when the window is closed
+close-to-background-explanation = Kanta will hide its window and keep playing. To bring it back, start Kanta again or use your desktop's media controls. You can change this in the settings.
+show-window = Show Kanta
+close-to-background-tray-explanation = Kanta is still playing. Use its icon in the system tray to bring the window back or to quit.
+keep-playing = Keep playing
+quit = Quit
+
+# Autostart
+autostart = Start Kanta when you log in
+autostart-minimized = When started at login, start minimized with the previous queue loaded and paused
+
+# Resume position
+resume-at = Resumes at { $position }
+
+# Volume on launch
+remember-volume = Remember the volume between launches
+launch-volume = Volume on launch
+
+# Track tooltip
+format = Format
+stream = Stream
+
+# Queue columns
+length = Length
+
+# Library search
+search-library = Search library
+
+# Library rescan
+rescan = Rescan
+scanning-library = Scanning the library…
+library-scan-report = { $added } added, { $updated } updated, { $removed } removed
+library-up-to-date = The library is up to date
+
+# Library excludes
+library-excludes = Excluded from the library
+exclude-folder = Exclude folder
+library-excludes-note = Patterns without a slash match file and folder names (e.g. *.wav); patterns with a slash match paths inside the music folders (e.g. **/ringtones/**).
+
+# Network shares
+unavailable-folder = { $folder } (unavailable)
+folder-unreachable = { $folder } is not reachable. If it is on a network share, check that the share is mounted.
+
+# Background jobs
+jobs = Jobs ({ $count })
+background-jobs = Background jobs
+job-library-scan = Library scan
+job-loudness = Loudness analysis
+job-tempo = Tempo analysis
+job-idle = Idle
+job-progress = { $done } / { $total }
+job-paused = Paused at { $done } / { $total }
+resume = Resume
+job-concurrency = Parallel jobs
+job-concurrency-note = How many tracks are analyzed at the same time, per kind of analysis. Cancelled analyses are skipped until Kanta restarts.
+
+# Caches
+caches = Caches
+cache-limit = Cache limit
+cache-usage = Caches use { $size }
+measuring-cache = Measuring cache size…
+clear-caches = Clear caches
+caches-note = Downloaded audio and cover art are evicted oldest first when the limit is exceeded. Clearing also drops cached tempo and loudness analysis.
+
+# Playlist changes
+playlist-changed-prompt = The loaded playlist changed on disk ({ $added } added, { $removed } removed). Merge the changes into the queue?
+merge = Merge
+ignore = Ignore
+
+# Saving playlists
+save-playlist = Save playlist
+overwrite-playlist = Overwrite playlist?
+overwrite-playlist-explanation = “{ $name }” already exists or was changed by another program. Saving replaces its contents with the current queue.
+overwrite = Overwrite
+
+# Appending playlists
+append-playlist = Append playlist
+append = Append
+
+# Compilations
+various-artists = Various Artists
+
+# Cue sheets
+cue-sheet-track = Track { $number }
+hidden-track = Hidden track
+hidden-tracks = Add hidden tracks from before the first song of a cue sheet to the queue
+
+# Hooks
+hooks = Hook command
+hooks-note = Runs through the shell when the track changes or playback starts, pauses or stops. Track details are passed in KANTA_EVENT, KANTA_STATE, KANTA_TITLE, KANTA_ARTIST, KANTA_ALBUM, KANTA_PATH, KANTA_DURATION and KANTA_POSITION, and as JSON on standard input.
+
+# Plugins
+plugins = Plugins
+no-plugins = No plugins loaded
+plugins-note = Native plugins (.so, .dylib or .dll) in { $dir } are loaded at startup. They receive the same events as the hook command plus a position update every second while playing, and can show a status panel here.
+
+# Scripting
+script-console = Script console
+script-console-note = Lua with a `kanta` table: play, pause, next, previous, seek, set_volume, volume, state, position, track and on(event, handler) for track-changed, play, pause, stop and position events. { $path } runs at startup.
+run-script = Run
+clear-output = Clear output
+
+# Event stream
+event-stream = Broadcast player events over WebSocket
+port = Port
+event-stream-note = Dashboards and stream overlays can connect to { $url } to receive track changes, play, pause and stop, volume changes and a position update every second as JSON. Only this computer can connect, and pages from other websites are refused.
+
+# Web remote
+web-remote = Web remote for phones on this network
+web-remote-note = Open { $url } on a phone or tablet on the same network. Anyone with this link can control playback; create a new link to lock out old ones.
+web-remote-disabled-note = Serves a small page with transport buttons, volume and the upcoming queue, so any browser on your network can act as a remote.
+new-web-remote-link = Create a new link
+up-next = Up next
+
+# Now playing file
+now-playing-file = Now playing file
+now-playing-file-note = Keeps a text or JSON file updated with the current track for stream overlays such as OBS text sources. The cover is written next to it as a -cover image.
+text-filter = Text files
+
+# Snapcast
+snapcast = Stream to Snapcast
+snapcast-note = Sends playback as 48 kHz 16-bit stereo PCM to a Snapcast server instead of this computer's speakers, for synchronized multi-room playback. Add a source such as tcp://0.0.0.0:4953?name=Kanta&mode=server to snapserver.conf and enter the server's address here. Press Enter after changing the address to reconnect.
+
+# AirPlay
+airplay-speaker = Stream to an AirPlay speaker…
+airplay-searching = Searching for AirPlay speakers…
+find-airplay-speakers = Find speakers
+airplay-note = Sends playback as Apple Lossless to an AirPlay (RAOP) speaker on the local network instead of this computer's speakers. Only receivers that accept unencrypted streams are listed, such as shairport-sync and many older AirPlay speakers. The position display is delayed by the speaker's buffer, about two seconds.
+
+# Volume
+muted = Muted
+volume-level = Volume { $percent }%
+
+# Resume rewind
+resume-rewind = Rewind spoken word on resume
+seconds = Seconds
+resume-rewind-note = When resuming an audiobook or podcast after a pause of more than a minute, go back this many seconds for context. Tracks count as spoken word if they are .m4b files, have chapters, or have a genre such as Audiobook, Spoken Word or Podcast.
+
+# Alarm
+alarm = Alarm at
+alarm-current-queue = Play the current queue
+alarm-note = At this time (HH:MM) Kanta loads the chosen playlist, starts playing and fades the volume in from zero over a minute. Kanta has to be running, e.g. in the background when closing the window.
+alarm-invalid-time = Enter the alarm time as HH:MM, e.g. 07:30.
+
+# Pinned playlists
+pin = Pin
+unpin = Unpin
+append-pinned-playlists = Ctrl+1–9 appends pinned playlists instead of replacing the queue
@@ -0,0 +1,563 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{IpAddr, Ipv4Addr, SocketAddr, TcpStream, UdpSocket},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{anyhow, bail};
+use rodio::mixer::{self, Mixer, MixerSource};
+use tracing::{debug, info, warn};
+
+pub const SAMPLE_RATE: u32 = 44100;
+pub const CHANNELS: u16 = 2;
+
+const SERVICE: &str = "_raop._tcp.local";
+const MDNS_ADDRESS: (Ipv4Addr, u16) = (Ipv4Addr::new(224, 0, 0, 251), 5353);
+const DISCOVERY_TIME: Duration = Duration::from_secs(3);
+const TIMEOUT: Duration = Duration::from_secs(2);
+const FRAMES_PER_PACKET: u32 = 352;
+const LATENCY_FRAMES: u32 = 88200;
+const NTP_EPOCH_OFFSET: u64 = 2_208_988_800;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Speaker {
+    pub name: String,
+    pub address: SocketAddr,
+}
+
+impl fmt::Display for Speaker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+pub fn discover() -> anyhow::Result<Vec<Speaker>> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))?;
+    socket.set_multicast_ttl_v4(255)?;
+    let query = query_packet();
+    socket.send_to(&query, MDNS_ADDRESS)?;
+
+    let mut instances = HashSet::new();
+    let mut services = HashMap::new();
+    let mut hosts = HashMap::new();
+    let mut sources = HashMap::new();
+    let mut unsupported = HashSet::new();
+    let started_at = Instant::now();
+    let mut queried_again = false;
+    let mut buffer = [0; 9000];
+    while let Some(remaining) = DISCOVERY_TIME.checked_sub(started_at.elapsed()) {
+        if !queried_again && started_at.elapsed() >= DISCOVERY_TIME / 3 {
+            queried_again = true;
+            socket.send_to(&query, MDNS_ADDRESS)?;
+        }
+        socket.set_read_timeout(Some(
+            remaining
+                .min(DISCOVERY_TIME / 3)
+                .max(Duration::from_millis(1)),
+        ))?;
+        let (len, source) = match socket.recv_from(&mut buffer) {
+            Ok(received) => received,
+            Err(e)
+                if matches!(
+                    e.kind(),
+                    io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                ) =>
+            {
+                continue;
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let Some(records) = parse_records(&buffer[..len]) else {
+            debug!("Ignoring a malformed mDNS response from {}", source);
+            continue;
+        };
+        for record in records {
+            match record.data {
+                RecordData::Pointer(instance) if record.name.eq_ignore_ascii_case(SERVICE) => {
+                    instances.insert(instance);
+                }
+                RecordData::Service(port, target) => {
+                    sources.insert(record.name.clone(), source.ip());
+                    services.insert(record.name, (port, target));
+                }
+                RecordData::Address(address) => {
+                    hosts.insert(record.name, IpAddr::V4(address));
+                }
+                RecordData::Text(entries) if !supports_unencrypted_alac(&entries) => {
+                    unsupported.insert(record.name);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let mut speakers = instances
+        .into_iter()
+        .filter(|instance| !unsupported.contains(instance))
+        .filter_map(|instance| {
+            let (port, target) = services.get(&instance)?;
+            let ip = hosts.get(target).or(sources.get(&instance))?;
+            let label = instance.strip_suffix(&format!(".{}", SERVICE))?;
+            let name = label.split_once('@').map_or(label, |(_, name)| name);
+            Some(Speaker {
+                name: name.to_string(),
+                address: SocketAddr::new(*ip, *port),
+            })
+        })
+        .collect::<Vec<_>>();
+    speakers.sort_by(|a, b| a.name.cmp(&b.name));
+    speakers.dedup();
+    Ok(speakers)
+}
+
+fn supports_unencrypted_alac(entries: &[String]) -> bool {
+    let includes = |key: &str, value: &str| {
+        entries
+            .iter()
+            .filter_map(|entry| entry.split_once('='))
+            .find(|(name, _)| *name == key)
+            .is_none_or(|(_, values)| values.split(',').any(|v| v == value))
+    };
+    includes("et", "0") && includes("cn", "1")
+}
+
+fn query_packet() -> Vec<u8> {
+    let mut packet = vec![0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0];
+    for label in SERVICE.split('.') {
+        packet.push(label.len() as u8);
+        packet.extend(label.as_bytes());
+    }
+    packet.extend([0, 0, 12, 0, 1]);
+    packet
+}
+
+struct Record {
+    name: String,
+    data: RecordData,
+}
+
+enum RecordData {
+    Pointer(String),
+    Service(u16, String),
+    Address(Ipv4Addr),
+    Text(Vec<String>),
+    Other,
+}
+
+fn parse_records(packet: &[u8]) -> Option<Vec<Record>> {
+    let count = |offset: usize| {
+        Some(u16::from_be_bytes([
+            *packet.get(offset)?,
+            *packet.get(offset + 1)?,
+        ]))
+    };
+    let questions = count(4)?;
+    let records = count(6)? as usize + count(8)? as usize + count(10)? as usize;
+
+    let mut offset = 12;
+    for _ in 0..questions {
+        offset = read_name(packet, offset)?.1 + 4;
+    }
+
+    let mut parsed = Vec::with_capacity(records);
+    for _ in 0..records {
+        let (name, next) = read_name(packet, offset)?;
+        let kind = count(next)?;
+        let len = count(next + 8)? as usize;
+        let start = next + 10;
+        let data = packet.get(start..start + len)?;
+        let data = match kind {
+            12 => RecordData::Pointer(read_name(packet, start)?.0),
+            33 if len >= 6 => RecordData::Service(
+                u16::from_be_bytes([data[4], data[5]]),
+                read_name(packet, start + 6)?.0,
+            ),
+            1 if len == 4 => RecordData::Address(Ipv4Addr::new(data[0], data[1], data[2], data[3])),
+            16 => {
+                let mut entries = vec![];
+                let mut rest = data;
+                while let Some((&entry_len, tail)) = rest.split_first() {
+                    let entry = tail.get(..entry_len as usize)?;
+                    entries.push(String::from_utf8_lossy(entry).into_owned());
+                    rest = &tail[entry_len as usize..];
+                }
+                RecordData::Text(entries)
+            }
+            _ => RecordData::Other,
+        };
+        parsed.push(Record { name, data });
+        offset = start + len;
+    }
+    Some(parsed)
+}
+
+fn read_name(packet: &[u8], mut offset: usize) -> Option<(String, usize)> {
+    let mut labels = vec![];
+    let mut end = None;
+    for _ in 0..64 {
+        let len = *packet.get(offset)? as usize;
+        if len & 0xc0 == 0xc0 {
+            let pointer = (len & 0x3f) << 8 | *packet.get(offset + 1)? as usize;
+            end.get_or_insert(offset + 2);
+            offset = pointer;
+        } else if len == 0 {
+            return Some((labels.join("."), end.unwrap_or(offset + 1)));
+        } else {
+            let label = packet.get(offset + 1..offset + 1 + len)?;
+            labels.push(String::from_utf8_lossy(label).into_owned());
+            offset += 1 + len;
+        }
+    }
+    None
+}
+
+pub struct AirPlayOutput {
+    speaker: Speaker,
+    mixer: Mixer,
+    latency: Duration,
+    stopped: Arc<AtomicBool>,
+}
+
+impl AirPlayOutput {
+    pub fn connect(speaker: Speaker) -> anyhow::Result<AirPlayOutput> {
+        let mut rtsp = Rtsp::connect(speaker.address)?;
+        let local_ip = rtsp.stream.local_addr()?.ip();
+        let ssrc = fastrand::u32(..);
+        rtsp.url = format!("rtsp://{}/{}", local_ip, ssrc);
+
+        let audio = UdpSocket::bind((local_ip, 0))?;
+        let control = UdpSocket::bind((local_ip, 0))?;
+        let timing = UdpSocket::bind((local_ip, 0))?;
+        timing.set_read_timeout(Some(TIMEOUT))?;
+
+        rtsp.request("OPTIONS", Some("*"), &[], None)?;
+        let sdp = format!(
+            "v=0\r\no=iTunes {} 0 IN IP4 {}\r\ns=iTunes\r\nc=IN IP4 {}\r\nt=0 0\r\nm=audio 0 RTP/AVP 96\r\na=rtpmap:96 AppleLossless\r\na=fmtp:96 {} 0 16 40 10 14 {} 255 0 0 {}\r\n",
+            ssrc,
+            local_ip,
+            speaker.address.ip(),
+            FRAMES_PER_PACKET,
+            CHANNELS,
+            SAMPLE_RATE
+        );
+        rtsp.request("ANNOUNCE", None, &[], Some(("application/sdp", &sdp)))?;
+        let transport = format!(
+            "RTP/AVP/UDP;unicast;interleaved=0-1;mode=record;control_port={};timing_port={}",
+            control.local_addr()?.port(),
+            timing.local_addr()?.port()
+        );
+        let headers = rtsp.request("SETUP", None, &[("Transport", &transport)], None)?;
+        let transport = headers
+            .get("transport")
+            .ok_or_else(|| anyhow!("{} did not return a transport", speaker))?;
+        let port = |name: &str| {
+            transport
+                .split(';')
+                .filter_map(|part| part.split_once('='))
+                .find(|(key, _)| *key == name)
+                .and_then(|(_, value)| value.parse::<u16>().ok())
+                .ok_or_else(|| anyhow!("{} did not return a {}", speaker, name))
+        };
+        audio.connect((speaker.address.ip(), port("server_port")?))?;
+        control.connect((speaker.address.ip(), port("control_port")?))?;
+        rtsp.session = headers
+            .get("session")
+            .map(|session| session.split(';').next().unwrap_or_default().to_string());
+
+        let sequence = fastrand::u16(..);
+        let timestamp = fastrand::u32(..);
+        let rtp_info = format!("seq={};rtptime={}", sequence, timestamp);
+        let headers = rtsp.request(
+            "RECORD",
+            None,
+            &[("Range", "npt=0-"), ("RTP-Info", &rtp_info)],
+            None,
+        )?;
+        let latency_frames = headers
+            .get("audio-latency")
+            .and_then(|latency| latency.parse().ok())
+            .unwrap_or(0)
+            .max(LATENCY_FRAMES);
+        info!(
+            "Streaming to AirPlay speaker {} at {}",
+            speaker, speaker.address
+        );
+
+        let (mixer, source) = mixer::mixer(CHANNELS, SAMPLE_RATE);
+        let stopped = Arc::new(AtomicBool::new(false));
+        let timing_stopped = stopped.clone();
+        thread::spawn(move || answer_timing_requests(timing, &timing_stopped));
+        let stream_stopped = stopped.clone();
+        let session = Session {
+            rtsp,
+            audio,
+            control,
+            ssrc,
+            sequence,
+            timestamp,
+            latency_frames,
+        };
+        thread::spawn(move || session.stream(source, &stream_stopped));
+
+        Ok(AirPlayOutput {
+            speaker,
+            mixer,
+            latency: Duration::from_secs_f64(latency_frames as f64 / SAMPLE_RATE as f64),
+            stopped,
+        })
+    }
+
+    pub fn speaker(&self) -> &Speaker {
+        &self.speaker
+    }
+
+    pub fn mixer(&self) -> &Mixer {
+        &self.mixer
+    }
+
+    pub fn latency(&self) -> Duration {
+        self.latency
+    }
+}
+
+impl Drop for AirPlayOutput {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::Relaxed);
+    }
+}
+
+struct Rtsp {
+    stream: TcpStream,
+    reader: BufReader<TcpStream>,
+    url: String,
+    sequence: u32,
+    session: Option<String>,
+    client: String,
+}
+
+impl Rtsp {
+    fn connect(address: SocketAddr) -> anyhow::Result<Rtsp> {
+        let stream = TcpStream::connect_timeout(&address, TIMEOUT)?;
+        stream.set_read_timeout(Some(TIMEOUT))?;
+        stream.set_write_timeout(Some(TIMEOUT))?;
+        Ok(Rtsp {
+            reader: BufReader::new(stream.try_clone()?),
+            stream,
+            url: String::new(),
+            sequence: 0,
+            session: None,
+            client: format!("{:016X}", fastrand::u64(..)),
+        })
+    }
+
+    fn request(
+        &mut self,
+        method: &str,
+        url: Option<&str>,
+        headers: &[(&str, &str)],
+        body: Option<(&str, &str)>,
+    ) -> anyhow::Result<HashMap<String, String>> {
+        self.sequence += 1;
+        let mut request = format!(
+            "{} {} RTSP/1.0\r\nCSeq: {}\r\nUser-Agent: Kanta/{}\r\nClient-Instance: {}\r\nDACP-ID: {}\r\n",
+            method,
+            url.unwrap_or(&self.url),
+            self.sequence,
+            env!("CARGO_PKG_VERSION"),
+            self.client,
+            self.client
+        );
+        if let Some(session) = &self.session {
+            request.push_str(&format!("Session: {}\r\n", session));
+        }
+        for (name, value) in headers {
+            request.push_str(&format!("{}: {}\r\n", name, value));
+        }
+        match body {
+            Some((content_type, body)) => request.push_str(&format!(
+                "Content-Type: {}\r\nContent-Length: {}\r\n\r\n{}",
+                content_type,
+                body.len(),
+                body
+            )),
+            None => request.push_str("\r\n"),
+        }
+        self.stream.write_all(request.as_bytes())?;
+
+        let mut status = String::new();
+        self.reader.read_line(&mut status)?;
+        let mut headers = HashMap::new();
+        loop {
+            let mut line = String::new();
+            if self.reader.read_line(&mut line)? == 0 || line.trim_end().is_empty() {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':') {
+                headers.insert(name.trim().to_ascii_lowercase(), value.trim().to_string());
+            }
+        }
+        if let Some(len) = headers
+            .get("content-length")
+            .and_then(|len| len.parse::<usize>().ok())
+        {
+            self.reader
+                .by_ref()
+                .take(len as u64)
+                .read_to_end(&mut vec![])?;
+        }
+
+        if status.split_whitespace().nth(1) != Some("200") {
+            bail!("{} was rejected: {}", method, status.trim_end());
+        }
+        Ok(headers)
+    }
+}
+
+struct Session {
+    rtsp: Rtsp,
+    audio: UdpSocket,
+    control: UdpSocket,
+    ssrc: u32,
+    sequence: u16,
+    timestamp: u32,
+    latency_frames: u32,
+}
+
+impl Session {
+    fn stream(mut self, mut source: MixerSource, stopped: &AtomicBool) {
+        let started_at = Instant::now();
+        let mut packets = 0u64;
+        let mut samples = Vec::with_capacity(FRAMES_PER_PACKET as usize * CHANNELS as usize);
+        let mut failed = false;
+        while !stopped.load(Ordering::Relaxed) {
+            if packets.is_multiple_of((SAMPLE_RATE / FRAMES_PER_PACKET) as u64) {
+                self.control.send(&self.sync_packet(packets == 0)).ok();
+            }
+
+            samples.clear();
+            samples.extend(
+                source
+                    .by_ref()
+                    .take(FRAMES_PER_PACKET as usize * CHANNELS as usize)
+                    .map(|sample| (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16),
+            );
+            let packet = self.audio_packet(&samples, packets == 0);
+            match self.audio.send(&packet) {
+                Ok(_) => failed = false,
+                Err(e) if !failed => {
+                    warn!("Failed to send audio to the AirPlay speaker: {}", e);
+                    failed = true;
+                }
+                Err(_) => {}
+            }
+            self.sequence = self.sequence.wrapping_add(1);
+            self.timestamp = self.timestamp.wrapping_add(FRAMES_PER_PACKET);
+
+            packets += 1;
+            let due = started_at
+                + Duration::from_secs_f64(
+                    (packets * FRAMES_PER_PACKET as u64) as f64 / SAMPLE_RATE as f64,
+                );
+            if let Some(wait) = due.checked_duration_since(Instant::now()) {
+                thread::sleep(wait);
+            }
+        }
+
+        if let Err(e) = self.rtsp.request("TEARDOWN", None, &[], None) {
+            debug!("Failed to end the AirPlay session: {}", e);
+        }
+    }
+
+    fn audio_packet(&self, samples: &[i16], first: bool) -> Vec<u8> {
+        let mut packet = vec![0x80, if first { 0xe0 } else { 0x60 }];
+        packet.extend(self.sequence.to_be_bytes());
+        packet.extend(self.timestamp.to_be_bytes());
+        packet.extend(self.ssrc.to_be_bytes());
+        packet.extend(alac_frame(samples));
+        packet
+    }
+
+    fn sync_packet(&self, first: bool) -> Vec<u8> {
+        let mut packet = vec![if first { 0x90 } else { 0x80 }, 0xd4, 0x00, 0x07];
+        packet.extend(
+            self.timestamp
+                .wrapping_sub(self.latency_frames)
+                .to_be_bytes(),
+        );
+        packet.extend(ntp_now());
+        packet.extend(self.timestamp.to_be_bytes());
+        packet
+    }
+}
+
+fn answer_timing_requests(socket: UdpSocket, stopped: &AtomicBool) {
+    let mut request = [0; 32];
+    while !stopped.load(Ordering::Relaxed) {
+        let Ok((len, source)) = socket.recv_from(&mut request) else {
+            continue;
+        };
+        if len < 32 || request[1] & 0x7f != 0x52 {
+            continue;
+        }
+        let mut reply = vec![0x80, 0xd3, 0x00, 0x07, 0, 0, 0, 0];
+        reply.extend(&request[24..32]);
+        let now = ntp_now();
+        reply.extend(now);
+        reply.extend(now);
+        socket.send_to(&reply, source).ok();
+    }
+}
+
+fn ntp_now() -> [u8; 8] {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    let seconds = now.as_secs() + NTP_EPOCH_OFFSET;
+    let fraction = ((now.subsec_nanos() as u64) << 32) / 1_000_000_000;
+    ((seconds << 32) | fraction).to_be_bytes()
+}
+
+fn alac_frame(samples: &[i16]) -> Vec<u8> {
+    let mut bits = BitWriter::default();
+    bits.write(1, 3);
+    bits.write(0, 4);
+    bits.write(0, 12);
+    bits.write(1, 1);
+    bits.write(0, 2);
+    bits.write(1, 1);
+    bits.write(samples.len() as u32 / CHANNELS as u32, 32);
+    for &sample in samples {
+        bits.write(sample as u16 as u32, 16);
+    }
+    bits.write(7, 3);
+    bits.bytes
+}
+
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    len: usize,
+}
+
+impl BitWriter {
+    fn write(&mut self, value: u32, count: usize) {
+        for bit in (0..count).rev() {
+            if self.len.is_multiple_of(8) {
+                self.bytes.push(0);
+            }
+            if value >> bit & 1 == 1
+                && let Some(byte) = self.bytes.last_mut()
+            {
+                *byte |= 0x80 >> (self.len % 8);
+            }
+            self.len += 1;
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use std::sync::OnceLock;
+
+use time::{Date, OffsetDateTime, UtcOffset};
+
+static STARTUP_OFFSET: OnceLock<UtcOffset> = OnceLock::new();
+
+pub fn capture_local_offset() {
+    STARTUP_OFFSET.get_or_init(|| UtcOffset::current_local_offset().unwrap_or(UtcOffset::UTC));
+}
+
+pub fn parse_time(time: &str) -> Option<(u8, u8)> {
+    let (hour, minute) = time.trim().split_once(':')?;
+    let (hour, minute) = (hour.parse().ok()?, minute.parse().ok()?);
+    (hour < 24 && minute < 60).then_some((hour, minute))
+}
+
+pub fn is_due(time: &str, fired_on: &mut Option<Date>) -> bool {
+    let Some((hour, minute)) = parse_time(time) else {
+        return false;
+    };
+    let now = local_now();
+    if (now.hour(), now.minute()) != (hour, minute) || *fired_on == Some(now.date()) {
+        return false;
+    }
+    *fired_on = Some(now.date());
+    true
+}
+
+fn local_now() -> OffsetDateTime {
+    let offset = UtcOffset::current_local_offset()
+        .ok()
+        .or(STARTUP_OFFSET.get().copied())
+        .unwrap_or(UtcOffset::UTC);
+    OffsetDateTime::now_utc().to_offset(offset)
+}
@@ -0,0 +1,77 @@
+use std::collections::HashMap;
+
+use iced::{theme::Palette, widget::image::Handle, Color, Theme};
+
+const THUMBNAIL_SIZE: u32 = 64;
+const MIN_PRIMARY_BRIGHTNESS: f32 = 0.6;
+const BACKGROUND_TINT: f32 = 0.2;
+
+pub fn from_cover(cover: &Handle) -> Option<Theme> {
+    let Handle::Bytes(_, bytes) = cover else {
+        return None;
+    };
+    let color = dominant_color(bytes)?;
+
+    let brightness = color.r.max(color.g).max(color.b);
+    let primary = if brightness < MIN_PRIMARY_BRIGHTNESS {
+        scale(color, MIN_PRIMARY_BRIGHTNESS / brightness.max(f32::EPSILON))
+    } else {
+        color
+    };
+
+    let dark = Palette::DARK;
+    let palette = Palette {
+        background: mix(dark.background, color, BACKGROUND_TINT),
+        primary,
+        ..dark
+    };
+    Some(Theme::custom("Album", palette))
+}
+
+fn dominant_color(bytes: &[u8]) -> Option<Color> {
+    let cover = image::load_from_memory(bytes)
+        .ok()?
+        .thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE)
+        .to_rgb8();
+
+    let mut buckets: HashMap<[u8; 3], (f32, [f32; 3])> = HashMap::new();
+    for pixel in cover.pixels() {
+        let [r, g, b] = pixel.0.map(|channel| channel as f32 / 255.0);
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        if max < 0.15 || min > 0.9 {
+            continue;
+        }
+
+        let saturation = (max - min) / max;
+        let weight = 0.1 + saturation;
+        let (total_weight, sum) = buckets
+            .entry(pixel.0.map(|channel| channel >> 4))
+            .or_default();
+        *total_weight += weight;
+        for (sum, channel) in sum.iter_mut().zip([r, g, b]) {
+            *sum += channel * weight;
+        }
+    }
+
+    let (weight, [r, g, b]) = buckets
+        .into_values()
+        .max_by(|(a, _), (b, _)| a.total_cmp(b))?;
+    Some(Color::from_rgb(r / weight, g / weight, b / weight))
+}
+
+fn scale(color: Color, factor: f32) -> Color {
+    Color::from_rgb(
+        (color.r * factor).min(1.0),
+        (color.g * factor).min(1.0),
+        (color.b * factor).min(1.0),
+    )
+}
+
+fn mix(a: Color, b: Color, amount: f32) -> Color {
+    Color::from_rgb(
+        a.r + (b.r - a.r) * amount,
+        a.g + (b.g - a.g) * amount,
+        a.b + (b.b - a.b) * amount,
+    )
+}
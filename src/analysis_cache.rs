@@ -0,0 +1,147 @@
+use std::{
+    collections::HashMap,
+    fs, iter,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{loudness::Loudness, paths};
+
+const SAVE_DELAY: Duration = Duration::from_secs(5);
+const CACHE_FILE: &str = "analysis.json";
+const LEGACY_FILES: [&str; 2] = ["tempo.json", "loudness.json"];
+
+static CACHE: Mutex<Option<Cache>> = Mutex::new(None);
+
+#[derive(Default)]
+struct Cache {
+    entries: HashMap<PathBuf, Entry>,
+    changed_at: Option<Instant>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct Entry {
+    bpm: Option<f32>,
+    loudness: Option<Loudness>,
+}
+
+pub fn bpm(path: &Path) -> Option<f32> {
+    with_cache(|cache| cache.entries.get(path)?.bpm)
+}
+
+pub fn loudness(path: &Path) -> Option<Loudness> {
+    with_cache(|cache| cache.entries.get(path)?.loudness)
+}
+
+pub fn store_bpm(path: &Path, bpm: f32) {
+    update(path, |entry| entry.bpm = Some(bpm));
+}
+
+pub fn store_loudness(path: &Path, loudness: Loudness) {
+    update(path, |entry| entry.loudness = Some(loudness));
+}
+
+pub fn save_if_due() -> anyhow::Result<()> {
+    save_when(|changed_at| changed_at.elapsed() >= SAVE_DELAY)
+}
+
+pub fn save() -> anyhow::Result<()> {
+    save_when(|_| true)
+}
+
+pub fn size() -> u64 {
+    let size = |name| -> anyhow::Result<u64> {
+        Ok(fs::metadata(paths::app_data_dir()?.join(name))?.len())
+    };
+    iter::once(CACHE_FILE)
+        .chain(LEGACY_FILES)
+        .map(|name| size(name).unwrap_or(0))
+        .sum()
+}
+
+pub fn clear() -> anyhow::Result<()> {
+    *CACHE.lock().unwrap_or_else(|e| e.into_inner()) = Some(Cache::default());
+    let path = cache_path()?;
+    if path.exists() {
+        fs::remove_file(path)?;
+    }
+    remove_legacy_files()
+}
+
+fn with_cache<T>(f: impl FnOnce(&mut Cache) -> T) -> T {
+    let mut cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    f(cache.get_or_insert_with(load))
+}
+
+fn update(path: &Path, f: impl FnOnce(&mut Entry)) {
+    with_cache(|cache| {
+        f(cache.entries.entry(path.to_path_buf()).or_default());
+        cache.changed_at.get_or_insert_with(Instant::now);
+    });
+}
+
+fn save_when(due: impl FnOnce(Instant) -> bool) -> anyhow::Result<()> {
+    let contents = with_cache(|cache| match cache.changed_at {
+        Some(changed_at) if due(changed_at) => {
+            cache.changed_at = None;
+            Some(serde_json::to_string(&cache.entries))
+        }
+        _ => None,
+    });
+    let Some(contents) = contents.transpose()? else {
+        return Ok(());
+    };
+    fs::write(cache_path()?, contents)?;
+    remove_legacy_files()
+}
+
+fn load() -> Cache {
+    let load = || -> anyhow::Result<HashMap<PathBuf, Entry>> {
+        Ok(serde_json::from_str(&fs::read_to_string(cache_path()?)?)?)
+    };
+    if let Ok(entries) = load() {
+        return Cache {
+            entries,
+            changed_at: None,
+        };
+    }
+
+    let legacy = |name| -> anyhow::Result<String> {
+        Ok(fs::read_to_string(paths::app_data_dir()?.join(name))?)
+    };
+    let mut cache = Cache::default();
+    let bpms: HashMap<PathBuf, f32> = legacy(LEGACY_FILES[0])
+        .and_then(|contents| Ok(serde_json::from_str(&contents)?))
+        .unwrap_or_default();
+    for (path, bpm) in bpms {
+        cache.entries.entry(path).or_default().bpm = Some(bpm);
+    }
+    let loudnesses: HashMap<PathBuf, Loudness> = legacy(LEGACY_FILES[1])
+        .and_then(|contents| Ok(serde_json::from_str(&contents)?))
+        .unwrap_or_default();
+    for (path, loudness) in loudnesses {
+        cache.entries.entry(path).or_default().loudness = Some(loudness);
+    }
+    if !cache.entries.is_empty() {
+        cache.changed_at = Some(Instant::now());
+    }
+    cache
+}
+
+fn remove_legacy_files() -> anyhow::Result<()> {
+    for name in LEGACY_FILES {
+        let path = paths::app_data_dir()?.join(name);
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+    }
+    Ok(())
+}
+
+fn cache_path() -> anyhow::Result<PathBuf> {
+    Ok(paths::app_data_dir()?.join(CACHE_FILE))
+}
@@ -0,0 +1,18 @@
+use std::env;
+
+const ROLE_VARS: [(&str, &str); 2] = [
+    ("PULSE_PROP_media.role", "music"),
+    ("PIPEWIRE_PROPS", "{ media.role = Music }"),
+];
+
+pub fn declare_music() {
+    if !cfg!(target_os = "linux") {
+        return;
+    }
+    for (var, value) in ROLE_VARS {
+        if env::var_os(var).is_none() {
+            // SAFETY: called at startup, before any other thread is spawned.
+            unsafe { env::set_var(var, value) };
+        }
+    }
+}
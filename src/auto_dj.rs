@@ -0,0 +1,98 @@
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+use tracing::error;
+
+use crate::{browser, config::AutoDj, exclude, track::Track, track_stats::TrackStats};
+
+const BATCH_LEN: usize = 5;
+const SAMPLE_LEN: usize = 200;
+
+pub fn pick(
+    mode: AutoDj,
+    music_folders: &[PathBuf],
+    excludes: &[String],
+    queued: &HashSet<PathBuf>,
+    seeds: &[Track],
+    stats: &HashMap<PathBuf, TrackStats>,
+) -> Vec<Track> {
+    let mut candidates = music_folders
+        .iter()
+        .filter_map(|folder| {
+            browser::tracks(folder)
+                .inspect_err(|e| error!("Failed to list {}: {}", folder.display(), e))
+                .ok()
+                .map(|paths| {
+                    paths
+                        .into_iter()
+                        .filter(|path| !exclude::is_excluded(excludes, folder, path, false))
+                })
+        })
+        .flatten()
+        .filter(|path| !queued.contains(path))
+        .collect::<Vec<_>>();
+    fastrand::shuffle(&mut candidates);
+
+    match mode {
+        AutoDj::Off => vec![],
+        AutoDj::TopRated => {
+            candidates.sort_by_key(|path| {
+                let stats = stats.get(path).copied().unwrap_or_default();
+                (stats.play_count > 0, Reverse(stats.rating))
+            });
+            candidates
+                .into_iter()
+                .filter_map(|path| Track::load(path).ok())
+                .take(BATCH_LEN)
+                .collect()
+        }
+        AutoDj::Similar => {
+            let artists = seeds
+                .iter()
+                .flat_map(|track| track.artists())
+                .map(|artist| artist.to_lowercase())
+                .collect::<HashSet<_>>();
+            let genres = seeds
+                .iter()
+                .flat_map(|track| track.genres())
+                .map(|genre| genre.to_lowercase())
+                .collect::<HashSet<_>>();
+            let folders = seeds
+                .iter()
+                .filter_map(|track| artist_folder(track.path()))
+                .collect::<HashSet<_>>();
+            let is_near = |path: &Path| artist_folder(path).is_some_and(|f| folders.contains(f));
+
+            candidates.sort_by_key(|path| !is_near(path));
+            let mut scored = candidates
+                .iter()
+                .take(SAMPLE_LEN)
+                .filter_map(|path| Track::load(path.clone()).ok())
+                .map(|track| {
+                    let matches = |values: &[String], set: &HashSet<String>| {
+                        values
+                            .iter()
+                            .any(|value| set.contains(&value.to_lowercase()))
+                    };
+                    let score = 3 * u32::from(matches(track.artists(), &artists))
+                        + 2 * u32::from(matches(track.genres(), &genres))
+                        + u32::from(is_near(track.path()));
+                    (score, track)
+                })
+                .collect::<Vec<_>>();
+            scored.sort_by_key(|(score, _)| Reverse(*score));
+            scored
+                .into_iter()
+                .take(BATCH_LEN)
+                .map(|(_, track)| track)
+                .collect()
+        }
+    }
+}
+
+fn artist_folder(path: &Path) -> Option<&Path> {
+    path.parent()?.parent()
+}
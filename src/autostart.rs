@@ -0,0 +1,106 @@
+#[cfg(not(target_os = "windows"))]
+use std::path::PathBuf;
+#[cfg(target_os = "windows")]
+use std::process::Command;
+
+pub const ARG: &str = "--autostart";
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub fn set_enabled(enabled: bool) -> anyhow::Result<()> {
+    let path = dirs::config_dir()
+        .ok_or_else(|| anyhow::anyhow!("no config directory available"))?
+        .join("autostart")
+        .join("kanta.desktop");
+    if !enabled {
+        return remove(path);
+    }
+
+    let desktop_entry = format!(
+        "[Desktop Entry]\n\
+         Type=Application\n\
+         Name=Kanta\n\
+         Exec=\"{}\" {}\n\
+         Terminal=false\n\
+         X-GNOME-Autostart-enabled=true\n",
+        std::env::current_exe()?
+            .display()
+            .to_string()
+            .replace('"', "\\\""),
+        ARG
+    );
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(path, desktop_entry)?;
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+pub fn set_enabled(enabled: bool) -> anyhow::Result<()> {
+    const RUN_KEY: &str = r"HKCU\Software\Microsoft\Windows\CurrentVersion\Run";
+
+    let mut command = Command::new("reg");
+    if enabled {
+        let exe = std::env::current_exe()?;
+        command
+            .args(["add", RUN_KEY, "/v", "Kanta", "/d"])
+            .arg(format!("\"{}\" {}", exe.display(), ARG));
+    } else {
+        command.args(["delete", RUN_KEY, "/v", "Kanta"]);
+    }
+    let status = command.arg("/f").status()?;
+    if !status.success() && enabled {
+        anyhow::bail!("reg exited with {}", status);
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+pub fn set_enabled(enabled: bool) -> anyhow::Result<()> {
+    let path = dirs::home_dir()
+        .ok_or_else(|| anyhow::anyhow!("no home directory available"))?
+        .join("Library/LaunchAgents/app.kanta.autostart.plist");
+    if !enabled {
+        return remove(path);
+    }
+
+    let exe = std::env::current_exe()?;
+    let escape = |value: &str| {
+        value
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+    };
+    let plist = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" \"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n\
+         <plist version=\"1.0\">\n\
+         <dict>\n\
+         \t<key>Label</key>\n\
+         \t<string>app.kanta.autostart</string>\n\
+         \t<key>ProgramArguments</key>\n\
+         \t<array>\n\
+         \t\t<string>{}</string>\n\
+         \t\t<string>{}</string>\n\
+         \t</array>\n\
+         \t<key>RunAtLoad</key>\n\
+         \t<true/>\n\
+         </dict>\n\
+         </plist>\n",
+        escape(&exe.display().to_string()),
+        ARG
+    );
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(path, plist)?;
+    Ok(())
+}
+
+#[cfg(not(target_os = "windows"))]
+fn remove(path: PathBuf) -> anyhow::Result<()> {
+    if path.exists() {
+        std::fs::remove_file(path)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,91 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::bail;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::Config,
+    cues::{Cue, CueLibrary},
+    dsp::DspSettings,
+    lyrics_offsets::LyricsOffsets,
+    m3u8,
+    playlists::PlaylistLibrary,
+    session::SavedQueue,
+    track_dsp::TrackDspLibrary,
+    track_stats::{TrackStats, TrackStatsLibrary},
+    web_remote,
+};
+
+const BACKUP_VERSION: u32 = 3;
+
+#[derive(Serialize, Deserialize)]
+struct Backup {
+    version: u32,
+    config: Config,
+    playlists: BTreeMap<String, Vec<PathBuf>>,
+    queue: Option<SavedQueue>,
+    #[serde(default)]
+    track_stats: HashMap<PathBuf, TrackStats>,
+    #[serde(default)]
+    cues: HashMap<PathBuf, Vec<Cue>>,
+    #[serde(default)]
+    lyrics_offsets: HashMap<PathBuf, i32>,
+    #[serde(default)]
+    track_dsp: HashMap<PathBuf, DspSettings>,
+}
+
+pub fn export(path: &Path, config: &Config, queue: SavedQueue) -> anyhow::Result<()> {
+    let library = PlaylistLibrary::open()?;
+    let mut playlists = BTreeMap::new();
+    for name in library.names() {
+        playlists.insert(name.clone(), m3u8::read(&library.path(name))?);
+    }
+
+    let mut config = config.clone();
+    config.web_remote_token.clear();
+    config.scrobble.token.clear();
+    config.party_pin.clear();
+
+    let backup = Backup {
+        version: BACKUP_VERSION,
+        config,
+        playlists,
+        queue: Some(queue).filter(|queue| !queue.tracks.is_empty()),
+        track_stats: TrackStatsLibrary::open()?.all().clone(),
+        cues: CueLibrary::open()?.all().clone(),
+        lyrics_offsets: LyricsOffsets::open()?.all().clone(),
+        track_dsp: TrackDspLibrary::open()?.all().clone(),
+    };
+    fs::write(path, serde_json::to_string_pretty(&backup)?)?;
+    Ok(())
+}
+
+pub fn import(path: &Path) -> anyhow::Result<()> {
+    let backup: Backup = serde_json::from_str(&fs::read_to_string(path)?)?;
+    if backup.version > BACKUP_VERSION {
+        bail!("backup was made by a newer version of Kanta");
+    }
+
+    let current = Config::load();
+    let mut config = backup.config;
+    config.web_remote_token = web_remote::generate_token()?;
+    config.scrobble.token = current.scrobble.token;
+    config.party_pin = current.party_pin;
+    config.save()?;
+    let mut library = PlaylistLibrary::open()?;
+    for (name, paths) in &backup.playlists {
+        library.import(name, paths)?;
+    }
+    if let Some(queue) = backup.queue {
+        queue.save()?;
+    }
+    TrackStatsLibrary::open()?.merge(backup.track_stats)?;
+    CueLibrary::open()?.merge(backup.cues)?;
+    LyricsOffsets::open()?.merge(backup.lyrics_offsets)?;
+    TrackDspLibrary::open()?.merge(backup.track_dsp)?;
+    Ok(())
+}
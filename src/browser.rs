@@ -0,0 +1,129 @@
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::track::Track;
+
+pub const TRACK_EXTENSIONS: [&str; 4] = ["mp3", "ogg", "wav", "flac"];
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Chunk {
+    Number(u64),
+    Text(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct NaturalKey(Vec<Chunk>);
+
+#[derive(Debug, Clone)]
+pub struct Entry {
+    pub path: PathBuf,
+    pub is_dir: bool,
+}
+
+impl Entry {
+    pub fn name(&self) -> Cow<'_, str> {
+        self.path
+            .file_name()
+            .map(|name| name.to_string_lossy())
+            .unwrap_or_else(|| self.path.to_string_lossy())
+    }
+}
+
+pub fn list(dir: &Path) -> anyhow::Result<Vec<Entry>> {
+    let mut entries = fs::read_dir(dir)?
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                return None;
+            }
+            let path = entry.path();
+            let is_dir = path.is_dir();
+            (is_dir || is_track(&path)).then_some(Entry { path, is_dir })
+        })
+        .collect::<Vec<_>>();
+    entries.sort_by_cached_key(|entry| (!entry.is_dir, natural_key(&entry.name())));
+    Ok(entries)
+}
+
+pub fn tracks(dir: &Path) -> anyhow::Result<Vec<PathBuf>> {
+    let mut paths = vec![];
+    for entry in list(dir)? {
+        if entry.is_dir {
+            paths.extend(tracks(&entry.path)?);
+        } else {
+            paths.push(entry.path);
+        }
+    }
+    Ok(paths)
+}
+
+pub fn sort_album_tracks(tracks: &mut [Track]) {
+    let mut albums = HashMap::new();
+    for track in tracks.iter() {
+        let next = albums.len();
+        albums.entry(album_key(track)).or_insert(next);
+    }
+    tracks.sort_by_cached_key(|track| {
+        (
+            albums[&album_key(track)],
+            track.disc_number().unwrap_or(1),
+            track.track_number().unwrap_or(u32::MAX),
+            file_name_key(track.path()),
+        )
+    });
+}
+
+fn album_key(track: &Track) -> String {
+    match track.album() {
+        Some(album) => {
+            format!("{}\n{}", album, track.album_artist().unwrap_or_default()).to_lowercase()
+        }
+        None => track
+            .path()
+            .parent()
+            .map(|dir| dir.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+    }
+}
+
+pub fn file_name_key(path: &Path) -> NaturalKey {
+    natural_key(
+        &path
+            .file_name()
+            .map(|name| name.to_string_lossy())
+            .unwrap_or_default(),
+    )
+}
+
+pub fn natural_key(name: &str) -> NaturalKey {
+    let name = name.to_lowercase();
+    let mut chunks = vec![];
+    let mut rest = name.as_str();
+    while let Some(first) = rest.chars().next() {
+        let is_digit = first.is_ascii_digit();
+        let end = rest
+            .find(|c: char| c.is_ascii_digit() != is_digit)
+            .unwrap_or(rest.len());
+        let (chunk, remainder) = rest.split_at(end);
+        chunks.push(match chunk.parse() {
+            Ok(number) if is_digit => Chunk::Number(number),
+            _ => Chunk::Text(chunk.to_string()),
+        });
+        rest = remainder;
+    }
+    NaturalKey(chunks)
+}
+
+fn is_track(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| {
+            TRACK_EXTENSIONS
+                .iter()
+                .any(|track_ext| ext.eq_ignore_ascii_case(track_ext))
+        })
+}
@@ -0,0 +1,100 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use tracing::{error, info};
+
+use crate::{analysis_cache, paths, remote};
+
+const EVICTABLE_DIRS: [&str; 2] = ["remote", "covers"];
+
+pub fn usage() -> u64 {
+    let cache_dir = paths::cache_dir().map(|dir| dir_size(&dir)).unwrap_or(0);
+    cache_dir + analysis_cache::size()
+}
+
+pub fn clear() -> anyhow::Result<()> {
+    for entry in fs::read_dir(paths::cache_dir()?)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            fs::remove_dir_all(path)?;
+        } else {
+            fs::remove_file(path)?;
+        }
+    }
+    analysis_cache::clear()
+}
+
+pub fn evict(limit: u64) -> anyhow::Result<()> {
+    let mut usage = usage();
+    if usage <= limit {
+        return Ok(());
+    }
+
+    let cache_dir = paths::cache_dir()?;
+    let mut groups = HashMap::<PathBuf, (SystemTime, u64, Vec<PathBuf>)>::new();
+    for dir in EVICTABLE_DIRS {
+        let Ok(entries) = fs::read_dir(cache_dir.join(dir)) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+            let size = file_size(&path, metadata.len());
+            let group = groups
+                .entry(path.with_extension(""))
+                .or_insert((modified, 0, vec![]));
+            group.0 = group.0.max(modified);
+            group.1 += size;
+            group.2.push(path);
+        }
+    }
+
+    let mut groups = groups.into_values().collect::<Vec<_>>();
+    groups.sort_by_key(|(modified, ..)| *modified);
+    for (_, size, paths) in groups {
+        if usage <= limit {
+            break;
+        }
+        for path in paths {
+            if let Err(e) = fs::remove_file(&path) {
+                error!("Failed to evict {}: {}", path.display(), e);
+            }
+        }
+        usage = usage.saturating_sub(size);
+    }
+    if usage > limit {
+        usage = usage.saturating_sub(analysis_cache::size());
+        analysis_cache::clear()?;
+    }
+    info!("Evicted cached files down to {} bytes", usage);
+    Ok(())
+}
+
+fn dir_size(dir: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.metadata() {
+            Ok(metadata) if metadata.is_dir() => dir_size(&entry.path()),
+            Ok(metadata) => file_size(&entry.path(), metadata.len()),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+fn file_size(path: &Path, len: u64) -> u64 {
+    if path.extension().is_none() {
+        remote::cached_len(path).unwrap_or(len)
+    } else {
+        len
+    }
+}
@@ -0,0 +1,153 @@
+use crate::{
+    i18n::{self, t},
+    player::{PlaybackState, PlaylistSort},
+    Kanta, KantaMessage,
+};
+
+const MAX_RESULTS: usize = 12;
+
+pub struct Command {
+    pub label: String,
+    pub message: KantaMessage,
+}
+
+impl Command {
+    fn new(label: impl Into<String>, message: KantaMessage) -> Command {
+        Command {
+            label: label.into(),
+            message,
+        }
+    }
+}
+
+pub fn matching_commands(kanta: &Kanta, query: &str) -> Vec<Command> {
+    use KantaMessage::*;
+
+    let player = &kanta.player;
+    let mut commands = vec![
+        if player.state() == PlaybackState::Playing {
+            Command::new(t!("pause"), Pause)
+        } else {
+            Command::new(t!("play"), Play)
+        },
+        Command::new(t!("next-track"), JumpToNextTrack),
+        Command::new(t!("previous-track"), JumpToPreviousTrack),
+        Command::new(
+            if player.is_shuffling() {
+                t!("turn-shuffle-off")
+            } else {
+                t!("turn-shuffle-on")
+            },
+            SetShuffle(!player.is_shuffling()),
+        ),
+        Command::new(t!("add-track"), AddTrack),
+        Command::new(t!("load-playlist"), LoadPlaylist),
+        Command::new(t!("export-playlist"), ExportPlaylist),
+        Command::new(t!("clear-playlist"), ClearPlaylist),
+        Command::new(t!("deduplicate-playlist"), DeduplicatePlaylist),
+        Command::new(t!("scroll-to-now-playing"), ScrollToNowPlaying),
+        Command::new(t!("toggle-now-playing-view"), ToggleNowPlayingView),
+        Command::new(t!("toggle-karaoke"), ToggleKaraoke),
+        Command::new(
+            t!("show-current-track-in-file-manager"),
+            ShowCurrentTrackInFileManager,
+        ),
+        Command::new(t!("statistics"), ShowStatistics),
+        Command::new(t!("sync-to-device"), OpenSyncDialog),
+        Command::new(t!("export-history"), OpenHistoryExport),
+        Command::new(t!("settings"), OpenSettings),
+        Command::new(t!("switch-profile"), OpenProfilePicker),
+    ];
+    if kanta.scripting.is_some() {
+        commands.push(Command::new(t!("script-console"), OpenScriptConsole));
+    }
+    if let (Some(loved_tracks), Some(track)) = (&kanta.loved_tracks, player.current_track()) {
+        commands.push(Command::new(
+            if loved_tracks.is_loved(track.path()) {
+                t!("unlove-track")
+            } else {
+                t!("love-track")
+            },
+            ToggleLoveCurrentTrack,
+        ));
+    }
+    commands.extend(PlaylistSort::ALL.into_iter().map(|sort| {
+        Command::new(
+            t!("sort-playlist-by", sort = sort.to_string()),
+            SortPlaylist(sort),
+        )
+    }));
+    commands.extend(
+        kanta
+            .playlists
+            .iter()
+            .flat_map(|playlists| playlists.names())
+            .map(|name| {
+                Command::new(
+                    t!("load-named-playlist", name = name.as_str()),
+                    LoadNamedPlaylist(name.clone()),
+                )
+            }),
+    );
+    commands.extend(
+        kanta
+            .stations
+            .iter()
+            .flat_map(|stations| stations.stations())
+            .map(|station| {
+                Command::new(
+                    t!("play-station", name = station.name.as_str()),
+                    PlayStation(station.url.clone()),
+                )
+            }),
+    );
+    commands.extend(
+        i18n::LANGUAGES
+            .iter()
+            .filter(|language| language.id != i18n::current_language())
+            .map(|language| {
+                Command::new(
+                    t!("switch-language", language = language.name),
+                    SetLanguage(Some(language.id.to_string())),
+                )
+            }),
+    );
+    if kanta.config.language.is_some() {
+        commands.push(Command::new(t!("use-system-language"), SetLanguage(None)));
+    }
+
+    let query = query.to_lowercase();
+    let words = query.split_whitespace().collect::<Vec<_>>();
+    let is_match = |label: &str| {
+        let label = label.to_lowercase();
+        words.iter().all(|word| label.contains(word))
+    };
+
+    let mut matches = commands
+        .into_iter()
+        .filter(|command| is_match(&command.label))
+        .take(MAX_RESULTS)
+        .collect::<Vec<_>>();
+
+    if !words.is_empty() {
+        let track_matches = player
+            .playlist()
+            .iter()
+            .enumerate()
+            .map(|(index, track)| {
+                let title = track
+                    .title()
+                    .map(str::to_string)
+                    .unwrap_or_else(|| track.path().to_string_lossy().into_owned());
+                let label = match track.artist() {
+                    Some(artist) => t!("jump-to-track-by-artist", title = title, artist = artist),
+                    None => t!("jump-to-track", title = title),
+                };
+                Command::new(label, JumpToTrack(index))
+            })
+            .filter(|command| is_match(&command.label));
+        matches.extend(track_matches.take(MAX_RESULTS - matches.len()));
+    }
+
+    matches
+}
@@ -0,0 +1,420 @@
+use std::{
+    fmt, fs,
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+use tracing::error;
+
+use crate::{i18n::t, paths};
+
+const MAX_RECENT_ITEMS: usize = 10;
+
+pub const DEFAULT_TEXT_SIZE: f32 = 14.0;
+pub const TEXT_SIZE_RANGE: RangeInclusive<f32> = 10.0..=32.0;
+pub const UI_SCALE_RANGE: RangeInclusive<f32> = 0.5..=3.0;
+pub const SCROBBLE_PERCENT_RANGE: RangeInclusive<f32> = 10.0..=100.0;
+pub const SCROBBLE_MIN_DURATION_RANGE: RangeInclusive<f32> = 0.0..=300.0;
+pub const SHUFFLE_AVOID_RECENT_RANGE: RangeInclusive<f32> = 0.0..=48.0;
+pub const CACHE_LIMIT_RANGE: RangeInclusive<f32> = 64.0..=16384.0;
+pub const JOB_CONCURRENCY_RANGE: RangeInclusive<u8> = 1..=8;
+pub const QUEUE_COLUMN_WIDTH_RANGE: RangeInclusive<f32> = 48.0..=640.0;
+pub const PINNED_PLAYLIST_SLOTS: usize = 9;
+pub const LISTENBRAINZ_URL: &str = "https://api.listenbrainz.org";
+
+const SCROBBLE_MAX_THRESHOLD: Duration = Duration::from_secs(4 * 60);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub recent: Vec<RecentItem>,
+    pub language: Option<String>,
+    pub text_size: f32,
+    pub ui_scale: f32,
+    pub album_colors: bool,
+    pub file_associations_prompted: bool,
+    pub music_folders: Vec<PathBuf>,
+    pub library_excludes: Vec<String>,
+    pub normalize_volume: bool,
+    pub show_stream_titles: bool,
+    pub show_level_meters: bool,
+    pub karaoke_reduce_vocals: bool,
+    pub yield_to_calls: bool,
+    pub close_to_background: bool,
+    pub close_to_background_explained: bool,
+    pub autostart: bool,
+    pub autostart_minimized: bool,
+    pub volume: f32,
+    pub remember_volume: bool,
+    pub launch_volume: f32,
+    pub queue_columns: QueueColumns,
+    pub job_concurrency: u8,
+    pub cache_limit_mb: f32,
+    pub scrobble: ScrobbleSettings,
+    pub shuffle_avoid_recent_hours: f32,
+    pub shuffle_weighting: ShuffleWeighting,
+    pub auto_dj: AutoDj,
+    pub party_mode: bool,
+    pub party_pin: String,
+    pub hook_command: String,
+    pub event_stream: bool,
+    pub event_stream_port: u16,
+    pub web_remote: bool,
+    pub web_remote_port: u16,
+    pub web_remote_token: String,
+    pub now_playing_file: Option<PathBuf>,
+    pub snapcast: bool,
+    pub snapcast_address: String,
+    pub resume_rewind: bool,
+    pub resume_rewind_secs: u32,
+    pub alarm: AlarmSettings,
+    pub pinned_playlists: [Option<String>; PINNED_PLAYLIST_SLOTS],
+    pub append_pinned_playlists: bool,
+    pub hidden_tracks: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ShuffleWeighting {
+    #[default]
+    Even,
+    Rating,
+    LeastPlayed,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AutoDj {
+    #[default]
+    Off,
+    Similar,
+    TopRated,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueueColumn {
+    Artist,
+    Album,
+    Length,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QueueColumns {
+    pub artist: f32,
+    pub album: f32,
+    pub length: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScrobbleSettings {
+    pub server: String,
+    pub token: String,
+    pub min_percent: f32,
+    pub min_duration_secs: f32,
+    pub ignored_folders: Vec<PathBuf>,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            recent: vec![],
+            language: None,
+            text_size: DEFAULT_TEXT_SIZE,
+            ui_scale: 1.0,
+            album_colors: false,
+            file_associations_prompted: false,
+            music_folders: vec![],
+            library_excludes: vec![],
+            normalize_volume: false,
+            show_stream_titles: true,
+            show_level_meters: true,
+            karaoke_reduce_vocals: false,
+            yield_to_calls: false,
+            close_to_background: false,
+            close_to_background_explained: false,
+            autostart: false,
+            autostart_minimized: true,
+            volume: 1.0,
+            remember_volume: true,
+            launch_volume: 1.0,
+            queue_columns: QueueColumns::default(),
+            job_concurrency: 2,
+            cache_limit_mb: 2048.0,
+            scrobble: ScrobbleSettings::default(),
+            shuffle_avoid_recent_hours: 2.0,
+            shuffle_weighting: ShuffleWeighting::Even,
+            auto_dj: AutoDj::Off,
+            party_mode: false,
+            party_pin: String::new(),
+            hook_command: String::new(),
+            event_stream: false,
+            event_stream_port: 7381,
+            web_remote: false,
+            web_remote_port: 7380,
+            web_remote_token: String::new(),
+            now_playing_file: None,
+            snapcast: false,
+            snapcast_address: String::new(),
+            resume_rewind: true,
+            resume_rewind_secs: 5,
+            alarm: AlarmSettings::default(),
+            pinned_playlists: Default::default(),
+            append_pinned_playlists: false,
+            hidden_tracks: true,
+        }
+    }
+}
+
+impl Default for QueueColumns {
+    fn default() -> QueueColumns {
+        QueueColumns {
+            artist: 200.0,
+            album: 200.0,
+            length: 72.0,
+        }
+    }
+}
+
+impl QueueColumns {
+    pub fn width(&self, column: QueueColumn) -> f32 {
+        match column {
+            QueueColumn::Artist => self.artist,
+            QueueColumn::Album => self.album,
+            QueueColumn::Length => self.length,
+        }
+    }
+
+    pub fn set_width(&mut self, column: QueueColumn, width: f32) {
+        let width = width.clamp(
+            *QUEUE_COLUMN_WIDTH_RANGE.start(),
+            *QUEUE_COLUMN_WIDTH_RANGE.end(),
+        );
+        match column {
+            QueueColumn::Artist => self.artist = width,
+            QueueColumn::Album => self.album = width,
+            QueueColumn::Length => self.length = width,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AlarmSettings {
+    pub enabled: bool,
+    pub time: String,
+    pub playlist: Option<String>,
+}
+
+impl Default for AlarmSettings {
+    fn default() -> AlarmSettings {
+        AlarmSettings {
+            enabled: false,
+            time: "07:00".to_string(),
+            playlist: None,
+        }
+    }
+}
+
+impl Default for ScrobbleSettings {
+    fn default() -> ScrobbleSettings {
+        ScrobbleSettings {
+            server: String::new(),
+            token: String::new(),
+            min_percent: 50.0,
+            min_duration_secs: 30.0,
+            ignored_folders: vec![],
+        }
+    }
+}
+
+impl ScrobbleSettings {
+    pub fn is_enabled(&self) -> bool {
+        !self.token.trim().is_empty()
+    }
+
+    pub fn server_url(&self) -> &str {
+        match self.server.trim().trim_end_matches('/') {
+            "" => LISTENBRAINZ_URL,
+            server => server,
+        }
+    }
+
+    pub fn is_ignored(&self, path: &Path, duration: Duration) -> bool {
+        duration.as_secs_f32() < self.min_duration_secs
+            || self
+                .ignored_folders
+                .iter()
+                .any(|folder| path.starts_with(folder))
+    }
+
+    pub fn threshold(&self, duration: Duration) -> Duration {
+        duration
+            .mul_f32(self.min_percent / 100.0)
+            .min(SCROBBLE_MAX_THRESHOLD)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RecentItem {
+    Track(PathBuf),
+    Playlist(PathBuf),
+    Folder(PathBuf),
+}
+
+impl Config {
+    pub fn load() -> Config {
+        let load = || -> anyhow::Result<Config> {
+            let path = Self::path()?;
+            if !path.exists() {
+                return Ok(Config::default());
+            }
+            Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+        };
+        let mut config = load().unwrap_or_else(|e| {
+            error!("Failed to load config: {}", e);
+            Config::default()
+        });
+        config.text_size = config
+            .text_size
+            .clamp(*TEXT_SIZE_RANGE.start(), *TEXT_SIZE_RANGE.end());
+        config.ui_scale = config
+            .ui_scale
+            .clamp(*UI_SCALE_RANGE.start(), *UI_SCALE_RANGE.end());
+        config.volume = config.volume.clamp(0.0, 1.0);
+        config.launch_volume = config.launch_volume.clamp(0.0, 1.0);
+        config.cache_limit_mb = config
+            .cache_limit_mb
+            .clamp(*CACHE_LIMIT_RANGE.start(), *CACHE_LIMIT_RANGE.end());
+        config.job_concurrency = config
+            .job_concurrency
+            .clamp(*JOB_CONCURRENCY_RANGE.start(), *JOB_CONCURRENCY_RANGE.end());
+        for column in [QueueColumn::Artist, QueueColumn::Album, QueueColumn::Length] {
+            let width = config.queue_columns.width(column);
+            config.queue_columns.set_width(column, width);
+        }
+        config.scrobble.min_percent = config.scrobble.min_percent.clamp(
+            *SCROBBLE_PERCENT_RANGE.start(),
+            *SCROBBLE_PERCENT_RANGE.end(),
+        );
+        config.shuffle_avoid_recent_hours = config.shuffle_avoid_recent_hours.clamp(
+            *SHUFFLE_AVOID_RECENT_RANGE.start(),
+            *SHUFFLE_AVOID_RECENT_RANGE.end(),
+        );
+        config.scrobble.min_duration_secs = config.scrobble.min_duration_secs.clamp(
+            *SCROBBLE_MIN_DURATION_RANGE.start(),
+            *SCROBBLE_MIN_DURATION_RANGE.end(),
+        );
+        config
+    }
+
+    pub fn cache_limit(&self) -> u64 {
+        (self.cache_limit_mb as f64 * 1024.0 * 1024.0) as u64
+    }
+
+    pub fn resume_rewind(&self) -> Duration {
+        if self.resume_rewind {
+            Duration::from_secs(self.resume_rewind_secs as u64)
+        } else {
+            Duration::ZERO
+        }
+    }
+
+    pub fn pinned_slot(&self, name: &str) -> Option<usize> {
+        self.pinned_playlists
+            .iter()
+            .position(|pinned| pinned.as_deref() == Some(name))
+    }
+
+    pub fn toggle_pinned_playlist(&mut self, name: &str) {
+        match self.pinned_slot(name) {
+            Some(slot) => self.pinned_playlists[slot] = None,
+            None => {
+                if let Some(slot) = self.pinned_playlists.iter_mut().find(|slot| slot.is_none()) {
+                    *slot = Some(name.to_string());
+                }
+            }
+        }
+    }
+
+    pub fn rename_playlist_references(&mut self, name: &str, new_name: Option<&str>) {
+        if let Some(slot) = self.pinned_slot(name) {
+            self.pinned_playlists[slot] = new_name.map(str::to_string);
+        }
+        if self.alarm.playlist.as_deref() == Some(name) {
+            self.alarm.playlist = new_name.map(str::to_string);
+        }
+    }
+
+    pub fn startup_volume(&self) -> f32 {
+        if self.remember_volume {
+            self.volume
+        } else {
+            self.launch_volume
+        }
+    }
+
+    pub fn save(&self) -> anyhow::Result<()> {
+        fs::write(Self::path()?, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn add_recent(&mut self, item: RecentItem) -> anyhow::Result<()> {
+        self.recent.retain(|i| *i != item);
+        self.recent.insert(0, item);
+        self.recent.truncate(MAX_RECENT_ITEMS);
+        self.save()
+    }
+
+    fn path() -> anyhow::Result<PathBuf> {
+        Ok(paths::config_dir()?.join("config.json"))
+    }
+}
+
+impl ShuffleWeighting {
+    pub const ALL: [ShuffleWeighting; 3] = [
+        ShuffleWeighting::Even,
+        ShuffleWeighting::Rating,
+        ShuffleWeighting::LeastPlayed,
+    ];
+}
+
+impl fmt::Display for ShuffleWeighting {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&t!(match self {
+            ShuffleWeighting::Even => "shuffle-even",
+            ShuffleWeighting::Rating => "shuffle-by-rating",
+            ShuffleWeighting::LeastPlayed => "shuffle-least-played",
+        }))
+    }
+}
+
+impl AutoDj {
+    pub const ALL: [AutoDj; 3] = [AutoDj::Off, AutoDj::Similar, AutoDj::TopRated];
+}
+
+impl fmt::Display for AutoDj {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&t!(match self {
+            AutoDj::Off => "off",
+            AutoDj::Similar => "auto-dj-similar",
+            AutoDj::TopRated => "auto-dj-top-rated",
+        }))
+    }
+}
+
+impl fmt::Display for RecentItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (id, path) = match self {
+            RecentItem::Track(path) => ("recent-track", path),
+            RecentItem::Playlist(path) => ("recent-playlist", path),
+            RecentItem::Folder(path) => ("recent-folder", path),
+        };
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy())
+            .unwrap_or_else(|| path.to_string_lossy());
+        f.write_str(&t!(id, name = name.into_owned()))
+    }
+}
@@ -0,0 +1,141 @@
+use std::{
+    backtrace::Backtrace,
+    fmt::Write,
+    fs,
+    panic::{self, PanicHookInfo},
+    path::PathBuf,
+    sync::{Mutex, TryLockError},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use rfd::{MessageButtons, MessageDialog, MessageDialogResult, MessageLevel};
+use tracing::error;
+
+use crate::{file_manager, i18n::t, paths};
+
+const PENDING_FILE: &str = "pending";
+
+struct CrashContext {
+    track: Option<PathBuf>,
+    queue_len: usize,
+    audio_device: Option<String>,
+}
+
+static CONTEXT: Mutex<CrashContext> = Mutex::new(CrashContext {
+    track: None,
+    queue_len: 0,
+    audio_device: None,
+});
+
+pub fn install() {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        default_hook(info);
+
+        let path = match write_report(info) {
+            Ok(path) => path,
+            Err(e) => {
+                error!("Failed to write crash report: {}", e);
+                return;
+            }
+        };
+        error!("Kanta crashed, report written to {}", path.display());
+    }));
+}
+
+pub fn show_pending_report() {
+    let take_pending = || -> anyhow::Result<Option<PathBuf>> {
+        let pending = crash_dir()?.join(PENDING_FILE);
+        if !pending.exists() {
+            return Ok(None);
+        }
+        let path = PathBuf::from(fs::read_to_string(&pending)?);
+        fs::remove_file(&pending)?;
+        Ok(Some(path).filter(|path| path.is_file()))
+    };
+    let path = match take_pending() {
+        Ok(Some(path)) => path,
+        Ok(None) => return,
+        Err(e) => {
+            error!("Failed to check for crash reports: {}", e);
+            return;
+        }
+    };
+
+    let result = MessageDialog::new()
+        .set_level(MessageLevel::Error)
+        .set_title(t!("crash-title"))
+        .set_description(t!("crash-description", path = path.display().to_string()))
+        .set_buttons(MessageButtons::YesNo)
+        .show();
+    if result == MessageDialogResult::Yes
+        && let Err(e) = file_manager::open(&path)
+    {
+        error!("Failed to open crash report: {}", e);
+    }
+}
+
+pub fn set_playback_context(track: Option<PathBuf>, queue_len: usize) {
+    let mut context = CONTEXT.lock().unwrap_or_else(|e| e.into_inner());
+    context.track = track;
+    context.queue_len = queue_len;
+}
+
+pub fn set_audio_device(audio_device: Option<String>) {
+    CONTEXT
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .audio_device = audio_device;
+}
+
+fn write_report(info: &PanicHookInfo) -> anyhow::Result<PathBuf> {
+    // The panicking thread may hold the lock, so never wait for it.
+    let context = match CONTEXT.try_lock() {
+        Ok(context) => Some(context),
+        Err(TryLockError::Poisoned(e)) => Some(e.into_inner()),
+        Err(TryLockError::WouldBlock) => None,
+    };
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+
+    let mut report = String::new();
+    writeln!(report, "Kanta {} crashed", env!("CARGO_PKG_VERSION"))?;
+    writeln!(report, "Time: {} (Unix)", timestamp)?;
+    writeln!(
+        report,
+        "OS: {} {}",
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    )?;
+    writeln!(report)?;
+    writeln!(report, "Panic: {}", info)?;
+    writeln!(report)?;
+    match context.as_deref() {
+        Some(context) => {
+            match &context.track {
+                Some(track) => writeln!(report, "Current track: {}", track.display())?,
+                None => writeln!(report, "Current track: none")?,
+            }
+            writeln!(report, "Queue length: {}", context.queue_len)?;
+            writeln!(
+                report,
+                "Audio device: {}",
+                context.audio_device.as_deref().unwrap_or("none")
+            )?;
+        }
+        None => writeln!(report, "Playback context: unavailable")?,
+    }
+    writeln!(report)?;
+    writeln!(report, "Backtrace:\n{}", Backtrace::force_capture())?;
+
+    let dir = crash_dir()?;
+    let path = dir.join(format!("crash-{}.txt", timestamp));
+    fs::write(&path, report)?;
+    fs::write(dir.join(PENDING_FILE), path.to_string_lossy().as_bytes())?;
+    Ok(path)
+}
+
+fn crash_dir() -> anyhow::Result<PathBuf> {
+    let dir = paths::app_data_dir()?.join("crashes");
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
@@ -0,0 +1,86 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use anyhow::bail;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    paths,
+    state::{self, Schema},
+};
+
+const SCHEMA: Schema = Schema {
+    migrations: &[state::unversioned],
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Cue {
+    pub name: String,
+    pub position: Duration,
+}
+
+pub struct CueLibrary {
+    path: PathBuf,
+    cues: HashMap<PathBuf, Vec<Cue>>,
+}
+
+impl CueLibrary {
+    pub fn open() -> anyhow::Result<CueLibrary> {
+        let path = paths::data_dir()?.join("cues.json");
+        let cues = SCHEMA.load(&path)?.unwrap_or_default();
+        Ok(CueLibrary { path, cues })
+    }
+
+    pub fn all(&self) -> &HashMap<PathBuf, Vec<Cue>> {
+        &self.cues
+    }
+
+    pub fn cues(&self, track: &Path) -> &[Cue] {
+        self.cues.get(track).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    pub fn add(&mut self, track: &Path, name: &str, position: Duration) -> anyhow::Result<()> {
+        let name = name.trim();
+        if name.is_empty() {
+            bail!("cue name cannot be empty");
+        }
+        let cues = self.cues.entry(track.to_path_buf()).or_default();
+        let index = cues.partition_point(|cue| cue.position <= position);
+        cues.insert(
+            index,
+            Cue {
+                name: name.to_string(),
+                position,
+            },
+        );
+        self.save()
+    }
+
+    pub fn remove(&mut self, track: &Path, index: usize) -> anyhow::Result<()> {
+        let Some(cues) = self.cues.get_mut(track) else {
+            return Ok(());
+        };
+        if index < cues.len() {
+            cues.remove(index);
+        }
+        if cues.is_empty() {
+            self.cues.remove(track);
+        }
+        self.save()
+    }
+
+    pub fn merge(
+        &mut self,
+        imported: impl IntoIterator<Item = (PathBuf, Vec<Cue>)>,
+    ) -> anyhow::Result<()> {
+        self.cues.extend(imported);
+        self.save()
+    }
+
+    fn save(&self) -> anyhow::Result<()> {
+        SCHEMA.save(&self.path, &self.cues)
+    }
+}
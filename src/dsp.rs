@@ -0,0 +1,801 @@
+use std::{
+    collections::VecDeque,
+    f32::consts::PI,
+    ops::RangeInclusive,
+    sync::{
+        atomic::{AtomicBool, AtomicI32, AtomicU32, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use rodio::{source::SeekError, ChannelCount, Sample, SampleRate, Source};
+use serde::{Deserialize, Serialize};
+
+const LIMITER_THRESHOLD: f32 = 0.97;
+const LIMITER_LOOKAHEAD: Duration = Duration::from_millis(5);
+const LIMITER_RELEASE: Duration = Duration::from_millis(80);
+const PITCH_SHIFT_WINDOW: Duration = Duration::from_millis(60);
+const EQ_PEAK_Q: f32 = 1.0;
+const VOCAL_REDUCTION_BASS_CUTOFF: f32 = 150.0;
+const TAP_FLUSH_LEN: usize = 512;
+const TAP_MAX_CAPTURED: usize = 1 << 16;
+
+pub const PITCH_SHIFT_RANGE: RangeInclusive<i32> = -6..=6;
+pub const SPEED_RANGE: RangeInclusive<f32> = 0.5..=2.0;
+pub const EQ_GAIN_RANGE: RangeInclusive<i32> = -12..=12;
+pub const EQ_BANDS: [(BandKind, f32); 3] = [
+    (BandKind::LowShelf, 200.0),
+    (BandKind::Peak, 1000.0),
+    (BandKind::HighShelf, 4000.0),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DspSettings {
+    pub speed: f32,
+    pub eq: [i32; EQ_BANDS.len()],
+}
+
+pub struct DspControls {
+    pitch_ratio: AtomicU32,
+    eq: [AtomicI32; EQ_BANDS.len()],
+    vocal_reduction: AtomicBool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BandKind {
+    LowShelf,
+    Peak,
+    HighShelf,
+}
+
+impl Default for DspSettings {
+    fn default() -> DspSettings {
+        DspSettings {
+            speed: 1.0,
+            eq: [0; EQ_BANDS.len()],
+        }
+    }
+}
+
+impl DspControls {
+    pub fn pitch_ratio(&self) -> f32 {
+        f32::from_bits(self.pitch_ratio.load(Ordering::Relaxed))
+    }
+
+    pub fn set_pitch_ratio(&self, ratio: f32) {
+        self.pitch_ratio.store(ratio.to_bits(), Ordering::Relaxed);
+    }
+
+    pub fn eq(&self) -> [i32; EQ_BANDS.len()] {
+        self.eq.each_ref().map(|gain| gain.load(Ordering::Relaxed))
+    }
+
+    pub fn set_eq(&self, eq: [i32; EQ_BANDS.len()]) {
+        for (gain, value) in self.eq.iter().zip(eq) {
+            gain.store(value, Ordering::Relaxed);
+        }
+    }
+
+    pub fn vocal_reduction(&self) -> bool {
+        self.vocal_reduction.load(Ordering::Relaxed)
+    }
+
+    pub fn set_vocal_reduction(&self, enabled: bool) {
+        self.vocal_reduction.store(enabled, Ordering::Relaxed);
+    }
+}
+
+impl Default for DspControls {
+    fn default() -> DspControls {
+        DspControls {
+            pitch_ratio: AtomicU32::new(1f32.to_bits()),
+            eq: Default::default(),
+            vocal_reduction: AtomicBool::new(false),
+        }
+    }
+}
+
+pub struct Limiter<S: Source> {
+    input: S,
+    delay: VecDeque<Sample>,
+    delay_len: usize,
+    required_gains: VecDeque<(usize, f32)>,
+    samples_read: usize,
+    samples_written: usize,
+    gain: f32,
+    attack_coeff: f32,
+    release_coeff: f32,
+}
+
+impl<S: Source> Limiter<S> {
+    pub fn new(input: S) -> Limiter<S> {
+        let samples_per_second = input.sample_rate() as f32 * input.channels() as f32;
+        let delay_len = (samples_per_second * LIMITER_LOOKAHEAD.as_secs_f32()).ceil() as usize;
+        let release_len = samples_per_second * LIMITER_RELEASE.as_secs_f32();
+
+        Limiter {
+            input,
+            delay: VecDeque::with_capacity(delay_len + 1),
+            delay_len,
+            required_gains: VecDeque::new(),
+            samples_read: 0,
+            samples_written: 0,
+            gain: 1.0,
+            attack_coeff: 1.0 - (-4.0 / delay_len.max(1) as f32).exp(),
+            release_coeff: 1.0 - (-1.0 / release_len.max(1.0)).exp(),
+        }
+    }
+
+    fn read_sample(&mut self) -> bool {
+        let Some(sample) = self.input.next() else {
+            return false;
+        };
+
+        let peak = sample.abs();
+        let required_gain = if peak > LIMITER_THRESHOLD {
+            LIMITER_THRESHOLD / peak
+        } else {
+            1.0
+        };
+
+        // Keep the gains needed within the lookahead window in ascending order so
+        // the front is always the strongest reduction still ahead of the output.
+        while self
+            .required_gains
+            .back()
+            .is_some_and(|&(_, gain)| gain >= required_gain)
+        {
+            self.required_gains.pop_back();
+        }
+        self.required_gains
+            .push_back((self.samples_read, required_gain));
+
+        self.delay.push_back(sample);
+        self.samples_read += 1;
+        true
+    }
+
+    fn reset(&mut self) {
+        self.delay.clear();
+        self.required_gains.clear();
+        self.samples_read = 0;
+        self.samples_written = 0;
+        self.gain = 1.0;
+    }
+}
+
+impl<S: Source> Iterator for Limiter<S> {
+    type Item = Sample;
+
+    fn next(&mut self) -> Option<Sample> {
+        while self.delay.len() <= self.delay_len && self.read_sample() {}
+
+        let sample = self.delay.pop_front()?;
+
+        while self
+            .required_gains
+            .front()
+            .is_some_and(|&(index, _)| index < self.samples_written)
+        {
+            self.required_gains.pop_front();
+        }
+        let target_gain = self
+            .required_gains
+            .front()
+            .map(|&(_, gain)| gain)
+            .unwrap_or(1.0);
+
+        let coeff = if target_gain < self.gain {
+            self.attack_coeff
+        } else {
+            self.release_coeff
+        };
+        self.gain += (target_gain - self.gain) * coeff;
+        self.samples_written += 1;
+
+        Some((sample * self.gain).clamp(-1.0, 1.0))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.input.size_hint();
+        (
+            lower + self.delay.len(),
+            upper.map(|upper| upper + self.delay.len()),
+        )
+    }
+}
+
+impl<S: Source> Source for Limiter<S> {
+    fn current_span_len(&self) -> Option<usize> {
+        self.input.current_span_len()
+    }
+
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    fn try_seek(&mut self, position: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(position)?;
+        self.reset();
+        Ok(())
+    }
+}
+
+pub struct PitchShifter<S: Source> {
+    input: S,
+    controls: Arc<DspControls>,
+    channels: usize,
+    window_len: f32,
+    history: Vec<Sample>,
+    history_len: usize,
+    frames_written: usize,
+    phase: f32,
+    frame: Vec<Sample>,
+    frame_pos: usize,
+}
+
+impl<S: Source> PitchShifter<S> {
+    pub fn new(input: S, controls: Arc<DspControls>) -> PitchShifter<S> {
+        let channels = input.channels() as usize;
+        let window_len = (input.sample_rate() as f32 * PITCH_SHIFT_WINDOW.as_secs_f32()).max(2.0);
+        let history_len = window_len.ceil() as usize + 2;
+
+        PitchShifter {
+            input,
+            controls,
+            channels,
+            window_len,
+            history: vec![0.0; history_len * channels],
+            history_len,
+            frames_written: 0,
+            phase: 0.0,
+            frame: Vec::with_capacity(channels),
+            frame_pos: 0,
+        }
+    }
+
+    fn read_frame(&mut self) -> bool {
+        self.frame.clear();
+        self.frame_pos = 0;
+        self.frame.extend(self.input.by_ref().take(self.channels));
+        if self.frame.len() < self.channels {
+            return !self.frame.is_empty();
+        }
+
+        let slot = self.frames_written % self.history_len * self.channels;
+        self.history[slot..slot + self.channels].copy_from_slice(&self.frame);
+        self.frames_written += 1;
+
+        let ratio = self.controls.pitch_ratio();
+        if ratio == 1.0 {
+            return true;
+        }
+
+        // Two read heads half a window apart sweep through the recent input at
+        // the shifted rate, each faded out while it wraps around.
+        self.phase = (self.phase + 1.0 - ratio).rem_euclid(self.window_len);
+        let delays = [
+            self.phase,
+            (self.phase + self.window_len / 2.0).rem_euclid(self.window_len),
+        ];
+        for channel in 0..self.channels {
+            self.frame[channel] = delays
+                .iter()
+                .map(|&delay| {
+                    let weight = (PI * delay / self.window_len).sin().powi(2);
+                    weight * self.delayed(channel, delay)
+                })
+                .sum();
+        }
+        true
+    }
+
+    fn delayed(&self, channel: usize, delay: f32) -> Sample {
+        let newest = (self.frames_written - 1) % self.history_len;
+        let frames_back = delay.floor() as usize;
+        let sample = |frames_back: usize| {
+            let slot = (newest + self.history_len - frames_back) % self.history_len;
+            self.history[slot * self.channels + channel]
+        };
+        let (a, b) = (sample(frames_back), sample(frames_back + 1));
+        a + (b - a) * (delay - frames_back as f32)
+    }
+
+    fn reset(&mut self) {
+        self.history.fill(0.0);
+        self.frames_written = 0;
+        self.phase = 0.0;
+        self.frame.clear();
+        self.frame_pos = 0;
+    }
+}
+
+impl<S: Source> Iterator for PitchShifter<S> {
+    type Item = Sample;
+
+    fn next(&mut self) -> Option<Sample> {
+        if self.frame_pos == self.frame.len() && !self.read_frame() {
+            return None;
+        }
+        let sample = self.frame[self.frame_pos];
+        self.frame_pos += 1;
+        Some(sample)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let buffered = self.frame.len() - self.frame_pos;
+        let (lower, upper) = self.input.size_hint();
+        (lower + buffered, upper.map(|upper| upper + buffered))
+    }
+}
+
+impl<S: Source> Source for PitchShifter<S> {
+    fn current_span_len(&self) -> Option<usize> {
+        self.input.current_span_len()
+    }
+
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    fn try_seek(&mut self, position: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(position)?;
+        self.reset();
+        Ok(())
+    }
+}
+
+pub struct VocalReducer<S: Source> {
+    input: S,
+    controls: Arc<DspControls>,
+    channels: usize,
+    channel: usize,
+    bass: f32,
+    pending: Option<Sample>,
+}
+
+impl<S: Source> VocalReducer<S> {
+    pub fn new(input: S, controls: Arc<DspControls>) -> VocalReducer<S> {
+        let channels = input.channels() as usize;
+        VocalReducer {
+            input,
+            controls,
+            channels,
+            channel: 0,
+            bass: 0.0,
+            pending: None,
+        }
+    }
+}
+
+impl<S: Source> Iterator for VocalReducer<S> {
+    type Item = Sample;
+
+    fn next(&mut self) -> Option<Sample> {
+        if let Some(right) = self.pending.take() {
+            return Some(right);
+        }
+        let left = self.input.next()?;
+        let channel = self.channel;
+        self.channel = (self.channel + 1) % self.channels.max(1);
+        if channel != 0 || self.channels != 2 || !self.controls.vocal_reduction() {
+            return Some(left);
+        }
+        let Some(right) = self.input.next() else {
+            return Some(left);
+        };
+        self.channel = 0;
+
+        // Vocals usually sit in the center, so drop the mid signal and keep
+        // only its lows to hold on to bass and kick drum.
+        let alpha =
+            1.0 - (-2.0 * PI * VOCAL_REDUCTION_BASS_CUTOFF / self.input.sample_rate() as f32).exp();
+        let (mid, side) = ((left + right) / 2.0, (left - right) / 2.0);
+        self.bass += alpha * (mid - self.bass);
+        self.pending = Some(self.bass - side);
+        Some(self.bass + side)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let buffered = usize::from(self.pending.is_some());
+        let (lower, upper) = self.input.size_hint();
+        (lower + buffered, upper.map(|upper| upper + buffered))
+    }
+}
+
+impl<S: Source> Source for VocalReducer<S> {
+    fn current_span_len(&self) -> Option<usize> {
+        self.input.current_span_len()
+    }
+
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    fn try_seek(&mut self, position: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(position)?;
+        self.channel = 0;
+        self.bass = 0.0;
+        self.pending = None;
+        Ok(())
+    }
+}
+
+pub struct Equalizer<S: Source> {
+    input: S,
+    controls: Arc<DspControls>,
+    gains: [i32; EQ_BANDS.len()],
+    coeffs: [[f32; 5]; EQ_BANDS.len()],
+    states: Vec<[f32; 4]>,
+    channels: usize,
+    channel: usize,
+}
+
+impl<S: Source> Equalizer<S> {
+    pub fn new(input: S, controls: Arc<DspControls>) -> Equalizer<S> {
+        let channels = input.channels() as usize;
+        let mut equalizer = Equalizer {
+            input,
+            controls,
+            gains: [0; EQ_BANDS.len()],
+            coeffs: [[1.0, 0.0, 0.0, 0.0, 0.0]; EQ_BANDS.len()],
+            states: vec![[0.0; 4]; channels * EQ_BANDS.len()],
+            channels,
+            channel: 0,
+        };
+        equalizer.update_coeffs();
+        equalizer
+    }
+
+    fn update_coeffs(&mut self) {
+        self.gains = self.controls.eq();
+        let sample_rate = self.input.sample_rate() as f32;
+        for ((coeffs, (kind, frequency)), gain) in
+            self.coeffs.iter_mut().zip(EQ_BANDS).zip(self.gains)
+        {
+            *coeffs = biquad(
+                kind,
+                frequency.min(sample_rate * 0.45),
+                gain as f32,
+                sample_rate,
+            );
+        }
+    }
+}
+
+impl<S: Source> Iterator for Equalizer<S> {
+    type Item = Sample;
+
+    fn next(&mut self) -> Option<Sample> {
+        let sample = self.input.next()?;
+        if self.channel == 0 && self.controls.eq() != self.gains {
+            self.update_coeffs();
+        }
+        let channel = self.channel;
+        self.channel = (self.channel + 1) % self.channels.max(1);
+        if self.gains.iter().all(|gain| *gain == 0) {
+            return Some(sample);
+        }
+
+        let states = &mut self.states[channel * EQ_BANDS.len()..][..EQ_BANDS.len()];
+        let output = self.coeffs.iter().zip(states).fold(
+            sample,
+            |x, ([b0, b1, b2, a1, a2], [x1, x2, y1, y2])| {
+                let y = b0 * x + b1 * *x1 + b2 * *x2 - a1 * *y1 - a2 * *y2;
+                (*x2, *x1, *y2, *y1) = (*x1, x, *y1, y);
+                y
+            },
+        );
+        Some(output)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.input.size_hint()
+    }
+}
+
+impl<S: Source> Source for Equalizer<S> {
+    fn current_span_len(&self) -> Option<usize> {
+        self.input.current_span_len()
+    }
+
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    fn try_seek(&mut self, position: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(position)?;
+        self.states.fill([0.0; 4]);
+        self.channel = 0;
+        Ok(())
+    }
+}
+
+fn biquad(kind: BandKind, frequency: f32, gain_db: f32, sample_rate: f32) -> [f32; 5] {
+    let a = 10f32.powf(gain_db / 40.0);
+    let w0 = 2.0 * PI * frequency / sample_rate;
+    let (sin, cos) = w0.sin_cos();
+    let (b0, b1, b2, a0, a1, a2) = match kind {
+        BandKind::Peak => {
+            let alpha = sin / (2.0 * EQ_PEAK_Q);
+            (
+                1.0 + alpha * a,
+                -2.0 * cos,
+                1.0 - alpha * a,
+                1.0 + alpha / a,
+                -2.0 * cos,
+                1.0 - alpha / a,
+            )
+        }
+        BandKind::LowShelf | BandKind::HighShelf => {
+            let alpha = sin / 2.0 * 2f32.sqrt();
+            let root = 2.0 * a.sqrt() * alpha;
+            let sign = if kind == BandKind::LowShelf {
+                1.0
+            } else {
+                -1.0
+            };
+            (
+                a * ((a + 1.0) - sign * (a - 1.0) * cos + root),
+                sign * 2.0 * a * ((a - 1.0) - sign * (a + 1.0) * cos),
+                a * ((a + 1.0) - sign * (a - 1.0) * cos - root),
+                (a + 1.0) + sign * (a - 1.0) * cos + root,
+                -sign * 2.0 * ((a - 1.0) + sign * (a + 1.0) * cos),
+                (a + 1.0) + sign * (a - 1.0) * cos - root,
+            )
+        }
+    };
+    [b0 / a0, b1 / a0, b2 / a0, a1 / a0, a2 / a0]
+}
+
+#[derive(Default)]
+pub struct AudioTap {
+    peaks: [AtomicU32; 2],
+    capture: AtomicBool,
+    captured: Mutex<(Vec<Sample>, SampleRate)>,
+}
+
+pub struct Tap<S: Source> {
+    input: S,
+    tap: Arc<AudioTap>,
+    channels: usize,
+    channel: usize,
+    peaks: [f32; 2],
+    frame_sum: f32,
+    captured: Vec<Sample>,
+    samples_since_flush: usize,
+}
+
+impl AudioTap {
+    pub fn take(&self) -> [f32; 2] {
+        self.peaks
+            .each_ref()
+            .map(|peak| f32::from_bits(peak.swap(0, Ordering::Relaxed)))
+    }
+
+    pub fn set_capture(&self, capture: bool) {
+        self.capture.store(capture, Ordering::Relaxed);
+    }
+
+    pub fn take_captured(&self) -> (Vec<Sample>, SampleRate) {
+        let mut captured = self.captured.lock().unwrap_or_else(|e| e.into_inner());
+        (std::mem::take(&mut captured.0), captured.1)
+    }
+}
+
+impl<S: Source> Tap<S> {
+    pub fn new(input: S, tap: Arc<AudioTap>) -> Tap<S> {
+        let channels = input.channels() as usize;
+        Tap {
+            input,
+            tap,
+            channels,
+            channel: 0,
+            peaks: [0.0; 2],
+            frame_sum: 0.0,
+            captured: vec![],
+            samples_since_flush: 0,
+        }
+    }
+
+    fn flush(&mut self) {
+        for (peak, level) in self.tap.peaks.iter().zip(&mut self.peaks) {
+            peak.fetch_max(level.to_bits(), Ordering::Relaxed);
+            *level = 0.0;
+        }
+        if !self.captured.is_empty() {
+            let mut captured = self.tap.captured.lock().unwrap_or_else(|e| e.into_inner());
+            let sample_rate = self.input.sample_rate();
+            if captured.1 != sample_rate {
+                *captured = (vec![], sample_rate);
+            }
+            captured.0.append(&mut self.captured);
+            let excess = captured.0.len().saturating_sub(TAP_MAX_CAPTURED);
+            captured.0.drain(..excess);
+        }
+        self.samples_since_flush = 0;
+    }
+}
+
+impl<S: Source> Iterator for Tap<S> {
+    type Item = Sample;
+
+    fn next(&mut self) -> Option<Sample> {
+        let Some(sample) = self.input.next() else {
+            self.flush();
+            return None;
+        };
+
+        let level = sample.abs();
+        if self.channels == 1 {
+            self.peaks = self.peaks.map(|peak| peak.max(level));
+        } else {
+            let peak = &mut self.peaks[self.channel % 2];
+            *peak = peak.max(level);
+        }
+        self.channel = (self.channel + 1) % self.channels.max(1);
+
+        self.frame_sum += sample;
+        if self.channel == 0 {
+            if self.tap.capture.load(Ordering::Relaxed) {
+                self.captured
+                    .push(self.frame_sum / self.channels.max(1) as f32);
+            }
+            self.frame_sum = 0.0;
+        }
+
+        self.samples_since_flush += 1;
+        if self.samples_since_flush >= TAP_FLUSH_LEN {
+            self.flush();
+        }
+        Some(sample)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.input.size_hint()
+    }
+}
+
+impl<S: Source> Source for Tap<S> {
+    fn current_span_len(&self) -> Option<usize> {
+        self.input.current_span_len()
+    }
+
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    fn try_seek(&mut self, position: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(position)?;
+        self.channel = 0;
+        self.frame_sum = 0.0;
+        self.captured.clear();
+        Ok(())
+    }
+}
+
+pub struct Trim<S: Source> {
+    input: S,
+    start: Duration,
+    len: Option<Duration>,
+    remaining: usize,
+}
+
+impl<S: Source> Trim<S> {
+    pub fn new(mut input: S, start: Duration, len: Option<Duration>) -> Result<Trim<S>, SeekError> {
+        if !start.is_zero() {
+            input.try_seek(start)?;
+        }
+        let mut trim = Trim {
+            input,
+            start,
+            len,
+            remaining: usize::MAX,
+        };
+        trim.remaining = trim.samples_after(Duration::ZERO);
+        Ok(trim)
+    }
+
+    fn samples_after(&self, position: Duration) -> usize {
+        let Some(len) = self.len else {
+            return usize::MAX;
+        };
+        let frames = (len.saturating_sub(position).as_secs_f64()
+            * f64::from(self.input.sample_rate()))
+        .round();
+        frames as usize * usize::from(self.input.channels())
+    }
+}
+
+impl<S: Source> Iterator for Trim<S> {
+    type Item = Sample;
+
+    fn next(&mut self) -> Option<Sample> {
+        if self.remaining == 0 {
+            return None;
+        }
+        if self.len.is_some() {
+            self.remaining -= 1;
+        }
+        self.input.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.input.size_hint();
+        (
+            lower.min(self.remaining),
+            Some(upper.map_or(self.remaining, |upper| upper.min(self.remaining))),
+        )
+    }
+}
+
+impl<S: Source> Source for Trim<S> {
+    fn current_span_len(&self) -> Option<usize> {
+        match self.input.current_span_len() {
+            Some(len) => Some(len.min(self.remaining)),
+            None if self.len.is_some() => Some(self.remaining),
+            None => None,
+        }
+    }
+
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.len.or_else(|| self.input.total_duration())
+    }
+
+    fn try_seek(&mut self, position: Duration) -> Result<(), SeekError> {
+        let position = self.len.map_or(position, |len| position.min(len));
+        self.input.try_seek(self.start + position)?;
+        self.remaining = self.samples_after(position);
+        Ok(())
+    }
+}
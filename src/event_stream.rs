@@ -0,0 +1,329 @@
+use std::{
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{Ipv4Addr, Shutdown, TcpListener, TcpStream},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        mpsc::{self, Sender, SyncSender, TrySendError},
+        Arc, Mutex,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use anyhow::bail;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use sha1_smol::Sha1;
+use tracing::{debug, error, info};
+
+use crate::hooks::Payload;
+
+const WEBSOCKET_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+const TIMEOUT: Duration = Duration::from_secs(2);
+const MAX_HEADER_LEN: u64 = 8192;
+const CLIENT_BACKLOG: usize = 64;
+const MAX_CONNECTIONS: usize = 16;
+const MAX_CONTROL_LEN: u64 = 125;
+const OPCODE_TEXT: u8 = 0x1;
+const OPCODE_CLOSE: u8 = 0x8;
+const OPCODE_PING: u8 = 0x9;
+const OPCODE_PONG: u8 = 0xa;
+const LOCAL_HOSTS: [&str; 3] = ["localhost", "127.0.0.1", "[::1]"];
+
+pub struct EventStream {
+    port: u16,
+    sender: Sender<String>,
+    stopped: Arc<AtomicBool>,
+    accept: Option<JoinHandle<()>>,
+}
+
+impl EventStream {
+    pub fn start(port: u16) -> anyhow::Result<EventStream> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))?;
+        let clients = Arc::new(Mutex::new(Vec::<SyncSender<Arc<[u8]>>>::new()));
+        let stopped = Arc::new(AtomicBool::new(false));
+        let connections = Arc::new(AtomicUsize::new(0));
+        let (sender, receiver) = mpsc::channel::<String>();
+
+        let accepted = clients.clone();
+        let accept_stopped = stopped.clone();
+        let accept = thread::spawn(move || {
+            for stream in listener.incoming() {
+                if accept_stopped.load(Ordering::Relaxed) {
+                    break;
+                }
+                let Ok(stream) = stream else {
+                    continue;
+                };
+                if connections.fetch_add(1, Ordering::Relaxed) >= MAX_CONNECTIONS {
+                    connections.fetch_sub(1, Ordering::Relaxed);
+                    debug!("Dropping an event stream connection, too many are open");
+                    continue;
+                }
+                let accepted = accepted.clone();
+                let connections = connections.clone();
+                thread::spawn(move || {
+                    match handshake(stream) {
+                        Ok(stream) => serve(stream, &accepted),
+                        Err(e) => debug!("Rejected event stream connection: {}", e),
+                    }
+                    connections.fetch_sub(1, Ordering::Relaxed);
+                });
+            }
+        });
+
+        thread::spawn(move || {
+            for message in receiver {
+                let frame: Arc<[u8]> = frame(OPCODE_TEXT, message.as_bytes()).into();
+                clients
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .retain(|client| match client.try_send(frame.clone()) {
+                        Ok(()) => true,
+                        Err(TrySendError::Full(_)) => {
+                            debug!("Dropping an event stream client that fell behind");
+                            false
+                        }
+                        Err(TrySendError::Disconnected(_)) => false,
+                    });
+            }
+        });
+
+        info!("Broadcasting player events on ws://127.0.0.1:{}", port);
+        Ok(EventStream {
+            port,
+            sender,
+            stopped,
+            accept: Some(accept),
+        })
+    }
+
+    pub fn broadcast(&self, payload: &Payload) {
+        match serde_json::to_string(payload) {
+            Ok(message) => {
+                self.sender.send(message).ok();
+            }
+            Err(e) => error!("Failed to encode player event: {}", e),
+        }
+    }
+}
+
+impl Drop for EventStream {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::Relaxed);
+        TcpStream::connect((Ipv4Addr::LOCALHOST, self.port)).ok();
+        if let Some(accept) = self.accept.take() {
+            accept.join().ok();
+        }
+    }
+}
+
+fn handshake(stream: TcpStream) -> anyhow::Result<TcpStream> {
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+    let mut reader = BufReader::new(&stream).take(MAX_HEADER_LEN);
+    let mut key = None;
+    let mut upgrade = false;
+    let mut origin = None;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            break;
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            let (name, value) = (name.trim(), value.trim());
+            if name.eq_ignore_ascii_case("sec-websocket-key") {
+                key = Some(value.to_string());
+            } else if name.eq_ignore_ascii_case("upgrade") {
+                upgrade = value.eq_ignore_ascii_case("websocket");
+            } else if name.eq_ignore_ascii_case("origin") {
+                origin = Some(value.to_string());
+            }
+        }
+    }
+
+    if let Some(origin) = origin.filter(|origin| !is_local_origin(origin)) {
+        (&stream).write_all(b"HTTP/1.1 403 Forbidden\r\nContent-Length: 0\r\n\r\n")?;
+        bail!("origin {} is not allowed", origin);
+    }
+
+    let Some(key) = key.filter(|_| upgrade) else {
+        (&stream).write_all(
+            b"HTTP/1.1 426 Upgrade Required\r\nUpgrade: websocket\r\nContent-Length: 0\r\n\r\n",
+        )?;
+        bail!("not a WebSocket request");
+    };
+    let accept = accept_key(&key);
+    (&stream).write_all(
+        format!(
+            "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n\r\n",
+            accept
+        )
+        .as_bytes(),
+    )?;
+    stream.set_read_timeout(None)?;
+    Ok(stream)
+}
+
+fn serve(mut stream: TcpStream, clients: &Mutex<Vec<SyncSender<Arc<[u8]>>>>) {
+    let reader = match stream.try_clone() {
+        Ok(reader) => reader,
+        Err(e) => {
+            debug!("Failed to read from an event stream client: {}", e);
+            return;
+        }
+    };
+    debug!("Event stream client connected");
+    let (sender, frames) = mpsc::sync_channel::<Arc<[u8]>>(CLIENT_BACKLOG);
+    clients
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .push(sender.clone());
+    thread::spawn(move || answer_client(reader, sender));
+
+    for frame in frames {
+        if stream.write_all(&frame).is_err() || frame[0] & 0x0f == OPCODE_CLOSE {
+            break;
+        }
+    }
+    stream.shutdown(Shutdown::Both).ok();
+    debug!("Event stream client disconnected");
+}
+
+fn answer_client(mut reader: TcpStream, sender: SyncSender<Arc<[u8]>>) {
+    loop {
+        let (opcode, payload) = match read_frame(&mut reader) {
+            Ok(read) => read,
+            Err(e) => {
+                debug!("Closing an event stream connection: {}", e);
+                sender.send(frame(OPCODE_CLOSE, &[]).into()).ok();
+                return;
+            }
+        };
+        let reply = match opcode {
+            OPCODE_PING => frame(OPCODE_PONG, &payload),
+            OPCODE_CLOSE => {
+                sender.send(frame(OPCODE_CLOSE, &payload).into()).ok();
+                return;
+            }
+            _ => continue,
+        };
+        if sender.send(reply.into()).is_err() {
+            return;
+        }
+    }
+}
+
+fn read_frame(reader: &mut impl Read) -> anyhow::Result<(u8, Vec<u8>)> {
+    let mut header = [0; 2];
+    reader.read_exact(&mut header)?;
+    let opcode = header[0] & 0x0f;
+    if header[1] & 0x80 == 0 {
+        bail!("client frames must be masked");
+    }
+    let len = match header[1] & 0x7f {
+        126 => {
+            let mut len = [0; 2];
+            reader.read_exact(&mut len)?;
+            u64::from(u16::from_be_bytes(len))
+        }
+        127 => {
+            let mut len = [0; 8];
+            reader.read_exact(&mut len)?;
+            u64::from_be_bytes(len)
+        }
+        len => u64::from(len),
+    };
+    let mut mask = [0; 4];
+    reader.read_exact(&mut mask)?;
+
+    if opcode & 0x08 == 0 {
+        io::copy(&mut reader.take(len), &mut io::sink())?;
+        return Ok((opcode, Vec::new()));
+    }
+    if len > MAX_CONTROL_LEN {
+        bail!("control frame is too long");
+    }
+    let mut payload = vec![0; len as usize];
+    reader.read_exact(&mut payload)?;
+    for (i, byte) in payload.iter_mut().enumerate() {
+        *byte ^= mask[i % 4];
+    }
+    Ok((opcode, payload))
+}
+
+fn is_local_origin(origin: &str) -> bool {
+    if origin == "null" || origin.starts_with("file://") {
+        return true;
+    }
+    let Some((_, authority)) = origin.split_once("://") else {
+        return false;
+    };
+    let host = match authority.rsplit_once(':') {
+        Some((host, port)) if !host.is_empty() && port.bytes().all(|b| b.is_ascii_digit()) => host,
+        _ => authority,
+    };
+    LOCAL_HOSTS
+        .iter()
+        .any(|local| host.eq_ignore_ascii_case(local))
+}
+
+fn accept_key(key: &str) -> String {
+    let digest = Sha1::from(format!("{}{}", key, WEBSOCKET_GUID)).digest();
+    STANDARD.encode(digest.bytes())
+}
+
+fn frame(opcode: u8, payload: &[u8]) -> Vec<u8> {
+    let len = payload.len();
+    let mut frame = vec![0x80 | opcode];
+    if len < 126 {
+        frame.push(len as u8);
+    } else if len <= u16::MAX as usize {
+        frame.push(126);
+        frame.extend((len as u16).to_be_bytes());
+    } else {
+        frame.push(127);
+        frame.extend((len as u64).to_be_bytes());
+    }
+    frame.extend(payload);
+    frame
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accept_key_matches_rfc_6455_example() {
+        assert_eq!(
+            accept_key("dGhlIHNhbXBsZSBub25jZQ=="),
+            "s3pPLMBiTxaQ9kYGzzhZRbK+xOo="
+        );
+    }
+
+    #[test]
+    fn read_frame_unmasks_control_frames() {
+        let ping = [
+            0x89, 0x85, 0x37, 0xfa, 0x21, 0x3d, 0x7f, 0x9f, 0x4d, 0x51, 0x58,
+        ];
+        let (opcode, payload) = read_frame(&mut &ping[..]).unwrap();
+        assert_eq!(opcode, OPCODE_PING);
+        assert_eq!(payload, b"Hello");
+
+        let unmasked = [0x89, 0x05, b'H', b'e', b'l', b'l', b'o'];
+        assert!(read_frame(&mut &unmasked[..]).is_err());
+    }
+
+    #[test]
+    fn only_local_origins_are_allowed() {
+        assert!(is_local_origin("http://localhost:8080"));
+        assert!(is_local_origin("http://127.0.0.1"));
+        assert!(is_local_origin("http://[::1]:3000"));
+        assert!(is_local_origin("null"));
+        assert!(!is_local_origin("https://example.com"));
+        assert!(!is_local_origin("http://localhost.example.com"));
+    }
+}
@@ -0,0 +1,66 @@
+use std::path::Path;
+
+pub fn is_excluded(patterns: &[String], root: &Path, path: &Path, is_dir: bool) -> bool {
+    let Ok(relative) = path.strip_prefix(root) else {
+        return false;
+    };
+    let relative = relative
+        .to_string_lossy()
+        .replace('\\', "/")
+        .to_lowercase()
+        .chars()
+        .collect::<Vec<_>>();
+    if relative.is_empty() {
+        return false;
+    }
+    let name = match relative.iter().rposition(|c| *c == '/') {
+        Some(slash) => &relative[slash + 1..],
+        None => &relative[..],
+    };
+
+    patterns.iter().any(|pattern| {
+        let pattern = pattern.trim();
+        if Path::new(pattern).is_absolute() {
+            return path.starts_with(pattern);
+        }
+        let pattern = pattern.to_lowercase().chars().collect::<Vec<_>>();
+        match pattern.iter().position(|c| *c == '/') {
+            Some(_) => {
+                matches(&pattern, &relative)
+                    || (is_dir && matches(&pattern, &[&relative[..], &['/']].concat()))
+            }
+            None => matches(&pattern, name),
+        }
+    }) || path
+        .parent()
+        .filter(|parent| *parent != root)
+        .is_some_and(|parent| is_excluded(patterns, root, parent, true))
+}
+
+fn matches(pattern: &[char], text: &[char]) -> bool {
+    match pattern {
+        [] => text.is_empty(),
+        ['*', '*', '/', rest @ ..] => {
+            matches(rest, text)
+                || (0..text.len()).any(|i| text[i] == '/' && matches(rest, &text[i + 1..]))
+        }
+        ['*', '*', rest @ ..] => (0..=text.len()).any(|i| matches(rest, &text[i..])),
+        ['*', rest @ ..] => {
+            for i in 0..=text.len() {
+                if matches(rest, &text[i..]) {
+                    return true;
+                }
+                if text.get(i) == Some(&'/') {
+                    break;
+                }
+            }
+            false
+        }
+        ['?', rest @ ..] => text
+            .split_first()
+            .is_some_and(|(c, text)| *c != '/' && matches(rest, text)),
+        [c, rest @ ..] => text
+            .split_first()
+            .is_some_and(|(t, text)| t == c && matches(rest, text)),
+    }
+}
@@ -0,0 +1,85 @@
+#[cfg(not(target_os = "macos"))]
+use std::process::Command;
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+const MIME_TYPES: [&str; 8] = [
+    "audio/mpeg",
+    "audio/flac",
+    "audio/x-flac",
+    "audio/ogg",
+    "audio/mp4",
+    "audio/x-m4a",
+    "audio/x-mpegurl",
+    "application/vnd.apple.mpegurl",
+];
+
+#[cfg(target_os = "windows")]
+const EXTENSIONS: [&str; 5] = ["mp3", "flac", "ogg", "m4a", "m3u8"];
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub fn register() -> anyhow::Result<()> {
+    let exe = std::env::current_exe()?;
+    let applications = dirs::data_dir()
+        .ok_or_else(|| anyhow::anyhow!("no data directory available"))?
+        .join("applications");
+    std::fs::create_dir_all(&applications)?;
+
+    let desktop_entry = format!(
+        "[Desktop Entry]\n\
+         Type=Application\n\
+         Name=Kanta\n\
+         Exec=\"{}\" %F\n\
+         Terminal=false\n\
+         Categories=AudioVideo;Audio;Player;\n\
+         MimeType={};\n",
+        exe.display().to_string().replace('"', "\\\""),
+        MIME_TYPES.join(";")
+    );
+    std::fs::write(applications.join("kanta.desktop"), desktop_entry)?;
+
+    let status = Command::new("xdg-mime")
+        .arg("default")
+        .arg("kanta.desktop")
+        .args(MIME_TYPES)
+        .status()?;
+    if !status.success() {
+        anyhow::bail!("xdg-mime exited with {}", status);
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+pub fn register() -> anyhow::Result<()> {
+    let exe = std::env::current_exe()?;
+
+    let reg_add = |key: String, args: &[&str]| -> anyhow::Result<()> {
+        let status = Command::new("reg")
+            .arg("add")
+            .arg(key)
+            .args(args)
+            .arg("/f")
+            .status()?;
+        if !status.success() {
+            anyhow::bail!("reg exited with {}", status);
+        }
+        Ok(())
+    };
+
+    let command = format!("\"{}\" \"%1\"", exe.display());
+    reg_add(
+        r"HKCU\Software\Classes\Kanta.File\shell\open\command".into(),
+        &["/ve", "/d", &command],
+    )?;
+    for extension in EXTENSIONS {
+        reg_add(
+            format!(r"HKCU\Software\Classes\.{}\OpenWithProgids", extension),
+            &["/v", "Kanta.File", "/t", "REG_NONE"],
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+pub fn register() -> anyhow::Result<()> {
+    anyhow::bail!("file types are registered by the app bundle on macOS")
+}
@@ -0,0 +1,36 @@
+use std::{path::Path, process::Command};
+
+pub fn show_in_file_manager(path: &Path) -> anyhow::Result<()> {
+    #[cfg(target_os = "windows")]
+    {
+        let mut select = std::ffi::OsString::from("/select,");
+        select.push(path);
+        Command::new("explorer").arg(select).spawn()?;
+    }
+
+    #[cfg(target_os = "macos")]
+    Command::new("open").arg("-R").arg(path).spawn()?;
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        let folder = path
+            .parent()
+            .ok_or_else(|| anyhow::anyhow!("track has no containing folder"))?;
+        Command::new("xdg-open").arg(folder).spawn()?;
+    }
+
+    Ok(())
+}
+
+pub fn open(path: &Path) -> anyhow::Result<()> {
+    #[cfg(target_os = "windows")]
+    Command::new("explorer").arg(path).spawn()?;
+
+    #[cfg(target_os = "macos")]
+    Command::new("open").arg(path).spawn()?;
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    Command::new("xdg-open").arg(path).spawn()?;
+
+    Ok(())
+}
@@ -0,0 +1,286 @@
+use iced::{
+    advanced::{
+        layout, mouse, overlay,
+        renderer::{self, Quad, Renderer as _},
+        widget::{operation, tree, Operation, Tree},
+        Clipboard, Layout, Shell, Widget,
+    },
+    keyboard::{self, key::Named, Key},
+    touch, Border, Color, Element, Event, Length, Rectangle, Renderer, Size, Theme, Vector,
+};
+
+const OUTLINE_WIDTH: f32 = 2.0;
+
+pub struct Focus<'a, Message> {
+    content: Element<'a, Message>,
+}
+
+#[derive(Default)]
+struct State {
+    is_focused: bool,
+    modifiers: keyboard::Modifiers,
+}
+
+pub fn focusable<'a, Message>(content: impl Into<Element<'a, Message>>) -> Focus<'a, Message> {
+    Focus {
+        content: content.into(),
+    }
+}
+
+impl operation::Focusable for State {
+    fn is_focused(&self) -> bool {
+        self.is_focused
+    }
+
+    fn focus(&mut self) {
+        self.is_focused = true;
+    }
+
+    fn unfocus(&mut self) {
+        self.is_focused = false;
+    }
+}
+
+impl<Message> Focus<'_, Message> {
+    #[allow(clippy::too_many_arguments)]
+    fn update_content(
+        &mut self,
+        tree: &mut Tree,
+        event: Event,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+        viewport: &Rectangle,
+    ) {
+        let cursor = mouse::Cursor::Available(layout.bounds().center());
+        self.content.as_widget_mut().update(
+            &mut tree.children[0],
+            &event,
+            layout,
+            cursor,
+            renderer,
+            clipboard,
+            shell,
+            viewport,
+        );
+    }
+}
+
+impl<Message> Widget<Message, Theme, Renderer> for Focus<'_, Message> {
+    fn tag(&self) -> tree::Tag {
+        tree::Tag::of::<State>()
+    }
+
+    fn state(&self) -> tree::State {
+        tree::State::new(State::default())
+    }
+
+    fn children(&self) -> Vec<Tree> {
+        vec![Tree::new(&self.content)]
+    }
+
+    fn diff(&self, tree: &mut Tree) {
+        tree.diff_children(std::slice::from_ref(&self.content));
+    }
+
+    fn size(&self) -> Size<Length> {
+        self.content.as_widget().size()
+    }
+
+    fn size_hint(&self) -> Size<Length> {
+        self.content.as_widget().size_hint()
+    }
+
+    fn layout(
+        &mut self,
+        tree: &mut Tree,
+        renderer: &Renderer,
+        limits: &layout::Limits,
+    ) -> layout::Node {
+        self.content
+            .as_widget_mut()
+            .layout(&mut tree.children[0], renderer, limits)
+    }
+
+    fn operate(
+        &mut self,
+        tree: &mut Tree,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+        operation: &mut dyn Operation,
+    ) {
+        operation.focusable(None, layout.bounds(), tree.state.downcast_mut::<State>());
+        self.content
+            .as_widget_mut()
+            .operate(&mut tree.children[0], layout, renderer, operation);
+    }
+
+    fn update(
+        &mut self,
+        tree: &mut Tree,
+        event: &Event,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        renderer: &Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+        viewport: &Rectangle,
+    ) {
+        let state = tree.state.downcast_mut::<State>();
+        let is_focused = state.is_focused;
+        let modifiers = state.modifiers;
+        match event {
+            Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)) => {
+                state.modifiers = *modifiers;
+            }
+            Event::Mouse(mouse::Event::ButtonPressed(_))
+            | Event::Touch(touch::Event::FingerPressed { .. }) => {
+                state.is_focused = false;
+            }
+            Event::Keyboard(keyboard::Event::KeyPressed {
+                key: Key::Named(Named::Enter | Named::Space),
+                ..
+            }) if is_focused => {
+                for event in [
+                    mouse::Event::ButtonPressed(mouse::Button::Left),
+                    mouse::Event::ButtonReleased(mouse::Button::Left),
+                ] {
+                    self.update_content(
+                        tree,
+                        Event::Mouse(event),
+                        layout,
+                        renderer,
+                        clipboard,
+                        shell,
+                        viewport,
+                    );
+                }
+                shell.capture_event();
+                return;
+            }
+            Event::Keyboard(keyboard::Event::KeyPressed {
+                key: Key::Named(key @ (Named::ArrowUp | Named::ArrowDown)),
+                ..
+            }) if is_focused => {
+                // Sliders step with the arrow keys while hovered, pick lists cycle through
+                // their options when scrolled with the command modifier held.
+                self.update_content(
+                    tree,
+                    event.clone(),
+                    layout,
+                    renderer,
+                    clipboard,
+                    shell,
+                    viewport,
+                );
+                if !shell.is_event_captured() {
+                    let y = if *key == Named::ArrowUp { 1.0 } else { -1.0 };
+                    for event in [
+                        Event::Keyboard(keyboard::Event::ModifiersChanged(
+                            keyboard::Modifiers::COMMAND,
+                        )),
+                        Event::Mouse(mouse::Event::WheelScrolled {
+                            delta: mouse::ScrollDelta::Lines { x: 0.0, y },
+                        }),
+                        Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)),
+                    ] {
+                        self.update_content(
+                            tree, event, layout, renderer, clipboard, shell, viewport,
+                        );
+                    }
+                }
+                shell.capture_event();
+                return;
+            }
+            _ => {}
+        }
+
+        self.content.as_widget_mut().update(
+            &mut tree.children[0],
+            event,
+            layout,
+            cursor,
+            renderer,
+            clipboard,
+            shell,
+            viewport,
+        );
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+        renderer: &Renderer,
+    ) -> mouse::Interaction {
+        self.content.as_widget().mouse_interaction(
+            &tree.children[0],
+            layout,
+            cursor,
+            viewport,
+            renderer,
+        )
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut Renderer,
+        theme: &Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+    ) {
+        self.content.as_widget().draw(
+            &tree.children[0],
+            renderer,
+            theme,
+            style,
+            layout,
+            cursor,
+            viewport,
+        );
+
+        if tree.state.downcast_ref::<State>().is_focused {
+            renderer.fill_quad(
+                Quad {
+                    bounds: layout.bounds().expand(OUTLINE_WIDTH),
+                    border: Border {
+                        color: theme.palette().primary,
+                        width: OUTLINE_WIDTH,
+                        radius: OUTLINE_WIDTH.into(),
+                    },
+                    ..Quad::default()
+                },
+                Color::TRANSPARENT,
+            );
+        }
+    }
+
+    fn overlay<'b>(
+        &'b mut self,
+        tree: &'b mut Tree,
+        layout: Layout<'b>,
+        renderer: &Renderer,
+        viewport: &Rectangle,
+        translation: Vector,
+    ) -> Option<overlay::Element<'b, Message, Theme, Renderer>> {
+        self.content.as_widget_mut().overlay(
+            &mut tree.children[0],
+            layout,
+            renderer,
+            viewport,
+            translation,
+        )
+    }
+}
+
+impl<'a, Message: 'a> From<Focus<'a, Message>> for Element<'a, Message> {
+    fn from(focus: Focus<'a, Message>) -> Element<'a, Message> {
+        Element::new(focus)
+    }
+}
@@ -0,0 +1,347 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt, fs,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{anyhow, bail};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    i18n::t,
+    paths,
+    scrobble::LovedTracks,
+    state::{self, Schema},
+    track::Track,
+    track_stats::TrackStatsLibrary,
+};
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+const SCHEMA: Schema = Schema {
+    migrations: &[state::unversioned],
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Play {
+    pub path: PathBuf,
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    pub duration: Duration,
+    pub played_at: u64,
+}
+
+pub struct History {
+    path: PathBuf,
+    plays: Vec<Play>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportContents {
+    History,
+    Library,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DateRange {
+    from: Option<u64>,
+    until: Option<u64>,
+}
+
+#[derive(Serialize)]
+struct HistoryRow<'a> {
+    played_at: String,
+    path: &'a Path,
+    title: Option<&'a str>,
+    artist: Option<&'a str>,
+    album: Option<&'a str>,
+    duration_secs: u64,
+}
+
+#[derive(Serialize)]
+struct LibraryRow<'a> {
+    path: &'a Path,
+    title: Option<&'a str>,
+    artist: Option<&'a str>,
+    album: Option<&'a str>,
+    rating: Option<u8>,
+    play_count: u32,
+    loved: bool,
+    last_played: Option<String>,
+}
+
+impl History {
+    pub fn open() -> anyhow::Result<History> {
+        let path = paths::data_dir()?.join("history.json");
+        let plays = SCHEMA.load(&path)?.unwrap_or_default();
+        Ok(History { path, plays })
+    }
+
+    pub fn len(&self) -> usize {
+        self.plays.len()
+    }
+
+    pub fn last_played(&self) -> HashMap<&Path, u64> {
+        self.plays
+            .iter()
+            .map(|play| (play.path.as_path(), play.played_at))
+            .collect()
+    }
+
+    pub fn record(&mut self, track: &Track, started_at: SystemTime) -> anyhow::Result<()> {
+        self.plays.push(Play {
+            path: track.path().to_path_buf(),
+            title: track.title().map(str::to_string),
+            artist: track.artist().map(str::to_string),
+            album: track.album().map(str::to_string),
+            duration: track.duration(),
+            played_at: started_at.duration_since(UNIX_EPOCH)?.as_secs(),
+        });
+        SCHEMA.save(&self.path, &self.plays)
+    }
+}
+
+impl ExportFormat {
+    pub const ALL: [ExportFormat; 2] = [ExportFormat::Csv, ExportFormat::Json];
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+        }
+    }
+}
+
+impl fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ExportFormat::Csv => "CSV",
+            ExportFormat::Json => "JSON",
+        })
+    }
+}
+
+impl ExportContents {
+    pub const ALL: [ExportContents; 2] = [ExportContents::History, ExportContents::Library];
+}
+
+impl fmt::Display for ExportContents {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&t!(match self {
+            ExportContents::History => "listening-history",
+            ExportContents::Library => "library",
+        }))
+    }
+}
+
+impl DateRange {
+    pub fn parse(from: &str, until: &str) -> anyhow::Result<DateRange> {
+        let parse = |date: &str| match date.trim() {
+            "" => Ok(None),
+            date => parse_date(date)
+                .map(Some)
+                .ok_or_else(|| anyhow!("invalid date \"{}\", expected YYYY-MM-DD", date)),
+        };
+        let range = DateRange {
+            from: parse(from)?,
+            until: parse(until)?.map(|until| until + SECONDS_PER_DAY),
+        };
+        if let (Some(from), Some(until)) = (range.from, range.until)
+            && from >= until
+        {
+            bail!("the start date must not be after the end date");
+        }
+        Ok(range)
+    }
+
+    fn contains(&self, time: u64) -> bool {
+        self.from.is_none_or(|from| time >= from) && self.until.is_none_or(|until| time < until)
+    }
+}
+
+pub fn export(
+    path: &Path,
+    contents: ExportContents,
+    format: ExportFormat,
+    range: DateRange,
+    history: &History,
+    track_stats: Option<&TrackStatsLibrary>,
+    loved_tracks: Option<&LovedTracks>,
+) -> anyhow::Result<usize> {
+    let plays = history
+        .plays
+        .iter()
+        .filter(|play| range.contains(play.played_at));
+
+    let (count, output) = match contents {
+        ExportContents::History => {
+            let rows = plays
+                .map(|play| HistoryRow {
+                    played_at: format_timestamp(play.played_at),
+                    path: &play.path,
+                    title: play.title.as_deref(),
+                    artist: play.artist.as_deref(),
+                    album: play.album.as_deref(),
+                    duration_secs: play.duration.as_secs(),
+                })
+                .collect::<Vec<_>>();
+            let output = match format {
+                ExportFormat::Json => serde_json::to_string_pretty(&rows)?,
+                ExportFormat::Csv => to_csv(
+                    [
+                        "played_at",
+                        "path",
+                        "title",
+                        "artist",
+                        "album",
+                        "duration_secs",
+                    ],
+                    rows.iter().map(|row| {
+                        [
+                            row.played_at.clone(),
+                            row.path.display().to_string(),
+                            row.title.unwrap_or_default().to_string(),
+                            row.artist.unwrap_or_default().to_string(),
+                            row.album.unwrap_or_default().to_string(),
+                            row.duration_secs.to_string(),
+                        ]
+                    }),
+                ),
+            };
+            (rows.len(), output)
+        }
+        ExportContents::Library => {
+            let mut last_plays = BTreeMap::new();
+            for play in plays {
+                last_plays.insert(play.path.as_path(), Some(play));
+            }
+            if range.from.is_none() && range.until.is_none() {
+                for path in track_stats.into_iter().flat_map(TrackStatsLibrary::tracks) {
+                    last_plays.entry(path).or_insert(None);
+                }
+            }
+
+            let rows = last_plays
+                .into_iter()
+                .map(|(path, play)| {
+                    let stats = track_stats
+                        .map(|track_stats| track_stats.get(path))
+                        .unwrap_or_default();
+                    LibraryRow {
+                        path,
+                        title: play.and_then(|play| play.title.as_deref()),
+                        artist: play.and_then(|play| play.artist.as_deref()),
+                        album: play.and_then(|play| play.album.as_deref()),
+                        rating: stats.rating,
+                        play_count: stats.play_count,
+                        loved: loved_tracks.is_some_and(|loved| loved.is_loved(path)),
+                        last_played: play.map(|play| format_timestamp(play.played_at)),
+                    }
+                })
+                .collect::<Vec<_>>();
+            let output = match format {
+                ExportFormat::Json => serde_json::to_string_pretty(&rows)?,
+                ExportFormat::Csv => to_csv(
+                    [
+                        "path",
+                        "title",
+                        "artist",
+                        "album",
+                        "rating",
+                        "play_count",
+                        "loved",
+                        "last_played",
+                    ],
+                    rows.iter().map(|row| {
+                        [
+                            row.path.display().to_string(),
+                            row.title.unwrap_or_default().to_string(),
+                            row.artist.unwrap_or_default().to_string(),
+                            row.album.unwrap_or_default().to_string(),
+                            row.rating.map(|r| r.to_string()).unwrap_or_default(),
+                            row.play_count.to_string(),
+                            row.loved.to_string(),
+                            row.last_played.clone().unwrap_or_default(),
+                        ]
+                    }),
+                ),
+            };
+            (rows.len(), output)
+        }
+    };
+
+    fs::write(path, output)?;
+    Ok(count)
+}
+
+fn to_csv<const N: usize>(header: [&str; N], rows: impl Iterator<Item = [String; N]>) -> String {
+    let mut csv = header.join(",");
+    csv.push('\n');
+    for row in rows {
+        let fields = row
+            .iter()
+            .map(|field| escape_csv(field))
+            .collect::<Vec<_>>();
+        csv.push_str(&fields.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+fn escape_csv(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn parse_date(date: &str) -> Option<u64> {
+    let mut parts = date.splitn(3, '-').map(|part| part.parse::<i64>().ok());
+    let (year, month, day) = (parts.next()??, parts.next()??, parts.next()??);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146097 + day_of_era - 719468;
+    u64::try_from(days).ok().map(|days| days * SECONDS_PER_DAY)
+}
+
+fn format_timestamp(timestamp: u64) -> String {
+    let days = (timestamp / SECONDS_PER_DAY) as i64 + 719468;
+    let seconds = timestamp % SECONDS_PER_DAY;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
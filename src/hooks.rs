@@ -0,0 +1,128 @@
+use std::{
+    fmt,
+    io::Write,
+    process::{Command, Stdio},
+    thread,
+    time::Duration,
+};
+
+use serde::Serialize;
+use tracing::{debug, error, warn};
+
+use crate::{player::PlaybackState, track::Track};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookEvent {
+    TrackChanged,
+    Play,
+    Pause,
+    Stop,
+    Position,
+    Volume,
+}
+
+impl fmt::Display for HookEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            HookEvent::TrackChanged => "track-changed",
+            HookEvent::Play => "play",
+            HookEvent::Pause => "pause",
+            HookEvent::Stop => "stop",
+            HookEvent::Position => "position",
+            HookEvent::Volume => "volume",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Payload {
+    pub event: String,
+    pub state: String,
+    pub path: Option<String>,
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    pub duration_secs: Option<f64>,
+    pub position_secs: f64,
+    pub volume: f32,
+}
+
+impl Payload {
+    pub fn new(
+        event: HookEvent,
+        state: PlaybackState,
+        track: Option<&Track>,
+        position: Duration,
+        volume: f32,
+    ) -> Payload {
+        Payload {
+            event: event.to_string(),
+            state: state.as_str().to_string(),
+            path: track.map(|track| track.path().to_string_lossy().into_owned()),
+            title: track.and_then(|track| track.title().map(str::to_string)),
+            artist: track.and_then(|track| track.artist().map(str::to_string)),
+            album: track.and_then(|track| track.album().map(str::to_string)),
+            duration_secs: track.map(|track| track.duration().as_secs_f64()),
+            position_secs: position.as_secs_f64(),
+            volume,
+        }
+    }
+}
+
+pub fn run(command: &str, payload: &Payload) {
+    let command = command.trim();
+    if command.is_empty() {
+        return;
+    }
+
+    let command = command.to_string();
+    let payload = payload.clone();
+    thread::spawn(move || {
+        if let Err(e) = spawn(&command, &payload) {
+            error!("Failed to run hook command \"{}\": {:#}", command, e);
+        }
+    });
+}
+
+fn spawn(command: &str, payload: &Payload) -> anyhow::Result<()> {
+    let mut shell = if cfg!(target_os = "windows") {
+        let mut shell = Command::new("cmd");
+        shell.arg("/C");
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.arg("-c");
+        shell
+    };
+    let optional = |value: &Option<String>| value.clone().unwrap_or_default();
+    let mut child = shell
+        .arg(command)
+        .env("KANTA_EVENT", &payload.event)
+        .env("KANTA_STATE", &payload.state)
+        .env("KANTA_PATH", optional(&payload.path))
+        .env("KANTA_TITLE", optional(&payload.title))
+        .env("KANTA_ARTIST", optional(&payload.artist))
+        .env("KANTA_ALBUM", optional(&payload.album))
+        .env(
+            "KANTA_DURATION",
+            payload
+                .duration_secs
+                .map(|secs| format!("{:.0}", secs))
+                .unwrap_or_default(),
+        )
+        .env("KANTA_POSITION", format!("{:.0}", payload.position_secs))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .spawn()?;
+
+    if let Some(mut stdin) = child.stdin.take()
+        && let Err(e) = stdin.write_all(serde_json::to_string(payload)?.as_bytes())
+    {
+        debug!("Hook command did not read its input: {}", e);
+    }
+    let status = child.wait()?;
+    if !status.success() {
+        warn!("Hook command \"{}\" exited with {}", command, status);
+    }
+    Ok(())
+}
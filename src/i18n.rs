@@ -0,0 +1,116 @@
+use std::{fmt, sync::RwLock};
+
+use fluent_bundle::{concurrent::FluentBundle, FluentArgs, FluentResource};
+use tracing::error;
+use unic_langid::LanguageIdentifier;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Language {
+    pub id: &'static str,
+    pub name: &'static str,
+    source: &'static str,
+}
+
+pub const LANGUAGES: [Language; 2] = [
+    Language {
+        id: "en-US",
+        name: "English",
+        source: include_str!("../locales/en-US.ftl"),
+    },
+    Language {
+        id: "de",
+        name: "Deutsch",
+        source: include_str!("../locales/de.ftl"),
+    },
+];
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name)
+    }
+}
+
+struct Localizer {
+    language: &'static str,
+    bundle: FluentBundle<FluentResource>,
+    fallback: FluentBundle<FluentResource>,
+}
+
+static LOCALIZER: RwLock<Option<Localizer>> = RwLock::new(None);
+
+macro_rules! t {
+    ($id:expr) => {
+        $crate::i18n::translate($id, None)
+    };
+    ($id:expr, $($name:ident = $value:expr),+ $(,)?) => {{
+        let mut args = fluent_bundle::FluentArgs::new();
+        $(args.set(stringify!($name), $value);)+
+        $crate::i18n::translate($id, Some(&args))
+    }};
+}
+pub(crate) use t;
+
+pub fn set_language(id: Option<&str>) {
+    let requested = id
+        .map(str::to_string)
+        .or_else(sys_locale::get_locale)
+        .and_then(|locale| locale.replace('_', "-").parse::<LanguageIdentifier>().ok());
+    let language = requested
+        .and_then(|requested| {
+            LANGUAGES.iter().find(|language| {
+                language
+                    .id
+                    .parse::<LanguageIdentifier>()
+                    .is_ok_and(|id| id.language == requested.language)
+            })
+        })
+        .unwrap_or(&LANGUAGES[0]);
+
+    let localizer = Localizer {
+        language: language.id,
+        bundle: bundle(language),
+        fallback: bundle(&LANGUAGES[0]),
+    };
+    *LOCALIZER.write().unwrap_or_else(|e| e.into_inner()) = Some(localizer);
+}
+
+pub fn current_language() -> &'static str {
+    let localizer = LOCALIZER.read().unwrap_or_else(|e| e.into_inner());
+    localizer
+        .as_ref()
+        .map_or(LANGUAGES[0].id, |localizer| localizer.language)
+}
+
+pub fn translate(id: &str, args: Option<&FluentArgs>) -> String {
+    let localizer = LOCALIZER.read().unwrap_or_else(|e| e.into_inner());
+    let Some(localizer) = localizer.as_ref() else {
+        return id.to_string();
+    };
+
+    for bundle in [&localizer.bundle, &localizer.fallback] {
+        if let Some(pattern) = bundle.get_message(id).and_then(|message| message.value()) {
+            let mut errors = vec![];
+            return bundle
+                .format_pattern(pattern, args, &mut errors)
+                .into_owned();
+        }
+    }
+    id.to_string()
+}
+
+fn bundle(language: &Language) -> FluentBundle<FluentResource> {
+    let id = language.id.parse().unwrap_or_default();
+    let mut bundle = FluentBundle::new_concurrent(vec![id]);
+    bundle.set_use_isolating(false);
+
+    let resource = FluentResource::try_new(language.source.to_string()).unwrap_or_else(
+        |(resource, errors)| {
+            error!("Failed to parse {} translations: {:?}", language.id, errors);
+            resource
+        },
+    );
+    if let Err(errors) = bundle.add_resource(resource) {
+        error!("Failed to load {} translations: {:?}", language.id, errors);
+    }
+    bundle
+}
@@ -0,0 +1,800 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, bail};
+use tracing::warn;
+
+use crate::{
+    browser,
+    track_stats::{TrackStats, MAX_RATING},
+};
+
+pub const EXTENSIONS: [&str; 7] = ["xml", "m3u", "m3u8", "pls", "fpl", "csv", "txt"];
+
+const MAX_PLIST_DEPTH: usize = 64;
+
+#[derive(Debug, Clone, Default)]
+pub struct Import {
+    pub playlists: Vec<(String, Vec<PathBuf>)>,
+    pub stats: Vec<(PathBuf, TrackStats)>,
+    pub unmatched: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Report {
+    pub playlists: usize,
+    pub tracks: usize,
+    pub unmatched: usize,
+}
+
+impl Import {
+    pub fn report(&self) -> Report {
+        let tracks = self
+            .playlists
+            .iter()
+            .flat_map(|(_, paths)| paths)
+            .chain(self.stats.iter().map(|(path, _)| path))
+            .collect::<HashSet<_>>()
+            .len();
+        Report {
+            playlists: self.playlists.len(),
+            tracks,
+            unmatched: self.unmatched,
+        }
+    }
+}
+
+struct ParsedTrack {
+    path: PathBuf,
+    stats: Option<TrackStats>,
+}
+
+enum Plist {
+    Dict(Vec<(String, Plist)>),
+    Array(Vec<Plist>),
+    String(String),
+    Integer(i64),
+    Bool(bool),
+    Other,
+}
+
+enum Token {
+    Open(String),
+    Close(String),
+    Empty(String),
+    Text(String),
+}
+
+pub fn run(path: &Path, music_folders: &[PathBuf]) -> anyhow::Result<Import> {
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase)
+        .unwrap_or_default();
+    let name = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let dir = path.parent().unwrap_or(Path::new(""));
+
+    let playlists = match extension.as_str() {
+        "xml" => parse_itunes(&fs::read_to_string(path)?)?,
+        "fpl" => vec![(name, parse_fpl(&fs::read(path)?))],
+        "pls" => vec![(name, parse_pls(&fs::read_to_string(path)?, dir))],
+        "csv" | "txt" => parse_csv(&fs::read_to_string(path)?, &name, dir)?,
+        _ => vec![(name, parse_m3u(&fs::read_to_string(path)?, dir))],
+    };
+
+    let mut mapper = Mapper::new(music_folders);
+    let mut import = Import::default();
+    let mut stats = HashMap::new();
+    let mut unmatched = HashSet::new();
+    for (name, tracks) in playlists {
+        let mut paths = vec![];
+        for track in tracks {
+            let Some(path) = mapper.map(&track.path) else {
+                unmatched.insert(track.path);
+                continue;
+            };
+            if let Some(track_stats) = track.stats.filter(|stats| *stats != TrackStats::default()) {
+                stats.insert(path.clone(), track_stats);
+            }
+            paths.push(path);
+        }
+        if !name.is_empty() && !paths.is_empty() {
+            import.playlists.push((name, paths));
+        }
+    }
+    import.stats = stats.into_iter().collect();
+    import.unmatched = unmatched.len();
+
+    if import.playlists.is_empty() && import.stats.is_empty() {
+        bail!(
+            "no tracks from {} were found on this computer",
+            path.display()
+        );
+    }
+    if import.unmatched > 0 {
+        warn!(
+            "{} tracks from {} were not found on this computer",
+            import.unmatched,
+            path.display()
+        );
+    }
+    Ok(import)
+}
+
+struct Mapper<'a> {
+    music_folders: &'a [PathBuf],
+    index: Option<HashMap<String, Vec<PathBuf>>>,
+}
+
+impl Mapper<'_> {
+    fn new(music_folders: &[PathBuf]) -> Mapper<'_> {
+        Mapper {
+            music_folders,
+            index: None,
+        }
+    }
+
+    fn map(&mut self, path: &Path) -> Option<PathBuf> {
+        if path.is_file() {
+            return Some(path.to_path_buf());
+        }
+
+        let [file_name, parent_name] = last_components(path);
+        let index = self.index.get_or_insert_with(|| {
+            let mut index: HashMap<String, Vec<PathBuf>> = HashMap::new();
+            for folder in self.music_folders {
+                for track in browser::tracks(folder).unwrap_or_default() {
+                    let [name, _] = last_components(&track);
+                    index.entry(name).or_default().push(track);
+                }
+            }
+            index
+        });
+
+        let candidates = index.get(&file_name)?;
+        candidates
+            .iter()
+            .find(|candidate| last_components(candidate)[1] == parent_name)
+            .or(match candidates.as_slice() {
+                [only] => Some(only),
+                _ => None,
+            })
+            .cloned()
+    }
+}
+
+fn last_components(path: &Path) -> [String; 2] {
+    let path = path.to_string_lossy().to_lowercase();
+    let mut components = path.rsplit(['/', '\\']);
+    [(); 2].map(|()| components.next().unwrap_or_default().to_string())
+}
+
+fn parse_itunes(contents: &str) -> anyhow::Result<Vec<(String, Vec<ParsedTrack>)>> {
+    let tokens = tokenize(contents);
+    let mut position = 0;
+    let library = loop {
+        match tokens.get(position) {
+            Some(Token::Open(name)) if name == "dict" => {
+                break parse_plist(&tokens, &mut position, 0)?
+            }
+            Some(_) => position += 1,
+            None => bail!("not an iTunes or Music library"),
+        }
+    };
+
+    let mut tracks = HashMap::new();
+    if let Some(Plist::Dict(entries)) = library.get("Tracks") {
+        for (_, track) in entries {
+            let (Some(Plist::Integer(id)), Some(Plist::String(location))) =
+                (track.get("Track ID"), track.get("Location"))
+            else {
+                continue;
+            };
+            let Some(path) = file_url_to_path(location) else {
+                continue;
+            };
+            let rating = match (track.get("Rating"), track.get("Rating Computed")) {
+                (Some(Plist::Integer(rating)), None | Some(Plist::Bool(false))) => {
+                    Some((*rating / 20).clamp(0, MAX_RATING as i64) as u8)
+                }
+                _ => None,
+            };
+            let play_count = match track.get("Play Count") {
+                Some(Plist::Integer(count)) => (*count).max(0) as u32,
+                _ => 0,
+            };
+            tracks.insert(*id, (path, TrackStats { rating, play_count }));
+        }
+    }
+
+    let mut playlists = vec![];
+    if let Some(Plist::Array(entries)) = library.get("Playlists") {
+        for playlist in entries {
+            let is_builtin = ["Master", "Folder"]
+                .iter()
+                .any(|key| matches!(playlist.get(key), Some(Plist::Bool(true))))
+                || playlist.get("Distinguished Kind").is_some();
+            let (false, Some(Plist::String(name)), Some(Plist::Array(items))) = (
+                is_builtin,
+                playlist.get("Name"),
+                playlist.get("Playlist Items"),
+            ) else {
+                continue;
+            };
+            let items = items
+                .iter()
+                .filter_map(|item| match item.get("Track ID") {
+                    Some(Plist::Integer(id)) => tracks.get(id),
+                    _ => None,
+                })
+                .map(|(path, stats)| ParsedTrack {
+                    path: path.clone(),
+                    stats: Some(*stats),
+                })
+                .collect();
+            playlists.push((name.clone(), items));
+        }
+    }
+
+    playlists.push((
+        String::new(),
+        tracks
+            .into_values()
+            .map(|(path, stats)| ParsedTrack {
+                path,
+                stats: Some(stats),
+            })
+            .collect(),
+    ));
+    Ok(playlists)
+}
+
+impl Plist {
+    fn get(&self, key: &str) -> Option<&Plist> {
+        match self {
+            Plist::Dict(entries) => entries.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+}
+
+fn parse_plist(tokens: &[Token], position: &mut usize, depth: usize) -> anyhow::Result<Plist> {
+    if depth > MAX_PLIST_DEPTH {
+        bail!("library is nested too deeply");
+    }
+    let token = tokens
+        .get(*position)
+        .ok_or_else(|| anyhow!("unexpected end of library"))?;
+    *position += 1;
+    match token {
+        Token::Open(name) if name == "dict" => {
+            let mut entries = vec![];
+            loop {
+                match tokens.get(*position) {
+                    Some(Token::Close(name)) if name == "dict" => {
+                        *position += 1;
+                        return Ok(Plist::Dict(entries));
+                    }
+                    Some(Token::Open(name)) if name == "key" => {
+                        *position += 1;
+                        let key = read_text(tokens, position, "key");
+                        entries.push((key, parse_plist(tokens, position, depth + 1)?));
+                    }
+                    Some(_) => bail!("invalid dictionary in library"),
+                    None => bail!("unexpected end of library"),
+                }
+            }
+        }
+        Token::Open(name) if name == "array" => {
+            let mut items = vec![];
+            loop {
+                match tokens.get(*position) {
+                    Some(Token::Close(name)) if name == "array" => {
+                        *position += 1;
+                        return Ok(Plist::Array(items));
+                    }
+                    Some(_) => items.push(parse_plist(tokens, position, depth + 1)?),
+                    None => bail!("unexpected end of library"),
+                }
+            }
+        }
+        Token::Open(name) => {
+            let text = read_text(tokens, position, name);
+            Ok(match name.as_str() {
+                "string" => Plist::String(text),
+                "integer" => text.trim().parse().map_or(Plist::Other, Plist::Integer),
+                _ => Plist::Other,
+            })
+        }
+        Token::Empty(name) => Ok(match name.as_str() {
+            "true" => Plist::Bool(true),
+            "false" => Plist::Bool(false),
+            "dict" => Plist::Dict(vec![]),
+            "array" => Plist::Array(vec![]),
+            "string" => Plist::String(String::new()),
+            _ => Plist::Other,
+        }),
+        Token::Close(_) | Token::Text(_) => bail!("invalid library"),
+    }
+}
+
+fn read_text(tokens: &[Token], position: &mut usize, element: &str) -> String {
+    let mut text = String::new();
+    while let Some(token) = tokens.get(*position) {
+        *position += 1;
+        match token {
+            Token::Text(t) => text.push_str(t),
+            Token::Close(name) if name == element => break,
+            _ => {}
+        }
+    }
+    text
+}
+
+fn tokenize(contents: &str) -> Vec<Token> {
+    let mut tokens = vec![];
+    let mut rest = contents;
+    while let Some(start) = rest.find('<') {
+        let text = &rest[..start];
+        if !text.trim().is_empty() {
+            tokens.push(Token::Text(decode_entities(text)));
+        }
+        rest = &rest[start..];
+        let end = if rest.starts_with("<!--") {
+            rest.find("-->").map(|end| end + 3)
+        } else {
+            rest.find('>').map(|end| end + 1)
+        };
+        let Some(end) = end else {
+            break;
+        };
+        let tag = &rest[1..end - 1];
+        rest = &rest[end..];
+
+        if tag.starts_with(['?', '!']) {
+            continue;
+        }
+        let name = |tag: &str| {
+            tag.trim_matches('/')
+                .split_whitespace()
+                .next()
+                .unwrap_or_default()
+                .to_string()
+        };
+        tokens.push(if tag.starts_with('/') {
+            Token::Close(name(tag))
+        } else if tag.ends_with('/') {
+            Token::Empty(name(tag))
+        } else {
+            Token::Open(name(tag))
+        });
+    }
+    tokens
+}
+
+fn decode_entities(text: &str) -> String {
+    let mut decoded = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let Some(end) = rest.find(';') else {
+            break;
+        };
+        let entity = &rest[1..end];
+        let character = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(str::parse))
+                .and_then(Result::ok)
+                .and_then(char::from_u32),
+        };
+        match character {
+            Some(character) => {
+                decoded.push(character);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+fn parse_m3u(contents: &str, dir: &Path) -> Vec<ParsedTrack> {
+    contents
+        .lines()
+        .map(|line| line.trim().trim_start_matches('\u{feff}'))
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| resolve(line, dir))
+        .map(|path| ParsedTrack { path, stats: None })
+        .collect()
+}
+
+fn parse_pls(contents: &str, dir: &Path) -> Vec<ParsedTrack> {
+    let mut entries = contents
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.trim().split_once('=')?;
+            let number: u32 = key
+                .to_ascii_lowercase()
+                .strip_prefix("file")?
+                .parse()
+                .ok()?;
+            Some((number, resolve(value.trim(), dir)?))
+        })
+        .collect::<Vec<_>>();
+    entries.sort_by_key(|(number, _)| *number);
+    entries
+        .into_iter()
+        .map(|(_, path)| ParsedTrack { path, stats: None })
+        .collect()
+}
+
+fn parse_fpl(contents: &[u8]) -> Vec<ParsedTrack> {
+    const PREFIX: &[u8] = b"file://";
+    contents
+        .split(|&byte| byte == 0)
+        .filter_map(|entry| {
+            let entry = entry.strip_prefix(PREFIX)?;
+            let path = String::from_utf8_lossy(entry).into_owned();
+            Some(ParsedTrack {
+                path: PathBuf::from(path),
+                stats: None,
+            })
+        })
+        .collect()
+}
+
+fn parse_csv(
+    contents: &str,
+    name: &str,
+    dir: &Path,
+) -> anyhow::Result<Vec<(String, Vec<ParsedTrack>)>> {
+    let mut rows = contents
+        .trim_start_matches('\u{feff}')
+        .lines()
+        .filter(|line| !line.trim().is_empty());
+    let header = rows
+        .next()
+        .map(split_csv_row)
+        .ok_or_else(|| anyhow!("the CSV file is empty"))?;
+    let column = |names: &[&str]| {
+        header.iter().position(|column| {
+            let column = column.trim().to_ascii_lowercase();
+            names.contains(&column.as_str())
+        })
+    };
+    let path_column = column(&["path", "location", "file", "filename", "file path"])
+        .ok_or_else(|| anyhow!("the CSV file has no path, location or file column"))?;
+    let playlist_column = column(&["playlist", "playlist name"]);
+    let rating_column = column(&["rating", "stars"]);
+    let play_count_column = column(&["play count", "playcount", "plays"]);
+
+    let mut playlists: Vec<(String, Vec<ParsedTrack>)> = vec![];
+    for row in rows.map(split_csv_row) {
+        let field = |column: Option<usize>| column.and_then(|c| row.get(c)).map(|f| f.trim());
+        let Some(path) = field(Some(path_column)).and_then(|path| resolve(path, dir)) else {
+            continue;
+        };
+        let rating = field(rating_column)
+            .and_then(|rating| rating.parse::<f32>().ok())
+            .map(|rating| {
+                if rating > MAX_RATING as f32 {
+                    rating / 20.0
+                } else {
+                    rating
+                }
+            })
+            .map(|rating| rating.round().clamp(0.0, MAX_RATING as f32) as u8);
+        let play_count = field(play_count_column).and_then(|count| count.parse().ok());
+        let stats = (rating.is_some() || play_count.is_some()).then(|| TrackStats {
+            rating,
+            play_count: play_count.unwrap_or_default(),
+        });
+
+        let playlist = match playlist_column {
+            Some(_) => field(playlist_column).unwrap_or_default(),
+            None => name,
+        };
+        let track = ParsedTrack { path, stats };
+        match playlists.iter_mut().find(|(name, _)| name == playlist) {
+            Some((_, tracks)) => tracks.push(track),
+            None => playlists.push((playlist.to_string(), vec![track])),
+        }
+    }
+    Ok(playlists)
+}
+
+fn split_csv_row(row: &str) -> Vec<String> {
+    let separator = if row.contains(';') && !row.contains(',') {
+        ';'
+    } else {
+        ','
+    };
+    let mut fields = vec![];
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = row.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            c if c == separator && !in_quotes => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+fn resolve(entry: &str, dir: &Path) -> Option<PathBuf> {
+    if entry.starts_with("file:") {
+        return file_url_to_path(entry);
+    }
+    if entry.contains("://") {
+        return None;
+    }
+    let path = PathBuf::from(entry);
+    Some(if path.is_absolute() {
+        path
+    } else {
+        dir.join(path)
+    })
+}
+
+pub fn file_url_to_path(url: &str) -> Option<PathBuf> {
+    let path = url.strip_prefix("file://")?;
+    let path = path.strip_prefix("localhost").unwrap_or(path);
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let byte = match bytes[index] {
+            b'%' => bytes
+                .get(index + 1..index + 3)
+                .and_then(|hex| std::str::from_utf8(hex).ok())
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                .inspect(|_| index += 2),
+            byte => Some(byte),
+        };
+        decoded.push(byte.unwrap_or(b'%'));
+        index += 1;
+    }
+    let path = String::from_utf8(decoded).ok()?;
+    let is_windows_drive = path.as_bytes().get(2) == Some(&b':');
+    Some(PathBuf::from(if cfg!(windows) || is_windows_drive {
+        path.trim_start_matches('/').to_string()
+    } else {
+        path
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ITUNES_LIBRARY: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+	<key>Major Version</key><integer>1</integer>
+	<key>Tracks</key>
+	<dict>
+		<key>101</key>
+		<dict>
+			<key>Track ID</key><integer>101</integer>
+			<key>Name</key><string>Tom &amp; Jerry</string>
+			<key>Play Count</key><integer>7</integer>
+			<key>Rating</key><integer>80</integer>
+			<key>Location</key><string>file:///Users/me/Music/Tom%20&amp;%20Jerry.mp3</string>
+		</dict>
+		<key>102</key>
+		<dict>
+			<key>Track ID</key><integer>102</integer>
+			<key>Rating</key><integer>60</integer>
+			<key>Rating Computed</key><true/>
+			<key>Location</key><string>file:///Users/me/Music/Album/02.flac</string>
+		</dict>
+	</dict>
+	<key>Playlists</key>
+	<array>
+		<dict>
+			<key>Name</key><string>Library</string>
+			<key>Master</key><true/>
+			<key>Playlist Items</key>
+			<array>
+				<dict><key>Track ID</key><integer>101</integer></dict>
+			</array>
+		</dict>
+		<dict>
+			<key>Name</key><string>Road Trip</string>
+			<key>Playlist Items</key>
+			<array>
+				<dict><key>Track ID</key><integer>102</integer></dict>
+				<dict><key>Track ID</key><integer>101</integer></dict>
+				<dict><key>Track ID</key><integer>999</integer></dict>
+			</array>
+		</dict>
+	</array>
+</dict>
+</plist>
+"#;
+
+    const CSV_EXPORT: &str = "\u{feff}Playlist,Path,Rating,Play Count
+Favourites,/music/a.mp3,5,12
+Favourites,\"/music/b, \"\"live\"\".mp3\",100,
+Gym,relative/c.ogg,,3
+,https://example.com/stream.mp3,4,1
+";
+
+    const FPL_PLAYLIST: &[u8] =
+        b"\xe1\xa0\xd0\x65\x00\x00file:///music/a.mp3\x00\x10\x00file://C:\\Music\\b.flac\x00junk\x00";
+
+    fn paths(tracks: &[ParsedTrack]) -> Vec<&Path> {
+        tracks.iter().map(|track| track.path.as_path()).collect()
+    }
+
+    #[test]
+    fn itunes_library_yields_user_playlists_and_stats() {
+        let playlists = parse_itunes(ITUNES_LIBRARY).unwrap();
+        let names = playlists
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["Road Trip", ""]);
+
+        let road_trip = &playlists[0].1;
+        assert_eq!(
+            paths(road_trip),
+            [
+                Path::new("/Users/me/Music/Album/02.flac"),
+                Path::new("/Users/me/Music/Tom & Jerry.mp3"),
+            ]
+        );
+        assert_eq!(
+            road_trip[0].stats,
+            Some(TrackStats {
+                rating: None,
+                play_count: 0,
+            })
+        );
+        assert_eq!(
+            road_trip[1].stats,
+            Some(TrackStats {
+                rating: Some(4),
+                play_count: 7,
+            })
+        );
+        assert_eq!(playlists[1].1.len(), 2);
+    }
+
+    #[test]
+    fn deeply_nested_library_is_rejected() {
+        let depth = 100_000;
+        let library = format!(
+            "<plist><dict><key>Tracks</key>{}{}</dict></plist>",
+            "<array>".repeat(depth),
+            "</array>".repeat(depth)
+        );
+        assert!(parse_itunes(&library).is_err());
+    }
+
+    #[test]
+    fn tokenizer_skips_declarations_and_decodes_entities() {
+        let tokens = tokenize("<?xml?><!-- a > b --><a x=\"1\">&lt;&#65;&#x42;&bogus;</a><b/>");
+        assert!(matches!(&tokens[0], Token::Open(name) if name == "a"));
+        assert!(matches!(&tokens[1], Token::Text(text) if text == "<AB&bogus;"));
+        assert!(matches!(&tokens[2], Token::Close(name) if name == "a"));
+        assert!(matches!(&tokens[3], Token::Empty(name) if name == "b"));
+        assert_eq!(tokens.len(), 4);
+    }
+
+    #[test]
+    fn csv_export_is_grouped_by_playlist() {
+        let playlists = parse_csv(CSV_EXPORT, "export", Path::new("/base")).unwrap();
+        let names = playlists
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["Favourites", "Gym"]);
+
+        let favourites = &playlists[0].1;
+        assert_eq!(
+            paths(favourites),
+            [
+                Path::new("/music/a.mp3"),
+                Path::new("/music/b, \"live\".mp3")
+            ]
+        );
+        assert_eq!(
+            favourites[0].stats,
+            Some(TrackStats {
+                rating: Some(5),
+                play_count: 12,
+            })
+        );
+        assert_eq!(
+            favourites[1].stats,
+            Some(TrackStats {
+                rating: Some(5),
+                play_count: 0,
+            })
+        );
+        assert_eq!(paths(&playlists[1].1), [Path::new("/base/relative/c.ogg")]);
+    }
+
+    #[test]
+    fn csv_rows_split_on_semicolons_and_quotes() {
+        assert_eq!(split_csv_row("a;b;\"c;d\""), ["a", "b", "c;d"]);
+        assert_eq!(split_csv_row("a,\"b;c\",\"\""), ["a", "b;c", ""]);
+        assert_eq!(split_csv_row(""), [""]);
+    }
+
+    #[test]
+    fn csv_without_path_column_is_rejected() {
+        assert!(parse_csv("Title,Artist\nSong,Band\n", "export", Path::new("/")).is_err());
+    }
+
+    #[test]
+    fn fpl_playlist_yields_file_entries() {
+        let tracks = parse_fpl(FPL_PLAYLIST);
+        assert_eq!(
+            paths(&tracks),
+            [Path::new("/music/a.mp3"), Path::new("C:\\Music\\b.flac")]
+        );
+        assert!(tracks.iter().all(|track| track.stats.is_none()));
+    }
+
+    #[test]
+    fn mapper_only_guesses_unambiguous_file_names() {
+        let index = [
+            (
+                "01.flac",
+                vec!["/music/album a/01.flac", "/music/album b/01.flac"],
+            ),
+            ("song.mp3", vec!["/music/other/song.mp3"]),
+        ]
+        .into_iter()
+        .map(|(name, paths)| {
+            (
+                name.to_string(),
+                paths.into_iter().map(PathBuf::from).collect(),
+            )
+        })
+        .collect();
+        let mut mapper = Mapper {
+            music_folders: &[],
+            index: Some(index),
+        };
+
+        assert_eq!(
+            mapper.map(Path::new("C:\\Music\\Album B\\01.flac")),
+            Some(PathBuf::from("/music/album b/01.flac"))
+        );
+        assert_eq!(mapper.map(Path::new("/old/Album C/01.flac")), None);
+        assert_eq!(
+            mapper.map(Path::new("/old/elsewhere/song.mp3")),
+            Some(PathBuf::from("/music/other/song.mp3"))
+        );
+        assert_eq!(mapper.map(Path::new("/old/missing.ogg")), None);
+    }
+}
@@ -0,0 +1,158 @@
+#[cfg(unix)]
+use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+use std::{
+    fs::{self, OpenOptions},
+    io::{BufRead, BufReader, Write},
+    net::{Ipv4Addr, Shutdown, SocketAddr, TcpListener, TcpStream},
+    path::{Path, PathBuf},
+    sync::Mutex,
+    thread,
+    time::Duration,
+};
+
+use iced::futures::{channel::mpsc, Stream};
+use tracing::{error, info};
+
+use crate::{paths, web_remote};
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+static LISTENER: Mutex<Option<(TcpListener, String)>> = Mutex::new(None);
+static REGISTRATION: Mutex<Option<(PathBuf, String)>> = Mutex::new(None);
+
+pub fn forward_to_running_instance(paths: &[PathBuf]) -> bool {
+    let forward = || -> anyhow::Result<()> {
+        let contents = fs::read_to_string(instance_file()?)?;
+        let (port, token) = contents
+            .trim()
+            .split_once(' ')
+            .ok_or_else(|| anyhow::anyhow!("malformed instance file"))?;
+
+        let address = SocketAddr::from((Ipv4Addr::LOCALHOST, port.parse::<u16>()?));
+        let mut stream = TcpStream::connect_timeout(&address, TIMEOUT)?;
+        stream.set_read_timeout(Some(TIMEOUT))?;
+        stream.set_write_timeout(Some(TIMEOUT))?;
+        writeln!(stream, "{}", token)?;
+        for path in paths {
+            writeln!(stream, "{}", path.display())?;
+        }
+        stream.shutdown(Shutdown::Write)?;
+
+        let mut response = String::new();
+        BufReader::new(stream).read_line(&mut response)?;
+        if response.trim() != "ok" {
+            anyhow::bail!("unexpected response from running instance");
+        }
+        Ok(())
+    };
+    forward().is_ok()
+}
+
+pub fn listen() -> anyhow::Result<()> {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?;
+    let token = web_remote::generate_token()?;
+    let contents = format!("{} {}", listener.local_addr()?.port(), token);
+    let file = instance_file()?;
+    write_private(&file, &contents)?;
+
+    *REGISTRATION.lock().unwrap_or_else(|e| e.into_inner()) = Some((file, contents));
+    *LISTENER.lock().unwrap_or_else(|e| e.into_inner()) = Some((listener, token));
+    Ok(())
+}
+
+pub fn switch_profile() -> anyhow::Result<()> {
+    let mut registration = REGISTRATION.lock().unwrap_or_else(|e| e.into_inner());
+    let Some((file, contents)) = registration.as_mut() else {
+        return Ok(());
+    };
+    let new_file = instance_file()?;
+    if new_file == *file {
+        return Ok(());
+    }
+    write_private(&new_file, contents)?;
+    remove_if_ours(file, contents)?;
+    *file = new_file;
+    Ok(())
+}
+
+pub fn unregister() -> anyhow::Result<()> {
+    match REGISTRATION
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .take()
+    {
+        Some((file, contents)) => remove_if_ours(&file, &contents),
+        None => Ok(()),
+    }
+}
+
+pub fn opened_paths() -> impl Stream<Item = Vec<PathBuf>> {
+    let (sender, receiver) = mpsc::unbounded();
+
+    let listener = LISTENER.lock().unwrap_or_else(|e| e.into_inner()).take();
+    if let Some((listener, token)) = listener {
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                if sender.is_closed() {
+                    break;
+                }
+                let stream = match stream {
+                    Ok(stream) => stream,
+                    Err(e) => {
+                        error!("Failed to accept connection from another instance: {}", e);
+                        continue;
+                    }
+                };
+                let sender = sender.clone();
+                let token = token.clone();
+                thread::spawn(move || match receive_paths(&stream, &token) {
+                    Ok(paths) => {
+                        info!("Received paths from another instance: {:?}", paths);
+                        let _ = sender.unbounded_send(paths);
+                    }
+                    Err(e) => error!("Failed to receive paths from another instance: {}", e),
+                });
+            }
+        });
+    }
+
+    receiver
+}
+
+fn receive_paths(stream: &TcpStream, token: &str) -> anyhow::Result<Vec<PathBuf>> {
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+
+    let mut lines = BufReader::new(stream).lines();
+    if lines.next().transpose()?.as_deref() != Some(token) {
+        anyhow::bail!("connection with an invalid token");
+    }
+    let paths = lines
+        .map(|line| line.map(PathBuf::from))
+        .collect::<Result<_, _>>()?;
+    (&*stream).write_all(b"ok\n")?;
+    Ok(paths)
+}
+
+fn write_private(path: &Path, contents: &str) -> anyhow::Result<()> {
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+    let mut file = options.open(path)?;
+    #[cfg(unix)]
+    file.set_permissions(fs::Permissions::from_mode(0o600))?;
+    file.write_all(contents.as_bytes())?;
+    Ok(())
+}
+
+fn remove_if_ours(path: &Path, contents: &str) -> anyhow::Result<()> {
+    if fs::read_to_string(path).is_ok_and(|current| current == contents) {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+fn instance_file() -> anyhow::Result<PathBuf> {
+    Ok(paths::data_dir()?.join("instance"))
+}
@@ -0,0 +1,153 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+use anyhow::bail;
+
+use crate::i18n::t;
+
+const PAUSE_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum JobKind {
+    LibraryScan,
+    Loudness,
+    Tempo,
+}
+
+impl JobKind {
+    pub const ALL: [JobKind; 3] = [JobKind::LibraryScan, JobKind::Loudness, JobKind::Tempo];
+}
+
+impl fmt::Display for JobKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&t!(match self {
+            JobKind::LibraryScan => "job-library-scan",
+            JobKind::Loudness => "job-loudness",
+            JobKind::Tempo => "job-tempo",
+        }))
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Progress {
+    done: AtomicUsize,
+    total: AtomicUsize,
+    paused: AtomicBool,
+    cancelled: AtomicBool,
+}
+
+impl Progress {
+    pub fn start(&self, total: usize) {
+        self.done.store(0, Ordering::Relaxed);
+        self.total.store(total, Ordering::Relaxed);
+        self.cancelled.store(false, Ordering::Relaxed);
+    }
+
+    pub fn set_total(&self, total: usize) {
+        self.total.store(total, Ordering::Relaxed);
+    }
+
+    pub fn advance(&self) {
+        self.done.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn done(&self) -> usize {
+        self.done.load(Ordering::Relaxed)
+    }
+
+    pub fn total(&self) -> usize {
+        self.total.load(Ordering::Relaxed)
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.done() < self.total()
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Relaxed)
+    }
+
+    pub fn set_paused(&self, paused: bool) {
+        self.paused.store(paused, Ordering::Relaxed);
+    }
+
+    pub fn finish(&self) {
+        self.total.store(self.done(), Ordering::Relaxed);
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn checkpoint(&self) -> anyhow::Result<()> {
+        while self.is_paused() && !self.cancelled.load(Ordering::Relaxed) {
+            thread::sleep(PAUSE_POLL_INTERVAL);
+        }
+        if self.cancelled.load(Ordering::Relaxed) {
+            bail!("cancelled");
+        }
+        Ok(())
+    }
+}
+
+pub struct Jobs {
+    progress: HashMap<JobKind, Arc<Progress>>,
+}
+
+impl Default for Jobs {
+    fn default() -> Jobs {
+        Jobs {
+            progress: JobKind::ALL
+                .into_iter()
+                .map(|kind| (kind, Arc::default()))
+                .collect(),
+        }
+    }
+}
+
+impl Jobs {
+    pub fn get(&self, kind: JobKind) -> &Arc<Progress> {
+        &self.progress[&kind]
+    }
+
+    pub fn active(&self) -> usize {
+        self.progress
+            .values()
+            .filter(|progress| progress.is_active())
+            .count()
+    }
+}
+
+pub fn schedule(
+    progress: &Progress,
+    running: &HashSet<PathBuf>,
+    pending: impl IntoIterator<Item = PathBuf>,
+    limit: usize,
+) -> Vec<PathBuf> {
+    let mut seen = HashSet::new();
+    let pending = pending
+        .into_iter()
+        .filter(|path| !running.contains(path) && seen.insert(path.clone()))
+        .collect::<Vec<_>>();
+    if running.is_empty() && pending.is_empty() {
+        progress.start(0);
+        return vec![];
+    }
+    progress.set_total(progress.done() + running.len() + pending.len());
+    if progress.is_paused() {
+        return vec![];
+    }
+    pending
+        .into_iter()
+        .take(limit.saturating_sub(running.len()))
+        .collect()
+}
@@ -0,0 +1,352 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    fs::{self, File},
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+
+use serde::{Deserialize, Serialize};
+use symphonia::{
+    core::{
+        io::MediaSourceStream,
+        meta::{MetadataOptions, StandardTagKey},
+        probe::Hint,
+    },
+    default::get_probe,
+};
+use tracing::{error, warn};
+
+use crate::{browser, exclude, jobs::Progress, mounts, paths, track};
+
+const MAX_RESULTS: usize = 200;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Entry {
+    pub path: PathBuf,
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    #[serde(default)]
+    pub genre: Option<String>,
+    modified: u64,
+    size: u64,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ScanReport {
+    pub added: usize,
+    pub updated: usize,
+    pub removed: usize,
+}
+
+impl ScanReport {
+    pub fn has_changes(&self) -> bool {
+        self.added + self.updated + self.removed > 0
+    }
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct Cache {
+    directories: HashMap<PathBuf, Directory>,
+    entries: Vec<Entry>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Directory {
+    modified: u64,
+    children: Vec<(PathBuf, bool)>,
+}
+
+impl Entry {
+    fn read(path: PathBuf, (modified, size): (u64, u64)) -> Entry {
+        let mut entry = Entry {
+            path,
+            title: None,
+            artist: None,
+            album: None,
+            genre: None,
+            modified,
+            size,
+        };
+        let probed = File::open(&entry.path)
+            .map_err(anyhow::Error::from)
+            .and_then(|file| {
+                let mss = MediaSourceStream::new(Box::new(file), Default::default());
+                Ok(get_probe().format(
+                    &Hint::new(),
+                    mss,
+                    &Default::default(),
+                    &MetadataOptions::default(),
+                )?)
+            });
+        match probed {
+            Ok(mut probed) => {
+                if let Some(rev) = probed.format.metadata().current() {
+                    let find_tag = |key| {
+                        rev.tags()
+                            .iter()
+                            .find(|t| t.std_key == Some(key))
+                            .map(|t| t.value.to_string())
+                    };
+                    entry.title = find_tag(StandardTagKey::TrackTitle);
+                    entry.artist =
+                        track::join_values(&track::tag_values(rev.tags(), StandardTagKey::Artist));
+                    entry.album = find_tag(StandardTagKey::Album);
+                    entry.genre =
+                        track::join_values(&track::tag_values(rev.tags(), StandardTagKey::Genre));
+                }
+            }
+            Err(e) => error!("Failed to read tags of {}: {}", entry.path.display(), e),
+        }
+        entry
+    }
+
+    fn haystack(&self) -> String {
+        [
+            self.title.as_deref(),
+            self.artist.as_deref(),
+            self.album.as_deref(),
+            self.genre.as_deref(),
+            Some(&self.path.to_string_lossy()),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join("\n")
+        .to_lowercase()
+    }
+}
+
+pub struct LibraryIndex {
+    entries: Vec<Entry>,
+    haystacks: Vec<String>,
+    trigrams: HashMap<[char; 3], Vec<u32>>,
+    report: ScanReport,
+    unavailable: Vec<PathBuf>,
+}
+
+impl fmt::Debug for LibraryIndex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "LibraryIndex({} tracks)", self.entries.len())
+    }
+}
+
+impl LibraryIndex {
+    pub fn build(
+        music_folders: &[PathBuf],
+        excludes: &[String],
+        progress: &Progress,
+    ) -> anyhow::Result<LibraryIndex> {
+        let cache = load_cache();
+        let mut cached = cache
+            .entries
+            .into_iter()
+            .map(|entry| (entry.path.clone(), entry))
+            .collect::<HashMap<_, _>>();
+        let mut directories = cache.directories;
+
+        let mut paths = vec![];
+        let mut scanned_directories = HashMap::new();
+        let mut unavailable = vec![];
+        for folder in music_folders {
+            if !mounts::is_reachable(folder) {
+                warn!(
+                    "{} is not reachable, keeping its cached tracks",
+                    folder.display()
+                );
+                unavailable.push(folder.clone());
+                continue;
+            }
+            if let Err(e) = walk(
+                folder,
+                (folder, excludes),
+                &mut directories,
+                &mut scanned_directories,
+                &mut paths,
+            ) {
+                error!("Failed to list {}: {}", folder.display(), e);
+            }
+        }
+
+        progress.set_total(paths.len());
+        let mut report = ScanReport::default();
+        let mut entries = vec![];
+        for path in paths {
+            progress.checkpoint()?;
+            progress.advance();
+            let stat = stat(&path);
+            entries.push(match cached.remove(&path) {
+                Some(entry) if (entry.modified, entry.size) == stat => entry,
+                Some(_) => {
+                    report.updated += 1;
+                    Entry::read(path, stat)
+                }
+                None => {
+                    report.added += 1;
+                    Entry::read(path, stat)
+                }
+            });
+        }
+        let is_unavailable =
+            |path: &Path| unavailable.iter().any(|folder| path.starts_with(folder));
+        report.removed = cached
+            .keys()
+            .filter(|path| music_folders.iter().any(|folder| path.starts_with(folder)))
+            .filter(|path| !is_unavailable(path))
+            .count();
+
+        let indexed_len = entries.len();
+        entries.extend(
+            cached
+                .into_values()
+                .filter(|entry| is_unavailable(&entry.path)),
+        );
+        scanned_directories.extend(
+            directories
+                .into_iter()
+                .filter(|(dir, _)| is_unavailable(dir)),
+        );
+        let mut cache = Cache {
+            directories: scanned_directories,
+            entries,
+        };
+        fs::write(cache_path()?, serde_json::to_string(&cache)?)?;
+        cache.entries.truncate(indexed_len);
+        let entries = cache.entries;
+
+        let haystacks = entries.iter().map(Entry::haystack).collect::<Vec<_>>();
+        let mut trigrams = HashMap::<_, Vec<u32>>::new();
+        for (index, haystack) in haystacks.iter().enumerate() {
+            for trigram in trigrams_of(haystack) {
+                let postings = trigrams.entry(trigram).or_default();
+                if postings.last() != Some(&(index as u32)) {
+                    postings.push(index as u32);
+                }
+            }
+        }
+
+        Ok(LibraryIndex {
+            entries,
+            haystacks,
+            trigrams,
+            report,
+            unavailable,
+        })
+    }
+
+    pub fn unavailable(&self) -> &[PathBuf] {
+        &self.unavailable
+    }
+
+    pub fn report(&self) -> ScanReport {
+        self.report
+    }
+
+    pub fn entry(&self, index: usize) -> &Entry {
+        &self.entries[index]
+    }
+
+    pub fn search(&self, query: &str) -> Vec<usize> {
+        let query = query.to_lowercase();
+        let words = query.split_whitespace().collect::<Vec<_>>();
+        if words.is_empty() {
+            return vec![];
+        }
+
+        let candidates = words
+            .iter()
+            .filter_map(|word| self.candidates(word))
+            .min_by_key(Vec::len);
+        let matches = |index: &usize| {
+            let haystack = &self.haystacks[*index];
+            words.iter().all(|word| haystack.contains(word))
+        };
+        match candidates {
+            Some(candidates) => candidates
+                .into_iter()
+                .filter(matches)
+                .take(MAX_RESULTS)
+                .collect(),
+            None => (0..self.entries.len())
+                .filter(matches)
+                .take(MAX_RESULTS)
+                .collect(),
+        }
+    }
+
+    fn candidates(&self, word: &str) -> Option<Vec<usize>> {
+        let mut lists = trigrams_of(word)
+            .map(|trigram| self.trigrams.get(&trigram).map_or(&[][..], Vec::as_slice))
+            .collect::<Vec<_>>();
+        if lists.is_empty() {
+            return None;
+        }
+        lists.sort_by_key(|list| list.len());
+
+        let mut candidates = lists[0].to_vec();
+        for list in &lists[1..] {
+            candidates.retain(|index| list.binary_search(index).is_ok());
+        }
+        Some(candidates.into_iter().map(|index| index as usize).collect())
+    }
+}
+
+fn trigrams_of(text: &str) -> impl Iterator<Item = [char; 3]> {
+    let chars = text.chars().collect::<Vec<_>>();
+    (0..chars.len().saturating_sub(2)).map(move |i| [chars[i], chars[i + 1], chars[i + 2]])
+}
+
+fn walk(
+    dir: &Path,
+    (root, excludes): (&Path, &[String]),
+    cached: &mut HashMap<PathBuf, Directory>,
+    scanned: &mut HashMap<PathBuf, Directory>,
+    paths: &mut Vec<PathBuf>,
+) -> anyhow::Result<()> {
+    let (modified, _) = stat(dir);
+    let directory = match cached.remove(dir) {
+        Some(directory) if directory.modified == modified => directory,
+        _ => Directory {
+            modified,
+            children: browser::list(dir)?
+                .into_iter()
+                .map(|entry| (entry.path, entry.is_dir))
+                .collect(),
+        },
+    };
+    for (path, is_dir) in &directory.children {
+        if exclude::is_excluded(excludes, root, path, *is_dir) {
+            continue;
+        }
+        if *is_dir {
+            walk(path, (root, excludes), cached, scanned, paths)?;
+        } else {
+            paths.push(path.clone());
+        }
+    }
+    scanned.insert(dir.to_path_buf(), directory);
+    Ok(())
+}
+
+fn stat(path: &Path) -> (u64, u64) {
+    let Ok(metadata) = fs::metadata(path) else {
+        return (0, 0);
+    };
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |modified| modified.as_secs());
+    (modified, metadata.len())
+}
+
+fn load_cache() -> Cache {
+    cache_path()
+        .and_then(|path| Ok(serde_json::from_str(&fs::read_to_string(path)?)?))
+        .unwrap_or_default()
+}
+
+fn cache_path() -> anyhow::Result<PathBuf> {
+    Ok(paths::cache_dir()?.join("library_index.json"))
+}
@@ -0,0 +1,44 @@
+use tracing::Level;
+use tracing_appender::{
+    non_blocking::WorkerGuard,
+    rolling::{RollingFileAppender, Rotation},
+};
+use tracing_subscriber::{filter::LevelFilter, fmt, layer::SubscriberExt, util::SubscriberInitExt};
+
+use crate::paths;
+
+const MAX_LOG_FILES: usize = 7;
+
+pub fn init(verbose: bool) -> Option<WorkerGuard> {
+    let level = LevelFilter::from_level(if verbose { Level::DEBUG } else { Level::INFO });
+    let stderr = fmt::layer().with_writer(std::io::stderr);
+
+    let file = paths::log_dir().and_then(|dir| {
+        Ok(RollingFileAppender::builder()
+            .rotation(Rotation::DAILY)
+            .filename_prefix("kanta")
+            .filename_suffix("log")
+            .max_log_files(MAX_LOG_FILES)
+            .build(dir)?)
+    });
+    let (file, guard) = match file {
+        Ok(file) => {
+            let (writer, guard) = tracing_appender::non_blocking(file);
+            (
+                Some(fmt::layer().with_ansi(false).with_writer(writer)),
+                Some(guard),
+            )
+        }
+        Err(e) => {
+            eprintln!("Failed to open log file: {}", e);
+            (None, None)
+        }
+    };
+
+    tracing_subscriber::registry()
+        .with(level)
+        .with(stderr)
+        .with(file)
+        .init();
+    guard
+}
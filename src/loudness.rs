@@ -0,0 +1,168 @@
+use std::{f64::consts::PI, fs::File, io::BufReader, path::Path};
+
+use rodio::{Decoder, Source};
+use serde::{Deserialize, Serialize};
+
+pub const REFERENCE_LOUDNESS: f32 = -18.0;
+
+const BLOCK_STEPS: usize = 4;
+const STEPS_PER_SECOND: usize = 10;
+const ABSOLUTE_GATE: f64 = -70.0;
+const RELATIVE_GATE: f64 = -10.0;
+const OVERSAMPLING: usize = 4;
+const TAPS_PER_PHASE: usize = 12;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Loudness {
+    pub integrated: f32,
+    pub true_peak: f32,
+}
+
+impl Loudness {
+    pub fn gain(&self) -> f32 {
+        let gain = REFERENCE_LOUDNESS - self.integrated;
+        let headroom = -self.true_peak;
+        10f32.powf(gain.min(headroom) / 20.0)
+    }
+}
+
+pub fn analyze(path: &Path) -> anyhow::Result<Option<Loudness>> {
+    let source = Decoder::new(BufReader::new(File::open(path)?))?;
+    let channels = source.channels() as usize;
+    let sample_rate = source.sample_rate() as f64;
+    let step_len = (sample_rate as usize / STEPS_PER_SECOND).max(1) * channels;
+
+    let mut filters = vec![KWeighting::new(sample_rate); channels];
+    let mut peaks = vec![TruePeak::new(); channels];
+    let mut steps = vec![];
+    let mut energy = 0.0;
+    for (index, sample) in source.enumerate() {
+        let channel = index % channels;
+        let weighted = filters[channel].process(sample as f64);
+        energy += weighted * weighted;
+        peaks[channel].process(sample);
+        if (index + 1) % step_len == 0 {
+            steps.push(energy / (step_len / channels) as f64);
+            energy = 0.0;
+        }
+    }
+
+    let blocks = steps
+        .windows(BLOCK_STEPS)
+        .map(|window| window.iter().sum::<f64>() / BLOCK_STEPS as f64)
+        .filter(|&energy| loudness(energy) > ABSOLUTE_GATE)
+        .collect::<Vec<_>>();
+    if blocks.is_empty() {
+        return Ok(None);
+    }
+    let mean = |blocks: &[f64]| blocks.iter().sum::<f64>() / blocks.len() as f64;
+    let threshold = loudness(mean(&blocks)) + RELATIVE_GATE;
+    let gated_blocks = blocks
+        .into_iter()
+        .filter(|&energy| loudness(energy) > threshold)
+        .collect::<Vec<_>>();
+    let integrated = loudness(mean(&gated_blocks));
+
+    let true_peak = peaks.iter().map(|peak| peak.max).fold(0.0, f32::max);
+    Ok(Some(Loudness {
+        integrated: integrated as f32,
+        true_peak: 20.0 * true_peak.max(1e-6).log10(),
+    }))
+}
+
+fn loudness(energy: f64) -> f64 {
+    -0.691 + 10.0 * energy.max(1e-12).log10()
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Biquad {
+    b: [f64; 3],
+    a: [f64; 2],
+    state: [f64; 2],
+}
+
+impl Biquad {
+    fn process(&mut self, input: f64) -> f64 {
+        let output = self.b[0] * input + self.state[0];
+        self.state[0] = self.b[1] * input - self.a[0] * output + self.state[1];
+        self.state[1] = self.b[2] * input - self.a[1] * output;
+        output
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct KWeighting {
+    shelf: Biquad,
+    high_pass: Biquad,
+}
+
+impl KWeighting {
+    fn new(sample_rate: f64) -> KWeighting {
+        let k = (PI * 1681.974450955533 / sample_rate).tan();
+        let q = 0.7071752369554196;
+        let vh = 10f64.powf(3.999843853973347 / 20.0);
+        let vb = vh.powf(0.4996667741545416);
+        let a0 = 1.0 + k / q + k * k;
+        let shelf = Biquad {
+            b: [
+                (vh + vb * k / q + k * k) / a0,
+                2.0 * (k * k - vh) / a0,
+                (vh - vb * k / q + k * k) / a0,
+            ],
+            a: [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+            state: [0.0; 2],
+        };
+
+        let k = (PI * 38.13547087602444 / sample_rate).tan();
+        let q = 0.5003270373238773;
+        let a0 = 1.0 + k / q + k * k;
+        let high_pass = Biquad {
+            b: [1.0, -2.0, 1.0],
+            a: [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+            state: [0.0; 2],
+        };
+
+        KWeighting { shelf, high_pass }
+    }
+
+    fn process(&mut self, input: f64) -> f64 {
+        self.high_pass.process(self.shelf.process(input))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct TruePeak {
+    coefficients: [f32; OVERSAMPLING * TAPS_PER_PHASE],
+    history: [f32; TAPS_PER_PHASE],
+    max: f32,
+}
+
+impl TruePeak {
+    fn new() -> TruePeak {
+        let len = (OVERSAMPLING * TAPS_PER_PHASE) as f64;
+        let coefficients = std::array::from_fn(|index| {
+            let x = PI * (index as f64 - (len - 1.0) / 2.0) / OVERSAMPLING as f64;
+            let window = 0.5 - 0.5 * (2.0 * PI * (index as f64 + 0.5) / len).cos();
+            (x.sin() / x * window) as f32
+        });
+        TruePeak {
+            coefficients,
+            history: [0.0; TAPS_PER_PHASE],
+            max: 0.0,
+        }
+    }
+
+    fn process(&mut self, sample: f32) {
+        self.history.rotate_right(1);
+        self.history[0] = sample;
+        for phase in 0..OVERSAMPLING {
+            let value = self
+                .history
+                .iter()
+                .enumerate()
+                .map(|(tap, x)| x * self.coefficients[phase + tap * OVERSAMPLING])
+                .sum::<f32>();
+            self.max = self.max.max(value.abs());
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use std::time::Duration;
+
+#[derive(Debug, Clone)]
+pub enum Lyrics {
+    Plain(String),
+    Synced(Vec<LyricLine>),
+}
+
+#[derive(Debug, Clone)]
+pub struct LyricLine {
+    pub time: Duration,
+    pub text: String,
+}
+
+impl Lyrics {
+    pub fn parse(contents: &str) -> Lyrics {
+        let mut lines = vec![];
+        for line in contents.lines() {
+            let mut rest = line.trim();
+            let mut times = vec![];
+            while let Some((time, after)) = parse_timestamp_tag(rest) {
+                times.push(time);
+                rest = after;
+            }
+            lines.extend(times.into_iter().map(|time| LyricLine {
+                time,
+                text: rest.trim().to_string(),
+            }));
+        }
+
+        if lines.is_empty() {
+            return Lyrics::Plain(contents.to_string());
+        }
+        lines.sort_by_key(|line| line.time);
+        Lyrics::Synced(lines)
+    }
+
+    pub fn lines(&self) -> Vec<&str> {
+        match self {
+            Lyrics::Plain(contents) => contents.lines().collect(),
+            Lyrics::Synced(lines) => lines.iter().map(|line| line.text.as_str()).collect(),
+        }
+    }
+
+    pub fn current_line(&self, position: Duration) -> Option<usize> {
+        match self {
+            Lyrics::Plain(_) => None,
+            Lyrics::Synced(lines) => lines
+                .partition_point(|line| line.time <= position)
+                .checked_sub(1),
+        }
+    }
+}
+
+pub fn split_timestamp(line: &str) -> (Option<Duration>, &str) {
+    match parse_timestamp_tag(line.trim()) {
+        Some((time, rest)) => (Some(time), rest.trim()),
+        None => (None, line.trim()),
+    }
+}
+
+pub fn format_timestamp(time: Duration) -> String {
+    let centis = time.as_millis() / 10;
+    format!(
+        "[{:02}:{:02}.{:02}]",
+        centis / 6000,
+        centis / 100 % 60,
+        centis % 100
+    )
+}
+
+fn parse_timestamp_tag(line: &str) -> Option<(Duration, &str)> {
+    let (tag, rest) = line.strip_prefix('[')?.split_once(']')?;
+    let (minutes, seconds) = tag.split_once(':')?;
+    let minutes = minutes.parse::<u64>().ok()?;
+    let seconds = seconds.parse::<f64>().ok()?;
+    if !seconds.is_finite() || seconds < 0.0 {
+        return None;
+    }
+    Some((
+        Duration::from_secs(minutes * 60) + Duration::from_secs_f64(seconds),
+        rest,
+    ))
+}
@@ -0,0 +1,70 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use crate::{
+    paths,
+    state::{self, Schema},
+};
+
+pub const STEP_MS: i32 = 100;
+pub const MAX_MS: i32 = 5000;
+
+const SCHEMA: Schema = Schema {
+    migrations: &[state::unversioned],
+};
+
+pub struct LyricsOffsets {
+    path: PathBuf,
+    offsets: HashMap<PathBuf, i32>,
+}
+
+impl LyricsOffsets {
+    pub fn open() -> anyhow::Result<LyricsOffsets> {
+        let path = paths::data_dir()?.join("lyrics_offsets.json");
+        let offsets = SCHEMA.load(&path)?.unwrap_or_default();
+        Ok(LyricsOffsets { path, offsets })
+    }
+
+    pub fn all(&self) -> &HashMap<PathBuf, i32> {
+        &self.offsets
+    }
+
+    pub fn get(&self, track: &Path) -> i32 {
+        self.offsets.get(track).copied().unwrap_or_default()
+    }
+
+    pub fn set(&mut self, track: &Path, offset_ms: i32) -> anyhow::Result<()> {
+        let offset_ms = offset_ms.clamp(-MAX_MS, MAX_MS);
+        if offset_ms == 0 {
+            self.offsets.remove(track);
+        } else {
+            self.offsets.insert(track.to_path_buf(), offset_ms);
+        }
+        SCHEMA.save(&self.path, &self.offsets)
+    }
+
+    pub fn merge(
+        &mut self,
+        imported: impl IntoIterator<Item = (PathBuf, i32)>,
+    ) -> anyhow::Result<()> {
+        self.offsets.extend(
+            imported
+                .into_iter()
+                .map(|(track, offset_ms)| (track, offset_ms.clamp(-MAX_MS, MAX_MS)))
+                .filter(|&(_, offset_ms)| offset_ms != 0),
+        );
+        SCHEMA.save(&self.path, &self.offsets)
+    }
+}
+
+pub fn apply(position: Duration, offset_ms: i32) -> Duration {
+    let offset = Duration::from_millis(offset_ms.unsigned_abs() as u64);
+    if offset_ms >= 0 {
+        position.saturating_sub(offset)
+    } else {
+        position + offset
+    }
+}
@@ -0,0 +1,221 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::anyhow;
+use tracing::warn;
+
+use crate::{import, remote, track::Track};
+
+pub const EXTENSIONS: [&str; 3] = ["m3u8", "m3u", "pls"];
+
+pub fn is_playlist(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| EXTENSIONS.iter().any(|e| ext.eq_ignore_ascii_case(e)))
+}
+
+pub fn read(path: &Path) -> anyhow::Result<Vec<PathBuf>> {
+    let contents = fs::read_to_string(path)?;
+    let dir = path.parent().unwrap_or(Path::new(""));
+    let is_pls = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("pls"));
+    Ok(parse(&contents, dir, is_pls))
+}
+
+fn parse(contents: &str, dir: &Path, is_pls: bool) -> Vec<PathBuf> {
+    let contents = contents.trim_start_matches('\u{feff}');
+    let entries = if is_pls {
+        pls_entries(contents)
+    } else {
+        contents
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .collect()
+    };
+    entries
+        .into_iter()
+        .filter_map(|entry| resolve(entry, dir))
+        .collect()
+}
+
+fn pls_entries(contents: &str) -> Vec<&str> {
+    let mut entries = contents
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.trim().split_once('=')?;
+            let number: u32 = key
+                .to_ascii_lowercase()
+                .strip_prefix("file")?
+                .parse()
+                .ok()?;
+            Some((number, value.trim()))
+        })
+        .collect::<Vec<_>>();
+    entries.sort_by_key(|(number, _)| *number);
+    entries.into_iter().map(|(_, entry)| entry).collect()
+}
+
+fn resolve(entry: &str, dir: &Path) -> Option<PathBuf> {
+    if remote::is_url(entry) {
+        return Some(PathBuf::from(entry));
+    }
+    if entry.starts_with("file:") {
+        return import::file_url_to_path(entry);
+    }
+    Some(dir.join(normalize_separators(entry)))
+}
+
+fn normalize_separators(entry: &str) -> String {
+    if cfg!(target_os = "windows") {
+        entry.to_string()
+    } else {
+        entry.replace('\\', "/")
+    }
+}
+
+pub fn load_tracks(path: &Path, hidden_tracks: bool) -> anyhow::Result<Vec<Track>> {
+    read(path)?
+        .into_iter()
+        .map(|path| match remote::url(&path) {
+            Some(_) => Ok(vec![Track::load(path.clone())
+                .inspect_err(|e| warn!("Failed to probe {}: {}", path.display(), e))
+                .unwrap_or_else(|_| Track::unprobed_stream(path))]),
+            None => Track::load_all(path, hidden_tracks),
+        })
+        .collect::<anyhow::Result<Vec<_>>>()
+        .map(|tracks| tracks.into_iter().flatten().collect())
+}
+
+pub fn write<'a>(path: &Path, paths: impl IntoIterator<Item = &'a Path>) -> anyhow::Result<()> {
+    let m3u8_data = paths
+        .into_iter()
+        .map(|path| {
+            path.to_str()
+                .ok_or_else(|| anyhow!("path contains invalid UTF-8"))
+                .map(|s| s.to_string())
+        })
+        .collect::<Result<Vec<_>, _>>()?
+        .join("\n");
+    fs::write(path, m3u8_data)?;
+    Ok(())
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Diff {
+    pub added: Vec<(usize, PathBuf)>,
+    pub removed: Vec<PathBuf>,
+}
+
+impl Diff {
+    pub fn new(old: &[PathBuf], new: &[PathBuf]) -> Diff {
+        let counts = |paths: &[PathBuf]| {
+            let mut counts = HashMap::<PathBuf, usize>::new();
+            for path in paths {
+                *counts.entry(path.clone()).or_default() += 1;
+            }
+            counts
+        };
+        let unmatched = |paths: &[PathBuf], other: &[PathBuf]| {
+            let mut counts = counts(other);
+            paths
+                .iter()
+                .enumerate()
+                .filter(move |(_, path)| match counts.get_mut(*path) {
+                    Some(count) if *count > 0 => {
+                        *count -= 1;
+                        false
+                    }
+                    _ => true,
+                })
+                .map(|(index, path)| (index, path.clone()))
+                .collect::<Vec<_>>()
+        };
+        Diff {
+            added: unmatched(new, old),
+            removed: unmatched(old, new)
+                .into_iter()
+                .map(|(_, path)| path)
+                .collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_handles_playlist_quirks() {
+        let dir = Path::new("/music/list");
+        let cases: [(&str, bool, Vec<PathBuf>); 4] = [
+            (
+                "\u{feff}#EXTM3U\r\n#EXTINF:1,A\r\na.mp3\r\n\r\nsub\\b.mp3\r\n",
+                false,
+                vec![dir.join("a.mp3"), dir.join("sub").join("b.mp3")],
+            ),
+            (
+                "..\\other\\c.mp3\n../d.mp3\n",
+                false,
+                vec![
+                    dir.join("..").join("other").join("c.mp3"),
+                    dir.join("..").join("d.mp3"),
+                ],
+            ),
+            (
+                "https://example.com/stream\nfile:///music/e.mp3\n",
+                false,
+                vec![
+                    PathBuf::from("https://example.com/stream"),
+                    PathBuf::from("/music/e.mp3"),
+                ],
+            ),
+            (
+                "[playlist]\r\nFile2=b.mp3\r\nTitle1=A\r\nfile1=a.mp3\r\nFile10=https://example.com/live\r\nNumberOfEntries=3\r\n",
+                true,
+                vec![
+                    dir.join("a.mp3"),
+                    dir.join("b.mp3"),
+                    PathBuf::from("https://example.com/live"),
+                ],
+            ),
+        ];
+        for (contents, is_pls, expected) in cases {
+            assert_eq!(parse(contents, dir, is_pls), expected, "{:?}", contents);
+        }
+    }
+
+    #[test]
+    fn diff_reports_added_and_removed_paths() {
+        let paths = |names: &[&str]| names.iter().map(PathBuf::from).collect::<Vec<_>>();
+        let cases = [
+            (&["a", "b", "c"][..], &["c", "a", "b"][..], vec![], vec![]),
+            (&["a", "b"], &["a", "c", "b"], vec![(1, "c")], vec![]),
+            (&["a", "b", "c"], &["c", "a"], vec![], vec!["b"]),
+            (&["a", "a", "b"], &["b", "a"], vec![], vec!["a"]),
+            (
+                &["a", "b"],
+                &["b", "c", "a", "a"],
+                vec![(1, "c"), (3, "a")],
+                vec![],
+            ),
+        ];
+        for (old, new, added, removed) in cases {
+            let diff = Diff::new(&paths(old), &paths(new));
+            let expected_added = added
+                .into_iter()
+                .map(|(index, name)| (index, PathBuf::from(name)))
+                .collect::<Vec<_>>();
+            assert_eq!(diff.added, expected_added, "{:?} -> {:?}", old, new);
+            assert_eq!(diff.removed, paths(&removed), "{:?} -> {:?}", old, new);
+        }
+    }
+}
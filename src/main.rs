@@ -1,275 +1,7783 @@
 #![deny(clippy::all)]
 
-use std::time::Duration;
+use std::{
+    borrow::Borrow,
+    collections::{BTreeSet, HashMap, HashSet},
+    fmt, fs, iter, mem,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
 
+use anyhow::anyhow;
 use iced::{
-    alignment::Vertical,
-    time,
-    widget::{button, column, row, scrollable, slider, text},
-    Color, Element, Length, Padding, Pixels, Settings, Subscription,
+    alignment::{Horizontal, Vertical},
+    clipboard, event,
+    futures::channel::{mpsc, oneshot},
+    keyboard, mouse, time,
+    widget::{
+        button, center, checkbox, column, container, image, mouse_area, opaque, operation,
+        operation::{AbsoluteOffset, RelativeOffset},
+        pick_list, pin, progress_bar, row, scrollable,
+        scrollable::Viewport,
+        slider, space, stack, text, text_editor, text_input, tooltip, Checkbox, PickList, Text,
+        TextInput,
+    },
+    window, Color, ContentFit, Element, Event, Length, Padding, Pixels, Point, Settings,
+    Subscription, Task, Theme,
 };
 use rfd::FileDialog;
+use tracing::{debug, error, info};
 
+mod airplay;
+mod alarm;
+mod album_theme;
+mod analysis_cache;
+mod audio_role;
+mod auto_dj;
+mod autostart;
+mod backup;
+mod browser;
+mod caches;
+mod command_palette;
+mod config;
+mod crash;
+mod cues;
+mod dsp;
+mod event_stream;
+mod exclude;
+mod file_associations;
+mod file_manager;
+mod focus;
+mod history;
+mod hooks;
+mod i18n;
+mod import;
+mod instance;
+mod jobs;
+mod library_index;
+mod logging;
+mod loudness;
+mod lyrics;
+mod lyrics_offsets;
+mod m3u8;
 mod media_controls;
+mod mounts;
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+mod mpris;
+mod now_playing;
+mod paths;
 mod player;
+mod playlists;
+mod plugins;
+mod radio;
+mod read_ahead;
+mod remote;
+mod scripting;
+mod scrobble;
+mod session;
+mod snapcast;
+mod spectrogram;
+mod state;
+mod stations;
+mod stats;
+mod sync;
+mod tempo;
 mod track;
+mod track_dsp;
+mod track_stats;
+mod transcode;
+mod tray;
+mod web_remote;
 
-use player::Player;
+use airplay::{AirPlayOutput, Speaker};
+use browser::TRACK_EXTENSIONS;
+use config::{
+    AutoDj, Config, QueueColumn, RecentItem, ShuffleWeighting, CACHE_LIMIT_RANGE,
+    DEFAULT_TEXT_SIZE, JOB_CONCURRENCY_RANGE, PINNED_PLAYLIST_SLOTS, SCROBBLE_MIN_DURATION_RANGE,
+    SCROBBLE_PERCENT_RANGE, SHUFFLE_AVOID_RECENT_RANGE, TEXT_SIZE_RANGE, UI_SCALE_RANGE,
+};
+use cues::CueLibrary;
+use dsp::{DspSettings, EQ_GAIN_RANGE, PITCH_SHIFT_RANGE, SPEED_RANGE};
+use event_stream::EventStream;
+use focus::focusable;
+use history::{DateRange, ExportContents, ExportFormat, History};
+use hooks::{HookEvent, Payload};
+use i18n::t;
+use jobs::{JobKind, Jobs};
+use library_index::{LibraryIndex, ScanReport};
+use loudness::Loudness;
+use lyrics::Lyrics;
+use lyrics_offsets::LyricsOffsets;
+use player::{ControlRequest, ExternalOutput, MediaRequest, PlaybackState, Player, PlaylistSort};
+use playlists::PlaylistLibrary;
+use plugins::{Metadata, Plugins};
+use scripting::Scripting;
+use scrobble::{Listen, LovedTracks, ScrobbleQueue, Scrobbler};
+use session::SavedQueue;
+use snapcast::SnapcastOutput;
+use spectrogram::Spectrogram;
+use stations::{Station, StationLibrary};
+use stats::QueueStats;
+use sync::SyncOptions;
 use track::Track;
+use track_dsp::TrackDspLibrary;
+use track_stats::{TrackStatsLibrary, MAX_RATING};
+use tray::{Tray, TrayEvent};
+use web_remote::{QueueEntry, RemoteState, WebRemote};
 
 const MUTED_COLOR: Color = Color::from_rgb(0.75, 0.75, 0.75);
 const SELECTED_COLOR: Color = Color::from_rgb(0.5, 1.0, 0.5);
 
+const PLAYLIST_ROW_HEIGHT: f32 = 32.0;
+const ALBUM_HEADER_HEIGHT: f32 = 48.0;
+const PLAYLIST_OVERSCAN: f32 = 320.0;
+const DEFAULT_PLAYLIST_VIEWPORT_HEIGHT: f32 = 2160.0;
+const PLAYLIST_SCROLLABLE_ID: &str = "playlist";
+const PLAYLIST_ICON_WIDTH: f32 = 16.0;
+const PLAYLIST_THUMBNAIL_SIZE: f32 = 24.0;
+const CONTEXT_MENU_WIDTH: f32 = 180.0;
+const PLAYLIST_SIDEBAR_WIDTH: f32 = 240.0;
+const TRACK_TOOLTIP_WIDTH: f32 = 480.0;
+
+const JUMP_TO_TIME_INPUT_ID: &str = "jump-to-time";
+const CUE_NAME_INPUT_ID: &str = "cue-name";
+const URL_INPUT_ID: &str = "url";
+const SCRIPT_INPUT_ID: &str = "script";
+const PARTY_PIN_INPUT_ID: &str = "party-pin";
+const ELAPSED_TIME_WIDTH: f32 = 140.0;
+const COMMAND_PALETTE_INPUT_ID: &str = "command-palette";
+const LYRICS_SCROLLABLE_ID: &str = "lyrics";
+const LYRICS_SEARCH_INPUT_ID: &str = "lyrics-search";
+const COMMAND_PALETTE_WIDTH: f32 = 480.0;
+
+const NOW_PLAYING_THUMBNAIL_SIZE: f32 = 32.0;
+const NOW_PLAYING_COVER_SIZE: f32 = 480.0;
+const SETTINGS_WIDTH: f32 = 480.0;
+const SEEK_STEP: Duration = Duration::from_secs(5);
+const TRACK_TOOLTIP_DELAY: Duration = Duration::from_millis(600);
+const QUEUE_SAVE_DELAY: Duration = Duration::from_secs(2);
+const QUEUE_POSITION_SAVE_INTERVAL: Duration = Duration::from_secs(15);
+const PLAYLIST_POLL_INTERVAL: Duration = Duration::from_secs(2);
+const POSITION_TICK_INTERVAL: Duration = Duration::from_secs(1);
+const WEB_REMOTE_STATE_INTERVAL: Duration = Duration::from_secs(1);
+const WEB_REMOTE_QUEUE_LEN: usize = 100;
+const VOLUME_SAVE_DELAY: Duration = Duration::from_secs(1);
+const VOLUME_STEP: f32 = 0.05;
+const VOLUME_OSD_DURATION: Duration = Duration::from_millis(1500);
+const ALARM_FADE_IN: Duration = Duration::from_secs(60);
+const MIN_SCROBBLE_BACKOFF: Duration = Duration::from_secs(30);
+const MAX_SCROBBLE_BACKOFF: Duration = Duration::from_secs(30 * 60);
+const LEVEL_METER_WIDTH: f32 = 80.0;
+const LEVEL_METER_FLOOR_DB: f32 = -60.0;
+const LEVEL_METER_DECAY: f32 = 0.7;
+const LEVEL_PEAK_HOLD: Duration = Duration::from_millis(1500);
+const LEVEL_CLIP_HOLD: Duration = Duration::from_secs(2);
+
 struct Kanta {
     player: Player,
+    config: Config,
+    collapsed_albums: HashSet<(Option<String>, String)>,
+    playlist_scroll_offset: f32,
+    playlist_viewport_height: f32,
+    follow_now_playing: bool,
+    cursor_position: Point,
+    context_menu: Option<(usize, Point)>,
+    track_info: Option<Track>,
+    stats: Option<QueueStats>,
+    sync_dialog: Option<SyncDialog>,
+    export_dialog: Option<ExportDialog>,
+    modifiers: keyboard::Modifiers,
+    selected_tracks: BTreeSet<usize>,
+    selection_anchor: Option<usize>,
+    playlists: Option<PlaylistLibrary>,
+    new_playlist_name: String,
+    new_library_exclude: String,
+    browser: Option<(PathBuf, Vec<browser::Entry>)>,
+    browsing: Option<PathBuf>,
+    stations: Option<StationLibrary>,
+    new_station: (String, String),
+    station_query: String,
+    station_results: Vec<Station>,
+    searching_stations: bool,
+    renaming_playlist: Option<(String, String)>,
+    hovered_track: Option<usize>,
+    dragged_tracks: Option<BTreeSet<usize>>,
+    resizing_column: Option<(QueueColumn, f32, f32)>,
+    queue_sort: Option<(PlaylistSort, bool, u64)>,
+    command_palette_query: Option<String>,
+    jump_to_time_input: Option<String>,
+    url_input: Option<String>,
+    party_pin_input: Option<String>,
+    quit_after_unlock: bool,
+    cues: Option<CueLibrary>,
+    new_cue: Option<(Duration, String)>,
+    scrobbler: Scrobbler,
+    scrobble_queue: Option<ScrobbleQueue>,
+    submitting_scrobbles: bool,
+    scrobble_retry_at: Instant,
+    scrobble_backoff: Duration,
+    loved_tracks: Option<LovedTracks>,
+    track_stats: Option<TrackStatsLibrary>,
+    track_dsp: Option<TrackDspLibrary>,
+    lyrics_offsets: Option<LyricsOffsets>,
+    lyrics_search: Option<LyricsSearch>,
+    sound_dialog_open: bool,
+    level_meters: LevelMeters,
+    importing: bool,
+    history: Option<History>,
+    shuffle_weights_revision: Option<u64>,
+    auto_dj_revision: Option<u64>,
+    auto_dj_loading: bool,
+    library_index: Option<Arc<LibraryIndex>>,
+    indexed_library: Option<(Vec<PathBuf>, Vec<String>)>,
+    scanning_library: bool,
+    library_rescan_requested: bool,
+    library_scan_report: Option<ScanReport>,
+    import_report: Option<import::Report>,
+    library_query: String,
+    library_results: Vec<usize>,
+    history_export: Option<HistoryExport>,
+    lyrics_editor: Option<LyricsEditor>,
+    now_playing_view: bool,
+    karaoke: bool,
+    window_hidden: bool,
+    close_prompt_open: bool,
+    spectrogram: Option<Spectrogram>,
+    settings_open: bool,
+    profiles: Vec<String>,
+    profile_picker_open: bool,
+    new_profile_name: String,
+    album_theme_source: Option<PathBuf>,
+    album_theme: Option<Theme>,
+    pending_queue: Option<SavedQueue>,
+    watched_playlist: Option<WatchedPlaylist>,
+    hook_state: (Option<usize>, Option<PathBuf>, PlaybackState),
+    plugins: Option<Plugins>,
+    scripting: Option<Scripting>,
+    event_stream: Option<EventStream>,
+    event_volume: f32,
+    web_remote: Option<WebRemote>,
+    tray: Option<Tray>,
+    web_remote_updated_at: Option<Instant>,
+    now_playing_written: Option<(Option<PathBuf>, Option<String>, PlaybackState)>,
+    airplay_speakers: Vec<Speaker>,
+    searching_airplay: bool,
+    script_input: Option<String>,
+    position_tick_at: Instant,
+    overwrite_prompt: Option<PathBuf>,
+    seen_queue_revision: u64,
+    saved_queue_revision: u64,
+    queue_changed_at: Instant,
+    queue_saved_at: Instant,
+    volume_changed_at: Option<Instant>,
+    muted_volume: Option<f32>,
+    volume_osd_until: Option<Instant>,
+    alarm_fired_on: Option<::time::Date>,
+    alarm_fade: Option<(Instant, f32)>,
+    jobs: Jobs,
+    jobs_panel_open: bool,
+    cache_usage: Option<u64>,
+    analyzing_tempo: HashSet<PathBuf>,
+    tempo_failures: HashSet<PathBuf>,
+    analyzing_loudness: HashSet<PathBuf>,
+    loudness_failures: HashSet<PathBuf>,
+    looking_up_metadata: Option<PathBuf>,
+    looked_up_metadata: HashSet<PathBuf>,
     error: Option<String>,
 }
 
+struct SyncDialog {
+    playlists: BTreeSet<String>,
+    destination: Option<PathBuf>,
+    transcode: bool,
+    format: transcode::Format,
+    bitrate: u32,
+    progress: Option<(usize, usize)>,
+    finished: bool,
+}
+
+struct WatchedPlaylist {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    paths: Vec<PathBuf>,
+    checked_at: Instant,
+    change: Option<(Vec<PathBuf>, m3u8::Diff)>,
+}
+
+struct HistoryExport {
+    contents: ExportContents,
+    format: ExportFormat,
+    from: String,
+    until: String,
+    exported: Option<usize>,
+}
+
+struct LevelMeters {
+    levels: [f32; 2],
+    peaks: [(f32, Instant); 2],
+    clipped_at: [Option<Instant>; 2],
+}
+
+struct LyricsEditor {
+    track: PathBuf,
+    content: text_editor::Content,
+    sync: Option<LyricsSync>,
+}
+
+struct LyricsSearch {
+    query: String,
+    current: usize,
+}
+
+struct LyricsSync {
+    lines: Vec<(Option<Duration>, String)>,
+    cursor: usize,
+}
+
+struct ExportDialog {
+    tracks: Vec<PathBuf>,
+    format: transcode::Format,
+    bitrate: u32,
+    progress: Option<(usize, usize)>,
+    failures: Vec<String>,
+    finished: bool,
+}
+
+impl LyricsEditor {
+    fn stop_sync(&mut self) {
+        if let Some(sync) = self.sync.take() {
+            let text = sync
+                .lines
+                .iter()
+                .map(|(time, text)| match time {
+                    Some(time) => format!("{}{}", lyrics::format_timestamp(*time), text),
+                    None => text.clone(),
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            self.content = text_editor::Content::with_text(&text);
+        }
+    }
+
+    fn save(&self) -> anyhow::Result<Option<Lyrics>> {
+        if remote::url(&self.track).is_some() {
+            return Err(anyhow!("lyrics can only be saved for local files"));
+        }
+        let path = self.track.with_extension("lrc");
+        let text = self.content.text();
+        if text.trim().is_empty() {
+            if path.exists() {
+                fs::remove_file(&path)?;
+            }
+            return Ok(None);
+        }
+        fs::write(&path, &text)?;
+        Ok(Some(Lyrics::parse(&text)))
+    }
+}
+
+impl LevelMeters {
+    fn new() -> LevelMeters {
+        LevelMeters {
+            levels: [0.0; 2],
+            peaks: [(0.0, Instant::now()); 2],
+            clipped_at: [None; 2],
+        }
+    }
+
+    fn update(&mut self, peaks: [f32; 2]) {
+        let now = Instant::now();
+        for (channel, peak) in peaks.into_iter().enumerate() {
+            self.levels[channel] = peak.max(self.levels[channel] * LEVEL_METER_DECAY);
+            let (held, held_at) = self.peaks[channel];
+            if peak >= held || now.duration_since(held_at) > LEVEL_PEAK_HOLD {
+                self.peaks[channel] = (peak.max(self.levels[channel]), now);
+            }
+            if peak >= 1.0 {
+                self.clipped_at[channel] = Some(now);
+            }
+        }
+    }
+
+    fn is_clipped(&self, channel: usize) -> bool {
+        self.clipped_at[channel].is_some_and(|clipped_at| clipped_at.elapsed() < LEVEL_CLIP_HOLD)
+    }
+}
+
+fn meter_fraction(level: f32) -> f32 {
+    let db = 20.0 * level.max(f32::MIN_POSITIVE).log10();
+    (1.0 - db / LEVEL_METER_FLOOR_DB).clamp(0.0, 1.0)
+}
+
+enum PlaylistEntry<'a> {
+    AlbumHeader {
+        index: usize,
+        len: usize,
+        album: &'a str,
+        album_artist: Option<String>,
+        key: (Option<String>, String),
+        is_collapsed: bool,
+    },
+    Track {
+        index: usize,
+        is_grouped: bool,
+    },
+}
+
+impl PlaylistEntry<'_> {
+    fn height(&self) -> f32 {
+        match self {
+            PlaylistEntry::AlbumHeader { .. } => ALBUM_HEADER_HEIGHT,
+            PlaylistEntry::Track { .. } => PLAYLIST_ROW_HEIGHT,
+        }
+    }
+}
+
+#[derive(Clone)]
+struct ConnectedOutput(Arc<Mutex<Option<ExternalOutput>>>);
+
+impl ConnectedOutput {
+    fn take(&self) -> Option<ExternalOutput> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner()).take()
+    }
+}
+
+impl fmt::Debug for ConnectedOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ConnectedOutput")
+    }
+}
+
+fn connect_output(
+    connect: impl FnOnce() -> anyhow::Result<ExternalOutput> + Send + 'static,
+    on_connected: fn(Result<ConnectedOutput, String>) -> KantaMessage,
+) -> Task<KantaMessage> {
+    let (sender, receiver) = oneshot::channel();
+    thread::spawn(move || {
+        let output = connect()
+            .map(|output| ConnectedOutput(Arc::new(Mutex::new(Some(output)))))
+            .map_err(|e| format!("{:#}", e));
+        sender.send(output).ok();
+    });
+    Task::perform(
+        async move {
+            receiver
+                .await
+                .unwrap_or_else(|_| Err("connection was cancelled".to_string()))
+        },
+        on_connected,
+    )
+}
+
 #[derive(Debug, Clone)]
 enum KantaMessage {
     AddTrack,
+    StartAddingUrl,
+    UrlInputChanged(String),
+    SubmitUrl,
+    RemoteTrackLoaded(Result<Box<Track>, String>),
     LoadPlaylist,
+    AppendPlaylist,
     ExportPlaylist,
+    SavePlaylist,
+    ConfirmOverwrite,
+    CancelOverwrite,
     ClearPlaylist,
     Play,
     Pause,
     JumpToPreviousTrack,
     JumpToNextTrack,
     JumpToTrack(usize),
+    ToggleAlbumGroup((Option<String>, String)),
+    PlaylistScrolled(Viewport),
+    ScrollToNowPlaying,
+    SetFollowNowPlaying(bool),
+    OpenContextMenu(usize),
+    CloseContextMenu,
+    PlayNext(usize),
+    RemoveTrack(usize),
+    ShowInFileManager(usize),
+    ShowCurrentTrackInFileManager,
+    ShowTrackInfo(usize),
+    CloseTrackInfo,
+    ShowStatistics,
+    CloseStatistics,
+    OpenJobsPanel,
+    CloseJobsPanel,
+    SetJobPaused(JobKind, bool),
+    CancelJob(JobKind),
+    SetJobConcurrency(u8),
+    SetCacheLimit(f32),
+    ApplyCacheLimit,
+    ClearCaches,
+    CacheUsageMeasured(u64),
+    OpenSyncDialog,
+    CloseSyncDialog,
+    ToggleSyncPlaylist(String),
+    ChooseSyncDestination,
+    SetSyncTranscode(bool),
+    SetSyncFormat(transcode::Format),
+    SetSyncBitrate(u32),
+    StartSync,
+    SyncProgress,
+    SyncFinished(Option<String>),
+    ExportTracks(Vec<usize>),
+    CloseExportDialog,
+    SetExportFormat(transcode::Format),
+    SetExportBitrate(u32),
+    StartExport,
+    ExportProgress(Option<String>),
+    ExportFinished,
+    CursorMoved(Point),
+    ModifiersChanged(keyboard::Modifiers),
+    SelectTrack(usize),
+    MoveTrackCursor(isize),
+    PlaySelectedTrack,
+    TogglePlayback,
+    SeekBackward,
+    SeekForward,
+    FocusNext,
+    FocusPrevious,
+    ClearSelection,
+    MoveSelectionUp,
+    MoveSelectionDown,
+    RemoveSelection,
+    ExportSelection,
+    NewPlaylistNameChanged(String),
+    CreateNamedPlaylist,
+    LoadNamedPlaylist(String),
+    AppendNamedPlaylist(String),
+    TogglePinnedPlaylist(String),
+    OpenPinnedPlaylist(usize),
+    SetAppendPinnedPlaylists(bool),
+    SetHiddenTracks(bool),
+    StartRenamingPlaylist(String),
+    RenamingPlaylistNameChanged(String),
+    ConfirmRenamingPlaylist,
+    CancelRenamingPlaylist,
+    DeleteNamedPlaylist(String),
+    AddToNamedPlaylist(String, usize),
+    HoverTrack(usize),
+    UnhoverTrack(usize),
+    MousePressed,
+    MouseReleased,
+    StartResizingColumn(QueueColumn),
+    DropOnNamedPlaylist(String),
+    RestoreQueue,
+    DiscardSavedQueue,
+    MergePlaylistChanges,
+    IgnorePlaylistChanges,
+    OpenRecent(RecentItem),
+    SortPlaylist(PlaylistSort),
+    SortQueueBy(PlaylistSort),
+    DeduplicatePlaylist,
+    SetShuffle(bool),
+    OpenCommandPalette,
+    CloseCommandPalette,
+    CommandPaletteQueryChanged(String),
+    SubmitCommandPalette,
+    RunCommand(Box<KantaMessage>),
+    SetLanguage(Option<String>),
+    StartJumpToTime,
+    JumpToTimeInputChanged(String),
+    SubmitJumpToTime,
+    StartAddingCue,
+    NewCueNameChanged(String),
+    AddCue,
+    JumpToCue(usize),
+    RemoveCue(usize),
+    ToggleNowPlayingView,
+    ToggleSpectrogram,
+    ToggleKaraoke,
+    SetKaraokeReduceVocals(bool),
+    OpenSettings,
+    CloseSettings,
+    SetTextSize(f32),
+    SetUiScale(f32),
+    SetAlbumColors(bool),
+    SetNormalizeVolume(bool),
+    SetShowStreamTitles(bool),
+    SetResumeRewind(bool),
+    SetResumeRewindSeconds(String),
+    SetAlarm(bool),
+    SetAlarmTime(String),
+    SetAlarmPlaylist(Option<String>),
+    SetShowLevelMeters(bool),
+    SetYieldToCalls(bool),
+    SetCloseToBackground(bool),
+    SetAutostart(bool),
+    SetRememberVolume(bool),
+    SetLaunchVolume(f32),
+    SetAutostartMinimized(bool),
+    SetScrobbleServer(String),
+    SetHookCommand(String),
+    SetEventStream(bool),
+    SetEventStreamPort(String),
+    SetWebRemote(bool),
+    SetWebRemotePort(String),
+    RegenerateWebRemoteToken,
+    ChooseNowPlayingFile,
+    SetSnapcast(bool),
+    SetSnapcastAddress(String),
+    ConnectSnapcast,
+    SnapcastConnected(Result<ConnectedOutput, String>),
+    FindAirPlaySpeakers,
+    AirPlaySpeakersFound(Result<Vec<Speaker>, String>),
+    SelectAirPlaySpeaker(Speaker),
+    AirPlayConnected(Result<ConnectedOutput, String>),
+    DisconnectAirPlay,
+    StopNowPlayingFile,
+    SetScrobbleToken(String),
+    SetScrobblePercent(f32),
+    SetScrobbleMinDuration(f32),
+    SetShuffleAvoidRecent(f32),
+    SetShuffleWeighting(ShuffleWeighting),
+    SetAutoDj(AutoDj),
+    SetPartyPin(String),
+    LockPartyMode,
+    StartUnlockingPartyMode,
+    PartyPinInputChanged(String),
+    UnlockPartyMode,
+    AutoDjLoaded(Vec<Track>),
+    LibraryIndexed((Vec<PathBuf>, Vec<String>), Option<Arc<LibraryIndex>>),
+    LibraryQueryChanged(String),
+    RescanLibrary,
+    AddScrobbleIgnoredFolder,
+    RemoveScrobbleIgnoredFolder(PathBuf),
+    RetryScrobbles,
+    ScrobblesSubmitted(Vec<Listen>, Result<(), String>),
+    ToggleLoveCurrentTrack,
+    SaveSettings,
+    AddMusicFolder,
+    RemoveMusicFolder(PathBuf),
+    ExcludeLibraryFolder,
+    LibraryExcludeInputChanged(String),
+    AddLibraryExclude,
+    RemoveLibraryExclude(String),
+    BrowseFolder(Option<PathBuf>),
+    FolderListed(PathBuf, Result<Vec<browser::Entry>, String>),
+    EnqueuePath(PathBuf),
+    NewStationNameChanged(String),
+    NewStationUrlChanged(String),
+    AddStation,
+    ImportStations,
+    PlayStation(String),
+    StationLoaded(Result<Box<Track>, String>),
+    ToggleFavoriteStation(String),
+    RemoveStation(String),
+    StationQueryChanged(String),
+    SearchStations,
+    StationsFound(Result<Vec<Station>, String>),
+    AddFoundStation(usize),
+    OpenPaths(Vec<PathBuf>),
+    CloseRequested,
+    HideWindow,
+    CancelClose,
+    Quit,
+    RegisterFileAssociations,
+    DismissFileAssociationPrompt,
+    OpenLogFolder,
+    ExportBackup,
+    ImportBackup,
+    ImportFromOtherPlayer,
+    OpenHistoryExport,
+    OpenLyricsEditor,
+    CloseLyricsEditor,
+    LyricsEdited(text_editor::Action),
+    StartLyricsSync,
+    StopLyricsSync,
+    StampLyricLine,
+    UnstampLyricLine,
+    MoveLyricCursor(isize),
+    SeekToLyricLine(usize),
+    SaveLyrics,
+    ShiftLyrics(i32),
+    ResetLyricsOffset,
+    CopyLyrics,
+    OpenLyricsSearch,
+    LyricsSearchChanged(String),
+    NextLyricsMatch,
+    PreviousLyricsMatch,
+    CloseLyricsSearch,
+    CloseHistoryExport,
+    SetHistoryExportContents(ExportContents),
+    SetHistoryExportFormat(ExportFormat),
+    HistoryExportFromChanged(String),
+    HistoryExportUntilChanged(String),
+    StartHistoryExport,
+    ImportFinished(Result<Box<import::Import>, String>),
+    OpenProfilePicker,
+    CloseProfilePicker,
+    SwitchProfile(Option<String>),
+    NewProfileNameChanged(String),
+    CreateProfile,
+    OpenScriptConsole,
+    CloseScriptConsole,
+    ScriptInputChanged(String),
+    RunScript,
+    ClearScriptOutput,
+    Dismiss,
     SetPosition(f32),
     SetVolume(f32),
+    VolumeUp,
+    VolumeDown,
+    ToggleMute,
+    SetPitch(i32),
+    OpenSoundDialog,
+    CloseSoundDialog,
+    SetSpeed(f32),
+    SetEqGain(usize, i32),
+    SaveTrackSound,
+    ForgetTrackSound,
+    TempoAnalyzed(PathBuf, Option<f32>),
+    LoudnessAnalyzed(PathBuf, Option<Loudness>),
+    MetadataLookedUp(PathBuf, Option<Metadata>),
     Tick,
 }
 
+impl KantaMessage {
+    fn is_allowed_in_party_mode(&self) -> bool {
+        use KantaMessage::*;
+
+        match self {
+            RunCommand(message) => message.is_allowed_in_party_mode(),
+            AddTrack
+            | StartAddingUrl
+            | UrlInputChanged(_)
+            | SubmitUrl
+            | RemoteTrackLoaded(_)
+            | ToggleAlbumGroup(_)
+            | PlaylistScrolled(_)
+            | ScrollToNowPlaying
+            | SetFollowNowPlaying(_)
+            | ShowTrackInfo(_)
+            | CloseTrackInfo
+            | CopyLyrics
+            | OpenLyricsSearch
+            | LyricsSearchChanged(_)
+            | NextLyricsMatch
+            | PreviousLyricsMatch
+            | CloseLyricsSearch
+            | ShowStatistics
+            | CloseStatistics
+            | SyncProgress
+            | SyncFinished(_)
+            | ExportProgress(_)
+            | ExportFinished
+            | SnapcastConnected(_)
+            | AirPlayConnected(_)
+            | CursorMoved(_)
+            | ModifiersChanged(_)
+            | MoveTrackCursor(_)
+            | FocusNext
+            | FocusPrevious
+            | ClearSelection
+            | HoverTrack(_)
+            | UnhoverTrack(_)
+            | MousePressed
+            | MouseReleased
+            | StartResizingColumn(_)
+            | OpenCommandPalette
+            | CloseCommandPalette
+            | CommandPaletteQueryChanged(_)
+            | SubmitCommandPalette
+            | ToggleNowPlayingView
+            | ToggleSpectrogram
+            | ToggleKaraoke
+            | AutoDjLoaded(_)
+            | LibraryIndexed(..)
+            | CacheUsageMeasured(_)
+            | LibraryQueryChanged(_)
+            | RescanLibrary
+            | StartUnlockingPartyMode
+            | PartyPinInputChanged(_)
+            | UnlockPartyMode
+            | ScrobblesSubmitted(..)
+            | BrowseFolder(_)
+            | FolderListed(..)
+            | EnqueuePath(_)
+            | OpenPaths(_)
+            | CloseRequested
+            | HideWindow
+            | CancelClose
+            | ImportFinished(_)
+            | StationsFound(_)
+            | Dismiss
+            | TempoAnalyzed(..)
+            | LoudnessAnalyzed(..)
+            | MetadataLookedUp(..)
+            | Tick => true,
+            _ => false,
+        }
+    }
+}
+
 impl Kanta {
-    fn new() -> Kanta {
-        Kanta {
-            player: Player::try_new().unwrap_or_else(|e| {
-                eprintln!("Failed to initialize audio: {}", e);
-                Player::default()
-            }),
+    fn new(
+        paths: Vec<PathBuf>,
+        show_profile_picker: bool,
+        autostarted: bool,
+    ) -> (Kanta, Task<KantaMessage>) {
+        let config = Config::load();
+        i18n::set_language(config.language.as_deref());
+
+        let mut player = Player::try_new().unwrap_or_else(|e| {
+            error!("Failed to initialize audio: {}", e);
+            Player::default()
+        });
+        player.set_normalize(config.normalize_volume);
+        player.set_show_stream_titles(config.show_stream_titles);
+        player.set_resume_rewind(config.resume_rewind());
+        player.set_locked(config.party_mode);
+        player.set_volume(config.startup_volume());
+        let track_dsp = TrackDspLibrary::open()
+            .inspect_err(|e| error!("Failed to open per-track sound settings: {}", e))
+            .ok();
+        if let Some(track_dsp) = &track_dsp {
+            player.set_dsp_overrides(track_dsp.all().clone());
+        }
+
+        let mut kanta = Kanta {
+            player,
+            config,
+            collapsed_albums: HashSet::new(),
+            playlist_scroll_offset: 0.0,
+            playlist_viewport_height: DEFAULT_PLAYLIST_VIEWPORT_HEIGHT,
+            follow_now_playing: false,
+            cursor_position: Point::ORIGIN,
+            context_menu: None,
+            track_info: None,
+            stats: None,
+            sync_dialog: None,
+            export_dialog: None,
+            modifiers: keyboard::Modifiers::default(),
+            selected_tracks: BTreeSet::new(),
+            selection_anchor: None,
+            playlists: PlaylistLibrary::open()
+                .inspect_err(|e| error!("Failed to open playlist library: {}", e))
+                .ok(),
+            new_playlist_name: String::new(),
+            new_library_exclude: String::new(),
+            browser: None,
+            browsing: None,
+            stations: StationLibrary::open()
+                .inspect_err(|e| error!("Failed to open station list: {}", e))
+                .ok(),
+            new_station: (String::new(), String::new()),
+            station_query: String::new(),
+            station_results: vec![],
+            searching_stations: false,
+            renaming_playlist: None,
+            hovered_track: None,
+            dragged_tracks: None,
+            resizing_column: None,
+            queue_sort: None,
+            command_palette_query: None,
+            jump_to_time_input: None,
+            url_input: None,
+            party_pin_input: None,
+            quit_after_unlock: false,
+            cues: CueLibrary::open()
+                .inspect_err(|e| error!("Failed to open cue points: {}", e))
+                .ok(),
+            new_cue: None,
+            scrobbler: Scrobbler::default(),
+            scrobble_queue: ScrobbleQueue::open()
+                .inspect_err(|e| error!("Failed to open scrobble queue: {}", e))
+                .ok(),
+            submitting_scrobbles: false,
+            scrobble_retry_at: Instant::now(),
+            scrobble_backoff: Duration::ZERO,
+            loved_tracks: LovedTracks::open()
+                .inspect_err(|e| error!("Failed to open loved tracks: {}", e))
+                .ok(),
+            track_stats: TrackStatsLibrary::open()
+                .inspect_err(|e| error!("Failed to open track ratings: {}", e))
+                .ok(),
+            track_dsp,
+            lyrics_offsets: LyricsOffsets::open()
+                .inspect_err(|e| error!("Failed to open lyrics offsets: {}", e))
+                .ok(),
+            lyrics_search: None,
+            sound_dialog_open: false,
+            level_meters: LevelMeters::new(),
+            importing: false,
+            history: History::open()
+                .inspect_err(|e| error!("Failed to open listening history: {}", e))
+                .ok(),
+            shuffle_weights_revision: None,
+            auto_dj_revision: None,
+            auto_dj_loading: false,
+            library_index: None,
+            indexed_library: None,
+            scanning_library: false,
+            library_rescan_requested: false,
+            library_scan_report: None,
+            import_report: None,
+            library_query: String::new(),
+            library_results: vec![],
+            history_export: None,
+            lyrics_editor: None,
+            now_playing_view: false,
+            karaoke: false,
+            window_hidden: false,
+            close_prompt_open: false,
+            spectrogram: None,
+            settings_open: false,
+            profiles: paths::profiles()
+                .inspect_err(|e| error!("Failed to list profiles: {}", e))
+                .unwrap_or_default(),
+            profile_picker_open: show_profile_picker,
+            new_profile_name: String::new(),
+            album_theme_source: None,
+            album_theme: None,
+            pending_queue: SavedQueue::load()
+                .inspect_err(|e| error!("Failed to load saved queue: {}", e))
+                .ok()
+                .flatten(),
+            watched_playlist: None,
+            hook_state: (None, None, PlaybackState::Stopped),
+            plugins: Plugins::load()
+                .inspect_err(|e| error!("Failed to load plugins: {}", e))
+                .ok(),
+            scripting: Scripting::new()
+                .inspect_err(|e| error!("Failed to start scripting: {}", e))
+                .ok(),
+            script_input: None,
+            event_stream: None,
+            event_volume: 0.0,
+            web_remote: None,
+            tray: None,
+            web_remote_updated_at: None,
+            now_playing_written: None,
+            airplay_speakers: vec![],
+            searching_airplay: false,
+            position_tick_at: Instant::now(),
+            overwrite_prompt: None,
+            seen_queue_revision: 0,
+            saved_queue_revision: 0,
+            queue_changed_at: Instant::now(),
+            queue_saved_at: Instant::now(),
+            volume_changed_at: None,
+            muted_volume: None,
+            volume_osd_until: None,
+            alarm_fired_on: None,
+            alarm_fade: None,
+            jobs: Jobs::default(),
+            jobs_panel_open: false,
+            cache_usage: None,
+            analyzing_tempo: HashSet::new(),
+            tempo_failures: HashSet::new(),
+            analyzing_loudness: HashSet::new(),
+            loudness_failures: HashSet::new(),
+            looking_up_metadata: None,
+            looked_up_metadata: HashSet::new(),
             error: None,
+        };
+        if let Some(plugins) = &kanta.plugins {
+            kanta.player.set_plugin_stages(plugins.stages());
+        }
+        if let Err(e) = kanta.start_event_stream() {
+            error!("Failed to start the event stream: {}", e);
+        }
+        if let Err(e) = kanta.start_web_remote() {
+            error!("Failed to start the web remote: {}", e);
+        }
+        if let Err(e) = kanta.start_tray() {
+            error!("Failed to show the tray icon: {}", e);
         }
+
+        let task = if !paths.is_empty() {
+            Task::done(KantaMessage::OpenPaths(paths))
+        } else if autostarted && kanta.pending_queue.is_some() {
+            Task::done(KantaMessage::RestoreQueue)
+        } else {
+            Task::none()
+        };
+        let task = Task::batch([
+            task,
+            maintain_caches(kanta.config.cache_limit(), false),
+            kanta.update_snapcast(),
+        ]);
+        if autostarted && kanta.config.autostart_minimized {
+            let minimize = window::latest().and_then(|id| window::minimize(id, true));
+            return (kanta, Task::batch([task, minimize]));
+        }
+        (kanta, task)
     }
 
-    fn update(&mut self, message: KantaMessage) {
+    fn update(&mut self, message: KantaMessage) -> Task<KantaMessage> {
         use KantaMessage::*;
 
+        if self.config.party_mode && !message.is_allowed_in_party_mode() {
+            match message {
+                Quit if self.config.party_pin.is_empty() => {}
+                Quit => {
+                    self.quit_after_unlock = true;
+                    return Task::batch([self.show_window(), Task::done(StartUnlockingPartyMode)]);
+                }
+                _ => return Task::none(),
+            }
+        }
+
+        let previous_index = self.player.playlist_index();
+
+        if matches!(
+            message,
+            LoadPlaylist
+                | LoadNamedPlaylist(_)
+                | OpenRecent(_)
+                | SortPlaylist(_)
+                | SortQueueBy(_)
+                | DeduplicatePlaylist
+                | ClearPlaylist
+                | PlayNext(_)
+                | RemoveTrack(_)
+        ) {
+            self.clear_selection();
+        }
+        if matches!(
+            message,
+            JumpToTrack(_)
+                | PlayNext(_)
+                | RemoveTrack(_)
+                | ShowInFileManager(_)
+                | ShowTrackInfo(_)
+                | ExportTracks(_)
+        ) {
+            self.context_menu = None;
+        }
+
         let result = match message {
             Play => self.player.play(),
             Pause => self.player.pause(),
+            TogglePlayback => {
+                if self.player.state() == PlaybackState::Playing {
+                    self.player.pause()
+                } else {
+                    self.player.play()
+                }
+            }
+            SeekBackward => self.player.seek_backward(SEEK_STEP),
+            SeekForward => self.player.seek_forward(SEEK_STEP),
             JumpToPreviousTrack => self.player.jump_to_previous_track(),
             JumpToNextTrack => self.player.jump_to_next_track(),
             JumpToTrack(index) => self.player.jump_to_track_at(index),
-            ClearPlaylist => self.player.clear_playlist(),
+            PlayNext(index) => {
+                self.player.play_next(index);
+                Ok(())
+            }
+            RemoveTrack(index) => self.player.remove_from_playlist(&BTreeSet::from([index])),
+            ShowInFileManager(index) => match self.player.playlist().get(index) {
+                Some(track) => file_manager::show_in_file_manager(track.path()),
+                None => Ok(()),
+            },
+            ShowCurrentTrackInFileManager => match self.player.current_track() {
+                Some(track) => file_manager::show_in_file_manager(track.path()),
+                None => Ok(()),
+            },
+            ClearPlaylist => {
+                self.watched_playlist = None;
+                self.player.clear_playlist()
+            }
+            DeduplicatePlaylist => self.player.deduplicate_playlist(),
+            SetShuffle(shuffle) => self.player.set_shuffle(shuffle),
+            SortPlaylist(sort) => {
+                self.player.sort_playlist(sort, false);
+                self.queue_sort = None;
+                Ok(())
+            }
+            SortQueueBy(sort) => {
+                let descending = self
+                    .queue_sort
+                    .is_some_and(|(current, descending, revision)| {
+                        current == sort
+                            && !descending
+                            && revision == self.player.playlist_revision()
+                    });
+                self.player.sort_playlist(sort, descending);
+                self.queue_sort = Some((sort, descending, self.player.playlist_revision()));
+                Ok(())
+            }
             SetPosition(position) => self.player.set_position(Duration::from_secs_f32(position)),
             SetVolume(volume) => {
+                self.muted_volume = None;
+                self.alarm_fade = None;
                 self.player.set_volume(volume);
                 Ok(())
             }
-            Tick => self.player.tick(),
+            VolumeUp => {
+                self.change_volume(VOLUME_STEP);
+                Ok(())
+            }
+            VolumeDown => {
+                self.change_volume(-VOLUME_STEP);
+                Ok(())
+            }
+            ToggleMute => {
+                self.alarm_fade = None;
+                match self.muted_volume.take() {
+                    Some(volume) => self.player.set_volume(volume),
+                    None => {
+                        self.muted_volume = Some(self.player.volume());
+                        self.player.set_volume(0.0);
+                    }
+                }
+                self.volume_osd_until = Some(Instant::now() + VOLUME_OSD_DURATION);
+                Ok(())
+            }
+            SetPitch(semitones) => {
+                self.player.set_pitch(semitones);
+                Ok(())
+            }
+            SetSpeed(speed) => self.player.set_dsp(DspSettings {
+                speed,
+                ..self.player.dsp()
+            }),
+            SetEqGain(band, gain) => {
+                let mut dsp = self.player.dsp();
+                dsp.eq[band] = gain;
+                self.player.set_dsp(dsp)
+            }
+            TempoAnalyzed(path, bpm) => {
+                self.analyzing_tempo.remove(&path);
+                self.jobs.get(JobKind::Tempo).advance();
+                match bpm {
+                    Some(bpm) => {
+                        self.player.set_bpm(&path, bpm);
+                        analysis_cache::store_bpm(&path, bpm);
+                        Ok(())
+                    }
+                    None => {
+                        self.tempo_failures.insert(path);
+                        Ok(())
+                    }
+                }
+            }
+            MetadataLookedUp(path, metadata) => {
+                self.looking_up_metadata = None;
+                if let Some(metadata) = metadata {
+                    self.player.fill_in_metadata(&path, &metadata);
+                }
+                self.looked_up_metadata.insert(path);
+                Ok(())
+            }
+            LoudnessAnalyzed(path, loudness) => {
+                self.analyzing_loudness.remove(&path);
+                self.jobs.get(JobKind::Loudness).advance();
+                match loudness {
+                    Some(loudness) => {
+                        self.player.set_loudness(&path, loudness);
+                        analysis_cache::store_loudness(&path, loudness);
+                        Ok(())
+                    }
+                    None => {
+                        self.loudness_failures.insert(path);
+                        Ok(())
+                    }
+                }
+            }
+            Tick => {
+                self.check_watched_playlist();
+                self.autosave_queue();
+                self.check_alarm();
+                self.autosave_volume();
+                if let Err(e) = analysis_cache::save_if_due() {
+                    error!("Failed to save the analysis cache: {}", e);
+                }
+                self.update_shuffle_weights();
+                self.level_meters.update(self.player.take_levels());
+                self.update_spectrogram();
+                self.emit_position_tick();
+                self.update_web_remote();
+                self.player.tick().and_then(|()| self.record_play())
+            }
 
-            AddTrack => {
-                if let Some(path) = FileDialog::new()
-                    .set_title("Add track")
-                    .add_filter("Tracks", &["mp3", "ogg", "wav", "flac"])
-                    .pick_file()
+            ToggleAlbumGroup(key) => {
+                if !self.collapsed_albums.remove(&key) {
+                    self.collapsed_albums.insert(key);
+                }
+                return Task::none();
+            }
+
+            ScrollToNowPlaying => return self.scroll_to_now_playing(),
+
+            SetFollowNowPlaying(follow_now_playing) => {
+                self.follow_now_playing = follow_now_playing;
+                return Task::none();
+            }
+
+            OpenContextMenu(index) => {
+                self.context_menu = Some((index, self.cursor_position));
+                return Task::none();
+            }
+
+            CloseContextMenu => {
+                self.context_menu = None;
+                return Task::none();
+            }
+
+            ShowStatistics => {
+                self.stats = Some(QueueStats::compute(self.player.playlist()));
+                return Task::none();
+            }
+
+            CloseStatistics => {
+                self.stats = None;
+                return Task::none();
+            }
+
+            OpenJobsPanel => {
+                self.jobs_panel_open = true;
+                return Task::none();
+            }
+
+            CloseJobsPanel => {
+                self.jobs_panel_open = false;
+                return Task::none();
+            }
+
+            SetJobPaused(kind, paused) => {
+                self.jobs.get(kind).set_paused(paused);
+                Ok(())
+            }
+
+            CancelJob(kind) => {
+                match kind {
+                    JobKind::LibraryScan => self.jobs.get(kind).cancel(),
+                    JobKind::Loudness => {
+                        let pending = self.pending_loudness();
+                        self.loudness_failures.extend(pending);
+                    }
+                    JobKind::Tempo => {
+                        let pending = self.pending_tempo();
+                        self.tempo_failures.extend(pending);
+                    }
+                }
+                Ok(())
+            }
+
+            SetJobConcurrency(limit) => {
+                self.config.job_concurrency = limit;
+                return Task::none();
+            }
+
+            SetCacheLimit(limit_mb) => {
+                self.config.cache_limit_mb = limit_mb;
+                return Task::none();
+            }
+
+            ApplyCacheLimit => {
+                if let Err(e) = self.config.save() {
+                    error!("Failed to save settings: {}", e);
+                }
+                return maintain_caches(self.config.cache_limit(), false);
+            }
+
+            ClearCaches => {
+                self.cache_usage = None;
+                return maintain_caches(self.config.cache_limit(), true);
+            }
+
+            CacheUsageMeasured(usage) => {
+                self.cache_usage = Some(usage);
+                return Task::none();
+            }
+
+            OpenLyricsEditor => {
+                if let Some(track) = self.player.current_track() {
+                    let text = match track.lyrics() {
+                        Some(Lyrics::Plain(text)) => text.clone(),
+                        Some(Lyrics::Synced(lines)) => lines
+                            .iter()
+                            .map(|line| {
+                                format!("{}{}", lyrics::format_timestamp(line.time), line.text)
+                            })
+                            .collect::<Vec<_>>()
+                            .join("\n"),
+                        None => String::new(),
+                    };
+                    self.lyrics_editor = Some(LyricsEditor {
+                        track: track.path().to_path_buf(),
+                        content: text_editor::Content::with_text(&text),
+                        sync: None,
+                    });
+                }
+                return Task::none();
+            }
+
+            CloseLyricsEditor => {
+                self.lyrics_editor = None;
+                return Task::none();
+            }
+
+            LyricsEdited(action) => {
+                if let Some(editor) = &mut self.lyrics_editor {
+                    editor.content.perform(action);
+                }
+                return Task::none();
+            }
+
+            StartLyricsSync => {
+                if let Some(editor) = &mut self.lyrics_editor {
+                    let lines = editor
+                        .content
+                        .text()
+                        .lines()
+                        .map(|line| {
+                            let (time, text) = lyrics::split_timestamp(line);
+                            (time, text.to_string())
+                        })
+                        .collect::<Vec<_>>();
+                    let cursor = lines
+                        .iter()
+                        .position(|(time, _)| time.is_none())
+                        .unwrap_or(0);
+                    editor.sync = Some(LyricsSync { lines, cursor });
+                }
+                return Task::none();
+            }
+
+            StopLyricsSync => {
+                if let Some(editor) = &mut self.lyrics_editor {
+                    editor.stop_sync();
+                }
+                return Task::none();
+            }
+
+            StampLyricLine => {
+                let position = self.player.position();
+                let is_playing_track = self.player.current_track().map(Track::path)
+                    == self
+                        .lyrics_editor
+                        .as_ref()
+                        .map(|editor| editor.track.as_path());
+                match self
+                    .lyrics_editor
+                    .as_mut()
+                    .and_then(|editor| editor.sync.as_mut())
                 {
-                    match Track::load(path).map_err(|e| e.to_string()) {
-                        Ok(track) => self.player.add_to_playlist(track),
-                        Err(e) => {
-                            self.error = Some(e);
+                    Some(_) if !is_playing_track => Err(anyhow!(
+                        "the track whose lyrics are being edited is not playing"
+                    )),
+                    Some(sync) => {
+                        if let Some((time, _)) = sync.lines.get_mut(sync.cursor) {
+                            *time = Some(position);
+                            sync.cursor += 1;
                         }
+                        Ok(())
                     }
+                    None => Ok(()),
                 }
-                return;
             }
 
-            LoadPlaylist => {
-                if let Some(path) = FileDialog::new()
-                    .set_title("Load playlist")
-                    .add_filter("Playlists", &["m3u8"])
-                    .pick_file()
+            UnstampLyricLine => {
+                if let Some(sync) = self
+                    .lyrics_editor
+                    .as_mut()
+                    .and_then(|editor| editor.sync.as_mut())
+                    && sync.cursor > 0
                 {
-                    if let Err(e) = self.player.load_m3u8_playlist(path.as_path()) {
-                        self.error = Some(e.to_string());
-                    }
+                    sync.cursor -= 1;
+                    sync.lines[sync.cursor].0 = None;
                 }
-                return;
+                return Task::none();
             }
 
-            ExportPlaylist => {
-                if let Some(path) = FileDialog::new()
-                    .set_title("Export playlist")
-                    .add_filter("Playlists", &["m3u8"])
-                    .save_file()
+            MoveLyricCursor(delta) => {
+                if let Some(sync) = self
+                    .lyrics_editor
+                    .as_mut()
+                    .and_then(|editor| editor.sync.as_mut())
                 {
-                    if let Err(e) = self.player.export_m3u8_playlist(path.as_path()) {
-                        self.error = Some(e.to_string());
-                    }
+                    sync.cursor = sync
+                        .cursor
+                        .saturating_add_signed(delta)
+                        .min(sync.lines.len());
                 }
-                return;
+                return Task::none();
             }
-        };
 
-        if let Err(e) = result {
-            self.error = Some(e.to_string());
-        }
-    }
+            SeekToLyricLine(index) => {
+                let Some(sync) = self
+                    .lyrics_editor
+                    .as_mut()
+                    .and_then(|editor| editor.sync.as_mut())
+                else {
+                    return Task::none();
+                };
+                sync.cursor = index;
+                match sync.lines.get(index).and_then(|(time, _)| *time) {
+                    Some(time) => self.player.set_position(time),
+                    None => Ok(()),
+                }
+            }
 
-    fn view(&self) -> Element<'_, KantaMessage> {
-        use KantaMessage::*;
+            SaveLyrics => match &mut self.lyrics_editor {
+                Some(editor) => {
+                    editor.stop_sync();
+                    editor.save().map(|lyrics| {
+                        self.player.set_lyrics(&editor.track, lyrics);
+                    })
+                }
+                None => Ok(()),
+            },
 
-        macro_rules! btn {
-            ($text:expr, $message:expr) => {
-                button($text).on_press($message)
-            };
-            ($text:expr, $message:expr, $style:ident) => {
-                button($text).on_press($message).style(button::$style)
-            };
-        }
+            ToggleSpectrogram => {
+                self.spectrogram = match self.spectrogram {
+                    Some(_) => None,
+                    None => Some(Spectrogram::new()),
+                };
+                return Task::none();
+            }
 
-        let play_pause_button = if self.player.current_track().is_some() {
-            if self.player.is_paused() {
-                btn!("Play", Play)
-            } else {
-                btn!("Pause", Pause)
+            ToggleKaraoke => {
+                self.karaoke = !self.karaoke;
+                self.update_vocal_reduction();
+                return Task::none();
             }
-        } else {
-            button("Stopped")
-        };
 
-        let position_slider = match self.player.current_track() {
-            Some(track) => {
-                let elapsed = self.player.position().as_secs_f32();
-                let total = track.duration().as_secs_f32();
-                slider(0.0..=total, elapsed, SetPosition)
+            SetKaraokeReduceVocals(reduce_vocals) => {
+                self.config.karaoke_reduce_vocals = reduce_vocals;
+                self.update_vocal_reduction();
+                self.config.save()
             }
-            None => slider(0.0..=1.0, 0.0, SetPosition),
-        };
 
-        let controls = row![]
-            .push(btn!("Prev", JumpToPreviousTrack, secondary))
-            .push(play_pause_button)
-            .push(btn!("Next", JumpToNextTrack, secondary))
-            .push(text("Position"))
-            .push(position_slider)
-            .push(text("Volume"))
-            .push(slider(0.0..=1.0, self.player.volume(), SetVolume).step(0.01))
-            .spacing(8)
-            .align_y(Vertical::Center);
+            OpenSoundDialog => {
+                self.sound_dialog_open = true;
+                return Task::none();
+            }
 
-        let playlist_row_padding = Padding {
-            top: 8.0,
-            bottom: 8.0,
-            left: 0.0,
-            right: 0.0,
-        };
+            CloseSoundDialog => {
+                self.sound_dialog_open = false;
+                return Task::none();
+            }
 
-        let playlist_controls = row![]
-            .push(btn!("Add track", AddTrack, secondary))
-            .push(btn!("Load playlist", LoadPlaylist, secondary))
-            .push(btn!("Export playlist", ExportPlaylist, secondary))
-            .push(btn!("Clear playlist", ClearPlaylist, danger))
-            .spacing(8);
+            SaveTrackSound => self.save_track_sound(Some(self.player.dsp())),
 
-        let muted = MUTED_COLOR;
-        let header_field = |name| text(name).width(Length::Fill).color(muted);
-        let playlist_header = row![]
-            .push(header_field("Artist"))
-            .push(header_field("Album"))
-            .push(header_field("Title"))
-            .push(header_field("Duration"))
-            .padding(playlist_row_padding);
+            ShiftLyrics(delta) => self.set_lyrics_offset(self.lyrics_offset() + delta),
 
-        let mut playlist_tracks = column![];
-        for (index, track) in self.player.playlist().iter().enumerate() {
-            let color = if self.player.playlist_index() == Some(index) {
-                SELECTED_COLOR
-            } else {
-                Color::WHITE
-            };
+            ResetLyricsOffset => self.set_lyrics_offset(0),
 
-            macro_rules! track_field {
-                ($method:ident, $default:expr) => {
-                    text(track.$method().unwrap_or($default))
-                        .width(Length::Fill)
-                        .color(color)
-                };
-                ($content:expr) => {
-                    text($content).width(Length::Fill).color(color)
+            CopyLyrics => {
+                return match self.player.current_track().and_then(Track::lyrics) {
+                    Some(lyrics) => clipboard::write(lyrics.lines().join("\n")),
+                    None => Task::none(),
                 };
             }
 
-            let path_str = track
-                .path()
-                .file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or("Unknown");
-
-            let total_seconds = track.duration().as_secs();
-            let hours = total_seconds / 3600;
-            let minutes = (total_seconds % 3600) / 60;
-            let seconds = total_seconds % 60;
-            let duration = if hours != 0 {
-                format!("{}:{:02}:{:02}", hours, minutes, seconds)
-            } else {
-                format!("{:02}:{:02}", minutes, seconds)
-            };
+            OpenLyricsSearch => {
+                self.lyrics_search.get_or_insert(LyricsSearch {
+                    query: String::new(),
+                    current: 0,
+                });
+                return operation::focus(LYRICS_SEARCH_INPUT_ID);
+            }
 
-            playlist_tracks = playlist_tracks.push(
-                btn!(
-                    row![]
-                        .push(track_field!(artist, "No artist"))
-                        .push(track_field!(album, "No album"))
-                        .push(track_field!(title, path_str))
-                        .push(track_field!(duration))
-                        .padding(playlist_row_padding),
-                    JumpToTrack(index),
-                    text
-                )
-                .padding(0),
-            );
-        }
-        let playlist_tracks = scrollable(playlist_tracks);
+            LyricsSearchChanged(query) => {
+                self.lyrics_search = Some(LyricsSearch { query, current: 0 });
+                return self.scroll_to_lyrics_match();
+            }
 
-        let playlist = column![]
-            .push(playlist_controls)
-            .push(playlist_header)
-            .push(playlist_tracks)
-            .height(Length::Fill);
+            NextLyricsMatch => return self.step_lyrics_match(1),
+
+            PreviousLyricsMatch => return self.step_lyrics_match(-1),
 
-        let lyrics = scrollable(
-            match self.player.current_track().and_then(|track| track.lyrics()) {
-                Some(lyrics) => text(lyrics),
-                None => text("No lyrics available").center().color(muted),
+            CloseLyricsSearch => {
+                self.lyrics_search = None;
+                return Task::none();
             }
-            .width(Length::Fill)
-            .height(Length::Fill),
-        )
-        .height(Length::Fill);
 
-        column![]
-            .push(controls)
-            .push(playlist)
-            .push(lyrics)
-            .spacing(8)
-            .padding(8)
-            .into()
-    }
+            ForgetTrackSound => self
+                .save_track_sound(None)
+                .and_then(|()| self.player.restore_session_dsp()),
 
-    fn subscription(&self) -> Subscription<KantaMessage> {
-        time::every(Duration::from_millis(100)).map(|_| KantaMessage::Tick)
-    }
-}
+            OpenSyncDialog => {
+                self.sync_dialog = Some(SyncDialog {
+                    playlists: BTreeSet::new(),
+                    destination: None,
+                    transcode: false,
+                    format: transcode::Format::Opus,
+                    bitrate: 160,
+                    progress: None,
+                    finished: false,
+                });
+                return Task::none();
+            }
+
+            CloseSyncDialog => {
+                if self
+                    .sync_dialog
+                    .as_ref()
+                    .is_none_or(|dialog| dialog.progress.is_none())
+                {
+                    self.sync_dialog = None;
+                }
+                return Task::none();
+            }
+
+            ToggleSyncPlaylist(name) => {
+                if let Some(dialog) = &mut self.sync_dialog
+                    && !dialog.playlists.remove(&name)
+                {
+                    dialog.playlists.insert(name);
+                }
+                return Task::none();
+            }
+
+            ChooseSyncDestination => {
+                if let Some(dialog) = &mut self.sync_dialog
+                    && let Some(destination) = FileDialog::new()
+                        .set_title(t!("sync-destination"))
+                        .pick_folder()
+                {
+                    dialog.destination = Some(destination);
+                }
+                return Task::none();
+            }
+
+            SetSyncTranscode(transcode) => {
+                if let Some(dialog) = &mut self.sync_dialog {
+                    dialog.transcode = transcode;
+                }
+                return Task::none();
+            }
+
+            SetSyncFormat(format) => {
+                if let Some(dialog) = &mut self.sync_dialog {
+                    dialog.format = format;
+                }
+                return Task::none();
+            }
+
+            SetSyncBitrate(bitrate) => {
+                if let Some(dialog) = &mut self.sync_dialog {
+                    dialog.bitrate = bitrate;
+                }
+                return Task::none();
+            }
+
+            StartSync => {
+                let (Some(dialog), Some(playlists)) = (&mut self.sync_dialog, &self.playlists)
+                else {
+                    return Task::none();
+                };
+                let Some(destination) = dialog.destination.clone() else {
+                    return Task::none();
+                };
+                let options = SyncOptions {
+                    destination,
+                    transcode: dialog.transcode.then_some((dialog.format, dialog.bitrate)),
+                };
+                let jobs = dialog
+                    .playlists
+                    .iter()
+                    .map(|name| Ok((name.clone(), m3u8::read(&playlists.path(name))?)))
+                    .collect::<anyhow::Result<Vec<_>>>();
+                match jobs {
+                    Ok(jobs) => {
+                        let total = jobs.iter().map(|(_, tracks)| tracks.len()).sum();
+                        dialog.progress = Some((0, total));
+                        dialog.finished = false;
+
+                        let (sender, receiver) = mpsc::unbounded();
+                        thread::spawn(move || {
+                            let result = jobs.iter().try_for_each(|(name, tracks)| {
+                                sync::sync_playlist(name, tracks, &options, || {
+                                    sender.unbounded_send(KantaMessage::SyncProgress).ok();
+                                })
+                            });
+                            let error = result.err().map(|e| format!("{:#}", e));
+                            sender
+                                .unbounded_send(KantaMessage::SyncFinished(error))
+                                .ok();
+                        });
+                        return Task::run(receiver, |message| message);
+                    }
+                    Err(e) => Err(e),
+                }
+            }
+
+            ExportTracks(indices) => {
+                self.export_dialog = Some(ExportDialog {
+                    tracks: indices
+                        .into_iter()
+                        .filter_map(|index| self.player.playlist().get(index))
+                        .map(|track| track.path().to_path_buf())
+                        .collect(),
+                    format: transcode::Format::Opus,
+                    bitrate: 160,
+                    progress: None,
+                    failures: vec![],
+                    finished: false,
+                });
+                return Task::none();
+            }
+
+            CloseExportDialog => {
+                if self
+                    .export_dialog
+                    .as_ref()
+                    .is_none_or(|dialog| dialog.progress.is_none())
+                {
+                    self.export_dialog = None;
+                }
+                return Task::none();
+            }
+
+            SetExportFormat(format) => {
+                if let Some(dialog) = &mut self.export_dialog {
+                    dialog.format = format;
+                }
+                return Task::none();
+            }
+
+            SetExportBitrate(bitrate) => {
+                if let Some(dialog) = &mut self.export_dialog {
+                    dialog.bitrate = bitrate;
+                }
+                return Task::none();
+            }
+
+            StartExport => {
+                let Some(dialog) = &mut self.export_dialog else {
+                    return Task::none();
+                };
+                let Some(destination) = FileDialog::new().set_title(t!("export-as")).pick_folder()
+                else {
+                    return Task::none();
+                };
+                dialog.progress = Some((0, dialog.tracks.len()));
+                dialog.failures.clear();
+                dialog.finished = false;
+
+                let (sender, receiver) = mpsc::unbounded();
+                let tracks = dialog.tracks.clone();
+                let (format, bitrate) = (dialog.format, dialog.bitrate);
+                thread::spawn(move || {
+                    transcode::transcode_all(
+                        &tracks,
+                        &destination,
+                        format,
+                        bitrate,
+                        |_, result| {
+                            let error = result.err().map(|e| format!("{:#}", e));
+                            sender
+                                .unbounded_send(KantaMessage::ExportProgress(error))
+                                .ok();
+                        },
+                    );
+                    sender.unbounded_send(KantaMessage::ExportFinished).ok();
+                });
+                return Task::run(receiver, |message| message);
+            }
+
+            ExportProgress(error) => {
+                if let Some(dialog) = &mut self.export_dialog {
+                    if let Some((done, _)) = &mut dialog.progress {
+                        *done += 1;
+                    }
+                    dialog.failures.extend(error.clone());
+                }
+                match error {
+                    Some(error) => Err(anyhow!("export failed: {}", error)),
+                    None => Ok(()),
+                }
+            }
+
+            ExportFinished => {
+                if let Some(dialog) = &mut self.export_dialog {
+                    dialog.progress = None;
+                    dialog.finished = true;
+                }
+                return Task::none();
+            }
+
+            SyncProgress => {
+                if let Some((done, _)) = self
+                    .sync_dialog
+                    .as_mut()
+                    .and_then(|dialog| dialog.progress.as_mut())
+                {
+                    *done += 1;
+                }
+                return Task::none();
+            }
+
+            SyncFinished(error) => {
+                if let Some(dialog) = &mut self.sync_dialog {
+                    dialog.progress = None;
+                    dialog.finished = error.is_none();
+                }
+                match error {
+                    Some(error) => Err(anyhow!("sync failed: {}", error)),
+                    None => Ok(()),
+                }
+            }
+
+            ShowTrackInfo(index) => {
+                self.track_info = self.player.playlist().get(index).cloned();
+                return Task::none();
+            }
+
+            CloseTrackInfo => {
+                self.track_info = None;
+                return Task::none();
+            }
+
+            ModifiersChanged(modifiers) => {
+                self.modifiers = modifiers;
+                return Task::none();
+            }
+
+            SelectTrack(index) => {
+                if self.modifiers.shift() {
+                    let anchor = self.selection_anchor.unwrap_or(index);
+                    if !self.modifiers.command() {
+                        self.selected_tracks.clear();
+                    }
+                    self.selected_tracks
+                        .extend(anchor.min(index)..=anchor.max(index));
+                    return Task::none();
+                }
+
+                self.selection_anchor = Some(index);
+                if self.modifiers.command() {
+                    if !self.selected_tracks.remove(&index) {
+                        self.selected_tracks.insert(index);
+                    }
+                    return Task::none();
+                }
+
+                self.selected_tracks = BTreeSet::from([index]);
+                self.player.jump_to_track_at(index)
+            }
+
+            MoveTrackCursor(step) => {
+                let len = self.player.playlist().len();
+                if len == 0 {
+                    return Task::none();
+                }
+                let index = match self.selection_anchor.or(self.player.playlist_index()) {
+                    Some(index) => index.saturating_add_signed(step).min(len - 1),
+                    None => 0,
+                };
+                self.selection_anchor = Some(index);
+                self.selected_tracks = BTreeSet::from([index]);
+                return self.reveal_track(index);
+            }
+
+            PlaySelectedTrack => match self.selection_anchor {
+                Some(index) => self.player.jump_to_track_at(index),
+                None => Ok(()),
+            },
+
+            FocusNext => return operation::focus_next(),
+
+            FocusPrevious => return operation::focus_previous(),
+
+            ClearSelection => {
+                self.clear_selection();
+                return Task::none();
+            }
+
+            MoveSelectionUp => {
+                let selected_tracks = std::mem::take(&mut self.selected_tracks);
+                for index in selected_tracks {
+                    if index > 0 && !self.selected_tracks.contains(&(index - 1)) {
+                        self.player.move_in_playlist(index, index - 1);
+                        self.selected_tracks.insert(index - 1);
+                    } else {
+                        self.selected_tracks.insert(index);
+                    }
+                }
+                return Task::none();
+            }
+
+            MoveSelectionDown => {
+                let last_index = self.player.playlist().len().saturating_sub(1);
+                let selected_tracks = std::mem::take(&mut self.selected_tracks);
+                for index in selected_tracks.into_iter().rev() {
+                    if index < last_index && !self.selected_tracks.contains(&(index + 1)) {
+                        self.player.move_in_playlist(index, index + 1);
+                        self.selected_tracks.insert(index + 1);
+                    } else {
+                        self.selected_tracks.insert(index);
+                    }
+                }
+                return Task::none();
+            }
+
+            RemoveSelection => {
+                let selected_tracks = std::mem::take(&mut self.selected_tracks);
+                self.selection_anchor = None;
+                self.player.remove_from_playlist(&selected_tracks)
+            }
+
+            ExportSelection => {
+                if let Some(path) = FileDialog::new()
+                    .set_title(t!("save-selection-as-playlist"))
+                    .add_filter(t!("playlists-filter"), &["m3u8"])
+                    .save_file()
+                    && let Err(e) = self
+                        .player
+                        .export_m3u8_selection(path.as_path(), &self.selected_tracks)
+                {
+                    self.error = Some(e.to_string());
+                }
+                return Task::none();
+            }
+
+            NewPlaylistNameChanged(name) => {
+                self.new_playlist_name = name;
+                return Task::none();
+            }
+
+            CreateNamedPlaylist => match self.playlists.as_mut() {
+                Some(playlists) => {
+                    let result = playlists.create(self.new_playlist_name.trim());
+                    if result.is_ok() {
+                        self.new_playlist_name.clear();
+                    }
+                    result
+                }
+                None => Ok(()),
+            },
+
+            LoadNamedPlaylist(name) => match &self.playlists {
+                Some(playlists) => {
+                    let path = playlists.path(&name);
+                    self.player
+                        .load_m3u8_playlist(&path, self.config.hidden_tracks)
+                        .map(|()| self.watch_playlist(path))
+                }
+                None => Ok(()),
+            },
+
+            AppendNamedPlaylist(name) => match &self.playlists {
+                Some(playlists) => self.append_playlist(&playlists.path(&name)),
+                None => Ok(()),
+            },
+
+            TogglePinnedPlaylist(name) => {
+                self.config.toggle_pinned_playlist(&name);
+                self.config.save()
+            }
+
+            OpenPinnedPlaylist(slot) => {
+                match self.config.pinned_playlists.get(slot).cloned().flatten() {
+                    Some(name) if self.config.append_pinned_playlists => {
+                        return self.update(AppendNamedPlaylist(name));
+                    }
+                    Some(name) => return self.update(LoadNamedPlaylist(name)),
+                    None => Ok(()),
+                }
+            }
+
+            SetAppendPinnedPlaylists(append_pinned_playlists) => {
+                self.config.append_pinned_playlists = append_pinned_playlists;
+                self.config.save()
+            }
+
+            SetHiddenTracks(hidden_tracks) => {
+                self.config.hidden_tracks = hidden_tracks;
+                self.config.save()
+            }
+
+            StartRenamingPlaylist(name) => {
+                self.renaming_playlist = Some((name.clone(), name));
+                return Task::none();
+            }
+
+            RenamingPlaylistNameChanged(new_name) => {
+                if let Some((_, name)) = self.renaming_playlist.as_mut() {
+                    *name = new_name;
+                }
+                return Task::none();
+            }
+
+            ConfirmRenamingPlaylist => {
+                match (self.playlists.as_mut(), self.renaming_playlist.take()) {
+                    (Some(playlists), Some((name, new_name))) => {
+                        playlists.rename(&name, new_name.trim()).and_then(|()| {
+                            self.config
+                                .rename_playlist_references(&name, Some(new_name.trim()));
+                            self.config.save()
+                        })
+                    }
+                    _ => Ok(()),
+                }
+            }
+
+            CancelRenamingPlaylist => {
+                self.renaming_playlist = None;
+                return Task::none();
+            }
+
+            DeleteNamedPlaylist(name) => match self.playlists.as_mut() {
+                Some(playlists) => playlists.delete(&name).and_then(|()| {
+                    self.config.rename_playlist_references(&name, None);
+                    self.config.save()
+                }),
+                None => Ok(()),
+            },
+
+            AddToNamedPlaylist(name, index) => {
+                match (&self.playlists, self.player.playlist().get(index)) {
+                    (Some(playlists), Some(track)) => playlists.append(&name, [track.path()]),
+                    _ => Ok(()),
+                }
+            }
+
+            HoverTrack(index) => {
+                self.hovered_track = Some(index);
+                return Task::none();
+            }
+
+            UnhoverTrack(index) => {
+                if self.hovered_track == Some(index) {
+                    self.hovered_track = None;
+                }
+                return Task::none();
+            }
+
+            MousePressed => {
+                self.dragged_tracks = self.hovered_track.map(|index| {
+                    if self.selected_tracks.contains(&index) {
+                        self.selected_tracks.clone()
+                    } else {
+                        BTreeSet::from([index])
+                    }
+                });
+                return Task::none();
+            }
+
+            MouseReleased => match self.resizing_column.take() {
+                Some(_) => self.config.save(),
+                None => return Task::none(),
+            },
+
+            StartResizingColumn(column) => {
+                self.resizing_column = Some((
+                    column,
+                    self.cursor_position.x,
+                    self.config.queue_columns.width(column),
+                ));
+                return Task::none();
+            }
+
+            DropOnNamedPlaylist(name) => match (&self.playlists, self.dragged_tracks.take()) {
+                (Some(playlists), Some(indices)) => {
+                    let tracks = self.player.playlist();
+                    playlists.append(
+                        &name,
+                        track::file_paths(indices.iter().filter_map(|&i| tracks.get(i))),
+                    )
+                }
+                _ => Ok(()),
+            },
+
+            RestoreQueue => {
+                let Some(queue) = self.pending_queue.take() else {
+                    return Task::none();
+                };
+
+                let position = queue.position;
+                let mut tracks = vec![];
+                let mut index = None;
+                for (saved_index, path) in queue.tracks.into_iter().enumerate() {
+                    let segment = queue.segments.get(saved_index).copied().flatten();
+                    match Track::load_segment(path, segment) {
+                        Ok(track) => {
+                            if queue.index == Some(saved_index) {
+                                index = Some(tracks.len());
+                            }
+                            tracks.push(track);
+                        }
+                        Err(e) => error!("Failed to restore track: {}", e),
+                    }
+                }
+                self.player
+                    .restore_playlist(tracks, index)
+                    .and_then(|()| self.player.set_position(position))
+            }
+
+            MergePlaylistChanges => self.merge_playlist_changes(),
+
+            IgnorePlaylistChanges => {
+                if let Some(watched) = self.watched_playlist.as_mut()
+                    && let Some((paths, _)) = watched.change.take()
+                {
+                    watched.paths = paths;
+                }
+                return Task::none();
+            }
+
+            DiscardSavedQueue => {
+                self.pending_queue = None;
+                SavedQueue::discard()
+            }
+
+            OpenCommandPalette => {
+                self.command_palette_query = Some(String::new());
+                return operation::focus(COMMAND_PALETTE_INPUT_ID);
+            }
+
+            CloseCommandPalette => {
+                self.command_palette_query = None;
+                return Task::none();
+            }
+
+            CommandPaletteQueryChanged(query) => {
+                self.command_palette_query = Some(query);
+                return Task::none();
+            }
+
+            SubmitCommandPalette => {
+                let query = self.command_palette_query.take().unwrap_or_default();
+                return match command_palette::matching_commands(self, &query)
+                    .into_iter()
+                    .next()
+                {
+                    Some(command) => self.update(command.message),
+                    None => Task::none(),
+                };
+            }
+
+            RunCommand(message) => {
+                self.command_palette_query = None;
+                return self.update(*message);
+            }
+
+            SetLanguage(language) => {
+                i18n::set_language(language.as_deref());
+                self.config.language = language;
+                self.config.save()
+            }
+
+            StartAddingUrl => {
+                self.url_input = Some(String::new());
+                return operation::focus(URL_INPUT_ID);
+            }
+
+            UrlInputChanged(input) => {
+                self.url_input = Some(input);
+                return Task::none();
+            }
+
+            OpenScriptConsole => {
+                self.script_input = Some(String::new());
+                return operation::focus(SCRIPT_INPUT_ID);
+            }
+
+            CloseScriptConsole => {
+                self.script_input = None;
+                return Task::none();
+            }
+
+            ScriptInputChanged(input) => {
+                self.script_input = Some(input);
+                return Task::none();
+            }
+
+            RunScript => {
+                let source = self.script_input.replace(String::new()).unwrap_or_default();
+                let source = source.trim();
+                let Some(scripting) = &self.scripting else {
+                    return Task::none();
+                };
+                if source.is_empty() {
+                    return Task::none();
+                }
+                let payload = Payload::new(
+                    HookEvent::Position,
+                    self.player.state(),
+                    self.player.current_track(),
+                    self.player.position(),
+                    self.player.volume(),
+                );
+                let requests = scripting.run(source, payload);
+                self.apply_control_requests(requests);
+                Ok(())
+            }
+
+            ClearScriptOutput => {
+                if let Some(scripting) = &self.scripting {
+                    scripting.clear_output();
+                }
+                return Task::none();
+            }
+
+            SubmitUrl => {
+                let url = self.url_input.take().unwrap_or_default();
+                let url = url.trim().to_string();
+                if url.is_empty() {
+                    return Task::none();
+                }
+                return load_remote_track(url, RemoteTrackLoaded);
+            }
+
+            RemoteTrackLoaded(track) => match track {
+                Ok(track) => {
+                    self.player.add_to_playlist(*track);
+                    Ok(())
+                }
+                Err(e) => Err(anyhow!(e)),
+            },
+
+            NewStationNameChanged(name) => {
+                self.new_station.0 = name;
+                return Task::none();
+            }
+
+            NewStationUrlChanged(url) => {
+                self.new_station.1 = url;
+                return Task::none();
+            }
+
+            AddStation => match &mut self.stations {
+                Some(stations) => {
+                    let (name, url) = &self.new_station;
+                    stations.add(name, url).map(|()| {
+                        self.new_station = (String::new(), String::new());
+                    })
+                }
+                None => Ok(()),
+            },
+
+            ImportStations => match (
+                &mut self.stations,
+                FileDialog::new()
+                    .set_title(t!("import-stations"))
+                    .add_filter(t!("stations-filter"), &["pls", "m3u", "m3u8"])
+                    .pick_file(),
+            ) {
+                (Some(stations), Some(path)) => stations.import(&path).and_then(|count| {
+                    if count == 0 {
+                        Err(anyhow!("no new stations found in {}", path.display()))
+                    } else {
+                        Ok(())
+                    }
+                }),
+                _ => Ok(()),
+            },
+
+            PlayStation(url) => return load_remote_track(url, StationLoaded),
+
+            StationLoaded(track) => match track {
+                Ok(track) => {
+                    self.player.add_to_playlist(*track);
+                    self.player
+                        .jump_to_track_at(self.player.playlist().len() - 1)
+                }
+                Err(e) => Err(anyhow!(e)),
+            },
+
+            ToggleFavoriteStation(url) => match &mut self.stations {
+                Some(stations) => stations.toggle_favorite(&url),
+                None => Ok(()),
+            },
+
+            RemoveStation(url) => match &mut self.stations {
+                Some(stations) => stations.remove(&url),
+                None => Ok(()),
+            },
+
+            StationQueryChanged(query) => {
+                self.station_query = query;
+                if self.station_query.trim().is_empty() {
+                    self.station_results.clear();
+                }
+                return Task::none();
+            }
+
+            SearchStations => {
+                let query = self.station_query.trim().to_string();
+                if query.is_empty() || self.searching_stations {
+                    return Task::none();
+                }
+                self.searching_stations = true;
+                let (sender, receiver) = oneshot::channel();
+                thread::spawn(move || {
+                    sender
+                        .send(stations::search(&query).map_err(|e| format!("{:#}", e)))
+                        .ok();
+                });
+                return Task::perform(
+                    async move {
+                        receiver
+                            .await
+                            .unwrap_or_else(|_| Err("search was cancelled".to_string()))
+                    },
+                    StationsFound,
+                );
+            }
+
+            StationsFound(results) => {
+                self.searching_stations = false;
+                results.map_err(|e| anyhow!(e)).and_then(|results| {
+                    if results.is_empty() {
+                        Err(anyhow!("no stations found for \"{}\"", self.station_query))
+                    } else {
+                        self.station_results = results;
+                        Ok(())
+                    }
+                })
+            }
+
+            AddFoundStation(index) => match (&mut self.stations, self.station_results.get(index)) {
+                (Some(stations), Some(station)) => stations.add(&station.name, &station.url),
+                _ => Ok(()),
+            },
+
+            StartJumpToTime => {
+                self.jump_to_time_input = Some(String::new());
+                return operation::focus(JUMP_TO_TIME_INPUT_ID);
+            }
+
+            JumpToTimeInputChanged(input) => {
+                self.jump_to_time_input = Some(input);
+                return Task::none();
+            }
+
+            SubmitJumpToTime => {
+                let input = self.jump_to_time_input.take().unwrap_or_default();
+                match parse_timestamp(&input) {
+                    Some(position) => self.player.set_position(position),
+                    None => Err(anyhow!("invalid timestamp \"{}\"", input)),
+                }
+            }
+
+            StartAddingCue => {
+                let count = self
+                    .player
+                    .current_track()
+                    .zip(self.cues.as_ref())
+                    .map(|(track, cues)| cues.cues(track.path()).len())
+                    .unwrap_or_default();
+                self.new_cue = Some((
+                    self.player.position(),
+                    t!("default-cue-name", number = count + 1),
+                ));
+                return operation::focus(CUE_NAME_INPUT_ID);
+            }
+
+            NewCueNameChanged(name) => {
+                if let Some((_, new_name)) = &mut self.new_cue {
+                    *new_name = name;
+                }
+                return Task::none();
+            }
+
+            AddCue => match (
+                self.new_cue.take(),
+                self.player.current_track(),
+                &mut self.cues,
+            ) {
+                (Some((position, name)), Some(track), Some(cues)) => {
+                    cues.add(track.path(), &name, position)
+                }
+                _ => Ok(()),
+            },
+
+            JumpToCue(index) => {
+                let cue = self
+                    .player
+                    .current_track()
+                    .zip(self.cues.as_ref())
+                    .and_then(|(track, cues)| cues.cues(track.path()).get(index));
+                match cue.map(|cue| cue.position) {
+                    Some(position) => self.player.set_position(position),
+                    None => Ok(()),
+                }
+            }
+
+            RemoveCue(index) => match (self.player.current_track(), &mut self.cues) {
+                (Some(track), Some(cues)) => cues.remove(track.path(), index),
+                _ => Ok(()),
+            },
+
+            ToggleNowPlayingView => {
+                self.now_playing_view = !self.now_playing_view;
+                let mode = if self.now_playing_view {
+                    window::Mode::Fullscreen
+                } else {
+                    window::Mode::Windowed
+                };
+                return window::latest().and_then(move |id| window::set_mode(id, mode));
+            }
+
+            OpenSettings => {
+                self.settings_open = true;
+                return maintain_caches(self.config.cache_limit(), false);
+            }
+
+            CloseSettings => {
+                self.settings_open = false;
+                return Task::none();
+            }
+
+            SetTextSize(text_size) => {
+                self.config.text_size = text_size;
+                return Task::none();
+            }
+
+            SetUiScale(ui_scale) => {
+                self.config.ui_scale = ui_scale;
+                return Task::none();
+            }
+
+            SetAlbumColors(album_colors) => {
+                self.config.album_colors = album_colors;
+                self.config.save()
+            }
+
+            SetNormalizeVolume(normalize_volume) => {
+                self.config.normalize_volume = normalize_volume;
+                self.player.set_normalize(normalize_volume);
+                self.config.save()
+            }
+
+            SetShowStreamTitles(show_stream_titles) => {
+                self.config.show_stream_titles = show_stream_titles;
+                self.player.set_show_stream_titles(show_stream_titles);
+                self.config.save()
+            }
+
+            SetResumeRewind(resume_rewind) => {
+                self.config.resume_rewind = resume_rewind;
+                self.player.set_resume_rewind(self.config.resume_rewind());
+                self.config.save()
+            }
+
+            SetResumeRewindSeconds(seconds) => {
+                let Ok(seconds) = seconds.parse() else {
+                    return Task::none();
+                };
+                self.config.resume_rewind_secs = seconds;
+                self.player.set_resume_rewind(self.config.resume_rewind());
+                self.config.save()
+            }
+
+            SetAlarm(alarm) => {
+                self.config.alarm.enabled = alarm;
+                self.config.save()
+            }
+
+            SetAlarmTime(time) => {
+                self.config.alarm.time = time;
+                self.alarm_fired_on = None;
+                self.config.save()
+            }
+
+            SetAlarmPlaylist(playlist) => {
+                self.config.alarm.playlist = playlist;
+                self.config.save()
+            }
+
+            SetShowLevelMeters(show_level_meters) => {
+                self.config.show_level_meters = show_level_meters;
+                self.config.save()
+            }
+
+            SetYieldToCalls(yield_to_calls) => {
+                self.config.yield_to_calls = yield_to_calls;
+                self.config.save()
+            }
+
+            SetCloseToBackground(close_to_background) => {
+                self.config.close_to_background = close_to_background;
+                self.config.save().and_then(|()| self.start_tray())
+            }
+
+            SetAutostart(autostart) => autostart::set_enabled(autostart).and_then(|()| {
+                self.config.autostart = autostart;
+                self.config.save()
+            }),
+
+            SetRememberVolume(remember_volume) => {
+                self.config.remember_volume = remember_volume;
+                self.config.volume = self.player.volume();
+                self.config.save()
+            }
+
+            SetLaunchVolume(launch_volume) => {
+                self.config.launch_volume = launch_volume;
+                return Task::none();
+            }
+
+            SetAutostartMinimized(autostart_minimized) => {
+                self.config.autostart_minimized = autostart_minimized;
+                self.config.save()
+            }
+
+            SetHookCommand(command) => {
+                self.config.hook_command = command;
+                self.config.save()
+            }
+
+            SetEventStream(event_stream) => {
+                self.config.event_stream = event_stream;
+                self.config.save().and_then(|()| self.start_event_stream())
+            }
+
+            SetWebRemote(web_remote) => {
+                self.config.web_remote = web_remote;
+                self.config.save().and_then(|()| self.start_web_remote())
+            }
+
+            SetWebRemotePort(port) => {
+                let Ok(port) = port.parse() else {
+                    return Task::none();
+                };
+                self.config.web_remote_port = port;
+                self.config.save().and_then(|()| self.start_web_remote())
+            }
+
+            RegenerateWebRemoteToken => {
+                self.config.web_remote_token.clear();
+                self.start_web_remote()
+            }
+
+            SetSnapcast(snapcast) => {
+                self.config.snapcast = snapcast;
+                if let Err(e) = self.config.save() {
+                    self.error = Some(e.to_string());
+                }
+                return self.update_snapcast();
+            }
+
+            SetSnapcastAddress(address) => {
+                self.config.snapcast_address = address;
+                self.config.save()
+            }
+
+            ConnectSnapcast => {
+                if !self.config.snapcast {
+                    return Task::none();
+                }
+                return self.update_snapcast();
+            }
+
+            SnapcastConnected(Err(e)) => {
+                self.config.snapcast = false;
+                self.config.save().and(Err(anyhow!(e)))
+            }
+
+            SnapcastConnected(Ok(output)) => match output.take() {
+                Some(output) if self.config.snapcast => self.player.set_output(Some(output)),
+                _ => Ok(()),
+            },
+
+            FindAirPlaySpeakers => {
+                if self.searching_airplay {
+                    return Task::none();
+                }
+                self.searching_airplay = true;
+                let (sender, receiver) = oneshot::channel();
+                thread::spawn(move || {
+                    sender
+                        .send(airplay::discover().map_err(|e| format!("{:#}", e)))
+                        .ok();
+                });
+                return Task::perform(
+                    async move {
+                        receiver
+                            .await
+                            .unwrap_or_else(|_| Err("search was cancelled".to_string()))
+                    },
+                    AirPlaySpeakersFound,
+                );
+            }
+
+            AirPlaySpeakersFound(speakers) => {
+                self.searching_airplay = false;
+                speakers.map_err(|e| anyhow!(e)).and_then(|speakers| {
+                    self.airplay_speakers = speakers;
+                    if self.airplay_speakers.is_empty() {
+                        Err(anyhow!("no AirPlay speakers found"))
+                    } else {
+                        Ok(())
+                    }
+                })
+            }
+
+            SelectAirPlaySpeaker(speaker) => {
+                return connect_output(
+                    move || AirPlayOutput::connect(speaker).map(ExternalOutput::AirPlay),
+                    AirPlayConnected,
+                );
+            }
+
+            AirPlayConnected(output) => output.map_err(|e| anyhow!(e)).and_then(|output| {
+                let Some(output) = output.take() else {
+                    return Ok(());
+                };
+                self.player.set_output(Some(output))?;
+                self.config.snapcast = false;
+                self.config.save()
+            }),
+
+            DisconnectAirPlay => self.player.set_output(None),
+
+            ChooseNowPlayingFile => match FileDialog::new()
+                .set_title(t!("now-playing-file"))
+                .add_filter(t!("text-filter"), &["txt"])
+                .add_filter("JSON", &["json"])
+                .set_file_name("now-playing.txt")
+                .save_file()
+            {
+                Some(path) => {
+                    self.config.now_playing_file = Some(path);
+                    self.now_playing_written = None;
+                    self.config.save()
+                }
+                None => Ok(()),
+            },
+
+            StopNowPlayingFile => {
+                self.config.now_playing_file = None;
+                self.config.save()
+            }
+
+            SetEventStreamPort(port) => {
+                let Ok(port) = port.parse() else {
+                    return Task::none();
+                };
+                self.config.event_stream_port = port;
+                self.config.save().and_then(|()| self.start_event_stream())
+            }
+
+            SetScrobbleServer(server) => {
+                self.config.scrobble.server = server;
+                self.config.save()
+            }
+
+            SetScrobbleToken(token) => {
+                self.config.scrobble.token = token;
+                self.config.save()
+            }
+
+            SetScrobblePercent(min_percent) => {
+                self.config.scrobble.min_percent = min_percent;
+                return Task::none();
+            }
+
+            SetScrobbleMinDuration(min_duration_secs) => {
+                self.config.scrobble.min_duration_secs = min_duration_secs;
+                return Task::none();
+            }
+
+            SetShuffleAvoidRecent(hours) => {
+                self.config.shuffle_avoid_recent_hours = hours;
+                self.shuffle_weights_revision = None;
+                return Task::none();
+            }
+
+            SetShuffleWeighting(weighting) => {
+                self.config.shuffle_weighting = weighting;
+                self.shuffle_weights_revision = None;
+                self.config.save()
+            }
+
+            SetAutoDj(auto_dj) => {
+                self.config.auto_dj = auto_dj;
+                self.auto_dj_revision = None;
+                self.config.save()
+            }
+
+            SetPartyPin(pin) => {
+                self.config.party_pin = pin;
+                self.config.save()
+            }
+
+            LockPartyMode => {
+                self.config.party_mode = true;
+                self.player.set_locked(true);
+                self.settings_open = false;
+                self.profile_picker_open = false;
+                self.context_menu = None;
+                self.config.save()
+            }
+
+            StartUnlockingPartyMode => {
+                if !self.config.party_pin.is_empty() {
+                    self.party_pin_input = Some(String::new());
+                    return operation::focus(PARTY_PIN_INPUT_ID);
+                }
+                self.config.party_mode = false;
+                self.player.set_locked(false);
+                self.config.save()
+            }
+
+            PartyPinInputChanged(input) => {
+                self.party_pin_input = Some(input);
+                return Task::none();
+            }
+
+            UnlockPartyMode => {
+                let quit = std::mem::take(&mut self.quit_after_unlock);
+                if self.party_pin_input.take().as_deref() != Some(self.config.party_pin.as_str()) {
+                    Err(anyhow!("wrong PIN"))
+                } else {
+                    self.config.party_mode = false;
+                    self.player.set_locked(false);
+                    if quit {
+                        return Task::done(Quit);
+                    }
+                    self.config.save()
+                }
+            }
+
+            AutoDjLoaded(tracks) => {
+                self.auto_dj_loading = false;
+                for track in tracks {
+                    self.player.add_to_playlist(track);
+                }
+                Ok(())
+            }
+
+            LibraryIndexed(library, index) => {
+                if self.indexed_library.as_ref() == Some(&library) {
+                    self.scanning_library = false;
+                    self.jobs.get(JobKind::LibraryScan).finish();
+                    let rescan_requested = std::mem::take(&mut self.library_rescan_requested);
+                    if let Some(index) = index {
+                        let report = index.report();
+                        self.library_scan_report =
+                            (report.has_changes() || rescan_requested).then_some(report);
+                        self.library_index = Some(index);
+                        self.search_library();
+                    }
+                }
+                return Task::none();
+            }
+
+            RescanLibrary => {
+                if !self.scanning_library {
+                    self.indexed_library = None;
+                    self.library_rescan_requested = true;
+                    self.library_scan_report = None;
+                }
+                return Task::none();
+            }
+
+            LibraryQueryChanged(query) => {
+                self.library_query = query;
+                self.search_library();
+                return Task::none();
+            }
+
+            AddScrobbleIgnoredFolder => match FileDialog::new()
+                .set_title(t!("add-ignored-folder"))
+                .pick_folder()
+            {
+                Some(folder) if !self.config.scrobble.ignored_folders.contains(&folder) => {
+                    self.config.scrobble.ignored_folders.push(folder);
+                    self.config.save()
+                }
+                _ => Ok(()),
+            },
+
+            RemoveScrobbleIgnoredFolder(folder) => {
+                self.config
+                    .scrobble
+                    .ignored_folders
+                    .retain(|f| *f != folder);
+                self.config.save()
+            }
+
+            RetryScrobbles => {
+                self.scrobble_retry_at = Instant::now();
+                Ok(())
+            }
+
+            ScrobblesSubmitted(listens, result) => {
+                self.submitting_scrobbles = false;
+                match result {
+                    Ok(()) => {
+                        self.scrobble_backoff = Duration::ZERO;
+                        match &mut self.scrobble_queue {
+                            Some(queue) => queue.remove(&listens),
+                            None => Ok(()),
+                        }
+                    }
+                    Err(e) => {
+                        self.scrobble_backoff = (self.scrobble_backoff * 2)
+                            .clamp(MIN_SCROBBLE_BACKOFF, MAX_SCROBBLE_BACKOFF);
+                        self.scrobble_retry_at = Instant::now() + self.scrobble_backoff;
+                        error!(
+                            "Failed to submit {} listens, retrying in {:?}: {}",
+                            listens.len(),
+                            self.scrobble_backoff,
+                            e
+                        );
+                        return Task::none();
+                    }
+                }
+            }
+
+            ToggleLoveCurrentTrack => match (&mut self.loved_tracks, self.player.current_track()) {
+                (Some(loved_tracks), Some(track)) => {
+                    loved_tracks.toggle(track.path()).map(|loved| {
+                        let settings = self.config.scrobble.clone();
+                        if !settings.is_enabled() {
+                            return;
+                        }
+                        let Some(mbid) = track.recording_mbid().map(str::to_string) else {
+                            info!(
+                                "Not sending feedback for {}: it has no MusicBrainz recording ID",
+                                track.path().display()
+                            );
+                            return;
+                        };
+                        thread::spawn(move || {
+                            if let Err(e) = scrobble::love(&settings, &mbid, loved) {
+                                error!("Failed to send feedback: {:#}", e);
+                            }
+                        });
+                    })
+                }
+                _ => Ok(()),
+            },
+
+            SaveSettings => self.config.save(),
+
+            AddMusicFolder => match FileDialog::new()
+                .set_title(t!("add-music-folder"))
+                .pick_folder()
+            {
+                Some(folder) if !self.config.music_folders.contains(&folder) => {
+                    self.config.music_folders.push(folder);
+                    self.config.save()
+                }
+                _ => Ok(()),
+            },
+
+            ExcludeLibraryFolder => match FileDialog::new()
+                .set_title(t!("exclude-folder"))
+                .pick_folder()
+            {
+                Some(folder) => {
+                    let folder = folder.to_string_lossy().into_owned();
+                    if !self.config.library_excludes.contains(&folder) {
+                        self.config.library_excludes.push(folder);
+                    }
+                    self.config.save()
+                }
+                None => Ok(()),
+            },
+
+            LibraryExcludeInputChanged(pattern) => {
+                self.new_library_exclude = pattern;
+                return Task::none();
+            }
+
+            AddLibraryExclude => {
+                let pattern = std::mem::take(&mut self.new_library_exclude);
+                let pattern = pattern.trim();
+                if !pattern.is_empty() && !self.config.library_excludes.iter().any(|p| p == pattern)
+                {
+                    self.config.library_excludes.push(pattern.to_string());
+                }
+                self.config.save()
+            }
+
+            RemoveLibraryExclude(pattern) => {
+                self.config.library_excludes.retain(|p| *p != pattern);
+                self.config.save()
+            }
+
+            RemoveMusicFolder(folder) => {
+                self.config.music_folders.retain(|f| *f != folder);
+                if self
+                    .browser
+                    .as_ref()
+                    .is_some_and(|(dir, _)| dir.starts_with(&folder))
+                {
+                    self.browser = None;
+                }
+                self.config.save()
+            }
+
+            BrowseFolder(dir) => {
+                self.browsing = dir.clone();
+                match dir {
+                    Some(dir) => return list_folder(dir),
+                    None => {
+                        self.browser = None;
+                        Ok(())
+                    }
+                }
+            }
+
+            FolderListed(dir, entries) => {
+                if self.browsing.as_ref() != Some(&dir) {
+                    return Task::none();
+                }
+                self.browsing = None;
+                entries
+                    .map(|entries| self.browser = Some((dir, entries)))
+                    .map_err(|e| anyhow!(e))
+            }
+
+            EnqueuePath(path) => {
+                if path.is_dir() {
+                    self.add_folder(path)
+                } else {
+                    self.add_track(path)
+                }
+            }
+
+            OpenPaths(paths) => {
+                let first_new_index = self.player.playlist().len();
+                for path in paths {
+                    let result = if m3u8::is_playlist(&path) {
+                        self.append_playlist(&path)
+                            .and_then(|()| self.config.add_recent(RecentItem::Playlist(path)))
+                    } else {
+                        self.add_track(path)
+                    };
+                    if let Err(e) = result {
+                        error!("Failed to open file: {:#}", e);
+                    }
+                }
+
+                if self.player.current_track().is_none()
+                    && first_new_index < self.player.playlist().len()
+                    && let Err(e) = self.player.jump_to_track_at(first_new_index)
+                {
+                    error!("Failed to play opened file: {:#}", e);
+                }
+                return self.show_window();
+            }
+
+            CloseRequested => {
+                if self.config.party_mode {
+                    return self.hide_window();
+                }
+                if !self.config.close_to_background {
+                    return Task::done(Quit);
+                }
+                if !self.config.close_to_background_explained && self.tray.is_none() {
+                    self.close_prompt_open = true;
+                    return window::latest().and_then(window::gain_focus);
+                }
+                return Task::done(HideWindow);
+            }
+
+            HideWindow => {
+                self.close_prompt_open = false;
+                if !self.config.close_to_background_explained
+                    && let Some(tray) = &self.tray
+                    && let Err(e) = tray.notify(
+                        &t!("close-to-background"),
+                        &t!("close-to-background-tray-explanation"),
+                    )
+                {
+                    error!("Failed to show the tray notification: {}", e);
+                }
+                self.config.close_to_background_explained = true;
+                if let Err(e) = self.config.save() {
+                    error!("Failed to save config: {}", e);
+                }
+                return self.hide_window();
+            }
+
+            CancelClose => {
+                self.close_prompt_open = false;
+                return Task::none();
+            }
+
+            Quit => {
+                self.flush_queue();
+                if let Err(e) = analysis_cache::save() {
+                    error!("Failed to save the analysis cache: {}", e);
+                }
+                if let Err(e) = self.config.save() {
+                    error!("Failed to save config: {}", e);
+                }
+                if let Err(e) = self.player.shut_down() {
+                    error!("Failed to shut down the player: {}", e);
+                }
+                if let Err(e) = instance::unregister() {
+                    error!("Failed to remove the instance file: {}", e);
+                }
+                return iced::exit();
+            }
+
+            RegisterFileAssociations => {
+                self.config.file_associations_prompted = true;
+                file_associations::register().and_then(|()| self.config.save())
+            }
+
+            DismissFileAssociationPrompt => {
+                self.config.file_associations_prompted = true;
+                self.config.save()
+            }
+
+            OpenProfilePicker => {
+                self.settings_open = false;
+                self.profile_picker_open = true;
+                return Task::none();
+            }
+
+            CloseProfilePicker => {
+                self.profile_picker_open = false;
+                return Task::none();
+            }
+
+            SwitchProfile(profile) => {
+                self.profile_picker_open = false;
+                if profile == paths::profile() {
+                    return Task::none();
+                }
+                self.flush_queue();
+                paths::set_profile(profile);
+                self.load_profile()
+            }
+
+            NewProfileNameChanged(name) => {
+                self.new_profile_name = name;
+                return Task::none();
+            }
+
+            CreateProfile => {
+                let name = std::mem::take(&mut self.new_profile_name);
+                let name = name.trim();
+                paths::create_profile(name).and_then(|()| {
+                    self.profiles = paths::profiles()?;
+                    self.flush_queue();
+                    paths::set_profile(Some(name.to_string()));
+                    self.load_profile()
+                })
+            }
+
+            ExportBackup => match FileDialog::new()
+                .set_title(t!("export-backup"))
+                .add_filter(t!("backups-filter"), &["json"])
+                .set_file_name("kanta-backup.json")
+                .save_file()
+            {
+                Some(path) => backup::export(
+                    &path,
+                    &self.config,
+                    SavedQueue::new(
+                        self.player.playlist(),
+                        self.player.playlist_index(),
+                        self.player.position(),
+                    ),
+                ),
+                None => Ok(()),
+            },
+
+            ImportBackup => match FileDialog::new()
+                .set_title(t!("import-backup"))
+                .add_filter(t!("backups-filter"), &["json"])
+                .pick_file()
+            {
+                Some(path) => backup::import(&path).and_then(|()| self.load_profile()),
+                None => Ok(()),
+            },
+
+            OpenHistoryExport => {
+                self.settings_open = false;
+                self.history_export = Some(HistoryExport {
+                    contents: ExportContents::History,
+                    format: ExportFormat::Csv,
+                    from: String::new(),
+                    until: String::new(),
+                    exported: None,
+                });
+                return Task::none();
+            }
+
+            CloseHistoryExport => {
+                self.history_export = None;
+                return Task::none();
+            }
+
+            SetHistoryExportContents(contents) => {
+                if let Some(export) = &mut self.history_export {
+                    export.contents = contents;
+                    export.exported = None;
+                }
+                return Task::none();
+            }
+
+            SetHistoryExportFormat(format) => {
+                if let Some(export) = &mut self.history_export {
+                    export.format = format;
+                    export.exported = None;
+                }
+                return Task::none();
+            }
+
+            HistoryExportFromChanged(from) => {
+                if let Some(export) = &mut self.history_export {
+                    export.from = from;
+                    export.exported = None;
+                }
+                return Task::none();
+            }
+
+            HistoryExportUntilChanged(until) => {
+                if let Some(export) = &mut self.history_export {
+                    export.until = until;
+                    export.exported = None;
+                }
+                return Task::none();
+            }
+
+            StartHistoryExport => match (&mut self.history_export, &self.history) {
+                (Some(export), Some(history)) => DateRange::parse(&export.from, &export.until)
+                    .and_then(|range| {
+                        let Some(path) = FileDialog::new()
+                            .set_title(t!("export-history"))
+                            .add_filter(export.format.to_string(), &[export.format.extension()])
+                            .set_file_name(format!(
+                                "{}.{}",
+                                t!("history-file-name"),
+                                export.format.extension()
+                            ))
+                            .save_file()
+                        else {
+                            return Ok(());
+                        };
+                        export.exported = Some(history::export(
+                            &path,
+                            export.contents,
+                            export.format,
+                            range,
+                            history,
+                            self.track_stats.as_ref(),
+                            self.loved_tracks.as_ref(),
+                        )?);
+                        Ok(())
+                    }),
+                _ => Ok(()),
+            },
+
+            ImportFromOtherPlayer => {
+                let Some(path) = FileDialog::new()
+                    .set_title(t!("import-from-other-player"))
+                    .add_filter(t!("import-filter"), &import::EXTENSIONS)
+                    .pick_file()
+                else {
+                    return Task::none();
+                };
+                self.importing = true;
+                let music_folders = self.config.music_folders.clone();
+                let (sender, receiver) = oneshot::channel();
+                thread::spawn(move || {
+                    let result = import::run(&path, &music_folders)
+                        .map(Box::new)
+                        .map_err(|e| format!("{:#}", e));
+                    sender.send(result).ok();
+                });
+                return Task::perform(
+                    async move {
+                        receiver
+                            .await
+                            .unwrap_or_else(|_| Err("import was cancelled".to_string()))
+                    },
+                    ImportFinished,
+                );
+            }
+
+            ImportFinished(result) => {
+                self.importing = false;
+                result.map_err(|e| anyhow!(e)).and_then(|imported| {
+                    self.import_report = Some(imported.report());
+                    if let Some(playlists) = &mut self.playlists {
+                        for (name, tracks) in &imported.playlists {
+                            let name = playlists.unused_name(name);
+                            playlists.import(&name, tracks)?;
+                        }
+                    }
+                    match &mut self.track_stats {
+                        Some(track_stats) => track_stats.merge(imported.stats),
+                        None => Ok(()),
+                    }
+                })
+            }
+
+            OpenLogFolder => paths::log_dir().and_then(|dir| file_manager::open(&dir)),
+
+            Dismiss => {
+                self.settings_open = false;
+                self.profile_picker_open = false;
+                self.command_palette_query = None;
+                self.jump_to_time_input = None;
+                self.url_input = None;
+                self.party_pin_input = None;
+                self.quit_after_unlock = false;
+                self.new_cue = None;
+                self.context_menu = None;
+                self.track_info = None;
+                self.stats = None;
+                self.jobs_panel_open = false;
+                self.sound_dialog_open = false;
+                self.lyrics_search = None;
+                self.close_prompt_open = false;
+                self.overwrite_prompt = None;
+                self.history_export = None;
+                self.script_input = None;
+                if self
+                    .sync_dialog
+                    .as_ref()
+                    .is_some_and(|dialog| dialog.progress.is_none())
+                {
+                    self.sync_dialog = None;
+                }
+                if self
+                    .export_dialog
+                    .as_ref()
+                    .is_some_and(|dialog| dialog.progress.is_none())
+                {
+                    self.export_dialog = None;
+                }
+                return Task::none();
+            }
+
+            CursorMoved(position) => {
+                self.cursor_position = position;
+                if let Some((column, start_x, start_width)) = self.resizing_column {
+                    self.config
+                        .queue_columns
+                        .set_width(column, start_width - (position.x - start_x));
+                }
+                return Task::none();
+            }
+
+            PlaylistScrolled(viewport) => {
+                self.playlist_scroll_offset = viewport.absolute_offset().y;
+                self.playlist_viewport_height = viewport.bounds().height;
+                return Task::none();
+            }
+
+            AddTrack => match FileDialog::new()
+                .set_title(t!("add-track"))
+                .add_filter(t!("tracks-filter"), &TRACK_EXTENSIONS)
+                .pick_file()
+            {
+                Some(path) => self.add_track(path),
+                None => Ok(()),
+            },
+
+            AppendPlaylist => match FileDialog::new()
+                .set_title(t!("append-playlist"))
+                .add_filter(t!("playlists-filter"), &m3u8::EXTENSIONS)
+                .pick_file()
+            {
+                Some(path) => self
+                    .append_playlist(&path)
+                    .and_then(|()| self.config.add_recent(RecentItem::Playlist(path))),
+                None => Ok(()),
+            },
+
+            LoadPlaylist => match FileDialog::new()
+                .set_title(t!("load-playlist"))
+                .add_filter(t!("playlists-filter"), &m3u8::EXTENSIONS)
+                .pick_file()
+            {
+                Some(path) => self.load_playlist(path),
+                None => Ok(()),
+            },
+
+            OpenRecent(item) => match item {
+                RecentItem::Track(path) => self.add_track(path),
+                RecentItem::Playlist(path) => self.load_playlist(path),
+                RecentItem::Folder(path) => self.add_folder(path),
+            },
+
+            ExportPlaylist => match FileDialog::new()
+                .set_title(t!("export-playlist"))
+                .add_filter(t!("playlists-filter"), &["m3u8"])
+                .save_file()
+            {
+                Some(path) if path.extension().is_none() => {
+                    self.save_playlist_checked(path.with_extension("m3u8"))
+                }
+                Some(path) => self.save_playlist(path),
+                None => Ok(()),
+            },
+
+            SavePlaylist => match self.saved_playlist_path() {
+                Some(path)
+                    if self
+                        .watched_playlist
+                        .as_ref()
+                        .is_some_and(|watched| watched.change.is_some()) =>
+                {
+                    self.overwrite_prompt = Some(path);
+                    Ok(())
+                }
+                Some(path) => self.save_playlist(path),
+                None => return Task::done(ExportPlaylist),
+            },
+
+            ConfirmOverwrite => match self.overwrite_prompt.take() {
+                Some(path) => self.save_playlist(path),
+                None => Ok(()),
+            },
+
+            CancelOverwrite => {
+                self.overwrite_prompt = None;
+                return Task::none();
+            }
+        };
+
+        if let Err(e) = result {
+            error!("{:#}", e);
+            self.error = Some(e.to_string());
+        }
+
+        self.update_album_theme();
+        self.run_hooks();
+        self.write_now_playing();
+        self.emit_volume_change();
+        crash::set_playback_context(
+            self.player
+                .current_track()
+                .map(|track| track.path().to_path_buf()),
+            self.player.playlist().len(),
+        );
+
+        let analysis = Task::batch([
+            self.analyze_next_tempo(),
+            self.analyze_next_loudness(),
+            self.look_up_next_metadata(),
+            self.submit_scrobbles(),
+            self.continue_with_auto_dj(),
+            self.handle_media_requests(),
+            self.handle_tray_events(),
+            self.index_library(),
+        ]);
+        if self.follow_now_playing && self.player.playlist_index() != previous_index {
+            return Task::batch([self.scroll_to_now_playing(), analysis]);
+        }
+        analysis
+    }
+
+    fn hide_window(&mut self) -> Task<KantaMessage> {
+        self.window_hidden = true;
+        window::latest().and_then(|id| window::set_mode(id, window::Mode::Hidden))
+    }
+
+    fn show_window(&mut self) -> Task<KantaMessage> {
+        let focus = window::latest().and_then(window::gain_focus);
+        if !self.window_hidden {
+            return focus;
+        }
+        self.window_hidden = false;
+        let mode = if self.now_playing_view {
+            window::Mode::Fullscreen
+        } else {
+            window::Mode::Windowed
+        };
+        window::latest()
+            .and_then(move |id| window::set_mode(id, mode))
+            .chain(focus)
+    }
+
+    fn handle_media_requests(&mut self) -> Task<KantaMessage> {
+        let mut paths = vec![];
+        let mut tasks = vec![];
+        for request in self.player.take_media_requests() {
+            match request {
+                MediaRequest::Open(path) => paths.push(path),
+                MediaRequest::Raise => tasks.push(self.show_window()),
+                MediaRequest::Quit => tasks.push(Task::done(KantaMessage::Quit)),
+            }
+        }
+        if !paths.is_empty() {
+            tasks.push(Task::done(KantaMessage::OpenPaths(paths)));
+        }
+        Task::batch(tasks)
+    }
+
+    fn pending_tempo(&self) -> Vec<PathBuf> {
+        self.player
+            .playlist()
+            .iter()
+            .filter(|track| track.bpm().is_none() && !self.tempo_failures.contains(track.path()))
+            .map(|track| track.path().to_path_buf())
+            .collect()
+    }
+
+    fn pending_loudness(&self) -> Vec<PathBuf> {
+        if !self.config.normalize_volume {
+            return vec![];
+        }
+        self.player
+            .playlist()
+            .iter()
+            .filter(|track| {
+                track.gain().is_none() && !self.loudness_failures.contains(track.path())
+            })
+            .map(|track| track.path().to_path_buf())
+            .collect()
+    }
+
+    fn analyze_next_tempo(&mut self) -> Task<KantaMessage> {
+        let paths = jobs::schedule(
+            self.jobs.get(JobKind::Tempo),
+            &self.analyzing_tempo,
+            self.pending_tempo(),
+            self.config.job_concurrency.into(),
+        );
+        Task::batch(paths.into_iter().map(|path| {
+            self.analyzing_tempo.insert(path.clone());
+            let (sender, receiver) = oneshot::channel();
+            thread::spawn({
+                let path = path.clone();
+                move || {
+                    let bpm = tempo::analyze(&path)
+                        .inspect_err(|e| error!("Failed to analyze tempo: {}", e))
+                        .ok()
+                        .flatten();
+                    sender.send(bpm).ok();
+                }
+            });
+            Task::perform(async move { receiver.await.ok().flatten() }, move |bpm| {
+                KantaMessage::TempoAnalyzed(path, bpm)
+            })
+        }))
+    }
+
+    fn look_up_next_metadata(&mut self) -> Task<KantaMessage> {
+        let Some(providers) = self
+            .plugins
+            .as_ref()
+            .map(Plugins::metadata_providers)
+            .filter(|providers| !providers.is_empty())
+        else {
+            return Task::none();
+        };
+        if self.looking_up_metadata.is_some() {
+            return Task::none();
+        }
+        let Some(path) = self
+            .player
+            .playlist()
+            .iter()
+            .find(|track| {
+                !track.is_stream()
+                    && (track.title().is_none() || track.artist().is_none())
+                    && !self.looked_up_metadata.contains(track.path())
+            })
+            .map(|track| track.path().to_path_buf())
+        else {
+            return Task::none();
+        };
+
+        self.looking_up_metadata = Some(path.clone());
+        let (sender, receiver) = oneshot::channel();
+        thread::spawn({
+            let path = path.clone();
+            move || {
+                let metadata = providers
+                    .look_up(&path)
+                    .inspect_err(|e| error!("Failed to look up metadata: {}", e))
+                    .ok()
+                    .flatten();
+                sender.send(metadata).ok();
+            }
+        });
+        Task::perform(
+            async move { receiver.await.ok().flatten() },
+            move |metadata| KantaMessage::MetadataLookedUp(path, metadata),
+        )
+    }
+
+    fn analyze_next_loudness(&mut self) -> Task<KantaMessage> {
+        let paths = jobs::schedule(
+            self.jobs.get(JobKind::Loudness),
+            &self.analyzing_loudness,
+            self.pending_loudness(),
+            self.config.job_concurrency.into(),
+        );
+        Task::batch(paths.into_iter().map(|path| {
+            self.analyzing_loudness.insert(path.clone());
+            let (sender, receiver) = oneshot::channel();
+            thread::spawn({
+                let path = path.clone();
+                move || {
+                    let loudness = loudness::analyze(&path)
+                        .inspect_err(|e| error!("Failed to analyze loudness: {}", e))
+                        .ok()
+                        .flatten();
+                    sender.send(loudness).ok();
+                }
+            });
+            Task::perform(
+                async move { receiver.await.ok().flatten() },
+                move |loudness| KantaMessage::LoudnessAnalyzed(path, loudness),
+            )
+        }))
+    }
+
+    fn record_play(&mut self) -> anyhow::Result<()> {
+        let track = self.player.current_track();
+        let (Some(started_at), Some(track)) = (
+            self.scrobbler.tick(
+                track,
+                self.player.playlist_index(),
+                self.player.state() == PlaybackState::Playing,
+                &self.config.scrobble,
+            ),
+            track,
+        ) else {
+            return Ok(());
+        };
+
+        if let Some(history) = &mut self.history {
+            history.record(track, started_at)?;
+        }
+        if let Some(track_stats) = &mut self.track_stats {
+            track_stats.add_play(track.path())?;
+        }
+        self.shuffle_weights_revision = None;
+        let scrobble = &self.config.scrobble;
+        if scrobble.is_enabled()
+            && !scrobble.is_ignored(track.path(), track.duration())
+            && let Some(queue) = &mut self.scrobble_queue
+            && let Some(listen) = Listen::new(track, started_at)
+        {
+            queue.push(listen)?;
+        }
+        Ok(())
+    }
+
+    fn save_track_sound(&mut self, dsp: Option<DspSettings>) -> anyhow::Result<()> {
+        let (Some(track_dsp), Some(track)) = (&mut self.track_dsp, self.player.current_track())
+        else {
+            return Ok(());
+        };
+        track_dsp.set(track.path(), dsp)?;
+        self.player.set_dsp_overrides(track_dsp.all().clone());
+        Ok(())
+    }
+
+    fn lyrics_offset(&self) -> i32 {
+        match (&self.lyrics_offsets, self.player.current_track()) {
+            (Some(offsets), Some(track)) => offsets.get(track.path()),
+            _ => 0,
+        }
+    }
+
+    fn set_lyrics_offset(&mut self, offset_ms: i32) -> anyhow::Result<()> {
+        let (Some(offsets), Some(track)) = (&mut self.lyrics_offsets, self.player.current_track())
+        else {
+            return Ok(());
+        };
+        offsets.set(track.path(), offset_ms)
+    }
+
+    fn lyrics_matches(&self) -> Vec<usize> {
+        let (Some(search), Some(lyrics)) = (
+            &self.lyrics_search,
+            self.player.current_track().and_then(Track::lyrics),
+        ) else {
+            return vec![];
+        };
+        let query = search.query.trim().to_lowercase();
+        if query.is_empty() {
+            return vec![];
+        }
+        lyrics
+            .lines()
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| line.to_lowercase().contains(&query))
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    fn step_lyrics_match(&mut self, delta: isize) -> Task<KantaMessage> {
+        let count = self.lyrics_matches().len();
+        if let Some(search) = &mut self.lyrics_search
+            && count > 0
+        {
+            search.current = (search.current as isize + delta).rem_euclid(count as isize) as usize;
+        }
+        self.scroll_to_lyrics_match()
+    }
+
+    fn scroll_to_lyrics_match(&self) -> Task<KantaMessage> {
+        let (Some(search), Some(lyrics)) = (
+            &self.lyrics_search,
+            self.player.current_track().and_then(Track::lyrics),
+        ) else {
+            return Task::none();
+        };
+        let Some(&line) = self.lyrics_matches().get(search.current) else {
+            return Task::none();
+        };
+        let last_line = lyrics.lines().len().saturating_sub(1).max(1);
+        operation::snap_to(
+            LYRICS_SCROLLABLE_ID,
+            RelativeOffset {
+                x: 0.0,
+                y: line as f32 / last_line as f32,
+            },
+        )
+    }
+
+    fn update_vocal_reduction(&self) {
+        self.player
+            .set_vocal_reduction(self.karaoke && self.config.karaoke_reduce_vocals);
+    }
+
+    fn update_spectrogram(&mut self) {
+        let capture = self.now_playing_view && self.spectrogram.is_some();
+        self.player.set_capture_audio(capture);
+        if capture && let Some(spectrogram) = &mut self.spectrogram {
+            let (samples, sample_rate) = self.player.take_captured_audio();
+            spectrogram.push(&samples, sample_rate);
+        }
+    }
+
+    fn update_shuffle_weights(&mut self) {
+        let revision = self.player.playlist_revision();
+        if self.shuffle_weights_revision == Some(revision) {
+            return;
+        }
+        self.shuffle_weights_revision = Some(revision);
+
+        let avoid_recent = Duration::from_secs_f32(self.config.shuffle_avoid_recent_hours * 3600.0);
+        let recent_since = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .saturating_sub(avoid_recent)
+            .as_secs();
+        let last_played = self
+            .history
+            .as_ref()
+            .map(History::last_played)
+            .unwrap_or_default();
+
+        let weights = self
+            .player
+            .playlist()
+            .iter()
+            .map(|track| {
+                let path = track.path();
+                let stats = self
+                    .track_stats
+                    .as_ref()
+                    .map(|track_stats| track_stats.get(path))
+                    .unwrap_or_default();
+                let played_recently = !avoid_recent.is_zero()
+                    && last_played
+                        .get(path)
+                        .is_some_and(|played_at| *played_at >= recent_since);
+                let weight = match self.config.shuffle_weighting {
+                    _ if played_recently => 0.0,
+                    ShuffleWeighting::Even => 1.0,
+                    ShuffleWeighting::Rating => {
+                        f32::from(stats.rating.unwrap_or(MAX_RATING.div_ceil(2)) + 1)
+                    }
+                    ShuffleWeighting::LeastPlayed => 1.0 / (1.0 + stats.play_count as f32),
+                };
+                (path.to_path_buf(), weight)
+            })
+            .collect();
+        self.player.set_shuffle_weights(weights);
+    }
+
+    fn index_library(&mut self) -> Task<KantaMessage> {
+        let library = (
+            self.config.music_folders.clone(),
+            self.config.library_excludes.clone(),
+        );
+        if self.indexed_library.as_ref() == Some(&library) {
+            return Task::none();
+        }
+        self.indexed_library = Some(library.clone());
+        if library.0.is_empty() {
+            self.library_index = None;
+            self.search_library();
+            return Task::none();
+        }
+        self.scanning_library = true;
+        let progress = self.jobs.get(JobKind::LibraryScan).clone();
+        progress.start(0);
+
+        let (sender, receiver) = oneshot::channel();
+        thread::spawn({
+            let (folders, excludes) = library.clone();
+            move || {
+                let index = LibraryIndex::build(&folders, &excludes, &progress)
+                    .inspect_err(|e| error!("Failed to index the library: {}", e))
+                    .ok()
+                    .map(Arc::new);
+                sender.send(index).ok();
+            }
+        });
+        Task::perform(async move { receiver.await.ok().flatten() }, move |index| {
+            KantaMessage::LibraryIndexed(library, index)
+        })
+    }
+
+    fn search_library(&mut self) {
+        self.library_results = match &self.library_index {
+            Some(index) => index.search(&self.library_query),
+            None => vec![],
+        };
+    }
+
+    fn continue_with_auto_dj(&mut self) -> Task<KantaMessage> {
+        let revision = self.player.playlist_revision();
+        let playlist = self.player.playlist();
+        if self.config.auto_dj == AutoDj::Off
+            || self.auto_dj_loading
+            || self.auto_dj_revision == Some(revision)
+            || self.player.is_shuffling()
+            || self.config.music_folders.is_empty()
+            || playlist.is_empty()
+            || self.player.playlist_index() != Some(playlist.len() - 1)
+        {
+            return Task::none();
+        }
+        self.auto_dj_revision = Some(revision);
+        self.auto_dj_loading = true;
+
+        let mode = self.config.auto_dj;
+        let music_folders = self.config.music_folders.clone();
+        let excludes = self.config.library_excludes.clone();
+        let queued = playlist
+            .iter()
+            .map(|track| track.path().to_path_buf())
+            .collect::<HashSet<_>>();
+        let seeds = playlist[playlist.len().saturating_sub(5)..].to_vec();
+        let stats = self
+            .track_stats
+            .iter()
+            .flat_map(|track_stats| {
+                track_stats
+                    .tracks()
+                    .map(|path| (path.to_path_buf(), track_stats.get(path)))
+            })
+            .collect::<HashMap<_, _>>();
+        let (sender, receiver) = oneshot::channel();
+        thread::spawn(move || {
+            sender
+                .send(auto_dj::pick(
+                    mode,
+                    &music_folders,
+                    &excludes,
+                    &queued,
+                    &seeds,
+                    &stats,
+                ))
+                .ok();
+        });
+        Task::perform(
+            async move { receiver.await.unwrap_or_default() },
+            KantaMessage::AutoDjLoaded,
+        )
+    }
+
+    fn submit_scrobbles(&mut self) -> Task<KantaMessage> {
+        if self.submitting_scrobbles
+            || !self.config.scrobble.is_enabled()
+            || Instant::now() < self.scrobble_retry_at
+        {
+            return Task::none();
+        }
+        let Some(listens) = self
+            .scrobble_queue
+            .as_ref()
+            .map(|queue| queue.next_batch().to_vec())
+            .filter(|listens| !listens.is_empty())
+        else {
+            return Task::none();
+        };
+        self.submitting_scrobbles = true;
+
+        let (sender, receiver) = oneshot::channel();
+        let settings = self.config.scrobble.clone();
+        thread::spawn({
+            let listens = listens.clone();
+            move || {
+                let result = scrobble::submit(&settings, &listens).map_err(|e| format!("{:#}", e));
+                sender.send(result).ok();
+            }
+        });
+        Task::perform(
+            async move {
+                receiver
+                    .await
+                    .unwrap_or_else(|_| Err("submission was cancelled".to_string()))
+            },
+            move |result| KantaMessage::ScrobblesSubmitted(listens.clone(), result),
+        )
+    }
+
+    fn add_track(&mut self, path: PathBuf) -> anyhow::Result<()> {
+        for track in Track::load_all(path.clone(), self.config.hidden_tracks)? {
+            self.player.add_to_playlist(track);
+        }
+        self.config.add_recent(RecentItem::Track(path))
+    }
+
+    fn add_folder(&mut self, path: PathBuf) -> anyhow::Result<()> {
+        let mut tracks = browser::tracks(&path)?
+            .into_iter()
+            .filter_map(|path| {
+                Track::load_all(path, self.config.hidden_tracks)
+                    .inspect_err(|e| error!("Failed to load track: {}", e))
+                    .ok()
+            })
+            .flatten()
+            .collect::<Vec<_>>();
+        browser::sort_album_tracks(&mut tracks);
+        for track in tracks {
+            self.player.add_to_playlist(track);
+        }
+        self.config.add_recent(RecentItem::Folder(path))
+    }
+
+    fn load_playlist(&mut self, path: PathBuf) -> anyhow::Result<()> {
+        self.player
+            .load_m3u8_playlist(&path, self.config.hidden_tracks)?;
+        self.watch_playlist(path.clone());
+        self.config.add_recent(RecentItem::Playlist(path))
+    }
+
+    fn append_playlist(&mut self, path: &Path) -> anyhow::Result<()> {
+        for track in m3u8::load_tracks(path, self.config.hidden_tracks)? {
+            self.player.add_to_playlist(track);
+        }
+        Ok(())
+    }
+
+    fn saved_playlist_path(&self) -> Option<PathBuf> {
+        self.watched_playlist
+            .as_ref()
+            .map(|watched| watched.path.clone())
+            .filter(|path| {
+                path.extension()
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case("m3u8"))
+            })
+    }
+
+    fn save_playlist_checked(&mut self, path: PathBuf) -> anyhow::Result<()> {
+        if path.exists() {
+            self.overwrite_prompt = Some(path);
+            return Ok(());
+        }
+        self.save_playlist(path)
+    }
+
+    fn save_playlist(&mut self, path: PathBuf) -> anyhow::Result<()> {
+        self.player.export_m3u8_playlist(&path)?;
+        info!("Saved the queue to {}", path.display());
+        self.watch_playlist(path.clone());
+        self.config.add_recent(RecentItem::Playlist(path))
+    }
+
+    fn watch_playlist(&mut self, path: PathBuf) {
+        self.watched_playlist = Some(WatchedPlaylist {
+            modified: fs::metadata(&path).and_then(|m| m.modified()).ok(),
+            path,
+            paths: track::file_paths(self.player.playlist())
+                .into_iter()
+                .map(Path::to_path_buf)
+                .collect(),
+            checked_at: Instant::now(),
+            change: None,
+        });
+    }
+
+    fn check_watched_playlist(&mut self) {
+        let Some(watched) = self.watched_playlist.as_mut() else {
+            return;
+        };
+        if watched.checked_at.elapsed() < PLAYLIST_POLL_INTERVAL {
+            return;
+        }
+        watched.checked_at = Instant::now();
+
+        let modified = fs::metadata(&watched.path).and_then(|m| m.modified()).ok();
+        if modified.is_none() || modified == watched.modified {
+            return;
+        }
+        watched.modified = modified;
+        match m3u8::read(&watched.path) {
+            Ok(paths) => {
+                let diff = m3u8::Diff::new(&watched.paths, &paths);
+                info!(
+                    "{} changed on disk ({} added, {} removed)",
+                    watched.path.display(),
+                    diff.added.len(),
+                    diff.removed.len()
+                );
+                watched.change = (!diff.is_empty()).then_some((paths, diff));
+            }
+            Err(e) => error!("Failed to reread {}: {}", watched.path.display(), e),
+        }
+    }
+
+    fn merge_playlist_changes(&mut self) -> anyhow::Result<()> {
+        let Some((paths, diff)) = self
+            .watched_playlist
+            .as_mut()
+            .and_then(|watched| watched.change.take())
+        else {
+            return Ok(());
+        };
+
+        let current = self.player.playlist_index();
+        let mut removed = BTreeSet::new();
+        for path in &diff.removed {
+            let playlist = self.player.playlist();
+            let Some(index) = playlist.iter().enumerate().position(|(index, track)| {
+                Some(index) != current && !removed.contains(&index) && track.path() == path
+            }) else {
+                continue;
+            };
+            let segments = playlist[index + 1..]
+                .iter()
+                .zip(&playlist[index..])
+                .take_while(|(track, previous)| track.continues(previous))
+                .count();
+            removed.extend((index..=index + segments).filter(|&index| Some(index) != current));
+        }
+        self.player.remove_from_playlist(&removed)?;
+
+        for (index, path) in diff.added {
+            let playlist = self.player.playlist();
+            let at = match index.checked_sub(1) {
+                Some(previous) => playlist
+                    .iter()
+                    .rposition(|track| track.path() == paths[previous])
+                    .map_or(playlist.len(), |previous| previous + 1),
+                None => 0,
+            };
+            match Track::load_all(path.clone(), self.config.hidden_tracks) {
+                Ok(tracks) => {
+                    for (offset, track) in tracks.into_iter().enumerate() {
+                        self.player.insert_into_playlist(at + offset, track);
+                    }
+                }
+                Err(e) => error!("Failed to load {}: {}", path.display(), e),
+            }
+        }
+
+        if let Some(watched) = self.watched_playlist.as_mut() {
+            watched.paths = paths;
+        }
+        Ok(())
+    }
+
+    fn autosave_queue(&mut self) {
+        let revision = self.player.playlist_revision();
+        if revision != self.seen_queue_revision {
+            self.seen_queue_revision = revision;
+            self.queue_changed_at = Instant::now();
+        }
+
+        let position_outdated = self.player.state() == PlaybackState::Playing
+            && self.queue_saved_at.elapsed() >= QUEUE_POSITION_SAVE_INTERVAL;
+        if !position_outdated
+            && (self.seen_queue_revision == self.saved_queue_revision
+                || self.queue_changed_at.elapsed() < QUEUE_SAVE_DELAY)
+        {
+            return;
+        }
+
+        self.save_queue();
+    }
+
+    fn change_volume(&mut self, delta: f32) {
+        self.alarm_fade = None;
+        let volume = self.muted_volume.take().unwrap_or(self.player.volume());
+        self.player.set_volume((volume + delta).clamp(0.0, 1.0));
+        self.volume_osd_until = Some(Instant::now() + VOLUME_OSD_DURATION);
+    }
+
+    fn check_alarm(&mut self) {
+        if let Some((started_at, volume)) = self.alarm_fade {
+            let progress =
+                (started_at.elapsed().as_secs_f32() / ALARM_FADE_IN.as_secs_f32()).min(1.0);
+            self.player.set_volume(volume * progress);
+            if progress >= 1.0 {
+                self.alarm_fade = None;
+            }
+        }
+
+        if self.config.alarm.enabled
+            && alarm::is_due(&self.config.alarm.time, &mut self.alarm_fired_on)
+            && let Err(e) = self.start_alarm()
+        {
+            error!("Failed to start the alarm: {}", e);
+        }
+    }
+
+    fn start_alarm(&mut self) -> anyhow::Result<()> {
+        info!("Starting the alarm");
+        let playlist = self
+            .config
+            .alarm
+            .playlist
+            .as_ref()
+            .and_then(|name| Some(self.playlists.as_ref()?.path(name)));
+        if let Some(path) = playlist {
+            self.player
+                .load_m3u8_playlist(&path, self.config.hidden_tracks)?;
+            self.watch_playlist(path);
+            self.player.jump_to_track_at(0)?;
+        }
+
+        let volume = self.muted_volume.take().unwrap_or(self.player.volume());
+        self.player.set_volume(0.0);
+        self.alarm_fade = Some((Instant::now(), volume));
+        self.player.play()
+    }
+
+    fn autosave_volume(&mut self) {
+        let volume = self
+            .alarm_fade
+            .map(|(_, volume)| volume)
+            .or(self.muted_volume)
+            .unwrap_or(self.player.volume());
+        if self.config.remember_volume && volume != self.config.volume {
+            self.config.volume = volume;
+            self.volume_changed_at = Some(Instant::now());
+        }
+
+        if self
+            .volume_changed_at
+            .is_some_and(|changed_at| changed_at.elapsed() >= VOLUME_SAVE_DELAY)
+        {
+            self.volume_changed_at = None;
+            if let Err(e) = self.config.save() {
+                error!("Failed to save volume: {}", e);
+            }
+        }
+    }
+
+    fn save_queue(&mut self) {
+        let queue = SavedQueue::new(
+            self.player.playlist(),
+            self.player.playlist_index(),
+            self.player.position(),
+        );
+        match queue.save() {
+            Ok(()) => {
+                self.seen_queue_revision = self.player.playlist_revision();
+                self.saved_queue_revision = self.seen_queue_revision;
+                self.queue_saved_at = Instant::now();
+            }
+            Err(e) => error!("Failed to save queue: {}", e),
+        }
+    }
+
+    fn flush_queue(&mut self) {
+        if self.player.playlist_revision() != self.saved_queue_revision
+            || self.player.current_track().is_some()
+        {
+            self.save_queue();
+        }
+    }
+
+    fn load_profile(&mut self) -> anyhow::Result<()> {
+        if let Err(e) = instance::switch_profile() {
+            error!("Failed to register this instance for the profile: {}", e);
+        }
+        self.config = Config::load();
+        i18n::set_language(self.config.language.as_deref());
+        self.player.set_normalize(self.config.normalize_volume);
+        self.player
+            .set_show_stream_titles(self.config.show_stream_titles);
+        self.player.set_resume_rewind(self.config.resume_rewind());
+        self.player.set_locked(self.config.party_mode);
+        self.player.set_volume(self.config.startup_volume());
+        self.volume_changed_at = None;
+        self.update_vocal_reduction();
+        self.playlists = PlaylistLibrary::open()
+            .inspect_err(|e| error!("Failed to open playlist library: {}", e))
+            .ok();
+        self.browser = None;
+        self.stations = StationLibrary::open()
+            .inspect_err(|e| error!("Failed to open station list: {}", e))
+            .ok();
+        self.cues = CueLibrary::open()
+            .inspect_err(|e| error!("Failed to open cue points: {}", e))
+            .ok();
+        self.scrobble_queue = ScrobbleQueue::open()
+            .inspect_err(|e| error!("Failed to open scrobble queue: {}", e))
+            .ok();
+        self.scripting = Scripting::new()
+            .inspect_err(|e| error!("Failed to start scripting: {}", e))
+            .ok();
+        if let Err(e) = self.start_event_stream() {
+            error!("Failed to start the event stream: {}", e);
+        }
+        if let Err(e) = self.start_web_remote() {
+            error!("Failed to start the web remote: {}", e);
+        }
+        if let Err(e) = self.start_tray() {
+            error!("Failed to show the tray icon: {}", e);
+        }
+        self.scrobble_retry_at = Instant::now();
+        self.scrobble_backoff = Duration::ZERO;
+        self.loved_tracks = LovedTracks::open()
+            .inspect_err(|e| error!("Failed to open loved tracks: {}", e))
+            .ok();
+        self.track_stats = TrackStatsLibrary::open()
+            .inspect_err(|e| error!("Failed to open track ratings: {}", e))
+            .ok();
+        self.track_dsp = TrackDspLibrary::open()
+            .inspect_err(|e| error!("Failed to open per-track sound settings: {}", e))
+            .ok();
+        self.player.set_dsp_overrides(
+            self.track_dsp
+                .as_ref()
+                .map(|track_dsp| track_dsp.all().clone())
+                .unwrap_or_default(),
+        );
+        self.lyrics_offsets = LyricsOffsets::open()
+            .inspect_err(|e| error!("Failed to open lyrics offsets: {}", e))
+            .ok();
+        self.history = History::open()
+            .inspect_err(|e| error!("Failed to open listening history: {}", e))
+            .ok();
+        self.shuffle_weights_revision = None;
+        self.pending_queue = SavedQueue::load()
+            .inspect_err(|e| error!("Failed to load saved queue: {}", e))
+            .ok()
+            .flatten();
+
+        self.clear_selection();
+        self.player.clear_playlist()?;
+        self.seen_queue_revision = self.player.playlist_revision();
+        self.saved_queue_revision = self.seen_queue_revision;
+        Ok(())
+    }
+
+    fn update_album_theme(&mut self) {
+        let track = self
+            .player
+            .current_track()
+            .filter(|_| self.config.album_colors);
+        if track.map(Track::path) == self.album_theme_source.as_deref() {
+            return;
+        }
+
+        self.album_theme_source = track.map(|track| track.path().to_path_buf());
+        self.album_theme = track
+            .and_then(Track::cover)
+            .and_then(album_theme::from_cover);
+    }
+
+    fn write_now_playing(&mut self) {
+        let Some(path) = &self.config.now_playing_file else {
+            return;
+        };
+        let track = self.player.current_track();
+        let stream_title = self.player.stream_title();
+        let written = Some((
+            track.map(|track| track.path().to_path_buf()),
+            stream_title.clone(),
+            self.player.state(),
+        ));
+        if written == self.now_playing_written {
+            return;
+        }
+        if let Err(e) =
+            now_playing::write(path, track, stream_title.as_deref(), self.player.state())
+        {
+            error!("Failed to write {}: {}", path.display(), e);
+        }
+        self.now_playing_written = written;
+    }
+
+    fn run_hooks(&mut self) {
+        let track = self.player.current_track();
+        let state = (
+            self.player.playlist_index(),
+            track.map(|track| track.path().to_path_buf()),
+            self.player.state(),
+        );
+        if state == self.hook_state {
+            return;
+        }
+        let (previous_index, previous_path, _) = mem::replace(&mut self.hook_state, state);
+
+        let (index, path, state) = &self.hook_state;
+        let event = if path.is_some() && (*index, path) != (previous_index, &previous_path) {
+            HookEvent::TrackChanged
+        } else {
+            match state {
+                PlaybackState::Playing => HookEvent::Play,
+                PlaybackState::Paused => HookEvent::Pause,
+                PlaybackState::Stopped => HookEvent::Stop,
+            }
+        };
+        let payload = Payload::new(
+            event,
+            *state,
+            track,
+            self.player.position(),
+            self.player.volume(),
+        );
+        hooks::run(&self.config.hook_command, &payload);
+        self.emit_event(payload);
+    }
+
+    fn emit_position_tick(&mut self) {
+        if self.player.state() != PlaybackState::Playing
+            || self.position_tick_at.elapsed() < POSITION_TICK_INTERVAL
+        {
+            return;
+        }
+        self.position_tick_at = Instant::now();
+        let payload = Payload::new(
+            HookEvent::Position,
+            PlaybackState::Playing,
+            self.player.current_track(),
+            self.player.position(),
+            self.player.volume(),
+        );
+        self.emit_event(payload);
+    }
+
+    fn emit_volume_change(&mut self) {
+        let volume = self.player.volume();
+        if volume == self.event_volume {
+            return;
+        }
+        self.event_volume = volume;
+        let payload = Payload::new(
+            HookEvent::Volume,
+            self.player.state(),
+            self.player.current_track(),
+            self.player.position(),
+            volume,
+        );
+        self.emit_event(payload);
+    }
+
+    fn emit_event(&mut self, payload: Payload) {
+        if let Some(plugins) = &self.plugins {
+            plugins.emit(&payload);
+        }
+        if let Some(event_stream) = &self.event_stream {
+            event_stream.broadcast(&payload);
+        }
+        if let Some(scripting) = &self.scripting {
+            let requests = scripting.dispatch(payload);
+            self.apply_control_requests(requests);
+        }
+    }
+
+    fn start_event_stream(&mut self) -> anyhow::Result<()> {
+        self.event_stream = None;
+        if self.config.event_stream {
+            self.event_stream = Some(EventStream::start(self.config.event_stream_port)?);
+        }
+        Ok(())
+    }
+
+    fn update_snapcast(&mut self) -> Task<KantaMessage> {
+        if self.config.snapcast {
+            let address = self.config.snapcast_address.clone();
+            return connect_output(
+                move || SnapcastOutput::connect(&address).map(ExternalOutput::Snapcast),
+                KantaMessage::SnapcastConnected,
+            );
+        }
+        if let Some(ExternalOutput::Snapcast(_)) = self.player.output()
+            && let Err(e) = self.player.set_output(None)
+        {
+            self.error = Some(e.to_string());
+        }
+        Task::none()
+    }
+
+    fn start_tray(&mut self) -> anyhow::Result<()> {
+        self.tray = None;
+        if !self.config.close_to_background {
+            return Ok(());
+        }
+        self.tray = Some(Tray::try_new()?);
+        Ok(())
+    }
+
+    fn handle_tray_events(&mut self) -> Task<KantaMessage> {
+        let Some(tray) = &self.tray else {
+            return Task::none();
+        };
+        let events = iter::from_fn(|| tray.receive_event()).collect::<Vec<_>>();
+        Task::batch(events.into_iter().map(|event| match event {
+            TrayEvent::Show => self.show_window(),
+            TrayEvent::Quit => Task::done(KantaMessage::Quit),
+        }))
+    }
+
+    fn start_web_remote(&mut self) -> anyhow::Result<()> {
+        self.web_remote = None;
+        if !self.config.web_remote {
+            return Ok(());
+        }
+        if !web_remote::is_valid_token(&self.config.web_remote_token) {
+            self.config.web_remote_token = web_remote::generate_token()?;
+            self.config.save()?;
+        }
+        self.web_remote = Some(WebRemote::start(
+            self.config.web_remote_port,
+            &self.config.web_remote_token,
+        )?);
+        self.web_remote_updated_at = None;
+        Ok(())
+    }
+
+    fn update_web_remote(&mut self) {
+        let Some(web_remote) = &self.web_remote else {
+            return;
+        };
+        let requests = iter::from_fn(|| web_remote.receive_request()).collect::<Vec<_>>();
+        if requests.is_empty()
+            && self
+                .web_remote_updated_at
+                .is_some_and(|updated_at| updated_at.elapsed() < WEB_REMOTE_STATE_INTERVAL)
+        {
+            return;
+        }
+        self.apply_control_requests(requests);
+        self.web_remote_updated_at = Some(Instant::now());
+
+        let Some(web_remote) = &self.web_remote else {
+            return;
+        };
+        let playlist = self.player.playlist();
+        let index = self.player.playlist_index();
+        let queue = playlist
+            .iter()
+            .enumerate()
+            .skip(index.unwrap_or(0))
+            .take(WEB_REMOTE_QUEUE_LEN)
+            .map(|(index, track)| QueueEntry {
+                index,
+                title: track.title().map_or_else(
+                    || {
+                        track
+                            .path()
+                            .file_name()
+                            .map_or_else(|| t!("unknown"), |n| n.to_string_lossy().into_owned())
+                    },
+                    str::to_string,
+                ),
+                artist: track.artist().map(str::to_string),
+            })
+            .collect();
+        web_remote.set_state(&RemoteState {
+            playback: Payload::new(
+                HookEvent::Position,
+                self.player.state(),
+                self.player.current_track(),
+                self.player.position(),
+                self.player.volume(),
+            ),
+            index,
+            queue,
+        });
+    }
+
+    fn apply_control_requests(&mut self, requests: Vec<ControlRequest>) {
+        if self.config.party_mode && !requests.is_empty() {
+            debug!("Ignoring {} control requests in party mode", requests.len());
+            return;
+        }
+        for request in requests {
+            let result = match request {
+                ControlRequest::Play => self.player.play(),
+                ControlRequest::Pause => self.player.pause(),
+                ControlRequest::Next => self.player.jump_to_next_track(),
+                ControlRequest::Previous => self.player.jump_to_previous_track(),
+                ControlRequest::SetVolume(volume) => {
+                    self.muted_volume = None;
+                    self.alarm_fade = None;
+                    self.player.set_volume(volume);
+                    Ok(())
+                }
+                ControlRequest::Seek(position) => Duration::try_from_secs_f32(position)
+                    .map_err(anyhow::Error::from)
+                    .and_then(|position| self.player.set_position(position)),
+                ControlRequest::JumpTo(index) if index < self.player.playlist().len() => {
+                    self.player.jump_to_track_at(index)
+                }
+                ControlRequest::JumpTo(index) => Err(anyhow!("no track at index {}", index)),
+            };
+            if let Err(e) = result {
+                error!("Failed to apply control request {:?}: {}", request, e);
+            }
+        }
+    }
+
+    fn theme(&self) -> Option<Theme> {
+        self.album_theme.clone()
+    }
+
+    fn clear_selection(&mut self) {
+        self.selected_tracks.clear();
+        self.selection_anchor = None;
+    }
+
+    fn playlist_entries(&self) -> Vec<PlaylistEntry<'_>> {
+        let mut entries = vec![];
+        let tracks = self.player.playlist();
+        let mut index = 0;
+        while index < tracks.len() {
+            let track = &tracks[index];
+            let group_len = match track.album() {
+                Some(album) => tracks[index..]
+                    .iter()
+                    .take_while(|t| {
+                        t.album() == Some(album) && t.album_artist() == track.album_artist()
+                    })
+                    .count(),
+                None => 1,
+            };
+
+            let Some(album) = track.album().filter(|_| group_len > 1) else {
+                entries.push(PlaylistEntry::Track {
+                    index,
+                    is_grouped: false,
+                });
+                index += 1;
+                continue;
+            };
+
+            let group = &tracks[index..index + group_len];
+            let album_artist = match track.album_artist() {
+                Some(album_artist) => Some(album_artist.to_string()),
+                None if group.iter().any(Track::is_compilation)
+                    || group.iter().any(|t| t.artist() != track.artist()) =>
+                {
+                    Some(t!("various-artists"))
+                }
+                None => track.artist().map(str::to_string),
+            };
+            let key = (track.album_artist().map(str::to_string), album.to_string());
+            let is_collapsed = self.collapsed_albums.contains(&key);
+            entries.push(PlaylistEntry::AlbumHeader {
+                index,
+                len: group_len,
+                album,
+                album_artist,
+                key,
+                is_collapsed,
+            });
+            if !is_collapsed {
+                entries.extend(
+                    (index..index + group_len).map(|index| PlaylistEntry::Track {
+                        index,
+                        is_grouped: true,
+                    }),
+                );
+            }
+
+            index += group_len;
+        }
+
+        entries
+    }
+
+    fn track_offset(&self, track_index: usize) -> (f32, f32) {
+        let entries = self.playlist_entries();
+        let mut row_offset = 0.0;
+        for entry in &entries {
+            match *entry {
+                PlaylistEntry::Track { index, .. } if index == track_index => break,
+                PlaylistEntry::AlbumHeader {
+                    index,
+                    len,
+                    is_collapsed: true,
+                    ..
+                } if (index..index + len).contains(&track_index) => break,
+                _ => row_offset += entry.height(),
+            }
+        }
+        let total_height = entries.iter().map(PlaylistEntry::height).sum();
+
+        (row_offset, total_height)
+    }
+
+    fn scroll_to_now_playing(&mut self) -> Task<KantaMessage> {
+        let Some(current_index) = self.player.playlist_index() else {
+            return Task::none();
+        };
+
+        let (row_offset, total_height) = self.track_offset(current_index);
+
+        let max_offset = (total_height - self.playlist_viewport_height).max(0.0);
+        let offset = (row_offset - (self.playlist_viewport_height - PLAYLIST_ROW_HEIGHT) / 2.0)
+            .clamp(0.0, max_offset);
+        self.playlist_scroll_offset = offset;
+
+        operation::scroll_to(PLAYLIST_SCROLLABLE_ID, AbsoluteOffset { x: 0.0, y: offset })
+    }
+
+    fn reveal_track(&mut self, index: usize) -> Task<KantaMessage> {
+        let (row_offset, _) = self.track_offset(index);
+        let offset = if row_offset < self.playlist_scroll_offset {
+            row_offset
+        } else if row_offset + PLAYLIST_ROW_HEIGHT
+            > self.playlist_scroll_offset + self.playlist_viewport_height
+        {
+            row_offset + PLAYLIST_ROW_HEIGHT - self.playlist_viewport_height
+        } else {
+            return Task::none();
+        };
+        self.playlist_scroll_offset = offset;
+
+        operation::scroll_to(PLAYLIST_SCROLLABLE_ID, AbsoluteOffset { x: 0.0, y: offset })
+    }
+
+    fn title(&self) -> String {
+        let (Some(track), Some(index)) =
+            (self.player.current_track(), self.player.playlist_index())
+        else {
+            return "Kanta".to_string();
+        };
+        let title = track.title().map(str::to_string).unwrap_or_else(|| {
+            track
+                .path()
+                .file_name()
+                .and_then(|n| n.to_str())
+                .map_or_else(|| t!("unknown"), str::to_string)
+        });
+        t!(
+            "window-title",
+            title = title,
+            position = index + 1,
+            count = self.player.playlist().len()
+        )
+    }
+
+    fn view(&self) -> Element<'_, KantaMessage> {
+        use KantaMessage::*;
+
+        macro_rules! btn {
+            ($text:expr, $message:expr) => {
+                button($text).on_press($message)
+            };
+            ($text:expr, $message:expr, $style:ident) => {
+                button($text).on_press($message).style(button::$style)
+            };
+        }
+
+        let play_pause_button = match self.player.state() {
+            PlaybackState::Playing => focusable(btn!(self.text(t!("pause")), Pause)),
+            PlaybackState::Paused => focusable(btn!(self.text(t!("play")), Play)),
+            PlaybackState::Stopped if self.player.current_track().is_some() => {
+                focusable(btn!(self.text(t!("play")), Play))
+            }
+            PlaybackState::Stopped => focusable(button(self.text(t!("stopped")))),
+        };
+
+        let position_slider = match self.player.current_track() {
+            Some(track) => {
+                let elapsed = self.player.position().as_secs_f32();
+                let total = track.duration().as_secs_f32();
+                focusable(slider(0.0..=total, elapsed, SetPosition))
+            }
+            None => focusable(slider(0.0..=1.0, 0.0, SetPosition)),
+        };
+        let buffered = self.player.buffered().map_or(0.0, |buffered| buffered.end);
+        let position_slider: Element<'_, KantaMessage> =
+            if self.player.current_track().is_some_and(Track::is_stream) {
+                self.text(self.player.stream_title().unwrap_or_default())
+                    .width(Length::Fill)
+                    .into()
+            } else {
+                column![]
+                    .push(position_slider)
+                    .push(progress_bar(0.0..=1.0, buffered).girth(2))
+                    .spacing(2)
+                    .into()
+            };
+
+        let elapsed_time: Element<'_, KantaMessage> = match &self.jump_to_time_input {
+            Some(input) => self
+                .text_input("h:mm:ss", input)
+                .id(JUMP_TO_TIME_INPUT_ID)
+                .on_input(JumpToTimeInputChanged)
+                .on_submit(SubmitJumpToTime)
+                .width(ELAPSED_TIME_WIDTH)
+                .into(),
+            None => {
+                let total = self
+                    .player
+                    .current_track()
+                    .map(|track| format_duration(track.duration()))
+                    .unwrap_or_else(|| format_duration(Duration::ZERO));
+                focusable(
+                    btn!(
+                        self.text(format!(
+                            "{} / {}",
+                            format_duration(self.player.position()),
+                            total
+                        )),
+                        StartJumpToTime,
+                        text
+                    )
+                    .width(ELAPSED_TIME_WIDTH),
+                )
+                .into()
+            }
+        };
+
+        let now_playing_thumbnail: Element<'_, KantaMessage> =
+            match self.player.current_track().and_then(Track::cover) {
+                Some(handle) => mouse_area(
+                    image(handle.clone())
+                        .width(NOW_PLAYING_THUMBNAIL_SIZE)
+                        .height(NOW_PLAYING_THUMBNAIL_SIZE),
+                )
+                .on_double_click(ToggleNowPlayingView)
+                .on_right_press(ShowCurrentTrackInFileManager)
+                .into(),
+                None => space().width(NOW_PLAYING_THUMBNAIL_SIZE).into(),
+            };
+
+        let level_meter = |channel: usize| {
+            let clipped = self.level_meters.is_clipped(channel);
+            let peak = meter_fraction(self.level_meters.peaks[channel].0);
+            let peak_portion = (peak * 1000.0) as u16;
+            stack![
+                progress_bar(0.0..=1.0, meter_fraction(self.level_meters.levels[channel]))
+                    .girth(4)
+                    .style(if clipped {
+                        progress_bar::danger
+                    } else {
+                        progress_bar::primary
+                    }),
+                row![]
+                    .push(space().width(Length::FillPortion(peak_portion.max(1))))
+                    .push(
+                        container(space())
+                            .width(2)
+                            .height(4)
+                            .style(|_| container::Style {
+                                background: Some(Color::WHITE.into()),
+                                ..container::Style::default()
+                            })
+                    )
+                    .push(space().width(Length::FillPortion((1000 - peak_portion).max(1)))),
+            ]
+        };
+        let level_meters = column![]
+            .push(level_meter(0))
+            .push(level_meter(1))
+            .width(LEVEL_METER_WIDTH)
+            .spacing(2);
+
+        let controls = if self.config.party_mode {
+            let unlock: Element<'_, KantaMessage> = match &self.party_pin_input {
+                Some(input) => self
+                    .text_input(&t!("party-pin"), input)
+                    .id(PARTY_PIN_INPUT_ID)
+                    .on_input(PartyPinInputChanged)
+                    .on_submit(UnlockPartyMode)
+                    .secure(true)
+                    .width(120)
+                    .into(),
+                None => focusable(btn!(
+                    self.text(t!("unlock")),
+                    StartUnlockingPartyMode,
+                    secondary
+                ))
+                .into(),
+            };
+            let (elapsed, total) = match self.player.current_track() {
+                Some(track) => (self.player.position(), track.duration()),
+                None => (Duration::ZERO, Duration::ZERO),
+            };
+            row![]
+                .push(now_playing_thumbnail)
+                .push(self.text(t!("party-mode-locked")).color(MUTED_COLOR))
+                .push(self.text(format!(
+                    "{} / {}",
+                    format_duration(elapsed),
+                    format_duration(total)
+                )))
+                .push(progress_bar(
+                    0.0..=total.as_secs_f32().max(1.0),
+                    elapsed.as_secs_f32(),
+                ))
+                .push(unlock)
+        } else {
+            row![]
+                .push(now_playing_thumbnail)
+                .push(focusable(btn!(
+                    self.text(t!("previous")),
+                    JumpToPreviousTrack,
+                    secondary
+                )))
+                .push(play_pause_button)
+                .push(focusable(btn!(
+                    self.text(t!("next")),
+                    JumpToNextTrack,
+                    secondary
+                )))
+                .push(focusable(
+                    self.checkbox(self.player.is_shuffling())
+                        .label(t!("shuffle"))
+                        .on_toggle(SetShuffle),
+                ))
+                .push(elapsed_time)
+                .push(position_slider)
+                .push(self.text(t!("volume")))
+                .push(focusable(
+                    slider(0.0..=1.0, self.player.volume(), SetVolume).step(0.01),
+                ))
+                .push(self.config.show_level_meters.then_some(level_meters))
+                .push(focusable(btn!(
+                    self.text(t!(
+                        "pitch",
+                        semitones = format!("{:+}", self.player.pitch())
+                    )),
+                    SetPitch(0),
+                    text
+                )))
+                .push(focusable(
+                    slider(PITCH_SHIFT_RANGE, self.player.pitch(), SetPitch).width(80),
+                ))
+                .push(focusable(btn!(
+                    self.text(t!("party-mode")),
+                    LockPartyMode,
+                    secondary
+                )))
+                .push(focusable(btn!(
+                    self.text(t!("settings")),
+                    OpenSettings,
+                    secondary
+                )))
+        }
+        .spacing(8)
+        .align_y(Vertical::Center);
+
+        let mut cue_points = row![].spacing(8).align_y(Vertical::Center);
+        if let Some(track) = self.player.current_track() {
+            if let Some(loved_tracks) = &self.loved_tracks {
+                let label = if loved_tracks.is_loved(track.path()) {
+                    t!("loved")
+                } else {
+                    t!("love")
+                };
+                cue_points = cue_points.push(focusable(btn!(
+                    self.text(label),
+                    ToggleLoveCurrentTrack,
+                    text
+                )));
+            }
+            let cues = self
+                .cues
+                .as_ref()
+                .map(|cues| cues.cues(track.path()))
+                .unwrap_or_default();
+            for (index, cue) in cues.iter().enumerate() {
+                let label = if index < 9 {
+                    format!(
+                        "{}. {} {}",
+                        index + 1,
+                        cue.name,
+                        format_duration(cue.position)
+                    )
+                } else {
+                    format!("{} {}", cue.name, format_duration(cue.position))
+                };
+                cue_points = cue_points.push(
+                    row![]
+                        .push(focusable(btn!(
+                            self.text(label),
+                            JumpToCue(index),
+                            secondary
+                        )))
+                        .push(focusable(btn!(self.text("×"), RemoveCue(index), text))),
+                );
+            }
+            cue_points = cue_points.push(match &self.new_cue {
+                Some((position, name)) => Element::from(
+                    row![]
+                        .push(self.text(format_duration(*position)).color(MUTED_COLOR))
+                        .push(
+                            self.text_input(&t!("cue-name"), name)
+                                .id(CUE_NAME_INPUT_ID)
+                                .on_input(NewCueNameChanged)
+                                .on_submit(AddCue)
+                                .width(200),
+                        )
+                        .spacing(8)
+                        .align_y(Vertical::Center),
+                ),
+                None => focusable(btn!(self.text(t!("add-cue")), StartAddingCue, text)).into(),
+            });
+            if !self.config.party_mode {
+                cue_points = cue_points
+                    .push(focusable(btn!(
+                        self.text(t!("sound")),
+                        OpenSoundDialog,
+                        text
+                    )))
+                    .push(focusable(btn!(
+                        self.text(t!("edit-lyrics")),
+                        OpenLyricsEditor,
+                        text
+                    )));
+            }
+        }
+
+        let playlist_row_padding = Padding {
+            top: 8.0,
+            bottom: 8.0,
+            left: 0.0,
+            right: 0.0,
+        };
+
+        let url_input: Element<'_, KantaMessage> = match &self.url_input {
+            Some(input) => self
+                .text_input("http://…", input)
+                .id(URL_INPUT_ID)
+                .on_input(UrlInputChanged)
+                .on_submit(SubmitUrl)
+                .width(240)
+                .into(),
+            None => focusable(btn!(self.text(t!("add-url")), StartAddingUrl, secondary)).into(),
+        };
+
+        let playlist_controls = if self.config.party_mode {
+            row![]
+                .push(focusable(btn!(
+                    self.text(t!("add-track")),
+                    AddTrack,
+                    secondary
+                )))
+                .push(url_input)
+                .push(focusable(btn!(
+                    self.text(t!("now-playing")),
+                    ScrollToNowPlaying,
+                    secondary
+                )))
+                .push(focusable(
+                    self.checkbox(self.follow_now_playing)
+                        .label(t!("follow"))
+                        .on_toggle(SetFollowNowPlaying),
+                ))
+        } else {
+            row![]
+                .push(focusable(btn!(
+                    self.text(t!("add-track")),
+                    AddTrack,
+                    secondary
+                )))
+                .push(focusable(btn!(
+                    self.text(t!("load-playlist")),
+                    LoadPlaylist,
+                    secondary
+                )))
+                .push(focusable(btn!(
+                    self.text(t!("append-playlist")),
+                    AppendPlaylist,
+                    secondary
+                )))
+                .push(self.saved_playlist_path().map(|_| {
+                    focusable(btn!(
+                        self.text(t!("save-playlist")),
+                        SavePlaylist,
+                        secondary
+                    ))
+                }))
+                .push(focusable(btn!(
+                    self.text(t!("export-playlist")),
+                    ExportPlaylist,
+                    secondary
+                )))
+                .push(url_input)
+                .push(focusable(
+                    self.pick_list(
+                        self.config.recent.as_slice(),
+                        None::<RecentItem>,
+                        OpenRecent,
+                    )
+                    .placeholder(t!("recent")),
+                ))
+                .push(focusable(
+                    self.pick_list(PlaylistSort::ALL, None::<PlaylistSort>, SortPlaylist)
+                        .placeholder(t!("sort-by")),
+                ))
+                .push(focusable(btn!(
+                    self.text(t!("deduplicate")),
+                    DeduplicatePlaylist,
+                    secondary
+                )))
+                .push(focusable(btn!(
+                    self.text(t!("clear-playlist")),
+                    ClearPlaylist,
+                    danger
+                )))
+                .push(focusable(btn!(
+                    self.text(t!("now-playing")),
+                    ScrollToNowPlaying,
+                    secondary
+                )))
+                .push(focusable(btn!(
+                    self.text(t!("statistics")),
+                    ShowStatistics,
+                    secondary
+                )))
+                .push(focusable(btn!(
+                    self.text(t!("jobs", count = self.jobs.active())),
+                    OpenJobsPanel,
+                    secondary
+                )))
+                .push(focusable(
+                    self.checkbox(self.follow_now_playing)
+                        .label(t!("follow"))
+                        .on_toggle(SetFollowNowPlaying),
+                ))
+        }
+        .spacing(8)
+        .align_y(Vertical::Center);
+
+        let muted = MUTED_COLOR;
+        let columns = self.config.queue_columns;
+        let header_field = |name: String, sort: PlaylistSort| {
+            let arrow = match self.queue_sort {
+                Some((current, descending, revision))
+                    if current == sort && revision == self.player.playlist_revision() =>
+                {
+                    if descending {
+                        " ▾"
+                    } else {
+                        " ▴"
+                    }
+                }
+                _ => "",
+            };
+            button(self.text(format!("{name}{arrow}")).color(muted))
+                .on_press_maybe((!self.config.party_mode).then_some(SortQueueBy(sort)))
+                .style(button::text)
+                .padding(0)
+        };
+        let column_divider = |column| {
+            mouse_area(
+                container(self.text("│").color(muted))
+                    .width(8)
+                    .align_x(Horizontal::Center),
+            )
+            .interaction(mouse::Interaction::ResizingHorizontally)
+            .on_press(StartResizingColumn(column))
+        };
+        let playlist_header = row![]
+            .push(space().width(PLAYLIST_ICON_WIDTH + PLAYLIST_THUMBNAIL_SIZE + 16.0))
+            .push(focusable(
+                header_field(t!("title"), PlaylistSort::Title).width(Length::Fill),
+            ))
+            .push(column_divider(QueueColumn::Artist))
+            .push(focusable(
+                header_field(t!("artist"), PlaylistSort::Artist).width(columns.artist),
+            ))
+            .push(column_divider(QueueColumn::Album))
+            .push(focusable(
+                header_field(t!("album"), PlaylistSort::Album).width(columns.album),
+            ))
+            .push(column_divider(QueueColumn::Length))
+            .push(
+                container(focusable(header_field(
+                    t!("length"),
+                    PlaylistSort::Duration,
+                )))
+                .width(columns.length)
+                .align_x(Horizontal::Right),
+            )
+            .align_y(Vertical::Center)
+            .padding(playlist_row_padding);
+
+        let track_row = |index: usize| -> Element<'_, KantaMessage> {
+            let track = &self.player.playlist()[index];
+            let is_playing = self.player.playlist_index() == Some(index);
+            let color = if is_playing {
+                SELECTED_COLOR
+            } else {
+                Color::WHITE
+            };
+
+            macro_rules! track_field {
+                ($method:ident, $default:expr, $width:expr) => {
+                    self.text(track.$method().map_or($default, str::to_string))
+                        .width($width)
+                        .color(color)
+                };
+            }
+
+            let file_name = track
+                .path()
+                .file_name()
+                .and_then(|n| n.to_str())
+                .map_or_else(|| t!("unknown"), str::to_string);
+
+            let thumbnail: Element<'_, KantaMessage> = match track.cover() {
+                Some(handle) => image(handle.clone())
+                    .width(PLAYLIST_THUMBNAIL_SIZE)
+                    .height(PLAYLIST_THUMBNAIL_SIZE)
+                    .into(),
+                None => space().width(PLAYLIST_THUMBNAIL_SIZE).into(),
+            };
+
+            let row = btn!(
+                row![]
+                    .push(
+                        self.text(if is_playing { "▶" } else { "" })
+                            .width(PLAYLIST_ICON_WIDTH)
+                            .color(color)
+                    )
+                    .push(thumbnail)
+                    .push(track_field!(title, file_name.clone(), Length::Fill))
+                    .push(track_field!(artist, t!("no-artist"), columns.artist))
+                    .push(track_field!(album, t!("no-album"), columns.album))
+                    .push(
+                        self.text(format_duration(track.duration()))
+                            .width(columns.length)
+                            .align_x(Horizontal::Right)
+                            .color(color)
+                    )
+                    .spacing(8)
+                    .height(PLAYLIST_ROW_HEIGHT)
+                    .align_y(Vertical::Center),
+                SelectTrack(index)
+            )
+            .style(if self.selected_tracks.contains(&index) {
+                button::secondary
+            } else {
+                button::text
+            })
+            .padding(0);
+
+            let format = if track.is_stream() {
+                t!("stream")
+            } else {
+                track
+                    .path()
+                    .extension()
+                    .and_then(|e| e.to_str())
+                    .map_or_else(|| t!("unknown"), str::to_uppercase)
+            };
+            let details = container(
+                column![
+                    self.text(track.title().map_or(file_name, str::to_string))
+                        .size(self.text_size(16.0)),
+                    self.text(format!(
+                        "{}: {}",
+                        t!("artist"),
+                        track
+                            .artist()
+                            .map_or_else(|| t!("no-artist"), str::to_string)
+                    )),
+                    self.text(format!(
+                        "{}: {}",
+                        t!("album"),
+                        track.album().map_or_else(|| t!("no-album"), str::to_string)
+                    )),
+                    self.text(format!(
+                        "{}: {}",
+                        t!("duration"),
+                        format_duration(track.duration())
+                    )),
+                    self.text(format!("{}: {}", t!("path"), track.path().display())),
+                    self.text(format!("{}: {}", t!("format"), format)),
+                ]
+                .spacing(2),
+            )
+            .max_width(TRACK_TOOLTIP_WIDTH)
+            .padding(8)
+            .style(container::bordered_box);
+            let row =
+                tooltip(row, details, tooltip::Position::FollowCursor).delay(TRACK_TOOLTIP_DELAY);
+
+            mouse_area(row)
+                .on_right_press(OpenContextMenu(index))
+                .on_enter(HoverTrack(index))
+                .on_exit(UnhoverTrack(index))
+                .into()
+        };
+
+        let entries = self.playlist_entries();
+        let tracks = self.player.playlist();
+
+        let visible_top = self.playlist_scroll_offset - PLAYLIST_OVERSCAN;
+        let visible_bottom =
+            self.playlist_scroll_offset + self.playlist_viewport_height + PLAYLIST_OVERSCAN;
+
+        let mut playlist_tracks = column![];
+        let mut entry_bottom = 0.0;
+        let mut skipped_height = 0.0;
+        for entry in entries {
+            let entry_height = entry.height();
+            let entry_top = entry_bottom;
+            entry_bottom += entry_height;
+
+            if entry_bottom < visible_top || entry_top > visible_bottom {
+                skipped_height += entry_height;
+                continue;
+            }
+            if skipped_height > 0.0 {
+                playlist_tracks = playlist_tracks.push(space().height(skipped_height));
+                skipped_height = 0.0;
+            }
+
+            playlist_tracks = playlist_tracks.push(match entry {
+                PlaylistEntry::AlbumHeader {
+                    index,
+                    album,
+                    album_artist,
+                    key,
+                    is_collapsed,
+                    ..
+                } => {
+                    let track = &tracks[index];
+                    let cover: Element<'_, KantaMessage> = match track.cover() {
+                        Some(handle) => image(handle.clone()).width(40).height(40).into(),
+                        None => space().width(40).height(40).into(),
+                    };
+                    let album_header = row![]
+                        .push(self.text(if is_collapsed { "▸" } else { "▾" }).color(muted))
+                        .push(cover)
+                        .push(
+                            column![].push(self.text(album)).push(
+                                self.text(
+                                    album_artist
+                                        .into_iter()
+                                        .chain([track
+                                            .year()
+                                            .map_or_else(|| t!("unknown-year"), str::to_string)])
+                                        .collect::<Vec<_>>()
+                                        .join(" · "),
+                                )
+                                .color(muted),
+                            ),
+                        )
+                        .spacing(8)
+                        .height(ALBUM_HEADER_HEIGHT)
+                        .align_y(Vertical::Center);
+                    focusable(btn!(album_header, ToggleAlbumGroup(key), text).padding(0)).into()
+                }
+                PlaylistEntry::Track {
+                    index,
+                    is_grouped: false,
+                } => track_row(index),
+                PlaylistEntry::Track {
+                    index,
+                    is_grouped: true,
+                } => container(track_row(index))
+                    .padding(Padding {
+                        left: 16.0,
+                        ..Padding::ZERO
+                    })
+                    .into(),
+            });
+        }
+        if skipped_height > 0.0 {
+            playlist_tracks = playlist_tracks.push(space().height(skipped_height));
+        }
+        let playlist_tracks = scrollable(playlist_tracks)
+            .id(PLAYLIST_SCROLLABLE_ID)
+            .on_scroll(PlaylistScrolled);
+
+        let mut playlist = column![].push(playlist_controls);
+        if self.selected_tracks.len() > 1 {
+            playlist = playlist.push(
+                row![]
+                    .push(
+                        self.text(t!("tracks-selected", count = self.selected_tracks.len()))
+                            .color(muted),
+                    )
+                    .push(focusable(btn!(
+                        self.text(t!("move-up")),
+                        MoveSelectionUp,
+                        secondary
+                    )))
+                    .push(focusable(btn!(
+                        self.text(t!("move-down")),
+                        MoveSelectionDown,
+                        secondary
+                    )))
+                    .push(focusable(btn!(
+                        self.text(t!("save-as-playlist")),
+                        ExportSelection,
+                        secondary
+                    )))
+                    .push(focusable(btn!(
+                        self.text(t!("export-as")),
+                        ExportTracks(self.selected_tracks.iter().copied().collect()),
+                        secondary
+                    )))
+                    .push(focusable(btn!(
+                        self.text(t!("remove")),
+                        RemoveSelection,
+                        danger
+                    )))
+                    .push(focusable(btn!(
+                        self.text(t!("clear-selection")),
+                        ClearSelection,
+                        text
+                    )))
+                    .spacing(8)
+                    .padding(Padding {
+                        top: 8.0,
+                        ..Padding::ZERO
+                    })
+                    .align_y(Vertical::Center),
+            );
+        }
+        let playlist = playlist
+            .push(playlist_header)
+            .push(playlist_tracks)
+            .height(Length::Fill);
+
+        let lyrics = self.lyrics(self.config.text_size);
+
+        let mut content = column![];
+        if !self.config.file_associations_prompted && !cfg!(target_os = "macos") {
+            content = content.push(
+                row![]
+                    .push(self.text(t!("file-associations-prompt")))
+                    .push(focusable(btn!(
+                        self.text(t!("register")),
+                        RegisterFileAssociations
+                    )))
+                    .push(focusable(btn!(
+                        self.text(t!("not-now")),
+                        DismissFileAssociationPrompt,
+                        secondary
+                    )))
+                    .spacing(8)
+                    .align_y(Vertical::Center),
+            );
+        }
+        if let Some((_, diff)) = self
+            .watched_playlist
+            .as_ref()
+            .and_then(|watched| watched.change.as_ref())
+        {
+            content = content.push(
+                row![]
+                    .push(self.text(t!(
+                        "playlist-changed-prompt",
+                        added = diff.added.len(),
+                        removed = diff.removed.len()
+                    )))
+                    .push(focusable(btn!(
+                        self.text(t!("merge")),
+                        MergePlaylistChanges
+                    )))
+                    .push(focusable(btn!(
+                        self.text(t!("ignore")),
+                        IgnorePlaylistChanges,
+                        secondary
+                    )))
+                    .spacing(8)
+                    .align_y(Vertical::Center),
+            );
+        }
+        if let Some(queue) = &self.pending_queue {
+            content = content.push(
+                row![]
+                    .push(self.text(t!("restore-queue-prompt", count = queue.tracks.len())))
+                    .push(
+                        (queue.index.is_some() && !queue.position.is_zero()).then(|| {
+                            self.text(t!("resume-at", position = format_duration(queue.position)))
+                                .color(muted)
+                        }),
+                    )
+                    .push(focusable(btn!(self.text(t!("restore")), RestoreQueue)))
+                    .push(focusable(btn!(
+                        self.text(t!("discard")),
+                        DiscardSavedQueue,
+                        secondary
+                    )))
+                    .spacing(8)
+                    .align_y(Vertical::Center),
+            );
+        }
+        let content = content
+            .push(controls)
+            .push(cue_points.wrap().vertical_spacing(8))
+            .push(
+                row![]
+                    .push(self.playlist_sidebar())
+                    .push(playlist)
+                    .spacing(8),
+            )
+            .push(lyrics)
+            .spacing(8)
+            .padding(8);
+
+        let mut layers = if self.karaoke {
+            stack![self.karaoke_screen()]
+        } else if self.now_playing_view {
+            stack![self.now_playing_screen()]
+        } else {
+            stack![content]
+        };
+
+        if let Some((index, position)) = self.context_menu {
+            let menu_item = |label: String, message| {
+                focusable(
+                    btn!(self.text(label), message, text)
+                        .width(Length::Fill)
+                        .padding([4, 8]),
+                )
+            };
+            let mut menu_items = column![]
+                .push(menu_item(t!("play-now"), JumpToTrack(index)))
+                .push(menu_item(t!("play-next"), PlayNext(index)))
+                .push(menu_item(t!("remove"), RemoveTrack(index)))
+                .push(menu_item(
+                    t!("show-in-file-manager"),
+                    ShowInFileManager(index),
+                ))
+                .push(menu_item(t!("track-info"), ShowTrackInfo(index)))
+                .push(menu_item(t!("export-as"), ExportTracks(vec![index])));
+            for name in self.playlists.iter().flat_map(PlaylistLibrary::names) {
+                menu_items = menu_items.push(menu_item(
+                    t!("add-to-playlist", name = name.as_str()),
+                    AddToNamedPlaylist(name.clone(), index),
+                ));
+            }
+            let menu = container(menu_items)
+                .width(CONTEXT_MENU_WIDTH)
+                .padding(4)
+                .style(container::bordered_box);
+
+            layers = layers.push(opaque(
+                mouse_area(pin(menu).position(position))
+                    .on_press(CloseContextMenu)
+                    .on_right_press(CloseContextMenu),
+            ));
+        }
+
+        if let Some(track) = &self.track_info {
+            let track_stats = self
+                .track_stats
+                .as_ref()
+                .map(|track_stats| track_stats.get(track.path()))
+                .unwrap_or_default();
+            let info_field = |id, value: String| {
+                row![]
+                    .push(self.text(t!(id)).width(80).color(muted))
+                    .push(self.text(value))
+                    .spacing(8)
+            };
+            let info = container(
+                column![]
+                    .push(info_field(
+                        "title",
+                        track.title().map_or_else(|| t!("no-title"), str::to_string),
+                    ))
+                    .push(info_field(
+                        "artist",
+                        track
+                            .artist()
+                            .map_or_else(|| t!("no-artist"), str::to_string),
+                    ))
+                    .push(info_field(
+                        "album",
+                        track.album().map_or_else(|| t!("no-album"), str::to_string),
+                    ))
+                    .push(info_field(
+                        "year",
+                        track.year().map_or_else(|| t!("no-year"), str::to_string),
+                    ))
+                    .push(info_field("duration", format_duration(track.duration())))
+                    .push(info_field(
+                        "tempo",
+                        track.bpm().map_or_else(
+                            || t!("unknown"),
+                            |bpm| t!("bpm", bpm = bpm.round() as i64),
+                        ),
+                    ))
+                    .push(info_field(
+                        "loudness",
+                        match (track.replay_gain(), track.loudness()) {
+                            (Some(gain), _) => match track.album_replay_gain() {
+                                Some(album_gain) => t!(
+                                    "replay-gain-with-album",
+                                    gain = format!("{:+.1}", gain),
+                                    album_gain = format!("{:+.1}", album_gain)
+                                ),
+                                None => t!("replay-gain", gain = format!("{:+.1}", gain)),
+                            },
+                            (None, Some(loudness)) => t!(
+                                "loudness-value",
+                                integrated = format!("{:.1}", loudness.integrated),
+                                peak = format!("{:.1}", loudness.true_peak)
+                            ),
+                            (None, None) => t!("unknown"),
+                        },
+                    ))
+                    .push(info_field(
+                        "rating",
+                        match track_stats.rating {
+                            Some(rating) => format!(
+                                "{}{}",
+                                "★".repeat(rating as usize),
+                                "☆".repeat((MAX_RATING - rating) as usize)
+                            ),
+                            None => t!("unknown"),
+                        },
+                    ))
+                    .push(info_field("plays", track_stats.play_count.to_string()))
+                    .push(info_field("path", track.path().display().to_string()))
+                    .push(focusable(btn!(
+                        self.text(t!("close")),
+                        CloseTrackInfo,
+                        secondary
+                    )))
+                    .spacing(8),
+            )
+            .max_width(600)
+            .padding(16)
+            .style(container::bordered_box);
+
+            layers = layers.push(opaque(mouse_area(center(info)).on_press(CloseTrackInfo)));
+        }
+
+        if let Some(stats) = &self.stats {
+            let mut top_artists = column![].spacing(4);
+            for (artist, count) in &stats.top_artists {
+                top_artists = top_artists.push(self.setting(
+                    artist.clone(),
+                    count.to_string(),
+                    space().width(Length::Fill),
+                ));
+            }
+
+            let dialog = container(
+                column![]
+                    .push(self.text(t!("statistics")).size(self.text_size(20.0)))
+                    .push(self.setting(
+                        t!("tracks"),
+                        stats.tracks.to_string(),
+                        space().width(Length::Fill),
+                    ))
+                    .push(self.setting(
+                        t!("albums"),
+                        stats.albums.to_string(),
+                        space().width(Length::Fill),
+                    ))
+                    .push(self.setting(
+                        t!("artists"),
+                        stats.artists.to_string(),
+                        space().width(Length::Fill),
+                    ))
+                    .push(self.setting(
+                        t!("total-duration"),
+                        format_duration(stats.duration),
+                        space().width(Length::Fill),
+                    ))
+                    .push(self.setting(
+                        t!("size-on-disk"),
+                        stats::format_size(stats.size_on_disk),
+                        space().width(Length::Fill),
+                    ))
+                    .push(self.text(t!("top-artists")).color(muted))
+                    .push(top_artists)
+                    .push(focusable(btn!(
+                        self.text(t!("close")),
+                        CloseStatistics,
+                        secondary
+                    )))
+                    .spacing(8),
+            )
+            .width(SETTINGS_WIDTH)
+            .padding(16)
+            .style(container::bordered_box);
+
+            layers = layers.push(opaque(
+                mouse_area(center(opaque(dialog))).on_press(CloseStatistics),
+            ));
+        }
+
+        if self.jobs_panel_open {
+            let mut jobs = column![].spacing(8);
+            for kind in JobKind::ALL {
+                let progress = self.jobs.get(kind);
+                let (done, total) = (progress.done(), progress.total());
+                let is_paused = progress.is_paused();
+                jobs = jobs.push(
+                    column![]
+                        .push(
+                            row![]
+                                .push(self.text(kind.to_string()).width(Length::Fill))
+                                .push(
+                                    self.text(if !progress.is_active() {
+                                        t!("job-idle")
+                                    } else if is_paused {
+                                        t!("job-paused", done = done, total = total)
+                                    } else {
+                                        t!("job-progress", done = done, total = total)
+                                    })
+                                    .color(muted),
+                                )
+                                .push(focusable(btn!(
+                                    self.text(if is_paused { t!("resume") } else { t!("pause") }),
+                                    SetJobPaused(kind, !is_paused),
+                                    text
+                                )))
+                                .push(focusable(
+                                    button(self.text(t!("cancel")))
+                                        .on_press_maybe(
+                                            progress.is_active().then_some(CancelJob(kind)),
+                                        )
+                                        .style(button::text),
+                                ))
+                                .spacing(8)
+                                .align_y(Vertical::Center),
+                        )
+                        .push(progress_bar(0.0..=1.0, done as f32 / total.max(1) as f32).girth(4))
+                        .spacing(4),
+                );
+            }
+
+            let dialog = container(
+                column![]
+                    .push(self.text(t!("background-jobs")).size(self.text_size(20.0)))
+                    .push(jobs)
+                    .push(
+                        self.setting(
+                            t!("job-concurrency"),
+                            self.config.job_concurrency.to_string(),
+                            focusable(
+                                slider(
+                                    JOB_CONCURRENCY_RANGE,
+                                    self.config.job_concurrency,
+                                    SetJobConcurrency,
+                                )
+                                .on_release(SaveSettings),
+                            ),
+                        ),
+                    )
+                    .push(
+                        self.text(t!("job-concurrency-note"))
+                            .size(self.text_size(12.0))
+                            .color(muted),
+                    )
+                    .push(focusable(btn!(
+                        self.text(t!("close")),
+                        CloseJobsPanel,
+                        secondary
+                    )))
+                    .spacing(8),
+            )
+            .width(SETTINGS_WIDTH)
+            .padding(16)
+            .style(container::bordered_box);
+
+            layers = layers.push(opaque(
+                mouse_area(center(opaque(dialog))).on_press(CloseJobsPanel),
+            ));
+        }
+
+        if self.sound_dialog_open
+            && let Some(track) = self.player.current_track()
+        {
+            let dsp = self.player.dsp();
+            let saved = self
+                .track_dsp
+                .as_ref()
+                .and_then(|track_dsp| track_dsp.get(track.path()));
+            let band_names = [t!("bass"), t!("mids"), t!("treble")];
+
+            let mut dialog = column![]
+                .push(self.text(t!("sound")).size(self.text_size(20.0)))
+                .push(self.text(track.title().unwrap_or_default()).color(muted))
+                .push(self.setting(
+                    t!("speed"),
+                    format!("{:.2}×", dsp.speed),
+                    focusable(slider(SPEED_RANGE, dsp.speed, SetSpeed).step(0.05)),
+                ))
+                .spacing(8);
+            for (band, name) in band_names.into_iter().enumerate() {
+                dialog = dialog.push(self.setting(
+                    name,
+                    format!("{:+} dB", dsp.eq[band]),
+                    focusable(slider(EQ_GAIN_RANGE, dsp.eq[band], move |gain| {
+                        SetEqGain(band, gain)
+                    })),
+                ));
+            }
+            let dialog = dialog
+                .push(
+                    self.text(if saved.is_some() {
+                        t!("sound-saved-for-track")
+                    } else {
+                        t!("sound-not-saved")
+                    })
+                    .size(self.text_size(12.0))
+                    .color(muted),
+                )
+                .push(
+                    row![]
+                        .push(focusable(btn!(
+                            self.text(t!("save-for-track")),
+                            SaveTrackSound
+                        )))
+                        .push(focusable(btn!(
+                            self.text(t!("reset")),
+                            ForgetTrackSound,
+                            secondary
+                        )))
+                        .push(focusable(btn!(
+                            self.text(t!("close")),
+                            CloseSoundDialog,
+                            secondary
+                        )))
+                        .spacing(8),
+                );
+            let dialog = container(dialog)
+                .width(SETTINGS_WIDTH)
+                .padding(16)
+                .style(container::bordered_box);
+
+            layers = layers.push(opaque(
+                mouse_area(center(opaque(dialog))).on_press(CloseSoundDialog),
+            ));
+        }
+
+        if let (Some(input), Some(scripting)) = (&self.script_input, &self.scripting) {
+            let output = column(
+                scripting
+                    .output()
+                    .into_iter()
+                    .map(|line| self.text(line).size(self.text_size(12.0)).into()),
+            )
+            .spacing(2);
+            let dialog = column![]
+                .push(self.text(t!("script-console")).size(self.text_size(20.0)))
+                .push(
+                    scrollable(output)
+                        .anchor_bottom()
+                        .height(240)
+                        .width(Length::Fill),
+                )
+                .push(
+                    self.text_input("kanta.set_volume(30)", input)
+                        .id(SCRIPT_INPUT_ID)
+                        .on_input(ScriptInputChanged)
+                        .on_submit(RunScript),
+                )
+                .push(
+                    self.text(t!(
+                        "script-console-note",
+                        path = scripting::init_script_path()
+                            .map(|path| path.display().to_string())
+                            .unwrap_or_default()
+                    ))
+                    .size(self.text_size(12.0))
+                    .color(muted),
+                )
+                .push(
+                    row![]
+                        .push(focusable(btn!(self.text(t!("run-script")), RunScript)))
+                        .push(focusable(btn!(
+                            self.text(t!("clear-output")),
+                            ClearScriptOutput,
+                            secondary
+                        )))
+                        .push(focusable(btn!(
+                            self.text(t!("close")),
+                            CloseScriptConsole,
+                            secondary
+                        )))
+                        .spacing(8),
+                )
+                .spacing(8);
+            let dialog = container(dialog)
+                .width(SETTINGS_WIDTH * 1.25)
+                .padding(16)
+                .style(container::bordered_box);
+
+            layers = layers.push(opaque(
+                mouse_area(center(opaque(dialog))).on_press(CloseScriptConsole),
+            ));
+        }
+
+        if let Some(path) = &self.overwrite_prompt {
+            let dialog = column![]
+                .push(
+                    self.text(t!("overwrite-playlist"))
+                        .size(self.text_size(20.0)),
+                )
+                .push(self.text(t!(
+                    "overwrite-playlist-explanation",
+                    name = path
+                        .file_name()
+                        .unwrap_or(path.as_os_str())
+                        .to_string_lossy()
+                )))
+                .push(self.text(path.display().to_string()).color(muted))
+                .push(
+                    row![]
+                        .push(focusable(btn!(
+                            self.text(t!("overwrite")),
+                            ConfirmOverwrite,
+                            danger
+                        )))
+                        .push(focusable(btn!(
+                            self.text(t!("cancel")),
+                            CancelOverwrite,
+                            secondary
+                        )))
+                        .spacing(8),
+                )
+                .spacing(16);
+            let dialog = container(dialog)
+                .width(SETTINGS_WIDTH)
+                .padding(16)
+                .style(container::bordered_box);
+
+            layers = layers.push(opaque(
+                mouse_area(center(opaque(dialog))).on_press(CancelOverwrite),
+            ));
+        }
+
+        if self.close_prompt_open {
+            let dialog = column![]
+                .push(
+                    self.text(t!("close-to-background"))
+                        .size(self.text_size(20.0)),
+                )
+                .push(self.text(t!("close-to-background-explanation")))
+                .push(
+                    row![]
+                        .push(focusable(btn!(self.text(t!("keep-playing")), HideWindow)))
+                        .push(focusable(btn!(self.text(t!("quit")), Quit, secondary)))
+                        .push(focusable(btn!(
+                            self.text(t!("cancel")),
+                            CancelClose,
+                            secondary
+                        )))
+                        .spacing(8),
+                )
+                .spacing(16);
+            let dialog = container(dialog)
+                .width(SETTINGS_WIDTH)
+                .padding(16)
+                .style(container::bordered_box);
+
+            layers = layers.push(opaque(center(opaque(dialog))));
+        }
+
+        if let Some(editor) = &self.lyrics_editor {
+            let dialog = column![]
+                .push(self.text(t!("edit-lyrics")).size(self.text_size(20.0)))
+                .push(self.text(editor.track.display().to_string()).color(muted));
+            let dialog = match &editor.sync {
+                Some(sync) => {
+                    let lines =
+                        column(sync.lines.iter().enumerate().map(|(index, (time, line))| {
+                            let color = if index == sync.cursor {
+                                SELECTED_COLOR
+                            } else {
+                                Color::WHITE
+                            };
+                            focusable(
+                                button(
+                                    row![]
+                                        .push(
+                                            self.text(time.map_or_else(
+                                                || "[--:--.--]".to_string(),
+                                                lyrics::format_timestamp,
+                                            ))
+                                            .color(muted),
+                                        )
+                                        .push(self.text(line).color(color))
+                                        .spacing(8),
+                                )
+                                .on_press(SeekToLyricLine(index))
+                                .style(button::text)
+                                .padding(0),
+                            )
+                            .into()
+                        }));
+                    dialog
+                        .push(
+                            self.text(t!("lyrics-sync-help"))
+                                .size(self.text_size(12.0))
+                                .color(muted),
+                        )
+                        .push(scrollable(lines).height(400).width(Length::Fill))
+                        .push(
+                            row![]
+                                .push(focusable(btn!(self.text(t!("save")), SaveLyrics)))
+                                .push(focusable(btn!(
+                                    self.text(t!("done-syncing")),
+                                    StopLyricsSync,
+                                    secondary
+                                )))
+                                .push(focusable(btn!(
+                                    self.text(t!("close")),
+                                    CloseLyricsEditor,
+                                    secondary
+                                )))
+                                .spacing(8),
+                        )
+                }
+                None => dialog
+                    .push(
+                        text_editor(&editor.content)
+                            .size(self.config.text_size)
+                            .on_action(LyricsEdited)
+                            .height(400),
+                    )
+                    .push(
+                        self.text(t!("lyrics-save-note"))
+                            .size(self.text_size(12.0))
+                            .color(muted),
+                    )
+                    .push(
+                        row![]
+                            .push(focusable(btn!(self.text(t!("save")), SaveLyrics)))
+                            .push(focusable(btn!(
+                                self.text(t!("sync-timestamps")),
+                                StartLyricsSync,
+                                secondary
+                            )))
+                            .push(focusable(btn!(
+                                self.text(t!("close")),
+                                CloseLyricsEditor,
+                                secondary
+                            )))
+                            .spacing(8),
+                    ),
+            };
+            let dialog = container(dialog.spacing(8))
+                .width(SETTINGS_WIDTH * 1.25)
+                .padding(16)
+                .style(container::bordered_box);
+
+            layers = layers.push(opaque(center(opaque(dialog))));
+        }
+
+        if let Some(dialog) = &self.export_dialog {
+            let status: Element<'_, KantaMessage> = match dialog.progress {
+                Some((done, total)) => column![]
+                    .push(progress_bar(0.0..=total.max(1) as f32, done as f32))
+                    .push(
+                        self.text(t!("exporting", done = done, total = total))
+                            .color(muted),
+                    )
+                    .spacing(4)
+                    .into(),
+                None if dialog.finished => self
+                    .text(t!(
+                        "export-finished",
+                        count = dialog.tracks.len() - dialog.failures.len(),
+                        failed = dialog.failures.len()
+                    ))
+                    .color(muted)
+                    .into(),
+                None => space().into(),
+            };
+
+            let bitrate: Element<'_, KantaMessage> = if dialog.format.is_lossless() {
+                space().into()
+            } else {
+                row![]
+                    .push(focusable(self.pick_list(
+                        transcode::BITRATES,
+                        Some(dialog.bitrate),
+                        SetExportBitrate,
+                    )))
+                    .push(self.text(t!("kbps")))
+                    .spacing(8)
+                    .align_y(Vertical::Center)
+                    .into()
+            };
+
+            let dialog = container(
+                column![]
+                    .push(self.text(t!("export-as")).size(self.text_size(20.0)))
+                    .push(
+                        self.text(t!("tracks-selected", count = dialog.tracks.len()))
+                            .color(muted),
+                    )
+                    .push(
+                        row![]
+                            .push(focusable(self.pick_list(
+                                transcode::Format::ALL,
+                                Some(dialog.format),
+                                SetExportFormat,
+                            )))
+                            .push(bitrate)
+                            .spacing(8)
+                            .align_y(Vertical::Center),
+                    )
+                    .push(status)
+                    .push(
+                        row![]
+                            .push(focusable(
+                                button(self.text(t!("export"))).on_press_maybe(
+                                    (dialog.progress.is_none() && !dialog.tracks.is_empty())
+                                        .then_some(StartExport),
+                                ),
+                            ))
+                            .push(focusable(btn!(
+                                self.text(t!("close")),
+                                CloseExportDialog,
+                                secondary
+                            )))
+                            .spacing(8),
+                    )
+                    .spacing(8),
+            )
+            .width(SETTINGS_WIDTH)
+            .padding(16)
+            .style(container::bordered_box);
+
+            layers = layers.push(opaque(
+                mouse_area(center(opaque(dialog))).on_press(CloseExportDialog),
+            ));
+        }
+
+        if let Some(export) = &self.history_export {
+            let status = match export.exported {
+                Some(count) => t!("history-exported", count = count),
+                None => t!(
+                    "plays-recorded",
+                    count = self.history.as_ref().map_or(0, History::len)
+                ),
+            };
+
+            let dialog = container(
+                column![]
+                    .push(self.text(t!("export-history")).size(self.text_size(20.0)))
+                    .push(
+                        row![]
+                            .push(focusable(self.pick_list(
+                                ExportContents::ALL,
+                                Some(export.contents),
+                                SetHistoryExportContents,
+                            )))
+                            .push(focusable(self.pick_list(
+                                ExportFormat::ALL,
+                                Some(export.format),
+                                SetHistoryExportFormat,
+                            )))
+                            .spacing(8),
+                    )
+                    .push(
+                        row![]
+                            .push(self.text(t!("from")).color(muted))
+                            .push(
+                                self.text_input("YYYY-MM-DD", &export.from)
+                                    .on_input(HistoryExportFromChanged),
+                            )
+                            .push(self.text(t!("until")).color(muted))
+                            .push(
+                                self.text_input("YYYY-MM-DD", &export.until)
+                                    .on_input(HistoryExportUntilChanged)
+                                    .on_submit(StartHistoryExport),
+                            )
+                            .spacing(8)
+                            .align_y(Vertical::Center),
+                    )
+                    .push(
+                        self.text(t!("history-range-note"))
+                            .size(self.text_size(12.0))
+                            .color(muted),
+                    )
+                    .push(self.text(status).color(muted))
+                    .push(
+                        row![]
+                            .push(focusable(btn!(self.text(t!("export")), StartHistoryExport)))
+                            .push(focusable(btn!(
+                                self.text(t!("close")),
+                                CloseHistoryExport,
+                                secondary
+                            )))
+                            .spacing(8),
+                    )
+                    .spacing(8),
+            )
+            .width(SETTINGS_WIDTH)
+            .padding(16)
+            .style(container::bordered_box);
+
+            layers = layers.push(opaque(
+                mouse_area(center(opaque(dialog))).on_press(CloseHistoryExport),
+            ));
+        }
+
+        if let Some(dialog) = &self.sync_dialog {
+            let mut playlists = column![].spacing(4);
+            for name in self.playlists.iter().flat_map(|p| p.names()) {
+                playlists = playlists.push(focusable(
+                    self.checkbox(dialog.playlists.contains(name))
+                        .label(name)
+                        .on_toggle(|_| ToggleSyncPlaylist(name.clone())),
+                ));
+            }
+
+            let destination = dialog
+                .destination
+                .as_ref()
+                .map_or_else(|| t!("no-folder-chosen"), |d| d.display().to_string());
+
+            let status: Element<'_, KantaMessage> = match dialog.progress {
+                Some((done, total)) => column![]
+                    .push(progress_bar(0.0..=total.max(1) as f32, done as f32))
+                    .push(
+                        self.text(t!("syncing", done = done, total = total))
+                            .color(muted),
+                    )
+                    .spacing(4)
+                    .into(),
+                None if dialog.finished => self.text(t!("sync-finished")).color(muted).into(),
+                None => space().into(),
+            };
+
+            let can_sync = dialog.progress.is_none()
+                && dialog.destination.is_some()
+                && !dialog.playlists.is_empty();
+
+            let dialog = container(
+                column![]
+                    .push(self.text(t!("sync-to-device")).size(self.text_size(20.0)))
+                    .push(self.text(t!("playlists")).color(muted))
+                    .push(playlists)
+                    .push(
+                        self.setting(
+                            t!("sync-destination"),
+                            String::new(),
+                            row![]
+                                .push(self.text(destination).width(Length::Fill))
+                                .push(focusable(btn!(
+                                    self.text(t!("choose")),
+                                    ChooseSyncDestination,
+                                    secondary
+                                )))
+                                .spacing(8)
+                                .align_y(Vertical::Center),
+                        ),
+                    )
+                    .push(focusable(
+                        self.checkbox(dialog.transcode)
+                            .label(t!("transcode-lossless"))
+                            .on_toggle(SetSyncTranscode),
+                    ))
+                    .push(
+                        row![]
+                            .push(focusable(self.pick_list(
+                                transcode::Format::LOSSY,
+                                Some(dialog.format),
+                                SetSyncFormat,
+                            )))
+                            .push(focusable(self.pick_list(
+                                transcode::BITRATES,
+                                Some(dialog.bitrate),
+                                SetSyncBitrate,
+                            )))
+                            .push(self.text(t!("kbps")))
+                            .spacing(8)
+                            .align_y(Vertical::Center),
+                    )
+                    .push(status)
+                    .push(
+                        row![]
+                            .push(focusable(
+                                button(self.text(t!("sync")))
+                                    .on_press_maybe(can_sync.then_some(StartSync)),
+                            ))
+                            .push(focusable(btn!(
+                                self.text(t!("close")),
+                                CloseSyncDialog,
+                                secondary
+                            )))
+                            .spacing(8),
+                    )
+                    .spacing(8),
+            )
+            .width(SETTINGS_WIDTH)
+            .padding(16)
+            .style(container::bordered_box);
+
+            layers = layers.push(opaque(
+                mouse_area(center(opaque(dialog))).on_press(CloseSyncDialog),
+            ));
+        }
+
+        if self.settings_open {
+            let mut music_folders = column![]
+                .push(self.text(t!("music-folders")).color(muted))
+                .spacing(4);
+            for folder in &self.config.music_folders {
+                music_folders = music_folders.push(
+                    row![]
+                        .push(self.text(folder.to_string_lossy()).width(Length::Fill))
+                        .push(focusable(btn!(
+                            self.text(t!("remove")),
+                            RemoveMusicFolder(folder.clone()),
+                            text
+                        )))
+                        .align_y(Vertical::Center),
+                );
+            }
+            let mut music_folders = music_folders
+                .push(focusable(btn!(
+                    self.text(t!("add-music-folder")),
+                    AddMusicFolder,
+                    secondary
+                )))
+                .push(self.text(t!("library-excludes")).color(muted));
+            for pattern in &self.config.library_excludes {
+                music_folders = music_folders.push(
+                    row![]
+                        .push(self.text(pattern).width(Length::Fill))
+                        .push(focusable(btn!(
+                            self.text(t!("remove")),
+                            RemoveLibraryExclude(pattern.clone()),
+                            text
+                        )))
+                        .align_y(Vertical::Center),
+                );
+            }
+            let music_folders = music_folders
+                .push(
+                    row![]
+                        .push(
+                            self.text_input("**/ringtones/**", &self.new_library_exclude)
+                                .on_input(LibraryExcludeInputChanged)
+                                .on_submit(AddLibraryExclude),
+                        )
+                        .push(focusable(btn!(
+                            self.text(t!("add")),
+                            AddLibraryExclude,
+                            secondary
+                        )))
+                        .push(focusable(btn!(
+                            self.text(t!("exclude-folder")),
+                            ExcludeLibraryFolder,
+                            secondary
+                        )))
+                        .spacing(8)
+                        .align_y(Vertical::Center),
+                )
+                .push(
+                    self.text(t!("library-excludes-note"))
+                        .size(self.text_size(12.0))
+                        .color(muted),
+                );
+
+            let scrobble = &self.config.scrobble;
+            let mut scrobbling = column![]
+                .push(self.text(t!("scrobbling")).color(muted))
+                .push(
+                    self.text_input(&t!("scrobble-server"), &scrobble.server)
+                        .on_input(SetScrobbleServer),
+                )
+                .push(
+                    self.text_input(&t!("scrobble-token"), &scrobble.token)
+                        .on_input(SetScrobbleToken)
+                        .secure(true),
+                )
+                .push(
+                    self.text(t!("scrobble-server-note"))
+                        .size(self.text_size(12.0))
+                        .color(muted),
+                )
+                .push(
+                    self.setting(
+                        t!("scrobble-percent"),
+                        format!("{:.0}%", scrobble.min_percent),
+                        focusable(
+                            slider(
+                                SCROBBLE_PERCENT_RANGE,
+                                scrobble.min_percent,
+                                SetScrobblePercent,
+                            )
+                            .step(5.0)
+                            .on_release(SaveSettings),
+                        ),
+                    ),
+                )
+                .push(
+                    self.setting(
+                        t!("scrobble-min-duration"),
+                        format_duration(Duration::from_secs_f32(scrobble.min_duration_secs)),
+                        focusable(
+                            slider(
+                                SCROBBLE_MIN_DURATION_RANGE,
+                                scrobble.min_duration_secs,
+                                SetScrobbleMinDuration,
+                            )
+                            .step(5.0)
+                            .on_release(SaveSettings),
+                        ),
+                    ),
+                )
+                .push(
+                    row![]
+                        .push(
+                            self.text(t!(
+                                "pending-scrobbles",
+                                count = self.scrobble_queue.as_ref().map_or(0, ScrobbleQueue::len)
+                            ))
+                            .width(Length::Fill),
+                        )
+                        .push(focusable(btn!(
+                            self.text(t!("retry-now")),
+                            RetryScrobbles,
+                            text
+                        )))
+                        .align_y(Vertical::Center),
+                )
+                .push(self.text(t!("scrobble-ignored-folders")).color(muted))
+                .spacing(4);
+            for folder in &scrobble.ignored_folders {
+                scrobbling = scrobbling.push(
+                    row![]
+                        .push(self.text(folder.to_string_lossy()).width(Length::Fill))
+                        .push(focusable(btn!(
+                            self.text(t!("remove")),
+                            RemoveScrobbleIgnoredFolder(folder.clone()),
+                            text
+                        )))
+                        .align_y(Vertical::Center),
+                );
+            }
+            let scrobbling = scrobbling.push(focusable(btn!(
+                self.text(t!("add-ignored-folder")),
+                AddScrobbleIgnoredFolder,
+                secondary
+            )));
+
+            let mut plugin_panels = column![]
+                .push(self.text(t!("plugins")).color(muted))
+                .spacing(4);
+            if self.plugins.as_ref().is_none_or(Plugins::is_empty) {
+                plugin_panels = plugin_panels.push(self.text(t!("no-plugins")));
+            }
+            for (name, panel) in self.plugins.iter().flat_map(Plugins::panels) {
+                plugin_panels = plugin_panels.push(self.text(name));
+                if let Some(panel) = panel {
+                    plugin_panels = plugin_panels.push(self.text(panel).size(self.text_size(12.0)));
+                }
+            }
+            let plugin_panels = plugin_panels.push(
+                self.text(t!(
+                    "plugins-note",
+                    dir = plugins::dir()
+                        .map(|dir| dir.display().to_string())
+                        .unwrap_or_default()
+                ))
+                .size(self.text_size(12.0))
+                .color(muted),
+            );
+
+            let settings = container(
+                column![]
+                    .push(self.text(t!("settings")).size(self.text_size(20.0)))
+                    .push(
+                        self.setting(
+                            t!("language"),
+                            String::new(),
+                            focusable(
+                                self.pick_list(
+                                    i18n::LANGUAGES,
+                                    i18n::LANGUAGES
+                                        .iter()
+                                        .find(|language| language.id == i18n::current_language())
+                                        .copied(),
+                                    |language| SetLanguage(Some(language.id.to_string())),
+                                )
+                                .width(Length::Fill),
+                            ),
+                        ),
+                    )
+                    .push(
+                        self.setting(
+                            t!("text-size"),
+                            format!("{}", self.config.text_size),
+                            focusable(
+                                slider(TEXT_SIZE_RANGE, self.config.text_size, SetTextSize)
+                                    .step(1.0)
+                                    .on_release(SaveSettings),
+                            ),
+                        ),
+                    )
+                    .push(
+                        self.setting(
+                            t!("ui-scale"),
+                            format!("{:.0}%", self.config.ui_scale * 100.0),
+                            focusable(
+                                slider(UI_SCALE_RANGE, self.config.ui_scale, SetUiScale)
+                                    .step(0.05)
+                                    .on_release(SaveSettings),
+                            ),
+                        ),
+                    )
+                    .push(focusable(
+                        self.checkbox(self.config.album_colors)
+                            .label(t!("album-colors"))
+                            .on_toggle(SetAlbumColors),
+                    ))
+                    .push(focusable(
+                        self.checkbox(self.config.normalize_volume)
+                            .label(t!("normalize-volume"))
+                            .on_toggle(SetNormalizeVolume),
+                    ))
+                    .push(focusable(
+                        self.checkbox(self.config.show_stream_titles)
+                            .label(t!("show-stream-titles"))
+                            .on_toggle(SetShowStreamTitles),
+                    ))
+                    .push(focusable(
+                        self.checkbox(self.config.show_level_meters)
+                            .label(t!("show-level-meters"))
+                            .on_toggle(SetShowLevelMeters),
+                    ))
+                    .push(
+                        row![]
+                            .push(focusable(
+                                self.checkbox(self.config.resume_rewind)
+                                    .label(t!("resume-rewind"))
+                                    .on_toggle(SetResumeRewind)
+                                    .width(Length::Fill),
+                            ))
+                            .push(
+                                self.text_input(
+                                    &t!("seconds"),
+                                    &self.config.resume_rewind_secs.to_string(),
+                                )
+                                .on_input(SetResumeRewindSeconds)
+                                .width(80),
+                            )
+                            .spacing(8)
+                            .align_y(Vertical::Center),
+                    )
+                    .push(
+                        self.text(t!("resume-rewind-note"))
+                            .size(self.text_size(12.0))
+                            .color(muted),
+                    )
+                    .push(focusable(
+                        self.checkbox(self.config.hidden_tracks)
+                            .label(t!("hidden-tracks"))
+                            .on_toggle(SetHiddenTracks),
+                    ))
+                    .push(focusable(
+                        self.checkbox(self.config.append_pinned_playlists)
+                            .label(t!("append-pinned-playlists"))
+                            .on_toggle(SetAppendPinnedPlaylists),
+                    ))
+                    .push(
+                        row![]
+                            .push(focusable(
+                                self.checkbox(self.config.alarm.enabled)
+                                    .label(t!("alarm"))
+                                    .on_toggle(SetAlarm)
+                                    .width(Length::Fill),
+                            ))
+                            .push(
+                                self.text_input("07:00", &self.config.alarm.time)
+                                    .on_input(SetAlarmTime)
+                                    .width(80),
+                            )
+                            .spacing(8)
+                            .align_y(Vertical::Center),
+                    )
+                    .push(
+                        row![]
+                            .push(focusable(
+                                self.pick_list(
+                                    self.playlists
+                                        .as_ref()
+                                        .map(PlaylistLibrary::names)
+                                        .unwrap_or_default(),
+                                    self.config.alarm.playlist.clone(),
+                                    |name| SetAlarmPlaylist(Some(name)),
+                                )
+                                .placeholder(t!("alarm-current-queue"))
+                                .width(Length::Fill),
+                            ))
+                            .push(self.config.alarm.playlist.as_ref().map(|_| {
+                                focusable(btn!(self.text(t!("off")), SetAlarmPlaylist(None), text))
+                            }))
+                            .spacing(8)
+                            .align_y(Vertical::Center),
+                    )
+                    .push(
+                        self.text(if alarm::parse_time(&self.config.alarm.time).is_some() {
+                            t!("alarm-note")
+                        } else {
+                            t!("alarm-invalid-time")
+                        })
+                        .size(self.text_size(12.0))
+                        .color(muted),
+                    )
+                    .push(cfg!(target_os = "linux").then(|| {
+                        column![]
+                            .push(focusable(
+                                self.checkbox(self.config.yield_to_calls)
+                                    .label(t!("yield-to-calls"))
+                                    .on_toggle(SetYieldToCalls),
+                            ))
+                            .push(self.text(t!("yield-to-calls-note")).color(muted))
+                            .spacing(8)
+                    }))
+                    .push(focusable(
+                        self.checkbox(self.config.close_to_background)
+                            .label(t!("close-to-background"))
+                            .on_toggle(SetCloseToBackground),
+                    ))
+                    .push(focusable(
+                        self.checkbox(self.config.remember_volume)
+                            .label(t!("remember-volume"))
+                            .on_toggle(SetRememberVolume),
+                    ))
+                    .push((!self.config.remember_volume).then(|| {
+                        self.setting(
+                            t!("launch-volume"),
+                            format!("{:.0}%", self.config.launch_volume * 100.0),
+                            focusable(
+                                slider(0.0..=1.0, self.config.launch_volume, SetLaunchVolume)
+                                    .step(0.01)
+                                    .on_release(SaveSettings),
+                            ),
+                        )
+                    }))
+                    .push(focusable(
+                        self.checkbox(self.config.autostart)
+                            .label(t!("autostart"))
+                            .on_toggle(SetAutostart),
+                    ))
+                    .push(focusable(
+                        self.checkbox(self.config.autostart_minimized)
+                            .label(t!("autostart-minimized"))
+                            .on_toggle_maybe(
+                                self.config.autostart.then_some(SetAutostartMinimized),
+                            ),
+                    ))
+                    .push(
+                        self.setting(
+                            t!("shuffle-weighting"),
+                            String::new(),
+                            focusable(
+                                self.pick_list(
+                                    ShuffleWeighting::ALL,
+                                    Some(self.config.shuffle_weighting),
+                                    SetShuffleWeighting,
+                                )
+                                .width(Length::Fill),
+                            ),
+                        ),
+                    )
+                    .push(
+                        self.setting(
+                            t!("shuffle-avoid-recent"),
+                            match self.config.shuffle_avoid_recent_hours as u32 {
+                                0 => t!("off"),
+                                hours => t!("shuffle-hours", count = hours),
+                            },
+                            focusable(
+                                slider(
+                                    SHUFFLE_AVOID_RECENT_RANGE,
+                                    self.config.shuffle_avoid_recent_hours,
+                                    SetShuffleAvoidRecent,
+                                )
+                                .step(1.0)
+                                .on_release(SaveSettings),
+                            ),
+                        ),
+                    )
+                    .push(
+                        self.setting(
+                            t!("auto-dj"),
+                            String::new(),
+                            focusable(
+                                self.pick_list(AutoDj::ALL, Some(self.config.auto_dj), SetAutoDj)
+                                    .width(Length::Fill),
+                            ),
+                        ),
+                    )
+                    .push(
+                        self.text(t!("auto-dj-note"))
+                            .size(self.text_size(12.0))
+                            .color(muted),
+                    )
+                    .push(
+                        self.setting(
+                            t!("party-pin"),
+                            String::new(),
+                            self.text_input(&t!("party-pin-placeholder"), &self.config.party_pin)
+                                .on_input(SetPartyPin)
+                                .secure(true),
+                        ),
+                    )
+                    .push(music_folders)
+                    .push(self.text(t!("caches")).color(muted))
+                    .push(
+                        self.setting(
+                            t!("cache-limit"),
+                            stats::format_size(self.config.cache_limit()),
+                            focusable(
+                                slider(
+                                    CACHE_LIMIT_RANGE,
+                                    self.config.cache_limit_mb,
+                                    SetCacheLimit,
+                                )
+                                .step(64.0)
+                                .on_release(ApplyCacheLimit),
+                            ),
+                        ),
+                    )
+                    .push(
+                        row![]
+                            .push(
+                                self.text(match self.cache_usage {
+                                    Some(usage) => {
+                                        t!("cache-usage", size = stats::format_size(usage))
+                                    }
+                                    None => t!("measuring-cache"),
+                                })
+                                .width(Length::Fill),
+                            )
+                            .push(focusable(btn!(
+                                self.text(t!("clear-caches")),
+                                ClearCaches,
+                                secondary
+                            )))
+                            .align_y(Vertical::Center),
+                    )
+                    .push(
+                        self.text(t!("caches-note"))
+                            .size(self.text_size(12.0))
+                            .color(muted),
+                    )
+                    .push(scrobbling)
+                    .push(self.text(t!("hooks")).color(muted))
+                    .push(
+                        self.text_input("notify-send \"$KANTA_TITLE\"", &self.config.hook_command)
+                            .on_input(SetHookCommand),
+                    )
+                    .push(
+                        self.text(t!("hooks-note"))
+                            .size(self.text_size(12.0))
+                            .color(muted),
+                    )
+                    .push(
+                        row![]
+                            .push(focusable(
+                                self.checkbox(self.config.event_stream)
+                                    .label(t!("event-stream"))
+                                    .on_toggle(SetEventStream)
+                                    .width(Length::Fill),
+                            ))
+                            .push(
+                                self.text_input(
+                                    &t!("port"),
+                                    &self.config.event_stream_port.to_string(),
+                                )
+                                .on_input(SetEventStreamPort)
+                                .width(80),
+                            )
+                            .spacing(8)
+                            .align_y(Vertical::Center),
+                    )
+                    .push(
+                        self.text(t!(
+                            "event-stream-note",
+                            url = format!("ws://127.0.0.1:{}", self.config.event_stream_port)
+                        ))
+                        .size(self.text_size(12.0))
+                        .color(muted),
+                    )
+                    .push(
+                        row![]
+                            .push(focusable(
+                                self.checkbox(self.config.web_remote)
+                                    .label(t!("web-remote"))
+                                    .on_toggle(SetWebRemote)
+                                    .width(Length::Fill),
+                            ))
+                            .push(
+                                self.text_input(
+                                    &t!("port"),
+                                    &self.config.web_remote_port.to_string(),
+                                )
+                                .on_input(SetWebRemotePort)
+                                .width(80),
+                            )
+                            .spacing(8)
+                            .align_y(Vertical::Center),
+                    )
+                    .push(self.web_remote.as_ref().map(|_| {
+                        focusable(btn!(
+                            self.text(t!("new-web-remote-link")),
+                            RegenerateWebRemoteToken,
+                            secondary
+                        ))
+                    }))
+                    .push(
+                        self.text(match &self.web_remote {
+                            Some(web_remote) => t!("web-remote-note", url = web_remote.url()),
+                            None => t!("web-remote-disabled-note"),
+                        })
+                        .size(self.text_size(12.0))
+                        .color(muted),
+                    )
+                    .push(self.text(t!("now-playing-file")).color(muted))
+                    .push(
+                        row![]
+                            .push(
+                                self.text(
+                                    self.config.now_playing_file.as_ref().map_or_else(
+                                        || t!("off"),
+                                        |path| path.display().to_string(),
+                                    ),
+                                )
+                                .width(Length::Fill),
+                            )
+                            .push(focusable(btn!(
+                                self.text(t!("choose")),
+                                ChooseNowPlayingFile,
+                                secondary
+                            )))
+                            .push(self.config.now_playing_file.as_ref().map(|_| {
+                                focusable(btn!(self.text(t!("off")), StopNowPlayingFile, text))
+                            }))
+                            .spacing(8)
+                            .align_y(Vertical::Center),
+                    )
+                    .push(
+                        self.text(t!("now-playing-file-note"))
+                            .size(self.text_size(12.0))
+                            .color(muted),
+                    )
+                    .push(
+                        row![]
+                            .push(focusable(
+                                self.checkbox(matches!(
+                                    self.player.output(),
+                                    Some(ExternalOutput::Snapcast(_))
+                                ))
+                                .label(t!("snapcast"))
+                                .on_toggle(SetSnapcast),
+                            ))
+                            .push(
+                                self.text_input(
+                                    "snapserver.local:4953",
+                                    &self.config.snapcast_address,
+                                )
+                                .on_input(SetSnapcastAddress)
+                                .on_submit(ConnectSnapcast)
+                                .width(Length::Fill),
+                            )
+                            .spacing(8)
+                            .align_y(Vertical::Center),
+                    )
+                    .push(
+                        self.text(t!("snapcast-note"))
+                            .size(self.text_size(12.0))
+                            .color(muted),
+                    )
+                    .push(
+                        row![]
+                            .push(focusable(
+                                self.pick_list(
+                                    self.airplay_speakers.as_slice(),
+                                    match self.player.output() {
+                                        Some(ExternalOutput::AirPlay(airplay)) => {
+                                            Some(airplay.speaker().clone())
+                                        }
+                                        _ => None,
+                                    },
+                                    SelectAirPlaySpeaker,
+                                )
+                                .placeholder(if self.searching_airplay {
+                                    t!("airplay-searching")
+                                } else {
+                                    t!("airplay-speaker")
+                                })
+                                .width(Length::Fill),
+                            ))
+                            .push(focusable(btn!(
+                                self.text(t!("find-airplay-speakers")),
+                                FindAirPlaySpeakers,
+                                secondary
+                            )))
+                            .push(
+                                matches!(self.player.output(), Some(ExternalOutput::AirPlay(_)))
+                                    .then(|| {
+                                        focusable(btn!(
+                                            self.text(t!("off")),
+                                            DisconnectAirPlay,
+                                            text
+                                        ))
+                                    }),
+                            )
+                            .spacing(8)
+                            .align_y(Vertical::Center),
+                    )
+                    .push(
+                        self.text(t!("airplay-note"))
+                            .size(self.text_size(12.0))
+                            .color(muted),
+                    )
+                    .push(plugin_panels)
+                    .push(
+                        row![]
+                            .push(focusable(btn!(
+                                self.text(t!("switch-profile")),
+                                OpenProfilePicker,
+                                secondary
+                            )))
+                            .push(focusable(btn!(
+                                self.text(t!("export-backup")),
+                                ExportBackup,
+                                secondary
+                            )))
+                            .push(focusable(btn!(
+                                self.text(t!("import-backup")),
+                                ImportBackup,
+                                secondary
+                            )))
+                            .push(focusable(btn!(
+                                self.text(t!("export-history")),
+                                OpenHistoryExport,
+                                secondary
+                            )))
+                            .push(focusable(
+                                button(self.text(t!("import-from-other-player")))
+                                    .on_press_maybe(
+                                        (!self.importing).then_some(ImportFromOtherPlayer),
+                                    )
+                                    .style(button::secondary),
+                            ))
+                            .push(focusable(btn!(
+                                self.text(t!("register-file-types")),
+                                RegisterFileAssociations,
+                                secondary
+                            )))
+                            .push(focusable(btn!(
+                                self.text(t!("open-log-folder")),
+                                OpenLogFolder,
+                                secondary
+                            )))
+                            .spacing(8)
+                            .wrap()
+                            .vertical_spacing(8),
+                    )
+                    .push(self.import_report.map(|report| {
+                        self.text(t!(
+                            "import-report",
+                            playlists = report.playlists,
+                            tracks = report.tracks,
+                            unmatched = report.unmatched
+                        ))
+                        .size(self.text_size(12.0))
+                        .color(muted)
+                    }))
+                    .push(focusable(btn!(
+                        self.text(t!("close")),
+                        CloseSettings,
+                        secondary
+                    )))
+                    .spacing(12),
+            )
+            .width(SETTINGS_WIDTH)
+            .padding(16)
+            .style(container::bordered_box);
+
+            layers = layers.push(opaque(
+                mouse_area(center(opaque(settings))).on_press(CloseSettings),
+            ));
+        }
+
+        if self.profile_picker_open {
+            let current_profile = paths::profile();
+            let profile_button = |label: String, profile: Option<String>| {
+                focusable(
+                    btn!(self.text(label), SwitchProfile(profile.clone()))
+                        .style(if profile == current_profile {
+                            button::primary
+                        } else {
+                            button::secondary
+                        })
+                        .width(Length::Fill),
+                )
+            };
+
+            let mut profiles = column![]
+                .push(self.text(t!("choose-profile")).size(self.text_size(20.0)))
+                .push(profile_button(t!("default-profile"), None))
+                .spacing(8);
+            for name in &self.profiles {
+                profiles = profiles.push(profile_button(name.clone(), Some(name.clone())));
+            }
+            profiles = profiles.push(
+                row![]
+                    .push(
+                        self.text_input(&t!("new-profile"), &self.new_profile_name)
+                            .on_input(NewProfileNameChanged)
+                            .on_submit(CreateProfile),
+                    )
+                    .push(focusable(btn!(
+                        self.text(t!("create")),
+                        CreateProfile,
+                        secondary
+                    )))
+                    .spacing(8),
+            );
+
+            let picker = container(profiles)
+                .width(SETTINGS_WIDTH)
+                .padding(16)
+                .style(container::bordered_box);
+
+            layers = layers.push(opaque(
+                mouse_area(center(opaque(picker))).on_press(CloseProfilePicker),
+            ));
+        }
+
+        if let Some(query) = &self.command_palette_query {
+            let mut results = column![];
+            for command in command_palette::matching_commands(self, query) {
+                results = results.push(focusable(
+                    btn!(
+                        self.text(command.label),
+                        RunCommand(Box::new(command.message)),
+                        text
+                    )
+                    .width(Length::Fill)
+                    .padding([4, 8]),
+                ));
+            }
+
+            let palette = container(
+                column![]
+                    .push(
+                        self.text_input(&t!("command-palette-placeholder"), query)
+                            .id(COMMAND_PALETTE_INPUT_ID)
+                            .on_input(CommandPaletteQueryChanged)
+                            .on_submit(SubmitCommandPalette),
+                    )
+                    .push(results)
+                    .spacing(8),
+            )
+            .width(COMMAND_PALETTE_WIDTH)
+            .padding(8)
+            .style(container::bordered_box);
+
+            layers = layers.push(opaque(
+                mouse_area(container(palette).center_x(Length::Fill).padding(Padding {
+                    top: 64.0,
+                    ..Padding::ZERO
+                }))
+                .on_press(CloseCommandPalette),
+            ));
+        }
+
+        if self
+            .volume_osd_until
+            .is_some_and(|until| Instant::now() < until)
+        {
+            let volume = self.player.volume();
+            let osd = container(
+                column![]
+                    .push(self.text(match self.muted_volume {
+                        Some(_) => t!("muted"),
+                        None => t!("volume-level", percent = (volume * 100.0).round() as u32),
+                    }))
+                    .push(progress_bar(0.0..=1.0, volume).girth(6))
+                    .spacing(8)
+                    .align_x(Horizontal::Center),
+            )
+            .width(200)
+            .padding(16)
+            .style(container::bordered_box);
+            layers = layers.push(container(osd).center_x(Length::Fill).padding(Padding {
+                top: 64.0,
+                ..Padding::ZERO
+            }));
+        }
+
+        layers.into()
+    }
+
+    fn lyrics(&self, size: f32) -> Element<'_, KantaMessage> {
+        use KantaMessage::*;
+
+        let Some(lyrics) = self.player.current_track().and_then(Track::lyrics) else {
+            return container(
+                self.text(t!("no-lyrics"))
+                    .size(size)
+                    .center()
+                    .color(MUTED_COLOR),
+            )
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into();
+        };
+
+        let offset_ms = self.lyrics_offset();
+        let matches = self.lyrics_matches();
+        let current_match = self
+            .lyrics_search
+            .as_ref()
+            .and_then(|search| matches.get(search.current).copied());
+        let current_line =
+            lyrics.current_line(lyrics_offsets::apply(self.player.position(), offset_ms));
+        let content: Element<'_, KantaMessage> = match lyrics {
+            Lyrics::Plain(lyrics) if matches.is_empty() => self.text(lyrics).size(size).into(),
+            _ => column(lyrics.lines().into_iter().enumerate().map(|(index, line)| {
+                let color = if current_match == Some(index) {
+                    SELECTED_COLOR
+                } else if !matches.is_empty() {
+                    if matches.contains(&index) {
+                        Color::WHITE
+                    } else {
+                        MUTED_COLOR
+                    }
+                } else {
+                    match current_line {
+                        Some(current_line) if index == current_line => SELECTED_COLOR,
+                        Some(current_line) if index < current_line => MUTED_COLOR,
+                        _ => Color::WHITE,
+                    }
+                };
+                self.text(line).size(size).color(color).into()
+            }))
+            .into(),
+        };
+
+        let mut toolbar = row![].spacing(8).align_y(Vertical::Center);
+        match &self.lyrics_search {
+            Some(search) => {
+                toolbar = toolbar
+                    .push(
+                        self.text_input(&t!("find-in-lyrics"), &search.query)
+                            .id(LYRICS_SEARCH_INPUT_ID)
+                            .on_input(LyricsSearchChanged)
+                            .on_submit(NextLyricsMatch),
+                    )
+                    .push(
+                        self.text(match current_match {
+                            Some(_) => format!("{} / {}", search.current + 1, matches.len()),
+                            None => t!("no-matches"),
+                        })
+                        .color(MUTED_COLOR),
+                    )
+                    .push(focusable(
+                        button(self.text("↑"))
+                            .on_press(PreviousLyricsMatch)
+                            .style(button::secondary),
+                    ))
+                    .push(focusable(
+                        button(self.text("↓"))
+                            .on_press(NextLyricsMatch)
+                            .style(button::secondary),
+                    ))
+                    .push(focusable(
+                        button(self.text(t!("close")))
+                            .on_press(CloseLyricsSearch)
+                            .style(button::secondary),
+                    ));
+            }
+            None => {
+                toolbar = toolbar
+                    .push(focusable(
+                        button(self.text(t!("copy")))
+                            .on_press(CopyLyrics)
+                            .style(button::text)
+                            .padding(0),
+                    ))
+                    .push(focusable(
+                        button(self.text(t!("find")))
+                            .on_press(OpenLyricsSearch)
+                            .style(button::text)
+                            .padding(0),
+                    ));
+                if matches!(lyrics, Lyrics::Synced(_)) && !self.config.party_mode {
+                    toolbar = toolbar
+                        .push(space::horizontal())
+                        .push(focusable(
+                            button(self.text("−"))
+                                .on_press(ShiftLyrics(-lyrics_offsets::STEP_MS))
+                                .style(button::secondary),
+                        ))
+                        .push(focusable(
+                            button(self.text(t!(
+                                "lyrics-offset",
+                                offset = format!("{:+.1}", offset_ms as f32 / 1000.0)
+                            )))
+                            .on_press(ResetLyricsOffset)
+                            .style(button::text),
+                        ))
+                        .push(focusable(
+                            button(self.text("+"))
+                                .on_press(ShiftLyrics(lyrics_offsets::STEP_MS))
+                                .style(button::secondary),
+                        ));
+                }
+            }
+        }
+
+        column![]
+            .push(toolbar)
+            .push(
+                scrollable(container(content).width(Length::Fill))
+                    .id(LYRICS_SCROLLABLE_ID)
+                    .height(Length::Fill),
+            )
+            .spacing(8)
+            .into()
+    }
+
+    fn karaoke_screen(&self) -> Element<'_, KantaMessage> {
+        use KantaMessage::*;
+
+        let lines = match self.player.current_track().and_then(Track::lyrics) {
+            Some(lyrics @ Lyrics::Synced(lines)) => {
+                let position = lyrics_offsets::apply(self.player.position(), self.lyrics_offset());
+                let current = lyrics.current_line(position);
+                let next = current.map_or(0, |current| current + 1);
+                let start = current.map_or(Duration::ZERO, |current| lines[current].time);
+                let end = lines.get(next).map_or_else(
+                    || self.player.current_track().map_or(start, Track::duration),
+                    |line| line.time,
+                );
+                let progress = if end > start {
+                    position.saturating_sub(start).as_secs_f32() / (end - start).as_secs_f32()
+                } else {
+                    1.0
+                };
+
+                column![]
+                    .push(
+                        self.text(current.map_or("♪", |current| lines[current].text.as_str()))
+                            .size(self.text_size(56.0))
+                            .color(SELECTED_COLOR)
+                            .center(),
+                    )
+                    .push(
+                        progress_bar(0.0..=1.0, progress.min(1.0))
+                            .length(NOW_PLAYING_COVER_SIZE)
+                            .girth(4),
+                    )
+                    .push(
+                        self.text(lines.get(next).map_or("", |line| line.text.as_str()))
+                            .size(self.text_size(40.0))
+                            .color(MUTED_COLOR)
+                            .center(),
+                    )
+                    .spacing(24)
+                    .align_x(Horizontal::Center)
+            }
+            _ => column![].push(
+                self.text(t!("karaoke-needs-synced-lyrics"))
+                    .size(self.text_size(32.0))
+                    .color(MUTED_COLOR),
+            ),
+        };
+
+        let mut controls = row![].spacing(16).align_y(Vertical::Center);
+        if !self.config.party_mode {
+            controls = controls.push(focusable(
+                self.checkbox(self.config.karaoke_reduce_vocals)
+                    .label(t!("reduce-vocals"))
+                    .on_toggle(SetKaraokeReduceVocals),
+            ));
+        }
+        controls = controls.push(focusable(
+            button(self.text(t!("exit-karaoke")))
+                .on_press(ToggleKaraoke)
+                .style(button::secondary),
+        ));
+
+        column![]
+            .push(center(lines))
+            .push(controls)
+            .align_x(Horizontal::Center)
+            .padding(32)
+            .into()
+    }
+
+    fn now_playing_screen(&self) -> Element<'_, KantaMessage> {
+        use KantaMessage::*;
+
+        let Some(track) = self.player.current_track() else {
+            return mouse_area(center(
+                self.text(t!("nothing-playing"))
+                    .size(self.text_size(32.0))
+                    .color(MUTED_COLOR),
+            ))
+            .on_double_click(ToggleNowPlayingView)
+            .into();
+        };
+
+        let cover: Element<'_, KantaMessage> = match track.cover() {
+            Some(handle) => image(handle.clone())
+                .width(NOW_PLAYING_COVER_SIZE)
+                .height(NOW_PLAYING_COVER_SIZE)
+                .into(),
+            None => container(self.text(t!("no-cover")).color(MUTED_COLOR))
+                .center(NOW_PLAYING_COVER_SIZE)
+                .style(container::bordered_box)
+                .into(),
+        };
+
+        let title = track.title().map(str::to_string).unwrap_or_else(|| {
+            track
+                .path()
+                .file_name()
+                .and_then(|n| n.to_str())
+                .map_or_else(|| t!("unknown"), str::to_string)
+        });
+
+        let details = column![]
+            .push(mouse_area(cover).on_double_click(ToggleNowPlayingView))
+            .push(self.text(title).size(self.text_size(40.0)))
+            .push(
+                self.text(
+                    self.player
+                        .stream_title()
+                        .or_else(|| track.artist().map(str::to_string))
+                        .unwrap_or_else(|| t!("no-artist")),
+                )
+                .size(self.text_size(24.0))
+                .color(MUTED_COLOR),
+            )
+            .push(
+                progress_bar(
+                    0.0..=track.duration().as_secs_f32(),
+                    self.player.position().as_secs_f32(),
+                )
+                .length(NOW_PLAYING_COVER_SIZE),
+            )
+            .push(
+                self.text(format!(
+                    "{} / {}",
+                    format_duration(self.player.position()),
+                    format_duration(track.duration())
+                ))
+                .color(MUTED_COLOR),
+            )
+            .push(
+                row![]
+                    .push(focusable(
+                        button(self.text(t!("show-in-file-manager")))
+                            .on_press(ShowCurrentTrackInFileManager)
+                            .style(button::text)
+                            .padding(0),
+                    ))
+                    .push(focusable(
+                        button(self.text(t!("edit-lyrics")))
+                            .on_press(OpenLyricsEditor)
+                            .style(button::text)
+                            .padding(0),
+                    ))
+                    .push(focusable(
+                        button(self.text(t!("karaoke")))
+                            .on_press(ToggleKaraoke)
+                            .style(button::text)
+                            .padding(0),
+                    ))
+                    .push(focusable(
+                        button(self.text(if self.spectrogram.is_some() {
+                            t!("show-lyrics")
+                        } else {
+                            t!("show-spectrogram")
+                        }))
+                        .on_press(ToggleSpectrogram)
+                        .style(button::text)
+                        .padding(0),
+                    ))
+                    .spacing(16)
+                    .wrap()
+                    .vertical_spacing(8),
+            )
+            .width(NOW_PLAYING_COVER_SIZE)
+            .spacing(16);
+
+        let visualization: Element<'_, KantaMessage> = match &self.spectrogram {
+            Some(spectrogram) => column![]
+                .push(
+                    self.text(t!(
+                        "frequency-khz",
+                        khz = format!("{:.1}", spectrogram.sample_rate() as f32 / 2000.0)
+                    ))
+                    .color(MUTED_COLOR),
+                )
+                .push(
+                    image(spectrogram.image().clone())
+                        .width(Length::Fill)
+                        .height(Length::Fill)
+                        .content_fit(ContentFit::Fill),
+                )
+                .push(self.text(t!("frequency-khz", khz = "0")).color(MUTED_COLOR))
+                .spacing(4)
+                .into(),
+            None => self.lyrics(self.text_size(24.0)),
+        };
+
+        row![]
+            .push(center(details))
+            .push(center(visualization))
+            .spacing(32)
+            .padding(32)
+            .into()
+    }
+
+    fn playlist_sidebar(&self) -> Element<'_, KantaMessage> {
+        let mut sidebar = column![].spacing(16);
+        if let Some(playlists) = &self.playlists {
+            sidebar = sidebar.push(self.named_playlists(playlists));
+        }
+        if let Some(stations) = &self.stations {
+            sidebar = sidebar.push(self.radio_stations(stations));
+        }
+        sidebar = sidebar.push(self.folder_browser());
+
+        scrollable(sidebar)
+            .width(PLAYLIST_SIDEBAR_WIDTH)
+            .height(Length::Fill)
+            .into()
+    }
+
+    fn named_playlists<'a>(&'a self, playlists: &'a PlaylistLibrary) -> Element<'a, KantaMessage> {
+        use KantaMessage::*;
+
+        let mut sidebar = column![]
+            .push(self.text(t!("playlists")).color(MUTED_COLOR))
+            .push(
+                row![]
+                    .push(
+                        self.text_input(&t!("new-playlist"), &self.new_playlist_name)
+                            .on_input(NewPlaylistNameChanged)
+                            .on_submit(CreateNamedPlaylist),
+                    )
+                    .push(focusable(
+                        button(self.text(t!("create"))).on_press(CreateNamedPlaylist),
+                    ))
+                    .spacing(4),
+            )
+            .push(focusable(
+                button(self.text(t!("sync-to-device")))
+                    .on_press(OpenSyncDialog)
+                    .style(button::text),
+            ))
+            .spacing(4);
+
+        for name in playlists.names() {
+            let entry: Element<'_, KantaMessage> = match &self.renaming_playlist {
+                Some((original, new_name)) if original == name => row![]
+                    .push(
+                        self.text_input(name, new_name)
+                            .on_input(RenamingPlaylistNameChanged)
+                            .on_submit(ConfirmRenamingPlaylist),
+                    )
+                    .push(focusable(
+                        button(self.text(t!("save")))
+                            .on_press(ConfirmRenamingPlaylist)
+                            .style(button::secondary),
+                    ))
+                    .push(focusable(
+                        button(self.text(t!("cancel")))
+                            .on_press(CancelRenamingPlaylist)
+                            .style(button::text),
+                    ))
+                    .spacing(4)
+                    .into(),
+                _ => row![]
+                    .push(
+                        self.config
+                            .pinned_slot(name)
+                            .map(|slot| self.text(slot + 1).color(MUTED_COLOR)),
+                    )
+                    .push(
+                        mouse_area(focusable(
+                            button(self.text(name))
+                                .on_press(LoadNamedPlaylist(name.clone()))
+                                .style(button::text)
+                                .width(Length::Fill),
+                        ))
+                        .on_release(DropOnNamedPlaylist(name.clone())),
+                    )
+                    .push(focusable(
+                        button(self.text(t!("append")))
+                            .on_press(AppendNamedPlaylist(name.clone()))
+                            .style(button::text),
+                    ))
+                    .push(focusable(
+                        button(self.text(if self.config.pinned_slot(name).is_some() {
+                            t!("unpin")
+                        } else {
+                            t!("pin")
+                        }))
+                        .on_press_maybe(
+                            (self.config.pinned_slot(name).is_some()
+                                || self.config.pinned_playlists.contains(&None))
+                            .then(|| TogglePinnedPlaylist(name.clone())),
+                        )
+                        .style(button::text),
+                    ))
+                    .push(focusable(
+                        button(self.text(t!("rename")))
+                            .on_press(StartRenamingPlaylist(name.clone()))
+                            .style(button::text),
+                    ))
+                    .push(focusable(
+                        button(self.text(t!("delete")))
+                            .on_press(DeleteNamedPlaylist(name.clone()))
+                            .style(button::text),
+                    ))
+                    .align_y(Vertical::Center)
+                    .into(),
+            };
+            sidebar = sidebar.push(entry);
+        }
+
+        sidebar.into()
+    }
+
+    fn radio_stations<'a>(&'a self, stations: &'a StationLibrary) -> Element<'a, KantaMessage> {
+        use KantaMessage::*;
+
+        let (name, url) = &self.new_station;
+        let mut radio = column![]
+            .push(self.text(t!("radio")).color(MUTED_COLOR))
+            .push(
+                self.text_input(&t!("station-name"), name)
+                    .on_input(NewStationNameChanged),
+            )
+            .push(
+                row![]
+                    .push(
+                        self.text_input(&t!("station-url"), url)
+                            .on_input(NewStationUrlChanged)
+                            .on_submit(AddStation),
+                    )
+                    .push(focusable(button(self.text(t!("add"))).on_press(AddStation)))
+                    .spacing(4),
+            )
+            .push(focusable(
+                button(self.text(t!("import-stations")))
+                    .on_press(ImportStations)
+                    .style(button::text),
+            ))
+            .spacing(4);
+
+        for station in stations.stations() {
+            let favorite = if station.favorite { "★" } else { "☆" };
+            radio = radio.push(
+                row![]
+                    .push(focusable(
+                        button(self.text(favorite))
+                            .on_press(ToggleFavoriteStation(station.url.clone()))
+                            .style(button::text),
+                    ))
+                    .push(focusable(
+                        button(self.text(&station.name))
+                            .on_press(PlayStation(station.url.clone()))
+                            .style(button::text)
+                            .width(Length::Fill),
+                    ))
+                    .push(focusable(
+                        button(self.text(t!("delete")))
+                            .on_press(RemoveStation(station.url.clone()))
+                            .style(button::text),
+                    ))
+                    .align_y(Vertical::Center),
+            );
+        }
+
+        let search = self
+            .text_input(&t!("search-stations"), &self.station_query)
+            .on_input(StationQueryChanged);
+        radio = radio.push(if self.searching_stations {
+            search
+        } else {
+            search.on_submit(SearchStations)
+        });
+        for (index, station) in self.station_results.iter().enumerate() {
+            radio = radio.push(
+                row![]
+                    .push(focusable(
+                        button(self.text(&station.name))
+                            .on_press(PlayStation(station.url.clone()))
+                            .style(button::text)
+                            .width(Length::Fill),
+                    ))
+                    .push(focusable(
+                        button(self.text(t!("add")))
+                            .on_press(AddFoundStation(index))
+                            .style(button::text),
+                    ))
+                    .align_y(Vertical::Center),
+            );
+        }
+
+        radio.into()
+    }
+
+    fn folder_browser(&self) -> Element<'_, KantaMessage> {
+        use KantaMessage::*;
+
+        let mut browser = column![]
+            .push(self.text(t!("folders")).color(MUTED_COLOR))
+            .spacing(4);
+
+        let entry = |label: String, open: KantaMessage, enqueue: KantaMessage| {
+            row![]
+                .push(focusable(
+                    button(self.text(label))
+                        .on_press(open)
+                        .style(button::text)
+                        .width(Length::Fill),
+                ))
+                .push(focusable(
+                    button(self.text(t!("enqueue")))
+                        .on_press(enqueue)
+                        .style(button::text),
+                ))
+                .align_y(Vertical::Center)
+        };
+
+        if !self.config.music_folders.is_empty() {
+            browser = browser.push(
+                row![]
+                    .push(
+                        self.text_input(&t!("search-library"), &self.library_query)
+                            .on_input(LibraryQueryChanged),
+                    )
+                    .push(focusable(
+                        button(self.text(t!("rescan")))
+                            .on_press_maybe((!self.scanning_library).then_some(RescanLibrary))
+                            .style(button::text),
+                    ))
+                    .align_y(Vertical::Center),
+            );
+            if self.scanning_library {
+                browser = browser.push(self.text(t!("scanning-library")).color(MUTED_COLOR));
+            } else if let Some(report) = self.library_scan_report {
+                browser = browser.push(
+                    self.text(if report.has_changes() {
+                        t!(
+                            "library-scan-report",
+                            added = report.added,
+                            updated = report.updated,
+                            removed = report.removed
+                        )
+                    } else {
+                        t!("library-up-to-date")
+                    })
+                    .color(MUTED_COLOR),
+                );
+            }
+        }
+
+        match &self.browser {
+            _ if !self.library_query.trim().is_empty() => match &self.library_index {
+                Some(_) if self.library_results.is_empty() => {
+                    browser = browser.push(self.text(t!("no-matches")).color(MUTED_COLOR));
+                }
+                Some(index) => {
+                    for &result in &self.library_results {
+                        let entry = index.entry(result);
+                        let label = match (&entry.title, &entry.artist) {
+                            (Some(title), Some(artist)) => format!("{title} – {artist}"),
+                            (Some(title), None) => title.clone(),
+                            _ => entry.path.file_name().map_or_else(
+                                || t!("unknown"),
+                                |name| name.to_string_lossy().into_owned(),
+                            ),
+                        };
+                        browser = browser.push(focusable(
+                            button(self.text(label))
+                                .on_press(EnqueuePath(entry.path.clone()))
+                                .style(button::text)
+                                .width(Length::Fill),
+                        ));
+                    }
+                }
+                None => {}
+            },
+            Some((dir, entries)) => {
+                let parent = dir
+                    .parent()
+                    .filter(|_| !self.config.music_folders.contains(dir))
+                    .map(PathBuf::from);
+                browser = browser
+                    .push(
+                        self.text(dir.to_string_lossy())
+                            .size(self.text_size(12.0))
+                            .color(MUTED_COLOR),
+                    )
+                    .push(focusable(
+                        button(self.text(t!("up")))
+                            .on_press(BrowseFolder(parent))
+                            .style(button::text),
+                    ));
+                for item in entries {
+                    let open = if item.is_dir {
+                        BrowseFolder(Some(item.path.clone()))
+                    } else {
+                        EnqueuePath(item.path.clone())
+                    };
+                    let label = if item.is_dir {
+                        format!("{}/", item.name())
+                    } else {
+                        item.name().into_owned()
+                    };
+                    browser = browser.push(entry(label, open, EnqueuePath(item.path.clone())));
+                }
+            }
+            None if self.config.music_folders.is_empty() => {
+                browser = browser.push(focusable(
+                    button(self.text(t!("add-music-folder")))
+                        .on_press(AddMusicFolder)
+                        .style(button::secondary),
+                ));
+            }
+            None => {
+                let unavailable = self
+                    .library_index
+                    .as_ref()
+                    .map_or(&[][..], |index| index.unavailable());
+                for folder in &self.config.music_folders {
+                    let label = if unavailable.contains(folder) {
+                        t!("unavailable-folder", folder = folder.to_string_lossy())
+                    } else {
+                        folder.to_string_lossy().into_owned()
+                    };
+                    browser = browser.push(entry(
+                        label,
+                        BrowseFolder(Some(folder.clone())),
+                        EnqueuePath(folder.clone()),
+                    ));
+                }
+            }
+        }
+
+        browser.into()
+    }
+
+    fn setting<'a>(
+        &self,
+        label: String,
+        value: String,
+        control: impl Into<Element<'a, KantaMessage>>,
+    ) -> Element<'a, KantaMessage> {
+        row![]
+            .push(self.text(label).width(120).color(MUTED_COLOR))
+            .push(control)
+            .push(self.text(value).width(80).align_x(Horizontal::Right))
+            .spacing(8)
+            .align_y(Vertical::Center)
+            .into()
+    }
+
+    fn text_size(&self, size: f32) -> f32 {
+        size * self.config.text_size / DEFAULT_TEXT_SIZE
+    }
+
+    fn text<'a>(&self, content: impl text::IntoFragment<'a>) -> Text<'a> {
+        text(content).size(self.config.text_size)
+    }
+
+    fn text_input<'a>(&self, placeholder: &str, value: &str) -> TextInput<'a, KantaMessage> {
+        text_input(placeholder, value).size(self.config.text_size)
+    }
+
+    fn pick_list<'a, T, L, V>(
+        &self,
+        options: L,
+        selected: Option<V>,
+        on_selected: impl Fn(T) -> KantaMessage + 'a,
+    ) -> PickList<'a, T, L, V, KantaMessage>
+    where
+        T: ToString + PartialEq + Clone + 'a,
+        L: Borrow<[T]> + 'a,
+        V: Borrow<T> + 'a,
+    {
+        pick_list(options, selected, on_selected).text_size(self.config.text_size)
+    }
+
+    fn checkbox<'a>(&self, is_checked: bool) -> Checkbox<'a, KantaMessage> {
+        checkbox(is_checked).text_size(self.config.text_size)
+    }
+
+    fn subscription(&self) -> Subscription<KantaMessage> {
+        Subscription::batch([
+            time::every(Duration::from_millis(100)).map(|_| KantaMessage::Tick),
+            if self
+                .lyrics_editor
+                .as_ref()
+                .is_some_and(|editor| editor.sync.is_some())
+            {
+                event::listen_with(handle_lyrics_sync_event)
+            } else {
+                event::listen_with(handle_event)
+            },
+            Subscription::run(instance::opened_paths).map(KantaMessage::OpenPaths),
+            window::close_requests().map(|_| KantaMessage::CloseRequested),
+        ])
+    }
+}
+
+fn handle_event(event: Event, status: event::Status, _window: window::Id) -> Option<KantaMessage> {
+    use keyboard::{key::Named, Key};
+
+    match event {
+        Event::Mouse(mouse::Event::CursorMoved { position }) => {
+            Some(KantaMessage::CursorMoved(position))
+        }
+        Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+            Some(KantaMessage::MousePressed)
+        }
+        Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+            Some(KantaMessage::MouseReleased)
+        }
+        Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)) => {
+            Some(KantaMessage::ModifiersChanged(modifiers))
+        }
+        Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. }) => match key {
+            Key::Named(Named::AudioVolumeUp) => Some(KantaMessage::VolumeUp),
+            Key::Named(Named::AudioVolumeDown) => Some(KantaMessage::VolumeDown),
+            Key::Named(Named::AudioVolumeMute) => Some(KantaMessage::ToggleMute),
+            Key::Character(c) if modifiers.command() && c.as_str() == "k" => {
+                Some(KantaMessage::OpenCommandPalette)
+            }
+            Key::Character(c) if modifiers.command() && c.as_str() == "f" => {
+                Some(KantaMessage::OpenLyricsSearch)
+            }
+            Key::Character(c) if modifiers.command() && c.as_str() == "s" => {
+                Some(KantaMessage::SavePlaylist)
+            }
+            Key::Character(c) if modifiers.command() => match c.parse::<usize>() {
+                Ok(number @ 1..=PINNED_PLAYLIST_SLOTS) => {
+                    Some(KantaMessage::OpenPinnedPlaylist(number - 1))
+                }
+                _ => None,
+            },
+            Key::Named(Named::Escape) => Some(KantaMessage::Dismiss),
+            Key::Named(Named::F11) => Some(KantaMessage::ToggleNowPlayingView),
+            Key::Named(Named::Tab) if modifiers.shift() => Some(KantaMessage::FocusPrevious),
+            Key::Named(Named::Tab) => Some(KantaMessage::FocusNext),
+            _ if status == event::Status::Captured => None,
+            Key::Named(Named::Space) => Some(KantaMessage::TogglePlayback),
+            Key::Named(Named::ArrowUp) if modifiers.command() => Some(KantaMessage::VolumeUp),
+            Key::Named(Named::ArrowDown) if modifiers.command() => Some(KantaMessage::VolumeDown),
+            Key::Named(Named::ArrowUp) => Some(KantaMessage::MoveTrackCursor(-1)),
+            Key::Named(Named::ArrowDown) => Some(KantaMessage::MoveTrackCursor(1)),
+            Key::Named(Named::ArrowLeft) => Some(KantaMessage::SeekBackward),
+            Key::Named(Named::ArrowRight) => Some(KantaMessage::SeekForward),
+            Key::Named(Named::Enter) => Some(KantaMessage::PlaySelectedTrack),
+            Key::Named(Named::Delete) => Some(KantaMessage::RemoveSelection),
+            Key::Character(c) if !modifiers.command() && c.as_str() == "m" => {
+                Some(KantaMessage::ToggleMute)
+            }
+            Key::Character(c) if !modifiers.command() => match c.parse::<usize>() {
+                Ok(number @ 1..=9) => Some(KantaMessage::JumpToCue(number - 1)),
+                _ => None,
+            },
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn handle_lyrics_sync_event(
+    event: Event,
+    status: event::Status,
+    window: window::Id,
+) -> Option<KantaMessage> {
+    use keyboard::{key::Named, Key};
+
+    match &event {
+        Event::Keyboard(keyboard::Event::KeyPressed { key, .. }) => match key {
+            Key::Named(Named::Enter) => Some(KantaMessage::StampLyricLine),
+            Key::Named(Named::Backspace) => Some(KantaMessage::UnstampLyricLine),
+            Key::Named(Named::ArrowUp) => Some(KantaMessage::MoveLyricCursor(-1)),
+            Key::Named(Named::ArrowDown) => Some(KantaMessage::MoveLyricCursor(1)),
+            Key::Named(Named::Escape) => Some(KantaMessage::StopLyricsSync),
+            _ => handle_event(event, status, window),
+        },
+        _ => handle_event(event, status, window),
+    }
+}
+
+fn maintain_caches(limit: u64, clear: bool) -> Task<KantaMessage> {
+    let (sender, receiver) = oneshot::channel();
+    thread::spawn(move || {
+        let result = if clear {
+            caches::clear()
+        } else {
+            caches::evict(limit)
+        };
+        if let Err(e) = result {
+            error!("Failed to maintain caches: {}", e);
+        }
+        sender.send(caches::usage()).ok();
+    });
+    Task::perform(
+        async move { receiver.await.unwrap_or_default() },
+        KantaMessage::CacheUsageMeasured,
+    )
+}
+
+fn list_folder(dir: PathBuf) -> Task<KantaMessage> {
+    let (sender, receiver) = oneshot::channel();
+    thread::spawn({
+        let dir = dir.clone();
+        move || {
+            let entries = if mounts::is_reachable(&dir) {
+                browser::list(&dir).map_err(|e| format!("{:#}", e))
+            } else {
+                Err(t!("folder-unreachable", folder = dir.to_string_lossy()))
+            };
+            sender.send(entries).ok();
+        }
+    });
+    Task::perform(
+        async move {
+            receiver
+                .await
+                .unwrap_or_else(|_| Err("listing was cancelled".to_string()))
+        },
+        move |entries| KantaMessage::FolderListed(dir, entries),
+    )
+}
+
+fn load_remote_track(
+    url: String,
+    on_loaded: fn(Result<Box<Track>, String>) -> KantaMessage,
+) -> Task<KantaMessage> {
+    let (sender, receiver) = oneshot::channel();
+    thread::spawn(move || {
+        let track = Track::load(PathBuf::from(url))
+            .map(Box::new)
+            .map_err(|e| format!("{:#}", e));
+        sender.send(track).ok();
+    });
+    Task::perform(
+        async move {
+            receiver
+                .await
+                .unwrap_or_else(|_| Err("loading was cancelled".to_string()))
+        },
+        on_loaded,
+    )
+}
+
+fn parse_timestamp(input: &str) -> Option<Duration> {
+    let parts = input.trim().split(':').collect::<Vec<_>>();
+    if parts.is_empty() || parts.len() > 3 {
+        return None;
+    }
+
+    let (seconds, minutes_and_hours) = parts.split_last()?;
+    let seconds = seconds.parse::<f64>().ok()?;
+    if !seconds.is_finite() || seconds < 0.0 {
+        return None;
+    }
+
+    let mut total = seconds;
+    for (part, unit) in minutes_and_hours.iter().rev().zip([60.0, 3600.0]) {
+        total += part.parse::<u32>().ok()? as f64 * unit;
+    }
+    Some(Duration::from_secs_f64(total))
+}
+
+fn format_duration(duration: Duration) -> String {
+    let total_seconds = duration.as_secs();
+    let hours = total_seconds / 3600;
+    let minutes = (total_seconds % 3600) / 60;
+    let seconds = total_seconds % 60;
+    if hours != 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{:02}:{:02}", minutes, seconds)
+    }
+}
 
 fn main() -> iced::Result {
-    iced::application(Kanta::new, Kanta::update, Kanta::view)
-        .subscription(Kanta::subscription)
-        .title("Kanta")
-        .settings(Settings {
-            default_text_size: Pixels(14.0),
-            ..Default::default()
-        })
-        .run()
+    let mut verbose = false;
+    let mut autostarted = false;
+    let mut profile = None;
+    let mut paths = vec![];
+    let mut args = std::env::args_os().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--verbose" || arg == "-v" {
+            verbose = true;
+        } else if arg == autostart::ARG {
+            autostarted = true;
+        } else if arg == "--profile" {
+            profile = args.next().map(|name| name.to_string_lossy().into_owned());
+        } else {
+            paths.push(if arg.to_str().is_some_and(remote::is_url) {
+                arg.into()
+            } else {
+                std::path::absolute(&arg).unwrap_or_else(|_| arg.into())
+            });
+        }
+    }
+
+    alarm::capture_local_offset();
+
+    if let Some(Err(e)) = profile.as_deref().map(paths::validate_profile_name) {
+        eprintln!("Failed to select profile: {}", e);
+        std::process::exit(2);
+    }
+
+    let show_profile_picker =
+        profile.is_none() && paths::profiles().is_ok_and(|profiles| !profiles.is_empty());
+    paths::set_profile(profile);
+
+    let config = Config::load();
+    if config.yield_to_calls {
+        audio_role::declare_music();
+    }
+
+    let _log_guard = logging::init(verbose);
+    crash::install();
+
+    if instance::forward_to_running_instance(&paths) {
+        return Ok(());
+    }
+    if let Err(e) = instance::listen() {
+        error!("Failed to listen for other instances: {}", e);
+    }
+
+    i18n::set_language(config.language.as_deref());
+    crash::show_pending_report();
+
+    let text_size = config.text_size;
+
+    iced::application(
+        move || Kanta::new(paths.clone(), show_profile_picker, autostarted),
+        Kanta::update,
+        Kanta::view,
+    )
+    .subscription(Kanta::subscription)
+    .title(Kanta::title)
+    .exit_on_close_request(false)
+    .scale_factor(|kanta| kanta.config.ui_scale)
+    .theme(Kanta::theme)
+    .settings(Settings {
+        default_text_size: Pixels(text_size),
+        ..Default::default()
+    })
+    .run()
 }
@@ -1,18 +1,76 @@
 use std::{
+    fs,
+    path::PathBuf,
     sync::mpsc::{channel, Receiver},
-    time::Duration,
+    time::{Duration, Instant},
 };
 
-use souvlaki::{MediaControlEvent, MediaControls, MediaMetadata, MediaPlayback, MediaPosition};
+use iced::widget::image;
+use tracing::error;
+use url::Url;
 
-use crate::track::Track;
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+use crate::mpris::Mpris;
+use crate::{paths, player::PlaybackState, remote, track::Track};
+
+const POSITION_SYNC_TOLERANCE: Duration = Duration::from_secs(1);
+#[cfg(any(target_os = "windows", target_os = "macos"))]
+const SEEK_STEP: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MediaControlEvent {
+    Play,
+    Pause,
+    Toggle,
+    Next,
+    Previous,
+    Stop,
+    SeekBy(SeekDirection, Duration),
+    SetPosition(Duration),
+    SetVolume(f64),
+    SetRate(f64),
+    SetShuffle(bool),
+    OpenUri(String),
+    Raise,
+    Quit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeekDirection {
+    Forward,
+    Backward,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Metadata {
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    pub cover_url: Option<String>,
+    pub duration: Option<Duration>,
+}
 
 pub struct KantaMediaControls {
-    media_controls: MediaControls,
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    media_controls: Mpris,
+    #[cfg(any(target_os = "windows", target_os = "macos"))]
+    media_controls: souvlaki::MediaControls,
     event_rx: Receiver<MediaControlEvent>,
+    reported: Option<(PlaybackState, Duration, f32, Instant)>,
 }
 
 impl KantaMediaControls {
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    pub fn try_new() -> anyhow::Result<KantaMediaControls> {
+        let (event_tx, event_rx) = channel();
+        Ok(KantaMediaControls {
+            media_controls: Mpris::try_new(event_tx)?,
+            event_rx,
+            reported: None,
+        })
+    }
+
+    #[cfg(any(target_os = "windows", target_os = "macos"))]
     pub fn try_new() -> anyhow::Result<KantaMediaControls> {
         #[cfg(not(target_os = "windows"))]
         let hwnd = None;
@@ -30,15 +88,15 @@ impl KantaMediaControls {
             display_name: "Kanta",
             hwnd,
         };
-        let mut media_controls = MediaControls::new(config)?;
+        let mut media_controls = souvlaki::MediaControls::new(config)?;
 
         let (event_tx, event_rx) = channel();
 
         media_controls.attach({
             let tx = event_tx.clone();
             move |event| {
-                if let Err(e) = tx.send(event) {
-                    eprintln!("Failed to send media control event: {}", e);
+                if let Err(e) = tx.send(MediaControlEvent::from(event)) {
+                    error!("Failed to send media control event: {}", e);
                 }
             }
         })?;
@@ -46,41 +104,170 @@ impl KantaMediaControls {
         Ok(KantaMediaControls {
             media_controls,
             event_rx,
+            reported: None,
         })
     }
 
+    pub fn detach(&mut self) -> anyhow::Result<()> {
+        self.media_controls.detach()?;
+        Ok(())
+    }
+
     pub fn receive_event(&mut self) -> Option<MediaControlEvent> {
         self.event_rx.try_recv().ok()
     }
 
-    pub fn update_metadata(&mut self, track: &Track) -> anyhow::Result<()> {
-        self.media_controls.set_metadata(MediaMetadata {
-            title: track.title(),
-            artist: track.artist(),
-            album: track.album(),
-            duration: Some(track.duration()),
-            ..Default::default()
+    pub fn update_metadata(
+        &mut self,
+        track: &Track,
+        stream_title: Option<&str>,
+    ) -> anyhow::Result<()> {
+        let cover_url = track.cover().and_then(|cover| {
+            cache_cover(cover)
+                .inspect_err(|e| error!("Failed to cache cover for media controls: {}", e))
+                .ok()
+                .flatten()
+                .and_then(|path| Url::from_file_path(path).ok())
+                .map(String::from)
+        });
+        let metadata = Metadata {
+            title: stream_title.or(track.title()).map(String::from),
+            artist: track.artist().map(String::from),
+            album: track.album().map(String::from),
+            cover_url,
+            duration: Some(track.duration()).filter(|_| !track.is_stream()),
+        };
+
+        #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+        self.media_controls.set_metadata(metadata)?;
+
+        #[cfg(any(target_os = "windows", target_os = "macos"))]
+        self.media_controls.set_metadata(souvlaki::MediaMetadata {
+            title: metadata.title.as_deref(),
+            artist: metadata.artist.as_deref(),
+            album: metadata.album.as_deref(),
+            cover_url: metadata.cover_url.as_deref(),
+            duration: metadata.duration,
         })?;
+
         Ok(())
     }
 
     pub fn update_playback(
         &mut self,
-        is_idle: bool,
-        is_paused: bool,
+        state: PlaybackState,
         position: Duration,
+        speed: f32,
     ) -> anyhow::Result<()> {
-        let progress = Some(MediaPosition(position));
-
+        #[cfg(not(any(target_os = "windows", target_os = "macos")))]
         self.media_controls
-            .set_playback(if !is_idle && !is_paused {
-                MediaPlayback::Playing { progress }
-            } else if is_idle && is_paused {
-                MediaPlayback::Paused { progress }
-            } else {
-                MediaPlayback::Stopped
+            .set_playback(state, position, f64::from(speed))?;
+
+        #[cfg(any(target_os = "windows", target_os = "macos"))]
+        {
+            use souvlaki::{MediaPlayback, MediaPosition};
+
+            let progress = Some(MediaPosition(position));
+            self.media_controls.set_playback(match state {
+                PlaybackState::Playing => MediaPlayback::Playing { progress },
+                PlaybackState::Paused => MediaPlayback::Paused { progress },
+                PlaybackState::Stopped => MediaPlayback::Stopped,
             })?;
+        }
 
+        self.reported = Some((state, position, speed, Instant::now()));
+
+        Ok(())
+    }
+
+    pub fn sync_position(
+        &mut self,
+        state: PlaybackState,
+        position: Duration,
+        speed: f32,
+    ) -> anyhow::Result<()> {
+        let in_sync =
+            self.reported
+                .is_some_and(|(reported_state, reported, reported_speed, at)| {
+                    let expected = match reported_state {
+                        PlaybackState::Playing => reported + at.elapsed().mul_f32(speed),
+                        _ => reported,
+                    };
+                    reported_state == state
+                        && reported_speed == speed
+                        && expected.abs_diff(position) <= POSITION_SYNC_TOLERANCE
+                });
+        if in_sync {
+            return Ok(());
+        }
+        self.update_playback(state, position, speed)
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    pub fn update_shuffle(&mut self, shuffle: bool) -> anyhow::Result<()> {
+        self.media_controls.set_shuffle(shuffle)
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    pub fn update_volume(&mut self, volume: f32) -> anyhow::Result<()> {
+        self.media_controls.set_volume(f64::from(volume))
+    }
+
+    #[cfg(any(target_os = "windows", target_os = "macos"))]
+    pub fn update_shuffle(&mut self, _shuffle: bool) -> anyhow::Result<()> {
         Ok(())
     }
+
+    #[cfg(any(target_os = "windows", target_os = "macos"))]
+    pub fn update_volume(&mut self, _volume: f32) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(any(target_os = "windows", target_os = "macos"))]
+impl From<souvlaki::MediaControlEvent> for MediaControlEvent {
+    fn from(event: souvlaki::MediaControlEvent) -> MediaControlEvent {
+        use souvlaki::MediaControlEvent as Event;
+
+        let direction = |direction| match direction {
+            souvlaki::SeekDirection::Forward => SeekDirection::Forward,
+            souvlaki::SeekDirection::Backward => SeekDirection::Backward,
+        };
+        match event {
+            Event::Play => MediaControlEvent::Play,
+            Event::Pause => MediaControlEvent::Pause,
+            Event::Toggle => MediaControlEvent::Toggle,
+            Event::Next => MediaControlEvent::Next,
+            Event::Previous => MediaControlEvent::Previous,
+            Event::Stop => MediaControlEvent::Stop,
+            Event::Seek(seek) => MediaControlEvent::SeekBy(direction(seek), SEEK_STEP),
+            Event::SeekBy(seek, amount) => MediaControlEvent::SeekBy(direction(seek), amount),
+            Event::SetPosition(souvlaki::MediaPosition(position)) => {
+                MediaControlEvent::SetPosition(position)
+            }
+            Event::SetVolume(volume) => MediaControlEvent::SetVolume(volume),
+            Event::OpenUri(uri) => MediaControlEvent::OpenUri(uri),
+            Event::Raise => MediaControlEvent::Raise,
+            Event::Quit => MediaControlEvent::Quit,
+        }
+    }
+}
+
+fn cache_cover(cover: &image::Handle) -> anyhow::Result<Option<PathBuf>> {
+    let image::Handle::Bytes(_, bytes) = cover else {
+        return Ok(None);
+    };
+    let Some(extension) = ::image::guess_format(bytes)
+        .ok()
+        .and_then(|format| format.extensions_str().first())
+    else {
+        return Ok(None);
+    };
+    let dir = paths::cache_dir()?.join("covers");
+    fs::create_dir_all(&dir)?;
+    let path = dir.join(format!("{:016x}.{}", remote::fnv1a(bytes), extension));
+    if !path.exists() {
+        fs::write(&path, bytes)?;
+    }
+    Ok(Some(path))
 }
@@ -0,0 +1,102 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::mpsc,
+    thread,
+    time::Duration,
+};
+
+const STAT_TIMEOUT: Duration = Duration::from_secs(3);
+const NETWORK_FILESYSTEMS: [&str; 10] = [
+    "cifs",
+    "smb3",
+    "smbfs",
+    "nfs",
+    "nfs4",
+    "afpfs",
+    "davfs",
+    "webdav",
+    "fuse.sshfs",
+    "fuse.rclone",
+];
+
+pub fn is_network(path: &Path) -> bool {
+    if cfg!(target_os = "windows") {
+        return path.to_string_lossy().starts_with(r"\\");
+    }
+    mounts()
+        .into_iter()
+        .filter(|(mount_point, _)| path.starts_with(mount_point))
+        .max_by_key(|(mount_point, _)| mount_point.as_os_str().len())
+        .is_some_and(|(_, fs_type)| NETWORK_FILESYSTEMS.contains(&fs_type.as_str()))
+}
+
+pub fn is_reachable(path: &Path) -> bool {
+    let (sender, receiver) = mpsc::channel();
+    let path = path.to_path_buf();
+    thread::spawn(move || {
+        sender
+            .send(fs::metadata(path).is_ok_and(|metadata| metadata.is_dir()))
+            .ok();
+    });
+    receiver.recv_timeout(STAT_TIMEOUT).unwrap_or(false)
+}
+
+#[cfg(target_os = "linux")]
+fn mounts() -> Vec<(PathBuf, String)> {
+    let Ok(mountinfo) = fs::read_to_string("/proc/self/mountinfo") else {
+        return vec![];
+    };
+    mountinfo
+        .lines()
+        .filter_map(|line| {
+            let (fields, rest) = line.split_once(" - ")?;
+            let mount_point = fields.split(' ').nth(4)?;
+            let fs_type = rest.split(' ').next()?;
+            Some((PathBuf::from(unescape(mount_point)), fs_type.to_string()))
+        })
+        .collect()
+}
+
+#[cfg(target_os = "macos")]
+fn mounts() -> Vec<(PathBuf, String)> {
+    let Ok(output) = std::process::Command::new("mount").output() else {
+        return vec![];
+    };
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let (_, rest) = line.split_once(" on ")?;
+            let (mount_point, options) = rest.rsplit_once(" (")?;
+            let fs_type = options.split(',').next()?;
+            Some((PathBuf::from(mount_point), fs_type.to_string()))
+        })
+        .collect()
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn mounts() -> Vec<(PathBuf, String)> {
+    vec![]
+}
+
+#[cfg(target_os = "linux")]
+fn unescape(field: &str) -> String {
+    let mut unescaped = String::new();
+    let mut rest = field;
+    while let Some(backslash) = rest.find('\\') {
+        unescaped.push_str(&rest[..backslash]);
+        let code = rest.get(backslash + 1..backslash + 4);
+        match code.and_then(|code| u8::from_str_radix(code, 8).ok()) {
+            Some(byte) => {
+                unescaped.push(byte as char);
+                rest = &rest[backslash + 4..];
+            }
+            None => {
+                unescaped.push('\\');
+                rest = &rest[backslash + 1..];
+            }
+        }
+    }
+    unescaped.push_str(rest);
+    unescaped
+}
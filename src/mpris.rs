@@ -0,0 +1,405 @@
+use std::{
+    collections::HashMap,
+    sync::mpsc::Sender,
+    time::{Duration, Instant},
+};
+
+use iced::futures::executor::block_on;
+use tracing::error;
+use zbus::{
+    blocking::{connection, object_server::InterfaceRef, Connection},
+    fdo, interface,
+    object_server::SignalEmitter,
+    zvariant::{ObjectPath, OwnedObjectPath, Value},
+};
+
+use crate::{
+    dsp::SPEED_RANGE,
+    media_controls::{MediaControlEvent, Metadata, SeekDirection},
+    player::PlaybackState,
+};
+
+const BUS_NAME: &str = "org.mpris.MediaPlayer2.kanta";
+const OBJECT_PATH: &str = "/org/mpris/MediaPlayer2";
+const SEEK_TOLERANCE: Duration = Duration::from_secs(1);
+const URI_SCHEMES: [&str; 3] = ["file", "http", "https"];
+const MIME_TYPES: [&str; 6] = [
+    "audio/mpeg",
+    "audio/flac",
+    "audio/ogg",
+    "audio/wav",
+    "audio/mp4",
+    "audio/x-mpegurl",
+];
+
+pub struct Mpris {
+    connection: Connection,
+    player: InterfaceRef<PlayerInterface>,
+}
+
+struct RootInterface {
+    events: Sender<MediaControlEvent>,
+}
+
+struct PlayerInterface {
+    events: Sender<MediaControlEvent>,
+    track_id: OwnedObjectPath,
+    track_number: u64,
+    metadata: Metadata,
+    state: PlaybackState,
+    position: Duration,
+    reported_at: Instant,
+    rate: f64,
+    shuffle: bool,
+    volume: f64,
+}
+
+impl Mpris {
+    pub fn try_new(events: Sender<MediaControlEvent>) -> anyhow::Result<Mpris> {
+        let connection = connection::Builder::session()?
+            .name(BUS_NAME)?
+            .serve_at(
+                OBJECT_PATH,
+                RootInterface {
+                    events: events.clone(),
+                },
+            )?
+            .serve_at(
+                OBJECT_PATH,
+                PlayerInterface {
+                    events,
+                    track_id: track_id(0),
+                    track_number: 0,
+                    metadata: Metadata::default(),
+                    state: PlaybackState::Stopped,
+                    position: Duration::ZERO,
+                    reported_at: Instant::now(),
+                    rate: 1.0,
+                    shuffle: false,
+                    volume: 1.0,
+                },
+            )?
+            .build()?;
+        let player = connection
+            .object_server()
+            .interface::<_, PlayerInterface>(OBJECT_PATH)?;
+        Ok(Mpris { connection, player })
+    }
+
+    pub fn detach(&mut self) -> anyhow::Result<()> {
+        self.connection.release_name(BUS_NAME)?;
+        Ok(())
+    }
+
+    pub fn set_metadata(&mut self, metadata: Metadata) -> anyhow::Result<()> {
+        let mut player = self.player.get_mut();
+        player.track_number += 1;
+        player.track_id = track_id(player.track_number);
+        player.metadata = metadata;
+        player.position = Duration::ZERO;
+        player.reported_at = Instant::now();
+        let emitter = self.player.signal_emitter();
+        block_on(player.metadata_changed(emitter))?;
+        block_on(player.can_seek_changed(emitter))?;
+        Ok(())
+    }
+
+    pub fn set_playback(
+        &mut self,
+        state: PlaybackState,
+        position: Duration,
+        rate: f64,
+    ) -> anyhow::Result<()> {
+        let mut player = self.player.get_mut();
+        let seeked = state != PlaybackState::Stopped
+            && player.state != PlaybackState::Stopped
+            && player.current_position().abs_diff(position) > SEEK_TOLERANCE;
+        let state_changed = player.state != state;
+        let rate_changed = player.rate != rate;
+        player.state = state;
+        player.position = position;
+        player.reported_at = Instant::now();
+        player.rate = rate;
+
+        let emitter = self.player.signal_emitter();
+        if state_changed {
+            block_on(player.playback_status_changed(emitter))?;
+        }
+        if rate_changed {
+            block_on(player.rate_changed(emitter))?;
+        }
+        if seeked {
+            block_on(PlayerInterface::seeked(emitter, micros(position)))?;
+        }
+        Ok(())
+    }
+
+    pub fn set_shuffle(&mut self, shuffle: bool) -> anyhow::Result<()> {
+        let mut player = self.player.get_mut();
+        if player.shuffle != shuffle {
+            player.shuffle = shuffle;
+            block_on(player.shuffle_changed(self.player.signal_emitter()))?;
+        }
+        Ok(())
+    }
+
+    pub fn set_volume(&mut self, volume: f64) -> anyhow::Result<()> {
+        let mut player = self.player.get_mut();
+        if player.volume != volume {
+            player.volume = volume;
+            block_on(player.volume_changed(self.player.signal_emitter()))?;
+        }
+        Ok(())
+    }
+}
+
+impl PlayerInterface {
+    fn send(&self, event: MediaControlEvent) {
+        if let Err(e) = self.events.send(event) {
+            error!("Failed to send media control event: {}", e);
+        }
+    }
+
+    fn current_position(&self) -> Duration {
+        match self.state {
+            PlaybackState::Playing => self
+                .position
+                .saturating_add(self.reported_at.elapsed().mul_f64(self.rate)),
+            _ => self.position,
+        }
+    }
+}
+
+#[interface(name = "org.mpris.MediaPlayer2")]
+impl RootInterface {
+    fn raise(&self) {
+        self.events.send(MediaControlEvent::Raise).ok();
+    }
+
+    fn quit(&self) {
+        self.events.send(MediaControlEvent::Quit).ok();
+    }
+
+    #[zbus(property)]
+    fn can_quit(&self) -> bool {
+        true
+    }
+
+    #[zbus(property)]
+    fn can_raise(&self) -> bool {
+        true
+    }
+
+    #[zbus(property)]
+    fn has_track_list(&self) -> bool {
+        false
+    }
+
+    #[zbus(property)]
+    fn identity(&self) -> &str {
+        "Kanta"
+    }
+
+    #[zbus(property)]
+    fn desktop_entry(&self) -> &str {
+        "kanta"
+    }
+
+    #[zbus(property)]
+    fn supported_uri_schemes(&self) -> Vec<String> {
+        URI_SCHEMES.map(String::from).to_vec()
+    }
+
+    #[zbus(property)]
+    fn supported_mime_types(&self) -> Vec<String> {
+        MIME_TYPES.map(String::from).to_vec()
+    }
+}
+
+#[interface(name = "org.mpris.MediaPlayer2.Player")]
+impl PlayerInterface {
+    fn next(&self) {
+        self.send(MediaControlEvent::Next);
+    }
+
+    fn previous(&self) {
+        self.send(MediaControlEvent::Previous);
+    }
+
+    fn pause(&self) {
+        self.send(MediaControlEvent::Pause);
+    }
+
+    fn play_pause(&self) {
+        self.send(MediaControlEvent::Toggle);
+    }
+
+    fn stop(&self) {
+        self.send(MediaControlEvent::Stop);
+    }
+
+    fn play(&self) {
+        self.send(MediaControlEvent::Play);
+    }
+
+    fn seek(&self, offset: i64) {
+        let direction = if offset < 0 {
+            SeekDirection::Backward
+        } else {
+            SeekDirection::Forward
+        };
+        let amount = Duration::from_micros(offset.unsigned_abs());
+        self.send(MediaControlEvent::SeekBy(direction, amount));
+    }
+
+    fn set_position(&self, track_id: ObjectPath<'_>, position: i64) {
+        if track_id == *self.track_id && position >= 0 {
+            let position = Duration::from_micros(position as u64);
+            self.send(MediaControlEvent::SetPosition(position));
+        }
+    }
+
+    fn open_uri(&self, uri: String) {
+        self.send(MediaControlEvent::OpenUri(uri));
+    }
+
+    #[zbus(signal)]
+    async fn seeked(emitter: &SignalEmitter<'_>, position: i64) -> zbus::Result<()>;
+
+    #[zbus(property)]
+    fn playback_status(&self) -> &str {
+        match self.state {
+            PlaybackState::Playing => "Playing",
+            PlaybackState::Paused => "Paused",
+            PlaybackState::Stopped => "Stopped",
+        }
+    }
+
+    #[zbus(property)]
+    fn loop_status(&self) -> &str {
+        "None"
+    }
+
+    #[zbus(property)]
+    fn set_loop_status(&mut self, loop_status: String) -> fdo::Result<()> {
+        match loop_status.as_str() {
+            "None" => Ok(()),
+            _ => Err(fdo::Error::NotSupported(
+                "Kanta does not repeat tracks or playlists".to_string(),
+            )),
+        }
+    }
+
+    #[zbus(property)]
+    fn rate(&self) -> f64 {
+        self.rate
+    }
+
+    #[zbus(property)]
+    fn set_rate(&mut self, rate: f64) {
+        if rate == 0.0 {
+            self.send(MediaControlEvent::Pause);
+        } else if rate.is_finite() {
+            self.send(MediaControlEvent::SetRate(rate));
+        }
+    }
+
+    #[zbus(property)]
+    fn minimum_rate(&self) -> f64 {
+        f64::from(*SPEED_RANGE.start())
+    }
+
+    #[zbus(property)]
+    fn maximum_rate(&self) -> f64 {
+        f64::from(*SPEED_RANGE.end())
+    }
+
+    #[zbus(property)]
+    fn shuffle(&self) -> bool {
+        self.shuffle
+    }
+
+    #[zbus(property)]
+    fn set_shuffle(&mut self, shuffle: bool) {
+        self.send(MediaControlEvent::SetShuffle(shuffle));
+    }
+
+    #[zbus(property)]
+    fn metadata(&self) -> HashMap<&str, Value<'_>> {
+        let mut metadata = HashMap::new();
+        metadata.insert("mpris:trackid", Value::from(self.track_id.as_ref()));
+        if let Some(duration) = self.metadata.duration {
+            metadata.insert("mpris:length", Value::from(micros(duration)));
+        }
+        if let Some(cover_url) = &self.metadata.cover_url {
+            metadata.insert("mpris:artUrl", Value::from(cover_url.as_str()));
+        }
+        if let Some(title) = &self.metadata.title {
+            metadata.insert("xesam:title", Value::from(title.as_str()));
+        }
+        if let Some(artist) = &self.metadata.artist {
+            metadata.insert("xesam:artist", Value::from(vec![artist.as_str()]));
+        }
+        if let Some(album) = &self.metadata.album {
+            metadata.insert("xesam:album", Value::from(album.as_str()));
+        }
+        metadata
+    }
+
+    #[zbus(property)]
+    fn volume(&self) -> f64 {
+        self.volume
+    }
+
+    #[zbus(property)]
+    fn set_volume(&mut self, volume: f64) {
+        if volume.is_finite() {
+            self.send(MediaControlEvent::SetVolume(volume.clamp(0.0, 1.0)));
+        }
+    }
+
+    #[zbus(property(emits_changed_signal = "false"))]
+    fn position(&self) -> i64 {
+        micros(self.current_position())
+    }
+
+    #[zbus(property)]
+    fn can_go_next(&self) -> bool {
+        true
+    }
+
+    #[zbus(property)]
+    fn can_go_previous(&self) -> bool {
+        true
+    }
+
+    #[zbus(property)]
+    fn can_play(&self) -> bool {
+        true
+    }
+
+    #[zbus(property)]
+    fn can_pause(&self) -> bool {
+        true
+    }
+
+    #[zbus(property)]
+    fn can_seek(&self) -> bool {
+        self.metadata.duration.is_some()
+    }
+
+    #[zbus(property(emits_changed_signal = "const"))]
+    fn can_control(&self) -> bool {
+        true
+    }
+}
+
+fn track_id(number: u64) -> OwnedObjectPath {
+    ObjectPath::try_from(format!("/org/kanta/track/{number}"))
+        .expect("track ids are valid object paths")
+        .into()
+}
+
+fn micros(duration: Duration) -> i64 {
+    duration.as_micros().try_into().unwrap_or(i64::MAX)
+}
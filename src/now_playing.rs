@@ -0,0 +1,99 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use iced::widget::image;
+use serde::Serialize;
+
+use crate::{player::PlaybackState, track::Track};
+
+const COVER_EXTENSIONS: [&str; 2] = ["jpg", "png"];
+
+#[derive(Serialize)]
+struct NowPlaying<'a> {
+    state: &'static str,
+    title: Option<&'a str>,
+    artist: Option<&'a str>,
+    album: Option<&'a str>,
+    art: Option<PathBuf>,
+}
+
+pub fn write(
+    path: &Path,
+    track: Option<&Track>,
+    stream_title: Option<&str>,
+    state: PlaybackState,
+) -> anyhow::Result<()> {
+    let track = track.filter(|_| state != PlaybackState::Stopped);
+    let art = match track.and_then(Track::cover) {
+        Some(cover) => write_cover(path, cover)?,
+        None => {
+            remove_covers(path, None)?;
+            None
+        }
+    };
+
+    let title = stream_title.or(track.and_then(Track::title));
+    let artist = track
+        .and_then(Track::artist)
+        .filter(|_| stream_title.is_none());
+    let is_json = path
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("json"));
+    let contents = if is_json {
+        serde_json::to_string_pretty(&NowPlaying {
+            state: state.as_str(),
+            title,
+            artist,
+            album: track.and_then(Track::album),
+            art,
+        })?
+    } else {
+        match (artist, title) {
+            (Some(artist), Some(title)) => format!("{} - {}", artist, title),
+            (_, Some(title)) => title.to_string(),
+            (_, None) => track
+                .and_then(|track| track.path().file_stem())
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+        }
+    };
+
+    let temp_path = path.with_extension("tmp");
+    fs::write(&temp_path, contents)?;
+    fs::rename(&temp_path, path)?;
+    Ok(())
+}
+
+fn write_cover(path: &Path, cover: &image::Handle) -> anyhow::Result<Option<PathBuf>> {
+    let image::Handle::Bytes(_, bytes) = cover else {
+        return Ok(None);
+    };
+    let extension = if bytes.starts_with(b"\x89PNG") {
+        "png"
+    } else {
+        "jpg"
+    };
+    let cover_path = cover_path(path, extension);
+    remove_covers(path, Some(extension))?;
+    if fs::read(&cover_path).ok().as_deref() != Some(&bytes[..]) {
+        fs::write(&cover_path, bytes)?;
+    }
+    Ok(Some(cover_path))
+}
+
+fn remove_covers(path: &Path, keep: Option<&str>) -> anyhow::Result<()> {
+    for extension in COVER_EXTENSIONS {
+        let cover_path = cover_path(path, extension);
+        if Some(extension) != keep && cover_path.exists() {
+            fs::remove_file(cover_path)?;
+        }
+    }
+    Ok(())
+}
+
+fn cover_path(path: &Path, extension: &str) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!("{}-cover.{}", stem, extension))
+}
@@ -0,0 +1,101 @@
+use std::{fs, path::PathBuf, sync::RwLock};
+
+use anyhow::{anyhow, bail};
+
+use crate::browser;
+
+static PROFILE: RwLock<Option<String>> = RwLock::new(None);
+
+pub fn app_data_dir() -> anyhow::Result<PathBuf> {
+    let dir = dirs::data_dir()
+        .ok_or_else(|| anyhow!("no data directory available"))?
+        .join("kanta");
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+pub fn data_dir() -> anyhow::Result<PathBuf> {
+    profile_dir(app_data_dir()?)
+}
+
+pub fn cache_dir() -> anyhow::Result<PathBuf> {
+    let dir = dirs::cache_dir()
+        .ok_or_else(|| anyhow!("no cache directory available"))?
+        .join("kanta");
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+pub fn log_dir() -> anyhow::Result<PathBuf> {
+    let dir = dirs::state_dir()
+        .or_else(dirs::data_local_dir)
+        .ok_or_else(|| anyhow!("no log directory available"))?
+        .join("kanta")
+        .join("logs");
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+pub fn config_dir() -> anyhow::Result<PathBuf> {
+    profile_dir(app_config_dir()?)
+}
+
+pub fn profile() -> Option<String> {
+    PROFILE.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+pub fn set_profile(profile: Option<String>) {
+    *PROFILE.write().unwrap_or_else(|e| e.into_inner()) = profile;
+}
+
+pub fn profiles() -> anyhow::Result<Vec<String>> {
+    let dir = app_config_dir()?.join("profiles");
+    if !dir.exists() {
+        return Ok(vec![]);
+    }
+
+    let mut profiles = vec![];
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir()
+            && let Some(name) = entry.file_name().to_str()
+        {
+            profiles.push(name.to_string());
+        }
+    }
+    profiles.sort_by_cached_key(|name| browser::natural_key(name));
+    Ok(profiles)
+}
+
+pub fn validate_profile_name(name: &str) -> anyhow::Result<()> {
+    if name.is_empty()
+        || name.starts_with('.')
+        || name.contains(|c: char| matches!(c, '/' | '\\' | ':') || c.is_control())
+    {
+        bail!("invalid profile name \"{}\"", name);
+    }
+    Ok(())
+}
+
+pub fn create_profile(name: &str) -> anyhow::Result<()> {
+    validate_profile_name(name)?;
+    fs::create_dir_all(app_config_dir()?.join("profiles").join(name))?;
+    Ok(())
+}
+
+fn app_config_dir() -> anyhow::Result<PathBuf> {
+    let dir = dirs::config_dir()
+        .ok_or_else(|| anyhow!("no config directory available"))?
+        .join("kanta");
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+fn profile_dir(dir: PathBuf) -> anyhow::Result<PathBuf> {
+    let Some(profile) = profile() else {
+        return Ok(dir);
+    };
+    let dir = dir.join("profiles").join(profile);
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
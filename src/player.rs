@@ -1,41 +1,251 @@
 use std::{
-    fs::{self, File},
-    io::BufReader,
+    cmp::Ordering,
+    collections::{BTreeSet, HashMap, HashSet, VecDeque},
+    fmt, mem,
+    ops::Range,
     path::{Path, PathBuf},
-    time::Duration,
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
 };
 
 use anyhow::anyhow;
-use rodio::{Decoder, OutputStream, OutputStreamBuilder, Sink};
-use souvlaki::{MediaControlEvent, MediaPosition, SeekDirection};
+use rodio::{
+    cpal::traits::HostTrait, mixer::Mixer, Decoder, DeviceTrait, OutputStream, OutputStreamBuilder,
+    Sink, Source,
+};
+use tracing::{debug, info};
+
+use crate::{
+    airplay::AirPlayOutput,
+    browser, crash,
+    dsp::{
+        AudioTap, DspControls, DspSettings, Equalizer, Limiter, PitchShifter, Tap, Trim,
+        VocalReducer, SPEED_RANGE,
+    },
+    i18n::t,
+    import,
+    loudness::Loudness,
+    lyrics::Lyrics,
+    m3u8,
+    media_controls::{KantaMediaControls, MediaControlEvent, SeekDirection},
+    mounts,
+    plugins::{Metadata, PluginProcessor, PluginStages},
+    radio::IcyStream,
+    read_ahead::{BufferStatus, ReadAhead},
+    remote::{self, RemoteSource},
+    snapcast::SnapcastOutput,
+    track::{self, Track},
+};
+
+const MAX_HISTORY_LEN: usize = 1000;
+const SHUTDOWN_FADE: Duration = Duration::from_millis(300);
+const SHUTDOWN_FADE_STEPS: u32 = 15;
+const RESUME_REWIND_MIN_PAUSE: Duration = Duration::from_secs(60);
 
-use crate::{media_controls::KantaMediaControls, track::Track};
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MediaRequest {
+    Open(PathBuf),
+    Raise,
+    Quit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ControlRequest {
+    Play,
+    Pause,
+    Next,
+    Previous,
+    SetVolume(f32),
+    Seek(f32),
+    JumpTo(usize),
+}
+
+pub enum ExternalOutput {
+    Snapcast(SnapcastOutput),
+    AirPlay(AirPlayOutput),
+}
+
+impl ExternalOutput {
+    fn mixer(&self) -> &Mixer {
+        match self {
+            ExternalOutput::Snapcast(snapcast) => snapcast.mixer(),
+            ExternalOutput::AirPlay(airplay) => airplay.mixer(),
+        }
+    }
+
+    fn latency(&self) -> Duration {
+        match self {
+            ExternalOutput::Snapcast(_) => Duration::ZERO,
+            ExternalOutput::AirPlay(airplay) => airplay.latency(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlaybackState {
+    Playing,
+    Paused,
+    Stopped,
+}
+
+impl PlaybackState {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PlaybackState::Playing => "playing",
+            PlaybackState::Paused => "paused",
+            PlaybackState::Stopped => "stopped",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlaylistSort {
+    Artist,
+    Album,
+    Title,
+    Duration,
+    DateAdded,
+    Tempo,
+    Random,
+}
+
+impl PlaylistSort {
+    pub const ALL: [PlaylistSort; 7] = [
+        PlaylistSort::Artist,
+        PlaylistSort::Album,
+        PlaylistSort::Title,
+        PlaylistSort::Duration,
+        PlaylistSort::DateAdded,
+        PlaylistSort::Tempo,
+        PlaylistSort::Random,
+    ];
+
+    fn compare(self, a: &Track, b: &Track) -> Ordering {
+        let text = |value: Option<&str>| value.map(str::to_lowercase);
+        let album_order = |track: &Track| {
+            (
+                text(track.album()),
+                text(track.album_artist()),
+                track.disc_number(),
+                track.track_number(),
+                browser::file_name_key(track.path()),
+            )
+        };
+        let title = |track: &Track| match track.title() {
+            Some(title) => browser::natural_key(title),
+            None => browser::file_name_key(track.path()),
+        };
+
+        match self {
+            PlaylistSort::Artist => {
+                (text(a.artist()), album_order(a)).cmp(&(text(b.artist()), album_order(b)))
+            }
+            PlaylistSort::Album => album_order(a).cmp(&album_order(b)),
+            PlaylistSort::Title => title(a).cmp(&title(b)),
+            PlaylistSort::Duration => a.duration().cmp(&b.duration()),
+            PlaylistSort::DateAdded => a.added_at().cmp(&b.added_at()),
+            PlaylistSort::Tempo => {
+                let bpm = |track: &Track| track.bpm().unwrap_or(f32::INFINITY);
+                bpm(a).total_cmp(&bpm(b))
+            }
+            PlaylistSort::Random => Ordering::Equal,
+        }
+    }
+}
+
+impl fmt::Display for PlaylistSort {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&t!(match self {
+            PlaylistSort::Artist => "sort-artist",
+            PlaylistSort::Album => "sort-album",
+            PlaylistSort::Title => "sort-title",
+            PlaylistSort::Duration => "sort-duration",
+            PlaylistSort::DateAdded => "sort-date-added",
+            PlaylistSort::Tempo => "sort-tempo",
+            PlaylistSort::Random => "sort-random",
+        }))
+    }
+}
 
 #[derive(Default)]
 pub struct Player {
-    #[allow(dead_code)]
     stream: Option<OutputStream>,
     sink: Option<Sink>,
+    output: Option<ExternalOutput>,
     playlist: Vec<Track>,
     playlist_index: Option<usize>,
+    playlist_revision: u64,
+    shuffle: bool,
+    shuffle_weights: HashMap<PathBuf, f32>,
+    normalize: bool,
+    history: VecDeque<usize>,
     media_controls: Option<KantaMediaControls>,
+    buffer_status: Option<BufferStatus>,
+    stream_title: Arc<Mutex<Option<String>>>,
+    reported_stream_title: Option<String>,
+    show_stream_titles: bool,
+    locked: bool,
+    pitch: i32,
+    dsp: DspSettings,
+    session_dsp: DspSettings,
+    dsp_controls: Arc<DspControls>,
+    dsp_overrides: HashMap<PathBuf, DspSettings>,
+    audio_tap: Arc<AudioTap>,
+    plugin_stages: PluginStages,
+    stopped: bool,
+    paused_at: Option<Instant>,
+    resume_rewind: Duration,
+    media_requests: Vec<MediaRequest>,
 }
 
 impl Player {
     pub fn try_new() -> anyhow::Result<Player> {
+        let audio_device = rodio::cpal::default_host()
+            .default_output_device()
+            .and_then(|device| device.name().ok());
+        info!(
+            "Using audio device {}",
+            audio_device.as_deref().unwrap_or("unknown")
+        );
+        crash::set_audio_device(audio_device);
+
         let stream = OutputStreamBuilder::open_default_stream()?;
         let sink = Sink::connect_new(stream.mixer());
 
         Ok(Player {
             stream: Some(stream),
             sink: Some(sink),
+            output: None,
             playlist: vec![],
             playlist_index: None,
+            playlist_revision: 0,
+            shuffle: false,
+            shuffle_weights: HashMap::new(),
+            normalize: false,
+            history: VecDeque::new(),
             media_controls: Some(KantaMediaControls::try_new()?),
+            buffer_status: None,
+            stream_title: Arc::default(),
+            reported_stream_title: None,
+            show_stream_titles: true,
+            locked: false,
+            pitch: 0,
+            dsp: DspSettings::default(),
+            session_dsp: DspSettings::default(),
+            dsp_controls: Arc::default(),
+            dsp_overrides: HashMap::new(),
+            audio_tap: Arc::default(),
+            plugin_stages: PluginStages::default(),
+            stopped: false,
+            paused_at: None,
+            resume_rewind: Duration::ZERO,
+            media_requests: vec![],
         })
     }
 
     pub fn jump_to_track_at(&mut self, index: usize) -> anyhow::Result<()> {
+        self.push_history();
         self.playlist_index = Some(index);
         self.update_sink_to_current_track()?;
         Ok(())
@@ -45,6 +255,13 @@ impl Player {
         if self.playlist.is_empty() {
             return Ok(());
         }
+        if self.shuffle {
+            if let Some(index) = self.history.pop_back() {
+                self.playlist_index = Some(index);
+                self.update_sink_to_current_track()?;
+            }
+            return Ok(());
+        }
         let Some(index) = self.playlist_index.as_mut() else {
             return Ok(());
         };
@@ -60,7 +277,12 @@ impl Player {
             return Ok(());
         }
 
+        self.push_history();
         self.playlist_index = match self.playlist_index {
+            Some(index) if self.shuffle && self.playlist.len() > 1 => {
+                Some(self.pick_shuffled(Some(index)))
+            }
+            None if self.shuffle => Some(self.pick_shuffled(None)),
             Some(index) if index == self.playlist.len() - 1 => Some(index),
             Some(index) => Some(index + 1),
             None => Some(0),
@@ -71,7 +293,53 @@ impl Player {
         Ok(())
     }
 
+    pub fn is_shuffling(&self) -> bool {
+        self.shuffle
+    }
+
+    pub fn set_shuffle(&mut self, shuffle: bool) -> anyhow::Result<()> {
+        self.shuffle = shuffle;
+        if let Some(media_controls) = self.media_controls.as_mut() {
+            media_controls.update_shuffle(shuffle)?;
+        }
+        Ok(())
+    }
+
+    pub fn set_shuffle_weights(&mut self, shuffle_weights: HashMap<PathBuf, f32>) {
+        self.shuffle_weights = shuffle_weights;
+    }
+
+    pub fn set_normalize(&mut self, normalize: bool) {
+        self.normalize = normalize;
+    }
+
+    pub fn set_locked(&mut self, locked: bool) {
+        self.locked = locked;
+    }
+
+    pub fn set_resume_rewind(&mut self, resume_rewind: Duration) {
+        self.resume_rewind = resume_rewind;
+    }
+
+    pub fn set_show_stream_titles(&mut self, show_stream_titles: bool) {
+        self.show_stream_titles = show_stream_titles;
+    }
+
+    pub fn stream_title(&self) -> Option<String> {
+        if !self.show_stream_titles {
+            return None;
+        }
+        self.stream_title
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
     pub fn play(&mut self) -> anyhow::Result<()> {
+        if self.stopped {
+            self.update_sink_to_current_track()?;
+        }
+        self.rewind_after_long_pause()?;
         if let Some(sink) = &self.sink {
             sink.play();
             self.update_media_control_playback()?;
@@ -80,6 +348,9 @@ impl Player {
     }
 
     pub fn pause(&mut self) -> anyhow::Result<()> {
+        if self.state() == PlaybackState::Playing {
+            self.paused_at = Some(Instant::now());
+        }
         if let Some(sink) = &self.sink {
             sink.pause();
             self.update_media_control_playback()?;
@@ -87,8 +358,73 @@ impl Player {
         Ok(())
     }
 
-    pub fn is_paused(&self) -> bool {
-        self.sink.as_ref().map(|s| s.is_paused()).unwrap_or(true)
+    pub fn stop(&mut self) -> anyhow::Result<()> {
+        self.stopped = true;
+        if let Some(sink) = &self.sink {
+            sink.stop();
+        }
+        self.update_media_control_playback()
+    }
+
+    pub fn shut_down(&mut self) -> anyhow::Result<()> {
+        if let Some(sink) = &self.sink
+            && self.state() == PlaybackState::Playing
+        {
+            let volume = sink.volume();
+            for step in (0..SHUTDOWN_FADE_STEPS).rev() {
+                sink.set_volume(volume * step as f32 / SHUTDOWN_FADE_STEPS as f32);
+                thread::sleep(SHUTDOWN_FADE / SHUTDOWN_FADE_STEPS);
+            }
+        }
+        self.stop()?;
+        if let Some(mut media_controls) = self.media_controls.take() {
+            media_controls.detach()?;
+        }
+        Ok(())
+    }
+
+    pub fn output(&self) -> Option<&ExternalOutput> {
+        self.output.as_ref()
+    }
+
+    pub fn set_output(&mut self, output: Option<ExternalOutput>) -> anyhow::Result<()> {
+        let state = self.state();
+        let position = self.sink_position();
+        let volume = self.volume();
+        if let Some(sink) = self.sink.take() {
+            sink.stop();
+        }
+
+        self.output = output;
+        let mixer = match (&self.output, &self.stream) {
+            (Some(output), _) => Some(output.mixer()),
+            (None, Some(stream)) => Some(stream.mixer()),
+            (None, None) => None,
+        };
+        self.sink = mixer.map(Sink::connect_new);
+        self.set_volume(volume);
+
+        if state != PlaybackState::Stopped {
+            self.update_sink_to_current_track()?;
+            self.set_position(position)?;
+            if state == PlaybackState::Paused {
+                self.pause()?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn state(&self) -> PlaybackState {
+        match &self.sink {
+            Some(sink) if !sink.empty() && self.current_track().is_some() => {
+                if sink.is_paused() {
+                    PlaybackState::Paused
+                } else {
+                    PlaybackState::Playing
+                }
+            }
+            _ => PlaybackState::Stopped,
+        }
     }
 
     pub fn playlist(&self) -> &[Track] {
@@ -99,54 +435,251 @@ impl Player {
         self.playlist_index
     }
 
+    pub fn playlist_revision(&self) -> u64 {
+        self.playlist_revision
+    }
+
+    pub fn set_bpm(&mut self, path: &Path, bpm: f32) {
+        self.playlist
+            .iter_mut()
+            .filter(|track| track.path() == path)
+            .for_each(|track| track.set_bpm(bpm));
+    }
+
+    pub fn set_lyrics(&mut self, path: &Path, lyrics: Option<Lyrics>) {
+        self.playlist
+            .iter_mut()
+            .filter(|track| track.path() == path)
+            .for_each(|track| track.set_lyrics(lyrics.clone()));
+    }
+
+    pub fn fill_in_metadata(&mut self, path: &Path, metadata: &Metadata) {
+        self.playlist
+            .iter_mut()
+            .filter(|track| track.path() == path)
+            .for_each(|track| track.fill_in_metadata(metadata));
+    }
+
+    pub fn set_loudness(&mut self, path: &Path, loudness: Loudness) {
+        self.playlist
+            .iter_mut()
+            .filter(|track| track.path() == path)
+            .for_each(|track| track.set_loudness(loudness));
+    }
+
     pub fn add_to_playlist(&mut self, track: Track) {
         self.playlist.push(track);
+        self.playlist_revision += 1;
+    }
+
+    pub fn insert_into_playlist(&mut self, index: usize, track: Track) {
+        let index = index.min(self.playlist.len());
+        self.playlist.insert(index, track);
+        self.playlist_revision += 1;
+
+        let remap = |i: usize| if i >= index { i + 1 } else { i };
+        self.playlist_index = self.playlist_index.map(remap);
+        self.remap_history(|i| Some(remap(i)));
     }
 
-    pub fn load_m3u8_playlist(&mut self, path: &Path) -> anyhow::Result<()> {
-        let contents = fs::read_to_string(path)?;
-        self.playlist = contents
-            .lines()
-            .filter(|line| !line.trim().is_empty())
-            .map(|line| Track::load(PathBuf::from(line)))
-            .collect::<Result<_, _>>()?;
+    pub fn restore_playlist(
+        &mut self,
+        tracks: Vec<Track>,
+        index: Option<usize>,
+    ) -> anyhow::Result<()> {
+        self.playlist = tracks;
+        self.playlist_revision += 1;
+        self.playlist_index = index.filter(|&index| index < self.playlist.len());
+        self.history.clear();
         self.update_sink_to_current_track()?;
+        self.pause()
+    }
+
+    pub fn remove_from_playlist(&mut self, indices: &BTreeSet<usize>) -> anyhow::Result<()> {
+        let removes_current = self
+            .playlist_index
+            .is_some_and(|current| indices.contains(&current));
+
+        let mut index = 0;
+        self.playlist.retain(|_| {
+            let keep = !indices.contains(&index);
+            index += 1;
+            keep
+        });
+        self.playlist_revision += 1;
+        self.playlist_index = self
+            .playlist_index
+            .map(|current| current - indices.range(..current).count());
+        self.remap_history(|index| {
+            (!indices.contains(&index)).then(|| index - indices.range(..index).count())
+        });
+
+        if removes_current {
+            self.playlist_index = self
+                .playlist_index
+                .filter(|_| !self.playlist.is_empty())
+                .map(|current| current.min(self.playlist.len() - 1));
+            self.update_sink_to_current_track()?;
+        }
+
         Ok(())
     }
 
-    pub fn export_m3u8_playlist(&mut self, path: &Path) -> anyhow::Result<()> {
-        let m3u8_data = self
+    pub fn deduplicate_playlist(&mut self) -> anyhow::Result<()> {
+        let mut seen_paths = HashSet::new();
+        let mut seen_metadata = HashSet::new();
+        let mut is_duplicate = |track: &Track| {
+            let metadata = track.title().zip(track.artist()).map(|(title, artist)| {
+                (
+                    title.to_string(),
+                    artist.to_string(),
+                    track.duration().as_secs(),
+                )
+            });
+            let seen_path = !seen_paths.insert((track.path().to_path_buf(), track.segment()));
+            let seen_metadata = metadata.is_some_and(|metadata| !seen_metadata.insert(metadata));
+            seen_path || seen_metadata
+        };
+
+        if let Some(current) = self.current_track() {
+            is_duplicate(current);
+        }
+        let duplicates = self
             .playlist
             .iter()
-            .map(|track| {
-                track
-                    .path()
-                    .to_str()
-                    .ok_or_else(|| anyhow!("path contains invalid UTF-8"))
-                    .map(|s| s.to_string())
-            })
-            .collect::<Result<Vec<_>, _>>()?
-            .join("\n");
-        fs::write(path, m3u8_data)?;
+            .enumerate()
+            .filter(|&(index, track)| Some(index) != self.playlist_index && is_duplicate(track))
+            .map(|(index, _)| index)
+            .collect::<BTreeSet<_>>();
+
+        self.remove_from_playlist(&duplicates)
+    }
+
+    pub fn move_in_playlist(&mut self, from: usize, to: usize) {
+        if from >= self.playlist.len() || to >= self.playlist.len() || from == to {
+            return;
+        }
+        let track = self.playlist.remove(from);
+        self.playlist.insert(to, track);
+        self.playlist_revision += 1;
+
+        let remap = |index: usize| {
+            if index == from {
+                to
+            } else if from < index && index <= to {
+                index - 1
+            } else if to <= index && index < from {
+                index + 1
+            } else {
+                index
+            }
+        };
+        self.playlist_index = self.playlist_index.map(remap);
+        self.remap_history(|index| Some(remap(index)));
+    }
+
+    pub fn sort_playlist(&mut self, sort: PlaylistSort, descending: bool) {
+        let mut tracks = std::mem::take(&mut self.playlist)
+            .into_iter()
+            .enumerate()
+            .collect::<Vec<_>>();
+        match sort {
+            PlaylistSort::Random => fastrand::shuffle(&mut tracks),
+            _ if descending => tracks.sort_by(|(_, a), (_, b)| sort.compare(b, a)),
+            _ => tracks.sort_by(|(_, a), (_, b)| sort.compare(a, b)),
+        }
+
+        let mut new_positions = vec![0; tracks.len()];
+        for (position, &(index, _)) in tracks.iter().enumerate() {
+            new_positions[index] = position;
+        }
+        self.playlist_index = self.playlist_index.map(|current| new_positions[current]);
+        self.remap_history(|index| new_positions.get(index).copied());
+        self.playlist = tracks.into_iter().map(|(_, track)| track).collect();
+        self.playlist_revision += 1;
+    }
+
+    pub fn play_next(&mut self, index: usize) {
+        let to = match self.playlist_index {
+            Some(current) if index > current => current + 1,
+            Some(current) if index < current => current,
+            Some(_) => return,
+            None => 0,
+        };
+        self.move_in_playlist(index, to);
+    }
+
+    pub fn load_m3u8_playlist(&mut self, path: &Path, hidden_tracks: bool) -> anyhow::Result<()> {
+        self.playlist = m3u8::load_tracks(path, hidden_tracks)?;
+        self.playlist_revision += 1;
+        self.history.clear();
+        self.update_sink_to_current_track()?;
         Ok(())
     }
 
+    pub fn export_m3u8_playlist(&self, path: &Path) -> anyhow::Result<()> {
+        m3u8::write(path, track::file_paths(&self.playlist))
+    }
+
+    pub fn export_m3u8_selection(
+        &self,
+        path: &Path,
+        indices: &BTreeSet<usize>,
+    ) -> anyhow::Result<()> {
+        m3u8::write(
+            path,
+            track::file_paths(indices.iter().filter_map(|&i| self.playlist.get(i))),
+        )
+    }
+
     pub fn clear_playlist(&mut self) -> anyhow::Result<()> {
         self.playlist.clear();
+        self.playlist_revision += 1;
+        self.history.clear();
         self.update_sink_to_current_track()?;
         Ok(())
     }
 
     pub fn position(&self) -> Duration {
-        self.sink.as_ref().map(|s| s.get_pos()).unwrap_or_default()
+        let latency = self
+            .output
+            .as_ref()
+            .map_or(Duration::ZERO, ExternalOutput::latency);
+        self.sink_position().saturating_sub(latency)
+    }
+
+    fn sink_position(&self) -> Duration {
+        if self.stopped {
+            return Duration::ZERO;
+        }
+        self.sink
+            .as_ref()
+            .map(|s| s.get_pos().mul_f32(self.dsp.speed))
+            .unwrap_or_default()
     }
 
     pub fn set_position(&mut self, position: Duration) -> anyhow::Result<()> {
+        let Some(track) = self.current_track().filter(|track| !track.is_stream()) else {
+            return Ok(());
+        };
+        let position = position.min(track.duration());
         if let Some(sink) = &self.sink {
-            let _ = sink.try_seek(position);
+            sink.try_seek(position.div_f32(self.dsp.speed))
+                .map_err(|e| anyhow!("failed to seek to {:?}: {}", position, e))?;
         }
-        self.update_media_control_playback()?;
-        Ok(())
+        self.update_media_control_playback()
+    }
+
+    pub fn seek_forward(&mut self, amount: Duration) -> anyhow::Result<()> {
+        self.set_position(self.position().saturating_add(amount))
+    }
+
+    pub fn seek_backward(&mut self, amount: Duration) -> anyhow::Result<()> {
+        self.set_position(self.position().saturating_sub(amount))
+    }
+
+    pub fn buffered(&self) -> Option<Range<f32>> {
+        self.buffer_status.as_ref().map(BufferStatus::buffered)
     }
 
     pub fn volume(&self) -> f32 {
@@ -159,6 +692,73 @@ impl Player {
         }
     }
 
+    pub fn take_levels(&self) -> [f32; 2] {
+        self.audio_tap.take()
+    }
+
+    pub fn set_capture_audio(&self, capture: bool) {
+        self.audio_tap.set_capture(capture);
+    }
+
+    pub fn take_captured_audio(&self) -> (Vec<f32>, u32) {
+        self.audio_tap.take_captured()
+    }
+
+    pub fn pitch(&self) -> i32 {
+        self.pitch
+    }
+
+    pub fn set_pitch(&mut self, semitones: i32) {
+        self.pitch = semitones;
+        self.update_pitch_ratio();
+    }
+
+    pub fn dsp(&self) -> DspSettings {
+        self.dsp
+    }
+
+    pub fn set_dsp(&mut self, dsp: DspSettings) -> anyhow::Result<()> {
+        if !self.has_dsp_override() {
+            self.session_dsp = dsp;
+        }
+        self.apply_dsp(dsp)
+    }
+
+    pub fn restore_session_dsp(&mut self) -> anyhow::Result<()> {
+        self.apply_dsp(self.session_dsp)
+    }
+
+    fn has_dsp_override(&self) -> bool {
+        self.current_track()
+            .is_some_and(|track| self.dsp_overrides.contains_key(track.path()))
+    }
+
+    fn apply_dsp(&mut self, dsp: DspSettings) -> anyhow::Result<()> {
+        self.dsp = dsp;
+        if let Some(sink) = &self.sink {
+            sink.set_speed(dsp.speed);
+        }
+        self.dsp_controls.set_eq(dsp.eq);
+        self.update_pitch_ratio();
+        self.update_media_control_playback()
+    }
+
+    pub fn set_vocal_reduction(&self, enabled: bool) {
+        self.dsp_controls.set_vocal_reduction(enabled);
+    }
+
+    pub fn set_dsp_overrides(&mut self, dsp_overrides: HashMap<PathBuf, DspSettings>) {
+        self.dsp_overrides = dsp_overrides;
+    }
+
+    pub fn set_plugin_stages(&mut self, plugin_stages: PluginStages) {
+        self.plugin_stages = plugin_stages;
+    }
+
+    pub fn take_media_requests(&mut self) -> Vec<MediaRequest> {
+        mem::take(&mut self.media_requests)
+    }
+
     pub fn current_track(&self) -> Option<&Track> {
         self.playlist_index
             .and_then(|position| self.playlist.get(position))
@@ -166,7 +766,7 @@ impl Player {
 
     pub fn tick(&mut self) -> anyhow::Result<()> {
         let is_empty = self.sink.as_ref().map(|s| s.empty()).unwrap_or(true);
-        if is_empty {
+        if is_empty && !self.stopped {
             self.jump_to_next_track()?;
         }
 
@@ -177,8 +777,32 @@ impl Player {
                 vec![]
             };
 
+        let state = self.state();
+        let position = self.position();
+        let volume = self.volume();
+        if let Some(media_controls) = self.media_controls.as_mut() {
+            media_controls.sync_position(state, position, self.dsp.speed)?;
+            media_controls.update_volume(volume)?;
+        }
+
+        let stream_title = self.stream_title();
+        if stream_title != self.reported_stream_title {
+            if let Some(media_controls) = self.media_controls.as_mut()
+                && let Some(track) = self
+                    .playlist_index
+                    .and_then(|index| self.playlist.get(index))
+            {
+                media_controls.update_metadata(track, stream_title.as_deref())?;
+            }
+            self.reported_stream_title = stream_title;
+        }
+
         for event in events {
             use MediaControlEvent::*;
+            if self.locked {
+                debug!("Ignoring media control event in party mode: {:?}", event);
+                continue;
+            }
             use SeekDirection::*;
             match event {
                 Play => self.play()?,
@@ -186,56 +810,237 @@ impl Player {
                 Next => self.jump_to_next_track()?,
                 Previous => self.jump_to_previous_track()?,
                 SetVolume(volume) => self.set_volume(volume as f32),
-                SetPosition(MediaPosition(position)) => self.set_position(position)?,
-                Seek(direction) => match direction {
-                    Forward => self.set_position(self.position() + Duration::from_secs(10))?,
-                    Backward => self.set_position(self.position() - Duration::from_secs(10))?,
-                },
-                SeekBy(direction, amount) => match direction {
-                    Forward => self.set_position(self.position() + amount)?,
-                    Backward => self.set_position(self.position() - amount)?,
-                },
-                _ => eprintln!("unhandled media control event: {:?}", event),
+                SetPosition(position) => self.set_position(position)?,
+                SetRate(rate) => self.set_dsp(DspSettings {
+                    speed: (rate as f32).clamp(*SPEED_RANGE.start(), *SPEED_RANGE.end()),
+                    ..self.dsp
+                })?,
+                SetShuffle(shuffle) => self.set_shuffle(shuffle)?,
+                SeekBy(Forward, amount) => self.seek_forward(amount)?,
+                SeekBy(Backward, amount) => self.seek_backward(amount)?,
+                OpenUri(uri) => {
+                    let path = if remote::is_url(&uri) {
+                        Some(PathBuf::from(&uri))
+                    } else {
+                        import::file_url_to_path(&uri)
+                    };
+                    match path {
+                        Some(path) => self.media_requests.push(MediaRequest::Open(path)),
+                        None => debug!("Ignoring unsupported URI from media controls: {}", uri),
+                    }
+                }
+                Toggle if self.state() == PlaybackState::Playing => self.pause()?,
+                Toggle => self.play()?,
+                Stop => self.stop()?,
+                Raise => self.media_requests.push(MediaRequest::Raise),
+                Quit => self.media_requests.push(MediaRequest::Quit),
             }
         }
 
         Ok(())
     }
 
+    fn pick_shuffled(&self, current: Option<usize>) -> usize {
+        let weights = self
+            .playlist
+            .iter()
+            .enumerate()
+            .map(|(index, track)| match current {
+                Some(current) if current == index => 0.0,
+                _ => self
+                    .shuffle_weights
+                    .get(track.path())
+                    .copied()
+                    .unwrap_or(1.0),
+            })
+            .collect::<Vec<_>>();
+        let total = weights.iter().sum::<f32>();
+
+        if total > 0.0 {
+            let mut target = fastrand::f32() * total;
+            for (index, weight) in weights.iter().enumerate() {
+                if target < *weight {
+                    return index;
+                }
+                target -= weight;
+            }
+            if let Some(index) = weights.iter().rposition(|weight| *weight > 0.0) {
+                return index;
+            }
+        }
+
+        match current {
+            Some(current) => {
+                let next = fastrand::usize(..self.playlist.len() - 1);
+                if next >= current {
+                    next + 1
+                } else {
+                    next
+                }
+            }
+            None => fastrand::usize(..self.playlist.len()),
+        }
+    }
+
+    fn update_pitch_ratio(&self) {
+        self.dsp_controls
+            .set_pitch_ratio(2f32.powf(self.pitch as f32 / 12.0) / self.dsp.speed);
+    }
+
+    fn is_playing_album(&self) -> bool {
+        let Some(index) = self.playlist_index.filter(|_| !self.shuffle) else {
+            return false;
+        };
+        let Some(album) = self.playlist.get(index).and_then(Track::album) else {
+            return false;
+        };
+        [index.checked_sub(1), index.checked_add(1)]
+            .into_iter()
+            .flatten()
+            .filter_map(|index| self.playlist.get(index))
+            .any(|track| track.album() == Some(album))
+    }
+
+    fn push_history(&mut self) {
+        if let Some(index) = self.playlist_index {
+            self.history.push_back(index);
+            if self.history.len() > MAX_HISTORY_LEN {
+                self.history.pop_front();
+            }
+        }
+    }
+
+    fn remap_history(&mut self, remap: impl Fn(usize) -> Option<usize>) {
+        self.history = self.history.drain(..).filter_map(remap).collect();
+    }
+
     fn update_sink_to_current_track(&mut self) -> anyhow::Result<()> {
+        self.playlist_revision += 1;
+        self.stopped = false;
+
         if let Some(sink) = &self.sink {
             if !sink.empty() {
                 sink.skip_one();
             }
         }
 
+        self.buffer_status = None;
         let Some(track) = self.current_track().cloned() else {
             return Ok(());
         };
 
-        let file = File::open(track.path())?;
-        let reader = BufReader::new(file);
-        let source = Decoder::new(reader)?;
+        info!("Playing {}", track.path().display());
+        self.stream_title = Arc::default();
+        self.reported_stream_title = None;
+        let (reader, hint) = match remote::url(track.path()) {
+            Some(url) if track.is_stream() => {
+                let (stream, hint) = IcyStream::open(url, self.stream_title.clone())?;
+                (ReadAhead::new(stream, u64::MAX)?, hint)
+            }
+            Some(url) => {
+                let source = RemoteSource::open(url)?;
+                let byte_len = source.byte_len();
+                (ReadAhead::new(source, byte_len)?, None)
+            }
+            None => {
+                if let Some(dir) = track.path().parent()
+                    && mounts::is_network(dir)
+                    && !mounts::is_reachable(dir)
+                {
+                    anyhow::bail!(
+                        "{} is on a network share that is not reachable",
+                        dir.display()
+                    );
+                }
+                (ReadAhead::open(track.path())?, None)
+            }
+        };
+        let byte_len = reader.byte_len();
+        if !track.is_stream() {
+            self.buffer_status = Some(reader.status());
+        }
+        let mut decoder = Decoder::builder().with_data(reader);
+        decoder = if track.is_stream() {
+            decoder.with_seekable(false)
+        } else {
+            decoder.with_byte_len(byte_len)
+        };
+        if let Some(hint) = hint {
+            decoder = decoder.with_hint(hint);
+        }
+        let source = decoder.build()?;
+        let source = match track.segment() {
+            Some(segment) => Trim::new(source, segment.start, Some(track.duration())),
+            None => Trim::new(source, Duration::ZERO, None),
+        }
+        .map_err(|e| anyhow!("failed to seek to the start of the track: {}", e))?;
+        debug!(
+            "Decoding at {} Hz with {} channels",
+            source.sample_rate(),
+            source.channels()
+        );
+
+        let gain = if !self.normalize {
+            1.0
+        } else if self.is_playing_album() {
+            debug!("Using album gain");
+            track.album_gain().unwrap_or(1.0)
+        } else {
+            track.gain().unwrap_or(1.0)
+        };
+        debug!("Applying a gain of {:.2}", gain);
 
+        self.apply_dsp(
+            self.dsp_overrides
+                .get(track.path())
+                .copied()
+                .unwrap_or(self.session_dsp),
+        )?;
         if let Some(sink) = &self.sink {
-            sink.append(source);
+            sink.append(Limiter::new(Tap::new(
+                PluginProcessor::new(
+                    PitchShifter::new(
+                        Equalizer::new(
+                            VocalReducer::new(source.amplify(gain), self.dsp_controls.clone()),
+                            self.dsp_controls.clone(),
+                        ),
+                        self.dsp_controls.clone(),
+                    ),
+                    self.plugin_stages.clone(),
+                ),
+                self.audio_tap.clone(),
+            )));
         }
 
         if let Some(media_controls) = self.media_controls.as_mut() {
-            media_controls.update_metadata(&track)?;
+            media_controls.update_metadata(&track, None)?;
         }
         self.update_media_control_playback()?;
 
         Ok(())
     }
 
+    fn rewind_after_long_pause(&mut self) -> anyhow::Result<()> {
+        let Some(paused_at) = self.paused_at.take() else {
+            return Ok(());
+        };
+        if self.resume_rewind.is_zero()
+            || paused_at.elapsed() < RESUME_REWIND_MIN_PAUSE
+            || self.state() != PlaybackState::Paused
+            || !self.current_track().is_some_and(Track::is_spoken_word)
+        {
+            return Ok(());
+        }
+        debug!("Rewinding {:?} after a long pause", self.resume_rewind);
+        self.seek_backward(self.resume_rewind)
+    }
+
     fn update_media_control_playback(&mut self) -> anyhow::Result<()> {
-        let is_empty = self.sink.as_ref().map(|s| s.empty()).unwrap_or(true);
-        let is_paused = self.is_paused();
+        let state = self.state();
         let position = self.position();
 
         if let Some(media_controls) = self.media_controls.as_mut() {
-            media_controls.update_playback(is_empty, is_paused, position)?;
+            media_controls.update_playback(state, position, self.dsp.speed)?;
         }
         Ok(())
     }
@@ -0,0 +1,124 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::bail;
+
+use crate::{browser, m3u8, paths};
+
+pub struct PlaylistLibrary {
+    dir: PathBuf,
+    names: Vec<String>,
+}
+
+impl PlaylistLibrary {
+    pub fn open() -> anyhow::Result<PlaylistLibrary> {
+        let dir = paths::data_dir()?.join("playlists");
+        fs::create_dir_all(&dir)?;
+
+        let mut names = fs::read_dir(&dir)?
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                if path.extension()? != "m3u8" {
+                    return None;
+                }
+                Some(path.file_stem()?.to_str()?.to_string())
+            })
+            .collect::<Vec<_>>();
+        names.sort_by_cached_key(|name| browser::natural_key(name));
+
+        Ok(PlaylistLibrary { dir, names })
+    }
+
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    pub fn path(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{}.m3u8", name))
+    }
+
+    pub fn create(&mut self, name: &str) -> anyhow::Result<()> {
+        self.check_new_name(name)?;
+        m3u8::write(&self.path(name), [])?;
+        self.insert_name(name.to_string());
+        Ok(())
+    }
+
+    pub fn rename(&mut self, name: &str, new_name: &str) -> anyhow::Result<()> {
+        if name == new_name {
+            return Ok(());
+        }
+        self.check_new_name(new_name)?;
+        fs::rename(self.path(name), self.path(new_name))?;
+        self.names.retain(|n| n != name);
+        self.insert_name(new_name.to_string());
+        Ok(())
+    }
+
+    pub fn delete(&mut self, name: &str) -> anyhow::Result<()> {
+        fs::remove_file(self.path(name))?;
+        self.names.retain(|n| n != name);
+        Ok(())
+    }
+
+    pub fn append<'a>(
+        &self,
+        name: &str,
+        paths: impl IntoIterator<Item = &'a Path>,
+    ) -> anyhow::Result<()> {
+        let path = self.path(name);
+        let mut playlist = m3u8::read(&path)?;
+        playlist.extend(paths.into_iter().map(Path::to_path_buf));
+        m3u8::write(&path, playlist.iter().map(PathBuf::as_path))
+    }
+
+    pub fn import(&mut self, name: &str, paths: &[PathBuf]) -> anyhow::Result<()> {
+        check_name(name)?;
+        m3u8::write(&self.path(name), paths.iter().map(PathBuf::as_path))?;
+        if !self.names.iter().any(|n| n == name) {
+            self.insert_name(name.to_string());
+        }
+        Ok(())
+    }
+
+    pub fn unused_name(&self, name: &str) -> String {
+        let name = name.replace(['/', '\\'], "-");
+        let name = name.trim().trim_start_matches('.');
+        let name = if name.is_empty() { "Imported" } else { name };
+        let mut candidate = name.to_string();
+        let mut number = 2;
+        while self.names.contains(&candidate) {
+            candidate = format!("{} ({})", name, number);
+            number += 1;
+        }
+        candidate
+    }
+
+    fn check_new_name(&self, name: &str) -> anyhow::Result<()> {
+        check_name(name)?;
+        if self.names.iter().any(|n| n == name) {
+            bail!("a playlist named \"{}\" already exists", name);
+        }
+        Ok(())
+    }
+
+    fn insert_name(&mut self, name: String) {
+        let key = browser::natural_key(&name);
+        let index = self
+            .names
+            .partition_point(|n| browser::natural_key(n) < key);
+        self.names.insert(index, name);
+    }
+}
+
+fn check_name(name: &str) -> anyhow::Result<()> {
+    if name.trim().is_empty() {
+        bail!("playlist name cannot be empty");
+    }
+    if name.contains(['/', '\\']) || name.starts_with('.') {
+        bail!("playlist name cannot contain path separators or start with a dot");
+    }
+    Ok(())
+}
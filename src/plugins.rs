@@ -0,0 +1,363 @@
+use std::{
+    ffi::{c_char, CStr, CString},
+    fmt, fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use anyhow::bail;
+use libloading::Library;
+use rodio::{source::SeekError, ChannelCount, Sample, SampleRate, Source};
+use serde::Deserialize;
+use tracing::{error, info};
+
+use crate::{hooks::Payload, paths};
+
+const API_VERSION: u32 = 2;
+const LIBRARY_EXTENSIONS: [&str; 3] = ["so", "dylib", "dll"];
+const PROCESS_BLOCK_FRAMES: usize = 1024;
+
+type ApiVersionFn = unsafe extern "C" fn() -> u32;
+type NameFn = unsafe extern "C" fn() -> *const c_char;
+type EventFn = unsafe extern "C" fn(*const c_char);
+type PanelFn = unsafe extern "C" fn() -> *const c_char;
+type ProcessFn = unsafe extern "C" fn(*mut f32, usize, u16, u32);
+type LookupMetadataFn = unsafe extern "C" fn(*const c_char) -> *const c_char;
+
+struct Plugin {
+    name: String,
+    on_event: Option<EventFn>,
+    panel: Option<PanelFn>,
+    process: Option<ProcessFn>,
+    lookup_metadata: Option<LookupMetadataFn>,
+    library: Arc<Library>,
+}
+
+#[derive(Clone)]
+struct Hook<F> {
+    function: F,
+    _library: Arc<Library>,
+}
+
+#[derive(Clone, Default)]
+pub struct PluginStages {
+    stages: Vec<Hook<ProcessFn>>,
+}
+
+#[derive(Clone, Default)]
+pub struct MetadataProviders {
+    providers: Vec<Hook<LookupMetadataFn>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct Metadata {
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+}
+
+pub struct PluginProcessor<S: Source> {
+    input: S,
+    stages: PluginStages,
+    block: Vec<Sample>,
+    block_pos: usize,
+    block_channels: ChannelCount,
+    block_sample_rate: SampleRate,
+}
+
+impl Plugin {
+    fn load(path: &Path) -> anyhow::Result<Plugin> {
+        // SAFETY: plugins are trusted like the binary itself, and the copied function
+        // pointers stay valid because the library is stored alongside them.
+        unsafe {
+            let library = Library::new(path)?;
+            let api_version = library.get::<ApiVersionFn>(b"kanta_plugin_api_version")?();
+            if api_version != API_VERSION {
+                bail!(
+                    "plugin API version {} is not supported (expected {})",
+                    api_version,
+                    API_VERSION
+                );
+            }
+            let name = library.get::<NameFn>(b"kanta_plugin_name")?();
+            let name = if name.is_null() {
+                path.file_stem()
+                    .map(|stem| stem.to_string_lossy().into_owned())
+                    .unwrap_or_default()
+            } else {
+                CStr::from_ptr(name).to_string_lossy().into_owned()
+            };
+            let on_event = library
+                .get::<EventFn>(b"kanta_plugin_on_event")
+                .ok()
+                .map(|symbol| *symbol);
+            let panel = library
+                .get::<PanelFn>(b"kanta_plugin_panel")
+                .ok()
+                .map(|symbol| *symbol);
+            let process = library
+                .get::<ProcessFn>(b"kanta_plugin_process")
+                .ok()
+                .map(|symbol| *symbol);
+            let lookup_metadata = library
+                .get::<LookupMetadataFn>(b"kanta_plugin_lookup_metadata")
+                .ok()
+                .map(|symbol| *symbol);
+            Ok(Plugin {
+                name,
+                on_event,
+                panel,
+                process,
+                lookup_metadata,
+                library: Arc::new(library),
+            })
+        }
+    }
+
+    fn panel(&self) -> Option<String> {
+        let panel = self.panel?;
+        // SAFETY: the plugin returns a NUL-terminated string it owns until its next call.
+        unsafe {
+            let text = panel();
+            (!text.is_null()).then(|| CStr::from_ptr(text).to_string_lossy().into_owned())
+        }
+    }
+
+    fn hook<F: Copy>(&self, function: Option<F>) -> Option<Hook<F>> {
+        Some(Hook {
+            function: function?,
+            _library: self.library.clone(),
+        })
+    }
+}
+
+#[derive(Default)]
+pub struct Plugins {
+    plugins: Vec<Plugin>,
+}
+
+impl fmt::Debug for Plugins {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Plugins({} loaded)", self.plugins.len())
+    }
+}
+
+impl Plugins {
+    pub fn load() -> anyhow::Result<Plugins> {
+        let dir = dir()?;
+        fs::create_dir_all(&dir)?;
+
+        let mut paths = fs::read_dir(&dir)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<Vec<_>, _>>()?;
+        paths.retain(|path| is_library(path));
+        paths.sort();
+
+        let mut plugins = vec![];
+        for path in paths {
+            match Plugin::load(&path) {
+                Ok(plugin) => {
+                    info!("Loaded plugin \"{}\" from {}", plugin.name, path.display());
+                    plugins.push(plugin);
+                }
+                Err(e) => error!("Failed to load plugin {}: {}", path.display(), e),
+            }
+        }
+        Ok(Plugins { plugins })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.plugins.is_empty()
+    }
+
+    pub fn emit(&self, payload: &Payload) {
+        if !self.plugins.iter().any(|plugin| plugin.on_event.is_some()) {
+            return;
+        }
+        let Ok(json) = serde_json::to_string(payload)
+            .map_err(anyhow::Error::from)
+            .and_then(|json| Ok(CString::new(json)?))
+            .inspect_err(|e| error!("Failed to encode plugin event: {}", e))
+        else {
+            return;
+        };
+        for on_event in self.plugins.iter().filter_map(|plugin| plugin.on_event) {
+            // SAFETY: the event string outlives the call; plugins must copy what they keep.
+            unsafe { on_event(json.as_ptr()) };
+        }
+    }
+
+    pub fn panels(&self) -> Vec<(&str, Option<String>)> {
+        self.plugins
+            .iter()
+            .map(|plugin| (plugin.name.as_str(), plugin.panel()))
+            .collect()
+    }
+
+    pub fn stages(&self) -> PluginStages {
+        PluginStages {
+            stages: self
+                .plugins
+                .iter()
+                .filter_map(|plugin| plugin.hook(plugin.process))
+                .collect(),
+        }
+    }
+
+    pub fn metadata_providers(&self) -> MetadataProviders {
+        MetadataProviders {
+            providers: self
+                .plugins
+                .iter()
+                .filter_map(|plugin| plugin.hook(plugin.lookup_metadata))
+                .collect(),
+        }
+    }
+}
+
+impl PluginStages {
+    pub fn is_empty(&self) -> bool {
+        self.stages.is_empty()
+    }
+}
+
+impl MetadataProviders {
+    pub fn is_empty(&self) -> bool {
+        self.providers.is_empty()
+    }
+
+    pub fn look_up(&self, path: &Path) -> anyhow::Result<Option<Metadata>> {
+        let path = CString::new(path.to_string_lossy().into_owned())?;
+        for provider in &self.providers {
+            // SAFETY: the path outlives the call, and the plugin returns a NUL-terminated
+            // string it owns until its next call.
+            let json = unsafe {
+                let json = (provider.function)(path.as_ptr());
+                if json.is_null() {
+                    continue;
+                }
+                CStr::from_ptr(json).to_string_lossy().into_owned()
+            };
+            let metadata: Metadata = serde_json::from_str(&json)?;
+            if metadata != Metadata::default() {
+                return Ok(Some(metadata));
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl<S: Source> PluginProcessor<S> {
+    pub fn new(input: S, stages: PluginStages) -> PluginProcessor<S> {
+        let block_channels = input.channels();
+        let block_sample_rate = input.sample_rate();
+        PluginProcessor {
+            input,
+            stages,
+            block: vec![],
+            block_pos: 0,
+            block_channels,
+            block_sample_rate,
+        }
+    }
+
+    fn read_block(&mut self) -> bool {
+        self.block.clear();
+        self.block_pos = 0;
+        self.block_channels = self.input.channels();
+        self.block_sample_rate = self.input.sample_rate();
+        let len = self
+            .input
+            .current_span_len()
+            .filter(|&len| len > 0)
+            .unwrap_or(usize::MAX)
+            .min(PROCESS_BLOCK_FRAMES * self.block_channels as usize);
+        self.block.extend(self.input.by_ref().take(len));
+        if self.block.is_empty() {
+            return false;
+        }
+
+        for stage in &self.stages.stages {
+            // SAFETY: the block stays alive and unaliased for the duration of the call.
+            unsafe {
+                (stage.function)(
+                    self.block.as_mut_ptr(),
+                    self.block.len(),
+                    self.block_channels,
+                    self.block_sample_rate,
+                )
+            };
+        }
+        true
+    }
+}
+
+impl<S: Source> Iterator for PluginProcessor<S> {
+    type Item = Sample;
+
+    fn next(&mut self) -> Option<Sample> {
+        if self.stages.is_empty() {
+            return self.input.next();
+        }
+        if self.block_pos == self.block.len() && !self.read_block() {
+            return None;
+        }
+        let sample = self.block[self.block_pos];
+        self.block_pos += 1;
+        Some(sample)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let buffered = self.block.len() - self.block_pos;
+        let (lower, upper) = self.input.size_hint();
+        (lower + buffered, upper.map(|upper| upper + buffered))
+    }
+}
+
+impl<S: Source> Source for PluginProcessor<S> {
+    fn current_span_len(&self) -> Option<usize> {
+        match self.block.len() - self.block_pos {
+            0 => self.input.current_span_len(),
+            buffered => Some(buffered),
+        }
+    }
+
+    fn channels(&self) -> ChannelCount {
+        if self.block_pos < self.block.len() {
+            self.block_channels
+        } else {
+            self.input.channels()
+        }
+    }
+
+    fn sample_rate(&self) -> SampleRate {
+        if self.block_pos < self.block.len() {
+            self.block_sample_rate
+        } else {
+            self.input.sample_rate()
+        }
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    fn try_seek(&mut self, position: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(position)?;
+        self.block.clear();
+        self.block_pos = 0;
+        Ok(())
+    }
+}
+
+pub fn dir() -> anyhow::Result<PathBuf> {
+    Ok(paths::app_data_dir()?.join("plugins"))
+}
+
+fn is_library(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| LIBRARY_EXTENSIONS.contains(&extension.to_lowercase().as_str()))
+}
@@ -0,0 +1,118 @@
+use std::{
+    io::{self, Read, Seek, SeekFrom},
+    sync::{Arc, Mutex},
+};
+
+use anyhow::bail;
+use tracing::debug;
+use ureq::BodyReader;
+
+use crate::remote::{self, header};
+
+pub struct StreamInfo {
+    pub name: Option<String>,
+}
+
+pub struct IcyStream {
+    body: BodyReader<'static>,
+    metadata_interval: Option<usize>,
+    until_metadata: usize,
+    title: Arc<Mutex<Option<String>>>,
+}
+
+pub fn probe(url: &str) -> anyhow::Result<Option<StreamInfo>> {
+    let response = remote::agent()
+        .get(url)
+        .header("Range", "bytes=0-0")
+        .call()?;
+    let is_stream = response.status() == 200
+        && (header(&response, "icy-metaint").is_some()
+            || header(&response, "icy-name").is_some()
+            || header(&response, "content-length").is_none());
+    Ok(is_stream.then(|| StreamInfo {
+        name: header(&response, "icy-name").map(str::to_string),
+    }))
+}
+
+impl IcyStream {
+    pub fn open(
+        url: &str,
+        title: Arc<Mutex<Option<String>>>,
+    ) -> anyhow::Result<(IcyStream, Option<&'static str>)> {
+        let response = remote::agent()
+            .get(url)
+            .header("Icy-MetaData", "1")
+            .call()?;
+        if response.status() != 200 {
+            bail!("{} returned HTTP {}", url, response.status());
+        }
+        let metadata_interval = header(&response, "icy-metaint")
+            .and_then(|interval| interval.trim().parse().ok())
+            .filter(|&interval| interval > 0);
+        let hint = header(&response, "content-type").and_then(|content_type| {
+            match content_type.split(';').next()?.trim() {
+                "audio/mpeg" | "audio/mp3" => Some("mp3"),
+                "audio/aac" | "audio/aacp" => Some("aac"),
+                "audio/ogg" | "application/ogg" => Some("ogg"),
+                "audio/flac" => Some("flac"),
+                "audio/wav" | "audio/x-wav" | "audio/wave" => Some("wav"),
+                _ => None,
+            }
+        });
+
+        Ok((
+            IcyStream {
+                body: response.into_body().into_reader(),
+                metadata_interval,
+                until_metadata: metadata_interval.unwrap_or_default(),
+                title,
+            },
+            hint,
+        ))
+    }
+
+    fn read_metadata(&mut self) -> io::Result<()> {
+        let mut len = [0];
+        self.body.read_exact(&mut len)?;
+        let mut metadata = vec![0; len[0] as usize * 16];
+        self.body.read_exact(&mut metadata)?;
+        if let Some(title) = parse_stream_title(&String::from_utf8_lossy(&metadata)) {
+            debug!("Stream title changed to {}", title);
+            *self.title.lock().unwrap_or_else(|e| e.into_inner()) =
+                Some(title).filter(|title| !title.is_empty());
+        }
+        Ok(())
+    }
+}
+
+impl Read for IcyStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let Some(interval) = self.metadata_interval else {
+            return self.body.read(buf);
+        };
+        if self.until_metadata == 0 {
+            self.read_metadata()?;
+            self.until_metadata = interval;
+        }
+        let len = buf.len().min(self.until_metadata);
+        let read = self.body.read(&mut buf[..len])?;
+        self.until_metadata -= read;
+        Ok(read)
+    }
+}
+
+impl Seek for IcyStream {
+    fn seek(&mut self, _: SeekFrom) -> io::Result<u64> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "radio streams cannot be seeked",
+        ))
+    }
+}
+
+fn parse_stream_title(metadata: &str) -> Option<String> {
+    let start = metadata.find("StreamTitle='")? + "StreamTitle='".len();
+    let rest = &metadata[start..];
+    let end = rest.find("';").or_else(|| rest.rfind('\''))?;
+    Some(rest[..end].trim().to_string())
+}
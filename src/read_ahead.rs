@@ -0,0 +1,235 @@
+use std::{
+    collections::VecDeque,
+    fs::File,
+    io::{self, Read, Seek, SeekFrom},
+    ops::Range,
+    path::Path,
+    sync::{Arc, Condvar, Mutex, MutexGuard},
+    thread,
+};
+
+use crate::mounts;
+
+const READ_AHEAD_LEN: u64 = 16 * 1024 * 1024;
+const KEEP_BEHIND_LEN: usize = 1024 * 1024;
+const NETWORK_READ_AHEAD_LEN: u64 = 128 * 1024 * 1024;
+const NETWORK_KEEP_BEHIND_LEN: usize = 32 * 1024 * 1024;
+const CHUNK_LEN: usize = 64 * 1024;
+
+pub struct ReadAhead {
+    shared: Arc<Shared>,
+    position: u64,
+}
+
+#[derive(Clone)]
+pub struct BufferStatus {
+    shared: Arc<Shared>,
+}
+
+struct Shared {
+    state: Mutex<State>,
+    changed: Condvar,
+    len: u64,
+    read_ahead_len: u64,
+    keep_behind_len: usize,
+}
+
+struct State {
+    start: u64,
+    buffer: VecDeque<u8>,
+    reader_position: u64,
+    restart_at: Option<u64>,
+    error: Option<io::Error>,
+    closed: bool,
+}
+
+impl ReadAhead {
+    pub fn open(path: &Path) -> io::Result<ReadAhead> {
+        let file = File::open(path)?;
+        let len = file.metadata()?.len();
+        if mounts::is_network(path) {
+            ReadAhead::with_window(file, len, NETWORK_READ_AHEAD_LEN, NETWORK_KEEP_BEHIND_LEN)
+        } else {
+            ReadAhead::new(file, len)
+        }
+    }
+
+    pub fn new(source: impl Read + Seek + Send + 'static, len: u64) -> io::Result<ReadAhead> {
+        ReadAhead::with_window(source, len, READ_AHEAD_LEN, KEEP_BEHIND_LEN)
+    }
+
+    fn with_window(
+        source: impl Read + Seek + Send + 'static,
+        len: u64,
+        read_ahead_len: u64,
+        keep_behind_len: usize,
+    ) -> io::Result<ReadAhead> {
+        let shared = Arc::new(Shared {
+            state: Mutex::new(State {
+                start: 0,
+                buffer: VecDeque::new(),
+                reader_position: 0,
+                restart_at: None,
+                error: None,
+                closed: false,
+            }),
+            changed: Condvar::new(),
+            len,
+            read_ahead_len,
+            keep_behind_len,
+        });
+
+        thread::Builder::new()
+            .name("read-ahead".to_string())
+            .spawn({
+                let shared = shared.clone();
+                move || fill(source, &shared)
+            })?;
+
+        Ok(ReadAhead {
+            shared,
+            position: 0,
+        })
+    }
+
+    pub fn byte_len(&self) -> u64 {
+        self.shared.len
+    }
+
+    pub fn status(&self) -> BufferStatus {
+        BufferStatus {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl BufferStatus {
+    pub fn buffered(&self) -> Range<f32> {
+        let state = self.shared.lock();
+        let len = self.shared.len.max(1) as f32;
+        let end = state.start + state.buffer.len() as u64;
+        state.start as f32 / len..end as f32 / len
+    }
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Read for ReadAhead {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() || self.position >= self.shared.len {
+            return Ok(0);
+        }
+
+        let mut state = self.shared.lock();
+        state.reader_position = self.position;
+        loop {
+            let end = state.start + state.buffer.len() as u64;
+            let is_ahead = self.position > end.saturating_add(self.shared.read_ahead_len);
+            if self.position < state.start || is_ahead {
+                if state.restart_at != Some(self.position) {
+                    state.restart_at = Some(self.position);
+                    self.shared.changed.notify_all();
+                }
+            } else if self.position < end {
+                let offset = (self.position - state.start) as usize;
+                let mut len = 0;
+                for (byte, buffered) in buf.iter_mut().zip(state.buffer.range(offset..)) {
+                    *byte = *buffered;
+                    len += 1;
+                }
+                self.position += len as u64;
+                state.reader_position = self.position;
+
+                let behind = (self.position - state.start) as usize;
+                let drop_len = behind.saturating_sub(self.shared.keep_behind_len);
+                state.buffer.drain(..drop_len);
+                state.start += drop_len as u64;
+                self.shared.changed.notify_all();
+                return Ok(len);
+            } else if let Some(error) = state.error.take() {
+                return Err(error);
+            }
+
+            state = self
+                .shared
+                .changed
+                .wait(state)
+                .unwrap_or_else(|e| e.into_inner());
+        }
+    }
+}
+
+impl Seek for ReadAhead {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let position = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => self.shared.len.checked_add_signed(offset),
+            SeekFrom::Current(offset) => self.position.checked_add_signed(offset),
+        };
+        let Some(position) = position else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "seek before the start of the file",
+            ));
+        };
+        self.position = position;
+        Ok(position)
+    }
+}
+
+impl Drop for ReadAhead {
+    fn drop(&mut self) {
+        self.shared.lock().closed = true;
+        self.shared.changed.notify_all();
+    }
+}
+
+fn fill(mut source: impl Read + Seek, shared: &Shared) {
+    let mut chunk = vec![0; CHUNK_LEN];
+    let mut state = shared.lock();
+    loop {
+        if state.closed {
+            return;
+        }
+        if let Some(offset) = state.restart_at.take() {
+            state.start = offset;
+            state.buffer.clear();
+            state.error = None;
+            if let Err(e) = source.seek(SeekFrom::Start(offset)) {
+                state.error = Some(e);
+                shared.changed.notify_all();
+            }
+        }
+
+        let end = state.start + state.buffer.len() as u64;
+        let is_full =
+            end >= shared.len || end.saturating_sub(state.reader_position) >= shared.read_ahead_len;
+        if is_full || state.error.is_some() {
+            state = shared
+                .changed
+                .wait(state)
+                .unwrap_or_else(|e| e.into_inner());
+            continue;
+        }
+
+        drop(state);
+        let result = source.read(&mut chunk);
+        state = shared.lock();
+        if state.restart_at.is_some() {
+            continue;
+        }
+        match result {
+            Ok(0) => {
+                state.error = Some(io::ErrorKind::UnexpectedEof.into());
+            }
+            Ok(len) => state.buffer.extend(&chunk[..len]),
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => state.error = Some(e),
+        }
+        shared.changed.notify_all();
+    }
+}
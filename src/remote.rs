@@ -0,0 +1,285 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, Read, Seek, SeekFrom, Write},
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+    time::Duration,
+};
+
+use anyhow::bail;
+use serde::{Deserialize, Serialize};
+use symphonia::core::io::MediaSource;
+use tracing::{debug, error};
+use ureq::{http::Response, Agent, Body, BodyReader};
+
+use crate::paths;
+
+const TIMEOUT: Duration = Duration::from_secs(15);
+
+pub fn is_url(path: &str) -> bool {
+    path.starts_with("http://") || path.starts_with("https://")
+}
+
+pub fn url(path: &Path) -> Option<&str> {
+    path.to_str().filter(|path| is_url(path))
+}
+
+pub fn agent() -> &'static Agent {
+    static AGENT: OnceLock<Agent> = OnceLock::new();
+    AGENT.get_or_init(|| {
+        Agent::config_builder()
+            .user_agent(concat!("kanta/", env!("CARGO_PKG_VERSION")))
+            .timeout_connect(Some(TIMEOUT))
+            .timeout_recv_response(Some(TIMEOUT))
+            .http_status_as_error(false)
+            .build()
+            .into()
+    })
+}
+
+pub fn header<'a>(response: &'a Response<Body>, name: &str) -> Option<&'a str> {
+    response
+        .headers()
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Validator {
+    len: u64,
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CacheIndex {
+    validator: Validator,
+    ranges: Vec<(u64, u64)>,
+}
+
+pub struct RemoteSource {
+    url: String,
+    len: u64,
+    validator: Validator,
+    position: u64,
+    cache: File,
+    cache_path: PathBuf,
+    cached: Vec<Range<u64>>,
+    stream: Option<(u64, BodyReader<'static>)>,
+}
+
+impl RemoteSource {
+    pub fn open(url: &str) -> anyhow::Result<RemoteSource> {
+        let response = agent().get(url).header("Range", "bytes=0-0").call()?;
+        let len = match response.status().as_u16() {
+            206 => header(&response, "content-range")
+                .and_then(|range| range.rsplit_once('/'))
+                .and_then(|(_, len)| len.trim().parse().ok()),
+            200 => bail!("{} does not support range requests", url),
+            status => bail!("{} returned HTTP {}", url, status),
+        };
+        let Some(len) = len else {
+            bail!("{} did not report its length", url);
+        };
+        let validator = Validator {
+            len,
+            etag: header(&response, "etag")
+                .filter(|etag| !etag.starts_with("W/"))
+                .map(str::to_string),
+            last_modified: header(&response, "last-modified").map(str::to_string),
+        };
+
+        let dir = paths::cache_dir()?.join("remote");
+        fs::create_dir_all(&dir)?;
+        let cache_path = dir.join(format!("{:016x}", fnv1a(url.as_bytes())));
+        let cache = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&cache_path)?;
+        let cached = match load_index(&cache_path) {
+            Some(index) if index.validator == validator => index
+                .ranges
+                .into_iter()
+                .map(|(start, end)| start..end)
+                .collect(),
+            Some(_) => {
+                debug!("{} changed since it was cached, discarding the cache", url);
+                cache.set_len(0)?;
+                vec![]
+            }
+            None => vec![],
+        };
+        cache.set_len(len)?;
+
+        Ok(RemoteSource {
+            url: url.to_string(),
+            len,
+            validator,
+            position: 0,
+            cache,
+            cache_path,
+            cached,
+            stream: None,
+        })
+    }
+
+    pub fn byte_len(&self) -> u64 {
+        self.len
+    }
+
+    fn cached_until(&self, position: u64) -> Option<u64> {
+        self.cached
+            .iter()
+            .find(|range| range.contains(&position))
+            .map(|range| range.end)
+    }
+
+    fn mark_cached(&mut self, range: Range<u64>) {
+        self.cached.push(range);
+        self.cached.sort_by_key(|range| range.start);
+        let mut merged: Vec<Range<u64>> = vec![];
+        for range in self.cached.drain(..) {
+            match merged.last_mut() {
+                Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+                _ => merged.push(range),
+            }
+        }
+        self.cached = merged;
+    }
+
+    fn stream_at(&mut self, position: u64) -> io::Result<&mut BodyReader<'static>> {
+        if self
+            .stream
+            .as_ref()
+            .is_none_or(|(offset, _)| *offset != position)
+        {
+            debug!("Requesting {} from byte {}", self.url, position);
+            let mut request = agent()
+                .get(&self.url)
+                .header("Range", format!("bytes={}-", position));
+            if let Some(validator) = self
+                .validator
+                .etag
+                .as_ref()
+                .or(self.validator.last_modified.as_ref())
+            {
+                request = request.header("If-Range", validator);
+            }
+            let response = request.call().map_err(io::Error::other)?;
+            if response.status() != 206 {
+                return Err(io::Error::other(format!(
+                    "{} returned HTTP {} for a range request",
+                    self.url,
+                    response.status()
+                )));
+            }
+            self.stream = Some((position, response.into_body().into_reader()));
+        }
+        Ok(&mut self.stream.as_mut().unwrap().1)
+    }
+}
+
+impl Read for RemoteSource {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() || self.position >= self.len {
+            return Ok(0);
+        }
+
+        if let Some(end) = self.cached_until(self.position) {
+            let len = buf.len().min((end - self.position) as usize);
+            self.cache.seek(SeekFrom::Start(self.position))?;
+            self.cache.read_exact(&mut buf[..len])?;
+            self.position += len as u64;
+            return Ok(len);
+        }
+
+        let next_cached = self
+            .cached
+            .iter()
+            .map(|range| range.start)
+            .filter(|&start| start > self.position)
+            .min()
+            .unwrap_or(self.len);
+        let len = buf.len().min((next_cached - self.position) as usize);
+        let position = self.position;
+        let read = self.stream_at(position)?.read(&mut buf[..len])?;
+        if read == 0 {
+            self.stream = None;
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+
+        self.cache.seek(SeekFrom::Start(position))?;
+        self.cache.write_all(&buf[..read])?;
+        self.mark_cached(position..position + read as u64);
+        self.position += read as u64;
+        if let Some((offset, _)) = &mut self.stream {
+            *offset = self.position;
+        }
+        Ok(read)
+    }
+}
+
+impl Seek for RemoteSource {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let position = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => self.len.checked_add_signed(offset),
+            SeekFrom::Current(offset) => self.position.checked_add_signed(offset),
+        };
+        let Some(position) = position else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "seek before the start of the stream",
+            ));
+        };
+        self.position = position;
+        Ok(position)
+    }
+}
+
+impl MediaSource for RemoteSource {
+    fn is_seekable(&self) -> bool {
+        true
+    }
+
+    fn byte_len(&self) -> Option<u64> {
+        Some(self.len)
+    }
+}
+
+impl Drop for RemoteSource {
+    fn drop(&mut self) {
+        let index = CacheIndex {
+            validator: self.validator.clone(),
+            ranges: self
+                .cached
+                .iter()
+                .map(|range| (range.start, range.end))
+                .collect(),
+        };
+        let result = serde_json::to_string(&index)
+            .map_err(anyhow::Error::from)
+            .and_then(|index| Ok(fs::write(self.cache_path.with_extension("ranges"), index)?));
+        if let Err(e) = result {
+            error!("Failed to save the cached ranges of {}: {}", self.url, e);
+        }
+    }
+}
+
+pub fn cached_len(cache_path: &Path) -> Option<u64> {
+    load_index(cache_path).map(|index| index.ranges.iter().map(|(start, end)| end - start).sum())
+}
+
+fn load_index(cache_path: &Path) -> Option<CacheIndex> {
+    let index = fs::read_to_string(cache_path.with_extension("ranges")).ok()?;
+    serde_json::from_str(&index).ok()
+}
+
+pub fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
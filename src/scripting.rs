@@ -0,0 +1,309 @@
+use std::{
+    cell::{Cell, RefCell},
+    fmt, fs, mem,
+    path::PathBuf,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+use anyhow::anyhow;
+use mlua::{Function, HookTriggers, Lua, MultiValue, Table, Value, Variadic};
+use tracing::{info, warn};
+
+use crate::{hooks::Payload, paths, player::ControlRequest};
+
+const MAX_OUTPUT_LINES: usize = 200;
+const HANDLERS_KEY: &str = "kanta_handlers";
+const DEADLINE: Duration = Duration::from_millis(250);
+const HOOK_INSTRUCTIONS: u32 = 10_000;
+
+#[derive(Default)]
+struct Shared {
+    payload: Option<Payload>,
+    requests: Vec<ControlRequest>,
+    output: Vec<String>,
+}
+
+impl Shared {
+    fn print(&mut self, line: String) {
+        self.output.push(line);
+        let excess = self.output.len().saturating_sub(MAX_OUTPUT_LINES);
+        self.output.drain(..excess);
+    }
+}
+
+pub struct Scripting {
+    lua: Lua,
+    shared: Rc<RefCell<Shared>>,
+    deadline: Rc<Cell<Option<Instant>>>,
+}
+
+impl fmt::Debug for Scripting {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Scripting({} output lines)",
+            self.shared.borrow().output.len()
+        )
+    }
+}
+
+impl Scripting {
+    pub fn new() -> anyhow::Result<Scripting> {
+        let lua = Lua::new();
+        let shared = Rc::new(RefCell::new(Shared::default()));
+        register(&lua, &shared).map_err(|e| anyhow!("{}", e))?;
+        let deadline = Rc::new(Cell::new(None::<Instant>));
+        let hook_deadline = deadline.clone();
+        lua.set_hook(
+            HookTriggers::new().every_nth_instruction(HOOK_INSTRUCTIONS),
+            move |_, _| match hook_deadline.get() {
+                Some(deadline) if Instant::now() > deadline => Err(mlua::Error::RuntimeError(
+                    format!("script ran for longer than {:?}", DEADLINE),
+                )),
+                _ => Ok(()),
+            },
+        );
+        let scripting = Scripting {
+            lua,
+            shared,
+            deadline,
+        };
+
+        let path = init_script_path()?;
+        if path.exists() {
+            let source = fs::read_to_string(&path)?;
+            let result = scripting.with_deadline(|| {
+                scripting
+                    .lua
+                    .load(source)
+                    .set_name(path.to_string_lossy())
+                    .exec()
+            });
+            match result {
+                Ok(()) => info!("Ran {}", path.display()),
+                Err(e) => {
+                    warn!("Failed to run {}: {}", path.display(), e);
+                    scripting.shared.borrow_mut().print(e.to_string());
+                }
+            }
+        }
+        Ok(scripting)
+    }
+
+    pub fn output(&self) -> Vec<String> {
+        self.shared.borrow().output.clone()
+    }
+
+    pub fn clear_output(&self) {
+        self.shared.borrow_mut().output.clear();
+    }
+
+    pub fn run(&self, source: &str, payload: Payload) -> Vec<ControlRequest> {
+        self.set_state(payload);
+        self.shared.borrow_mut().print(format!("> {}", source));
+
+        let chunk = self
+            .lua
+            .load(format!("return {}", source))
+            .set_name("console")
+            .into_function()
+            .or_else(|_| self.lua.load(source).set_name("console").into_function());
+        match chunk.and_then(|chunk| self.with_deadline(|| chunk.call::<_, MultiValue>(()))) {
+            Ok(values) if !values.is_empty() => {
+                let line = values.iter().map(display).collect::<Vec<_>>().join("\t");
+                self.shared.borrow_mut().print(line);
+            }
+            Ok(_) => {}
+            Err(e) => self.shared.borrow_mut().print(e.to_string()),
+        }
+        self.take_requests()
+    }
+
+    pub fn dispatch(&self, payload: Payload) -> Vec<ControlRequest> {
+        let event = payload.event.clone();
+        self.set_state(payload);
+
+        let handlers = self
+            .lua
+            .named_registry_value::<Table>(HANDLERS_KEY)
+            .and_then(|handlers| handlers.get::<_, Option<Table>>(event.as_str()));
+        match handlers {
+            Ok(Some(handlers)) => {
+                for handler in handlers.sequence_values::<Function>().flatten() {
+                    if let Err(e) = self.with_deadline(|| handler.call::<_, ()>(event.as_str())) {
+                        warn!("Script handler for \"{}\" failed: {}", event, e);
+                        self.shared.borrow_mut().print(e.to_string());
+                    }
+                }
+            }
+            Ok(None) => {}
+            Err(e) => warn!("Failed to look up script handlers: {}", e),
+        }
+        self.take_requests()
+    }
+
+    fn with_deadline<T>(&self, run: impl FnOnce() -> T) -> T {
+        self.deadline.set(Some(Instant::now() + DEADLINE));
+        let result = run();
+        self.deadline.set(None);
+        result
+    }
+
+    fn set_state(&self, payload: Payload) {
+        self.shared.borrow_mut().payload = Some(payload);
+    }
+
+    fn take_requests(&self) -> Vec<ControlRequest> {
+        mem::take(&mut self.shared.borrow_mut().requests)
+    }
+}
+
+pub fn init_script_path() -> anyhow::Result<PathBuf> {
+    Ok(paths::config_dir()?.join("init.lua"))
+}
+
+fn register(lua: &Lua, shared: &Rc<RefCell<Shared>>) -> mlua::Result<()> {
+    lua.set_named_registry_value(HANDLERS_KEY, lua.create_table()?)?;
+
+    let kanta = lua.create_table()?;
+    for (name, request) in [
+        ("play", ControlRequest::Play),
+        ("pause", ControlRequest::Pause),
+        ("next", ControlRequest::Next),
+        ("previous", ControlRequest::Previous),
+    ] {
+        let shared = shared.clone();
+        kanta.set(
+            name,
+            lua.create_function(move |_, ()| {
+                shared.borrow_mut().requests.push(request);
+                Ok(())
+            })?,
+        )?;
+    }
+
+    let requests = shared.clone();
+    kanta.set(
+        "set_volume",
+        lua.create_function(move |_, percent: f32| {
+            let percent = finite(percent, "set_volume")?;
+            let volume = (percent / 100.0).clamp(0.0, 1.0);
+            requests
+                .borrow_mut()
+                .requests
+                .push(ControlRequest::SetVolume(volume));
+            Ok(())
+        })?,
+    )?;
+    let requests = shared.clone();
+    kanta.set(
+        "seek",
+        lua.create_function(move |_, seconds: f32| {
+            let seconds = finite(seconds, "seek")?;
+            requests
+                .borrow_mut()
+                .requests
+                .push(ControlRequest::Seek(seconds.max(0.0)));
+            Ok(())
+        })?,
+    )?;
+
+    let state = shared.clone();
+    kanta.set(
+        "volume",
+        lua.create_function(move |_, ()| {
+            Ok(state
+                .borrow()
+                .payload
+                .as_ref()
+                .map_or(100.0, |payload| payload.volume * 100.0))
+        })?,
+    )?;
+    let state = shared.clone();
+    kanta.set(
+        "state",
+        lua.create_function(move |_, ()| {
+            Ok(state
+                .borrow()
+                .payload
+                .as_ref()
+                .map_or_else(|| "stopped".to_string(), |payload| payload.state.clone()))
+        })?,
+    )?;
+    let state = shared.clone();
+    kanta.set(
+        "position",
+        lua.create_function(move |_, ()| {
+            Ok(state
+                .borrow()
+                .payload
+                .as_ref()
+                .map_or(0.0, |payload| payload.position_secs))
+        })?,
+    )?;
+    let state = shared.clone();
+    kanta.set(
+        "track",
+        lua.create_function(move |lua, ()| {
+            let state = state.borrow();
+            let Some(payload) = state
+                .payload
+                .as_ref()
+                .filter(|payload| payload.path.is_some())
+            else {
+                return Ok(Value::Nil);
+            };
+            let track = lua.create_table()?;
+            track.set("path", payload.path.clone())?;
+            track.set("title", payload.title.clone())?;
+            track.set("artist", payload.artist.clone())?;
+            track.set("album", payload.album.clone())?;
+            track.set("duration", payload.duration_secs)?;
+            Ok(Value::Table(track))
+        })?,
+    )?;
+    kanta.set(
+        "on",
+        lua.create_function(|lua, (event, handler): (String, Function)| {
+            let handlers = lua.named_registry_value::<Table>(HANDLERS_KEY)?;
+            let list = match handlers.get::<_, Option<Table>>(event.as_str())? {
+                Some(list) => list,
+                None => {
+                    let list = lua.create_table()?;
+                    handlers.set(event, list.clone())?;
+                    list
+                }
+            };
+            list.push(handler)
+        })?,
+    )?;
+    lua.globals().set("kanta", kanta)?;
+
+    let output = shared.clone();
+    lua.globals().set(
+        "print",
+        lua.create_function(move |_, values: Variadic<Value>| {
+            let line = values.iter().map(display).collect::<Vec<_>>().join("\t");
+            info!("Script: {}", line);
+            output.borrow_mut().print(line);
+            Ok(())
+        })?,
+    )
+}
+
+fn display(value: &Value) -> String {
+    value
+        .to_string()
+        .unwrap_or_else(|_| value.type_name().to_string())
+}
+
+fn finite(value: f32, function: &str) -> mlua::Result<f32> {
+    if value.is_finite() {
+        Ok(value)
+    } else {
+        Err(mlua::Error::RuntimeError(format!(
+            "kanta.{function} expects a finite number"
+        )))
+    }
+}
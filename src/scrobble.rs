@@ -0,0 +1,230 @@
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::bail;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use crate::{
+    config::ScrobbleSettings,
+    paths, remote,
+    state::{self, Schema},
+    track::Track,
+};
+
+const MAX_TICK: Duration = Duration::from_secs(1);
+const MAX_BATCH_LEN: usize = 100;
+
+const LOVED_SCHEMA: Schema = Schema {
+    migrations: &[state::unversioned],
+};
+const QUEUE_SCHEMA: Schema = Schema {
+    migrations: &[state::unversioned],
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Listen {
+    pub artist: String,
+    pub title: String,
+    pub album: Option<String>,
+    pub duration: Duration,
+    pub recording_mbid: Option<String>,
+    pub listened_at: u64,
+}
+
+#[derive(Default)]
+pub struct Scrobbler {
+    playing: Option<Playing>,
+}
+
+struct Playing {
+    path: PathBuf,
+    index: Option<usize>,
+    started_at: SystemTime,
+    played: Duration,
+    last_tick: Instant,
+    counted: bool,
+}
+
+pub struct ScrobbleQueue {
+    path: PathBuf,
+    listens: Vec<Listen>,
+}
+
+pub struct LovedTracks {
+    path: PathBuf,
+    tracks: BTreeSet<PathBuf>,
+}
+
+impl Listen {
+    pub fn new(track: &Track, started_at: SystemTime) -> Option<Listen> {
+        Some(Listen {
+            artist: track.artist()?.to_string(),
+            title: track.title()?.to_string(),
+            album: track.album().map(str::to_string),
+            duration: track.duration(),
+            recording_mbid: track.recording_mbid().map(str::to_string),
+            listened_at: started_at.duration_since(UNIX_EPOCH).ok()?.as_secs(),
+        })
+    }
+}
+
+impl Scrobbler {
+    pub fn tick(
+        &mut self,
+        track: Option<&Track>,
+        index: Option<usize>,
+        is_playing: bool,
+        settings: &ScrobbleSettings,
+    ) -> Option<SystemTime> {
+        let now = Instant::now();
+        let Some(track) = track.filter(|track| !track.is_stream()) else {
+            self.playing = None;
+            return None;
+        };
+
+        let playing = match &mut self.playing {
+            Some(playing) if playing.index == index && playing.path == track.path() => playing,
+            _ => {
+                self.playing = Some(Playing {
+                    path: track.path().to_path_buf(),
+                    index,
+                    started_at: SystemTime::now(),
+                    played: Duration::ZERO,
+                    last_tick: now,
+                    counted: false,
+                });
+                return None;
+            }
+        };
+
+        let elapsed = now.duration_since(playing.last_tick).min(MAX_TICK);
+        playing.last_tick = now;
+        if is_playing {
+            playing.played += elapsed;
+        }
+
+        if playing.counted || playing.played < settings.threshold(track.duration()) {
+            return None;
+        }
+        playing.counted = true;
+        Some(playing.started_at)
+    }
+}
+
+impl ScrobbleQueue {
+    pub fn open() -> anyhow::Result<ScrobbleQueue> {
+        let path = paths::data_dir()?.join("scrobbles.json");
+        let listens = QUEUE_SCHEMA.load(&path)?.unwrap_or_default();
+        Ok(ScrobbleQueue { path, listens })
+    }
+
+    pub fn len(&self) -> usize {
+        self.listens.len()
+    }
+
+    pub fn next_batch(&self) -> &[Listen] {
+        &self.listens[..self.listens.len().min(MAX_BATCH_LEN)]
+    }
+
+    pub fn push(&mut self, listen: Listen) -> anyhow::Result<()> {
+        self.listens.push(listen);
+        self.save()
+    }
+
+    pub fn remove(&mut self, submitted: &[Listen]) -> anyhow::Result<()> {
+        self.listens.retain(|listen| !submitted.contains(listen));
+        self.save()
+    }
+
+    fn save(&self) -> anyhow::Result<()> {
+        QUEUE_SCHEMA.save(&self.path, &self.listens)
+    }
+}
+
+impl LovedTracks {
+    pub fn open() -> anyhow::Result<LovedTracks> {
+        let path = paths::data_dir()?.join("loved.json");
+        let tracks = LOVED_SCHEMA.load(&path)?.unwrap_or_default();
+        Ok(LovedTracks { path, tracks })
+    }
+
+    pub fn is_loved(&self, track: &Path) -> bool {
+        self.tracks.contains(track)
+    }
+
+    pub fn toggle(&mut self, track: &Path) -> anyhow::Result<bool> {
+        let loved = !self.tracks.remove(track);
+        if loved {
+            self.tracks.insert(track.to_path_buf());
+        }
+        LOVED_SCHEMA.save(&self.path, &self.tracks)?;
+        Ok(loved)
+    }
+}
+
+pub fn submit(settings: &ScrobbleSettings, listens: &[Listen]) -> anyhow::Result<()> {
+    let payload = listens
+        .iter()
+        .map(|listen| {
+            let mut additional_info = Map::new();
+            additional_info.insert("submission_client".to_string(), json!("Kanta"));
+            additional_info.insert(
+                "duration_ms".to_string(),
+                json!(listen.duration.as_millis() as u64),
+            );
+            if let Some(mbid) = &listen.recording_mbid {
+                additional_info.insert("recording_mbid".to_string(), json!(mbid));
+            }
+
+            let mut metadata = Map::new();
+            metadata.insert("artist_name".to_string(), json!(listen.artist));
+            metadata.insert("track_name".to_string(), json!(listen.title));
+            if let Some(album) = &listen.album {
+                metadata.insert("release_name".to_string(), json!(album));
+            }
+            metadata.insert(
+                "additional_info".to_string(),
+                Value::Object(additional_info),
+            );
+
+            json!({
+                "listened_at": listen.listened_at,
+                "track_metadata": metadata,
+            })
+        })
+        .collect::<Vec<_>>();
+    let listen_type = if listens.len() == 1 {
+        "single"
+    } else {
+        "import"
+    };
+    let body = json!({
+        "listen_type": listen_type,
+        "payload": payload,
+    });
+    post(settings, "1/submit-listens", &body)
+}
+
+pub fn love(settings: &ScrobbleSettings, recording_mbid: &str, loved: bool) -> anyhow::Result<()> {
+    let body = json!({
+        "recording_mbid": recording_mbid,
+        "score": if loved { 1 } else { 0 },
+    });
+    post(settings, "1/feedback/recording-feedback", &body)
+}
+
+fn post(settings: &ScrobbleSettings, endpoint: &str, body: &Value) -> anyhow::Result<()> {
+    let url = format!("{}/{}", settings.server_url(), endpoint);
+    let response = remote::agent()
+        .post(&url)
+        .header("Authorization", format!("Token {}", settings.token.trim()))
+        .send_json(body)?;
+    if !response.status().is_success() {
+        bail!("{} returned HTTP {}", url, response.status());
+    }
+    Ok(())
+}
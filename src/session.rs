@@ -0,0 +1,55 @@
+use std::{fs, path::PathBuf, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    paths,
+    state::{self, Schema},
+    track::{Segment, Track},
+};
+
+const SCHEMA: Schema = Schema {
+    migrations: &[state::unversioned],
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedQueue {
+    pub tracks: Vec<PathBuf>,
+    pub index: Option<usize>,
+    #[serde(default)]
+    pub position: Duration,
+    #[serde(default)]
+    pub segments: Vec<Option<Segment>>,
+}
+
+impl SavedQueue {
+    pub fn new(tracks: &[Track], index: Option<usize>, position: Duration) -> SavedQueue {
+        SavedQueue {
+            tracks: tracks.iter().map(|t| t.path().to_path_buf()).collect(),
+            index,
+            position,
+            segments: tracks.iter().map(Track::segment).collect(),
+        }
+    }
+
+    pub fn load() -> anyhow::Result<Option<SavedQueue>> {
+        let queue: Option<SavedQueue> = SCHEMA.load(&Self::path()?)?;
+        Ok(queue.filter(|q| !q.tracks.is_empty()))
+    }
+
+    pub fn save(&self) -> anyhow::Result<()> {
+        SCHEMA.save(&Self::path()?, self)
+    }
+
+    pub fn discard() -> anyhow::Result<()> {
+        let path = Self::path()?;
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+
+    fn path() -> anyhow::Result<PathBuf> {
+        Ok(paths::data_dir()?.join("queue.json"))
+    }
+}
@@ -0,0 +1,96 @@
+use std::{
+    io::Write,
+    net::{TcpStream, ToSocketAddrs},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use anyhow::anyhow;
+use rodio::mixer::{self, Mixer, MixerSource};
+use tracing::{info, warn};
+
+pub const SAMPLE_RATE: u32 = 48000;
+pub const CHANNELS: u16 = 2;
+
+const CHUNK_FRAMES: usize = 960;
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+const RECONNECT_INTERVAL: Duration = Duration::from_secs(5);
+
+pub struct SnapcastOutput {
+    mixer: Mixer,
+    stopped: Arc<AtomicBool>,
+}
+
+impl SnapcastOutput {
+    pub fn connect(address: &str) -> anyhow::Result<SnapcastOutput> {
+        let address = address.trim().to_string();
+        let stream = connect(&address)?;
+        info!("Streaming to Snapcast at {}", address);
+
+        let (mixer, source) = mixer::mixer(CHANNELS, SAMPLE_RATE);
+        let stopped = Arc::new(AtomicBool::new(false));
+        let stream_stopped = stopped.clone();
+        thread::spawn(move || stream_pcm(source, stream, &address, &stream_stopped));
+        Ok(SnapcastOutput { mixer, stopped })
+    }
+
+    pub fn mixer(&self) -> &Mixer {
+        &self.mixer
+    }
+}
+
+impl Drop for SnapcastOutput {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::Relaxed);
+    }
+}
+
+fn connect(address: &str) -> anyhow::Result<TcpStream> {
+    let socket_address = address
+        .to_socket_addrs()?
+        .next()
+        .ok_or_else(|| anyhow!("{} did not resolve to an address", address))?;
+    let stream = TcpStream::connect_timeout(&socket_address, CONNECT_TIMEOUT)?;
+    stream.set_nodelay(true)?;
+    stream.set_write_timeout(Some(CONNECT_TIMEOUT))?;
+    Ok(stream)
+}
+
+fn stream_pcm(mut source: MixerSource, stream: TcpStream, address: &str, stopped: &AtomicBool) {
+    let mut stream = Some(stream);
+    let mut reconnect_at = Instant::now();
+    let started_at = Instant::now();
+    let mut frames = 0u64;
+    let mut chunk = Vec::with_capacity(CHUNK_FRAMES * CHANNELS as usize * 2);
+    while !stopped.load(Ordering::Relaxed) {
+        chunk.clear();
+        for sample in source.by_ref().take(CHUNK_FRAMES * CHANNELS as usize) {
+            let sample = (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16;
+            chunk.extend(sample.to_le_bytes());
+        }
+
+        if stream.is_none() && Instant::now() >= reconnect_at {
+            reconnect_at = Instant::now() + RECONNECT_INTERVAL;
+            stream = connect(address)
+                .inspect(|_| info!("Reconnected to Snapcast at {}", address))
+                .ok();
+        }
+        if let Some(connection) = &mut stream
+            && let Err(e) = connection.write_all(&chunk)
+        {
+            warn!("Lost the connection to Snapcast at {}: {}", address, e);
+            stream = None;
+            reconnect_at = Instant::now() + RECONNECT_INTERVAL;
+        }
+
+        frames += CHUNK_FRAMES as u64;
+        let due = started_at + Duration::from_secs_f64(frames as f64 / SAMPLE_RATE as f64);
+        if let Some(wait) = due.checked_duration_since(Instant::now()) {
+            thread::sleep(wait);
+        }
+    }
+}
@@ -0,0 +1,111 @@
+use std::{collections::VecDeque, f32::consts::PI};
+
+use iced::widget::image;
+use symphonia::core::dsp::{complex::Complex, fft::Fft};
+
+const FFT_LEN: usize = 2048;
+const HOP_LEN: usize = 1024;
+const WIDTH: usize = 480;
+const HEIGHT: usize = 256;
+const FLOOR_DB: f32 = -120.0;
+
+pub struct Spectrogram {
+    fft: Fft,
+    window: Vec<f32>,
+    pending: Vec<f32>,
+    sample_rate: u32,
+    columns: VecDeque<[u8; HEIGHT]>,
+    handle: image::Handle,
+}
+
+impl Spectrogram {
+    pub fn new() -> Spectrogram {
+        let window = (0..FFT_LEN)
+            .map(|i| 0.5 - 0.5 * (2.0 * PI * i as f32 / FFT_LEN as f32).cos())
+            .collect();
+        let columns = VecDeque::from(vec![[0; HEIGHT]; WIDTH]);
+        let handle = render(&columns);
+        Spectrogram {
+            fft: Fft::new(FFT_LEN),
+            window,
+            pending: vec![],
+            sample_rate: 0,
+            columns,
+            handle,
+        }
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    pub fn image(&self) -> &image::Handle {
+        &self.handle
+    }
+
+    pub fn push(&mut self, samples: &[f32], sample_rate: u32) {
+        if samples.is_empty() {
+            return;
+        }
+        if sample_rate != self.sample_rate {
+            self.sample_rate = sample_rate;
+            self.pending.clear();
+        }
+        self.pending.extend_from_slice(samples);
+
+        let mut consumed = 0;
+        let mut buffer = vec![Complex::default(); FFT_LEN];
+        while self.pending.len() - consumed >= FFT_LEN {
+            let frame = &self.pending[consumed..consumed + FFT_LEN];
+            for ((value, sample), weight) in buffer.iter_mut().zip(frame).zip(&self.window) {
+                *value = Complex::new(sample * weight, 0.0);
+            }
+            self.fft.fft_inplace(&mut buffer);
+            self.columns.pop_front();
+            self.columns.push_back(column(&buffer));
+            consumed += HOP_LEN;
+        }
+        if consumed > 0 {
+            self.pending.drain(..consumed);
+            self.handle = render(&self.columns);
+        }
+    }
+}
+
+fn column(spectrum: &[Complex]) -> [u8; HEIGHT] {
+    let bins_per_row = FFT_LEN / 2 / HEIGHT;
+    let reference = FFT_LEN as f32 / 4.0;
+    let mut column = [0; HEIGHT];
+    for (row, value) in column.iter_mut().enumerate() {
+        let start = (HEIGHT - 1 - row) * bins_per_row;
+        let magnitude = spectrum[start..start + bins_per_row]
+            .iter()
+            .map(|bin| (bin.re * bin.re + bin.im * bin.im).sqrt())
+            .fold(0.0, f32::max);
+        let db = 20.0 * (magnitude / reference).max(f32::MIN_POSITIVE).log10();
+        *value = ((1.0 - db / FLOOR_DB).clamp(0.0, 1.0) * 255.0) as u8;
+    }
+    column
+}
+
+fn render(columns: &VecDeque<[u8; HEIGHT]>) -> image::Handle {
+    let mut pixels = vec![0; WIDTH * HEIGHT * 4];
+    for (x, column) in columns.iter().enumerate() {
+        for (y, intensity) in column.iter().enumerate() {
+            let offset = (y * WIDTH + x) * 4;
+            pixels[offset..offset + 4].copy_from_slice(&color(*intensity));
+        }
+    }
+    image::Handle::from_rgba(WIDTH as u32, HEIGHT as u32, pixels)
+}
+
+fn color(intensity: u8) -> [u8; 4] {
+    let t = intensity as f32 / 255.0;
+    let channel = |value: f32| (value.clamp(0.0, 1.0) * 255.0) as u8;
+    [
+        channel(t * 2.0 - 0.3),
+        channel(t * 3.0 - 2.0),
+        channel(if t < 0.5 { t * 2.0 } else { 2.0 - t * 2.0 }),
+        255,
+    ]
+}
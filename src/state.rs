@@ -0,0 +1,93 @@
+use std::{fs, path::Path};
+
+use anyhow::{anyhow, bail};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use tracing::{info, warn};
+
+pub type Migration = fn(Value) -> anyhow::Result<Value>;
+
+#[derive(Serialize)]
+struct Versioned<'a, T> {
+    version: u32,
+    data: &'a T,
+}
+
+pub struct Schema {
+    pub migrations: &'static [Migration],
+}
+
+impl Schema {
+    pub fn version(&self) -> u32 {
+        self.migrations.len() as u32
+    }
+
+    pub fn load<T: DeserializeOwned>(&self, path: &Path) -> anyhow::Result<Option<T>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let (version, mut data) = match serde_json::from_str(&fs::read_to_string(path)?)? {
+            Value::Object(mut object)
+                if object.len() == 2
+                    && object.get("version").is_some_and(Value::is_u64)
+                    && object.contains_key("data") =>
+            {
+                let version = object["version"]
+                    .as_u64()
+                    .and_then(|version| u32::try_from(version).ok())
+                    .ok_or_else(|| anyhow!("invalid version in {}", path.display()))?;
+                (version, object.remove("data").unwrap_or_default())
+            }
+            data => (0, data),
+        };
+
+        if version > self.version() {
+            let backup_path = path.with_extension(format!("v{}.json", version));
+            if !backup_path.exists() {
+                fs::copy(path, &backup_path)?;
+            }
+            warn!(
+                "{} was saved by a newer version of Kanta (format {}, expected {}), kept a copy at {}",
+                path.display(),
+                version,
+                self.version(),
+                backup_path.display()
+            );
+            return match serde_json::from_value(data) {
+                Ok(value) => Ok(Some(value)),
+                Err(e) => bail!(
+                    "{} was saved by a newer version of Kanta and cannot be read: {}",
+                    path.display(),
+                    e
+                ),
+            };
+        }
+
+        for (from, migrate) in self.migrations.iter().enumerate().skip(version as usize) {
+            data = migrate(data)?;
+            info!(
+                "Migrated {} from format {} to {}",
+                path.display(),
+                from,
+                from + 1
+            );
+        }
+        Ok(Some(serde_json::from_value(data)?))
+    }
+
+    pub fn save<T: Serialize>(&self, path: &Path, value: &T) -> anyhow::Result<()> {
+        let contents = Versioned {
+            version: self.version(),
+            data: value,
+        };
+        let temp_path = path.with_extension("tmp");
+        fs::write(&temp_path, serde_json::to_string(&contents)?)?;
+        fs::rename(temp_path, path)?;
+        Ok(())
+    }
+}
+
+pub fn unversioned(data: Value) -> anyhow::Result<Value> {
+    Ok(data)
+}
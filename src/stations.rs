@@ -0,0 +1,217 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::bail;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    browser, paths, remote,
+    state::{self, Schema},
+};
+
+const RADIO_BROWSER_URL: &str = "https://all.api.radio-browser.info/json/stations/search";
+const SEARCH_LIMIT: usize = 25;
+
+const SCHEMA: Schema = Schema {
+    migrations: &[state::unversioned],
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Station {
+    pub name: String,
+    pub url: String,
+    #[serde(default)]
+    pub favorite: bool,
+}
+
+pub struct StationLibrary {
+    path: PathBuf,
+    stations: Vec<Station>,
+}
+
+impl StationLibrary {
+    pub fn open() -> anyhow::Result<StationLibrary> {
+        let path = paths::data_dir()?.join("stations.json");
+        let stations = SCHEMA.load(&path)?.unwrap_or_default();
+        Ok(StationLibrary { path, stations })
+    }
+
+    pub fn stations(&self) -> &[Station] {
+        &self.stations
+    }
+
+    pub fn add(&mut self, name: &str, url: &str) -> anyhow::Result<()> {
+        let url = url.trim();
+        if !remote::is_url(url) {
+            bail!("\"{}\" is not a station URL", url);
+        }
+        let name = match name.trim() {
+            "" => url,
+            name => name,
+        };
+        if self.stations.iter().any(|station| station.url == url) {
+            bail!("a station with the URL {} already exists", url);
+        }
+        self.stations.push(Station {
+            name: name.to_string(),
+            url: url.to_string(),
+            favorite: false,
+        });
+        self.sort();
+        self.save()
+    }
+
+    pub fn import(&mut self, path: &Path) -> anyhow::Result<usize> {
+        let contents = fs::read_to_string(path)?;
+        let is_pls = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("pls"));
+        let imported = if is_pls {
+            parse_pls(&contents)
+        } else {
+            parse_m3u(&contents)
+        };
+
+        let mut count = 0;
+        for station in imported {
+            if !self.stations.iter().any(|s| s.url == station.url) {
+                self.stations.push(station);
+                count += 1;
+            }
+        }
+        self.sort();
+        self.save()?;
+        Ok(count)
+    }
+
+    pub fn remove(&mut self, url: &str) -> anyhow::Result<()> {
+        self.stations.retain(|station| station.url != url);
+        self.save()
+    }
+
+    pub fn toggle_favorite(&mut self, url: &str) -> anyhow::Result<()> {
+        if let Some(station) = self.stations.iter_mut().find(|s| s.url == url) {
+            station.favorite = !station.favorite;
+        }
+        self.sort();
+        self.save()
+    }
+
+    fn sort(&mut self) {
+        self.stations
+            .sort_by_cached_key(|station| (!station.favorite, browser::natural_key(&station.name)));
+    }
+
+    fn save(&self) -> anyhow::Result<()> {
+        SCHEMA.save(&self.path, &self.stations)
+    }
+}
+
+pub fn search(query: &str) -> anyhow::Result<Vec<Station>> {
+    #[derive(Deserialize)]
+    struct SearchResult {
+        name: String,
+        url_resolved: String,
+    }
+
+    let url = format!(
+        "{}?name={}&limit={}&hidebroken=true&order=clickcount&reverse=true",
+        RADIO_BROWSER_URL,
+        encode_query(query.trim()),
+        SEARCH_LIMIT
+    );
+    let mut response = remote::agent().get(&url).call()?;
+    if response.status() != 200 {
+        bail!("{} returned HTTP {}", RADIO_BROWSER_URL, response.status());
+    }
+    let results: Vec<SearchResult> = response.body_mut().read_json()?;
+    Ok(results
+        .into_iter()
+        .filter(|result| remote::is_url(&result.url_resolved))
+        .map(|result| Station {
+            name: result.name.trim().to_string(),
+            url: result.url_resolved,
+            favorite: false,
+        })
+        .collect())
+}
+
+fn parse_pls(contents: &str) -> Vec<Station> {
+    let mut stations: Vec<(u32, Option<String>, Option<String>)> = vec![];
+    for line in contents.lines() {
+        let Some((key, value)) = line.trim().split_once('=') else {
+            continue;
+        };
+        let key = key.to_ascii_lowercase();
+        let (field, number) = if let Some(number) = key.strip_prefix("file") {
+            (0, number)
+        } else if let Some(number) = key.strip_prefix("title") {
+            (1, number)
+        } else {
+            continue;
+        };
+        let Ok(number) = number.parse() else {
+            continue;
+        };
+        let index = match stations.iter().position(|(n, ..)| *n == number) {
+            Some(index) => index,
+            None => {
+                stations.push((number, None, None));
+                stations.len() - 1
+            }
+        };
+        let value = Some(value.trim().to_string());
+        if field == 0 {
+            stations[index].1 = value;
+        } else {
+            stations[index].2 = value;
+        }
+    }
+
+    stations.sort_by_key(|(number, ..)| *number);
+    stations
+        .into_iter()
+        .filter_map(|(_, url, name)| {
+            let url = url.filter(|url| remote::is_url(url))?;
+            Some(Station {
+                name: name.filter(|name| !name.is_empty()).unwrap_or(url.clone()),
+                url,
+                favorite: false,
+            })
+        })
+        .collect()
+}
+
+fn parse_m3u(contents: &str) -> Vec<Station> {
+    let mut stations = vec![];
+    let mut name = None;
+    for line in contents.lines().map(str::trim) {
+        if let Some(info) = line.strip_prefix("#EXTINF:") {
+            name = info
+                .split_once(',')
+                .map(|(_, name)| name.trim().to_string())
+                .filter(|name| !name.is_empty());
+        } else if remote::is_url(line) {
+            stations.push(Station {
+                name: name.take().unwrap_or_else(|| line.to_string()),
+                url: line.to_string(),
+                favorite: false,
+            });
+        }
+    }
+    stations
+}
+
+fn encode_query(query: &str) -> String {
+    query
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
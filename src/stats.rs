@@ -0,0 +1,69 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    time::Duration,
+};
+
+use crate::track::Track;
+
+const TOP_ARTISTS: usize = 5;
+
+pub struct QueueStats {
+    pub tracks: usize,
+    pub albums: usize,
+    pub artists: usize,
+    pub duration: Duration,
+    pub size_on_disk: u64,
+    pub top_artists: Vec<(String, usize)>,
+}
+
+impl QueueStats {
+    pub fn compute(tracks: &[Track]) -> QueueStats {
+        let mut albums = HashSet::new();
+        let mut artists = HashMap::new();
+        for track in tracks {
+            if let Some(album) = track.album() {
+                albums.insert((album, track.artist()));
+            }
+            for artist in track.artists() {
+                *artists.entry(artist.as_str()).or_insert(0) += 1;
+            }
+        }
+
+        let mut top_artists = artists
+            .iter()
+            .map(|(artist, count)| (artist.to_string(), *count))
+            .collect::<Vec<_>>();
+        top_artists.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then(a.cmp(b)));
+        top_artists.truncate(TOP_ARTISTS);
+
+        QueueStats {
+            tracks: tracks.len(),
+            albums: albums.len(),
+            artists: artists.len(),
+            duration: tracks.iter().map(Track::duration).sum(),
+            size_on_disk: tracks
+                .iter()
+                .filter_map(|track| fs::metadata(track.path()).ok())
+                .map(|metadata| metadata.len())
+                .sum(),
+            top_artists,
+        }
+    }
+}
+
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
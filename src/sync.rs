@@ -0,0 +1,61 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{m3u8, transcode};
+
+#[derive(Debug, Clone)]
+pub struct SyncOptions {
+    pub destination: PathBuf,
+    pub transcode: Option<(transcode::Format, u32)>,
+}
+
+pub fn sync_playlist(
+    name: &str,
+    tracks: &[PathBuf],
+    options: &SyncOptions,
+    mut on_track: impl FnMut(),
+) -> anyhow::Result<()> {
+    let mut entries = vec![];
+    for track in tracks {
+        let relative = device_path(track, options);
+        let target = options.destination.join(&relative);
+        if !target.exists() {
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            match options.transcode {
+                Some((format, bitrate)) if transcode::is_lossless(track) => {
+                    transcode::transcode(track, &target, format, bitrate)?
+                }
+                _ => {
+                    fs::copy(track, &target)?;
+                }
+            }
+        }
+        entries.push(relative);
+        on_track();
+    }
+
+    m3u8::write(
+        &options.destination.join(format!("{}.m3u8", name)),
+        entries.iter().map(PathBuf::as_path),
+    )
+}
+
+fn device_path(track: &Path, options: &SyncOptions) -> PathBuf {
+    let mut path = PathBuf::new();
+    if let Some(folder) = track.parent().and_then(Path::file_name) {
+        path.push(folder);
+    }
+    if let Some(file_name) = track.file_name() {
+        path.push(file_name);
+    }
+    if let Some((format, _)) = options.transcode
+        && transcode::is_lossless(track)
+    {
+        path.set_extension(format.extension());
+    }
+    path
+}
@@ -0,0 +1,80 @@
+use std::{fs::File, io::BufReader, path::Path};
+
+use rodio::{Decoder, Source};
+
+const ANALYSIS_SECONDS: usize = 60;
+const FRAMES_PER_SECOND: usize = 100;
+const MIN_BPM: f32 = 60.0;
+const MAX_BPM: f32 = 200.0;
+const PREFERRED_BPM: f32 = 120.0;
+
+pub fn analyze(path: &Path) -> anyhow::Result<Option<f32>> {
+    let source = Decoder::new(BufReader::new(File::open(path)?))?;
+    let channels = source.channels() as usize;
+    let frame_len = (source.sample_rate() as usize / FRAMES_PER_SECOND).max(1) * channels;
+
+    let mut energies = vec![];
+    let mut energy = 0.0;
+    for (index, sample) in source
+        .take(ANALYSIS_SECONDS * FRAMES_PER_SECOND * frame_len)
+        .enumerate()
+    {
+        energy += sample * sample;
+        if (index + 1) % frame_len == 0 {
+            energies.push((energy / frame_len as f32 + 1e-9).ln());
+            energy = 0.0;
+        }
+    }
+
+    let onsets = energies
+        .windows(2)
+        .map(|pair| (pair[1] - pair[0]).max(0.0))
+        .collect::<Vec<_>>();
+
+    let fps = FRAMES_PER_SECOND as f32;
+    let min_lag = (60.0 * fps / MAX_BPM).floor() as usize;
+    let max_lag = (60.0 * fps / MIN_BPM).ceil() as usize;
+    if onsets.len() <= max_lag * 4 {
+        return Ok(None);
+    }
+
+    let correlation = |lag: usize| -> f32 {
+        onsets
+            .iter()
+            .zip(&onsets[lag..])
+            .map(|(a, b)| a * b)
+            .sum::<f32>()
+            / (onsets.len() - lag) as f32
+    };
+    let correlations = (min_lag..=max_lag + 1).map(correlation).collect::<Vec<_>>();
+
+    let weight = |lag: f32| {
+        let octaves = (60.0 * fps / lag / PREFERRED_BPM).log2();
+        (-0.5 * octaves * octaves).exp()
+    };
+    let Some(best) = (1..correlations.len() - 1).max_by(|&a, &b| {
+        let a = correlations[a] * weight((min_lag + a) as f32);
+        let b = correlations[b] * weight((min_lag + b) as f32);
+        a.total_cmp(&b)
+    }) else {
+        return Ok(None);
+    };
+    if correlations[best] <= 0.0 {
+        return Ok(None);
+    }
+
+    let (before, peak, after) = (
+        correlations[best - 1],
+        correlations[best],
+        correlations[best + 1],
+    );
+    let curvature = before - 2.0 * peak + after;
+    let offset = if curvature < 0.0 {
+        0.5 * (before - after) / curvature
+    } else {
+        0.0
+    };
+    let lag = (min_lag + best) as f32 + offset;
+
+    Ok(Some((600.0 * fps / lag).round() / 10.0))
+}
@@ -1,29 +1,83 @@
 use std::{
-    fs::File,
-    io::BufReader,
+    collections::HashMap,
+    fs::{self, File},
+    hash::{DefaultHasher, Hash, Hasher},
+    io::{BufReader, Read, Seek, SeekFrom},
     path::{Path, PathBuf},
-    time::Duration,
+    sync::Mutex,
+    time::{Duration, SystemTime},
 };
 
 use anyhow::{anyhow, bail};
+use iced::widget::image;
 use rodio::{Decoder, Source};
+use serde::{Deserialize, Serialize};
 use symphonia::{
     core::{
-        io::MediaSourceStream,
-        meta::{MetadataOptions, StandardTagKey},
+        formats::FormatReader,
+        io::{MediaSource, MediaSourceStream},
+        meta::{MetadataOptions, StandardTagKey, StandardVisualKey, Tag},
         probe::Hint,
     },
     default::get_probe,
 };
+use tracing::warn;
+
+use crate::{
+    analysis_cache,
+    i18n::t,
+    loudness::Loudness,
+    lyrics::Lyrics,
+    plugins::Metadata,
+    radio::{self, StreamInfo},
+    remote::{self, RemoteSource},
+};
+
+const VBR_HEADER_SEARCH_LEN: usize = 256;
+const HIDDEN_TRACK_MIN_LEN: Duration = Duration::from_secs(10);
+const SPOKEN_WORD_GENRES: [&str; 4] = ["audiobook", "spoken", "podcast", "speech"];
+const MAX_SHARED_COVERS: usize = 1024;
+
+static COVERS: Mutex<Option<HashMap<(usize, u64), image::Handle>>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Segment {
+    pub start: Duration,
+    pub end: Duration,
+}
+
+#[derive(Debug, Clone)]
+struct Section {
+    number: u32,
+    title: Option<String>,
+    start: Duration,
+}
 
 #[derive(Debug, Clone)]
 pub struct Track {
     path: PathBuf,
     title: Option<String>,
     album: Option<String>,
+    album_artist: Option<String>,
+    is_compilation: bool,
     artist: Option<String>,
-    lyrics: Option<String>,
+    artists: Vec<String>,
+    genres: Vec<String>,
+    recording_mbid: Option<String>,
+    lyrics: Option<Lyrics>,
+    year: Option<String>,
+    track_number: Option<u32>,
+    disc_number: Option<u32>,
+    bpm: Option<f32>,
+    replay_gain: Option<f32>,
+    album_replay_gain: Option<f32>,
+    loudness: Option<Loudness>,
+    cover: Option<image::Handle>,
+    sections: Vec<Section>,
+    segment: Option<Segment>,
     duration: Duration,
+    added_at: SystemTime,
+    is_stream: bool,
 }
 
 impl Track {
@@ -35,6 +89,14 @@ impl Track {
         self.title.as_deref()
     }
 
+    pub fn album_artist(&self) -> Option<&str> {
+        self.album_artist.as_deref()
+    }
+
+    pub fn is_compilation(&self) -> bool {
+        self.is_compilation
+    }
+
     pub fn album(&self) -> Option<&str> {
         self.album.as_deref()
     }
@@ -43,22 +105,238 @@ impl Track {
         self.artist.as_deref()
     }
 
-    pub fn lyrics(&self) -> Option<&str> {
-        self.lyrics.as_deref()
+    pub fn artists(&self) -> &[String] {
+        &self.artists
+    }
+
+    pub fn genres(&self) -> &[String] {
+        &self.genres
+    }
+
+    pub fn recording_mbid(&self) -> Option<&str> {
+        self.recording_mbid.as_deref()
+    }
+
+    pub fn lyrics(&self) -> Option<&Lyrics> {
+        self.lyrics.as_ref()
+    }
+
+    pub fn year(&self) -> Option<&str> {
+        self.year.as_deref()
+    }
+
+    pub fn track_number(&self) -> Option<u32> {
+        self.track_number
+    }
+
+    pub fn disc_number(&self) -> Option<u32> {
+        self.disc_number
+    }
+
+    pub fn cover(&self) -> Option<&image::Handle> {
+        self.cover.as_ref()
+    }
+
+    pub fn bpm(&self) -> Option<f32> {
+        self.bpm
+    }
+
+    pub fn set_bpm(&mut self, bpm: f32) {
+        self.bpm = Some(bpm);
+    }
+
+    pub fn set_lyrics(&mut self, lyrics: Option<Lyrics>) {
+        self.lyrics = lyrics;
+    }
+
+    pub fn fill_in_metadata(&mut self, metadata: &Metadata) {
+        if self.title.is_none() {
+            self.title = metadata.title.clone();
+        }
+        if self.artist.is_none() {
+            self.artist = metadata.artist.clone();
+            if self.artists.is_empty() {
+                self.artists.extend(self.artist.clone());
+            }
+        }
+        if self.album.is_none() {
+            self.album = metadata.album.clone();
+        }
+    }
+
+    pub fn replay_gain(&self) -> Option<f32> {
+        self.replay_gain
+    }
+
+    pub fn album_replay_gain(&self) -> Option<f32> {
+        self.album_replay_gain
+    }
+
+    pub fn loudness(&self) -> Option<Loudness> {
+        self.loudness
+    }
+
+    pub fn set_loudness(&mut self, loudness: Loudness) {
+        self.loudness = Some(loudness);
+    }
+
+    pub fn gain(&self) -> Option<f32> {
+        self.replay_gain
+            .map(|gain| 10f32.powf(gain / 20.0))
+            .or_else(|| self.loudness.map(|loudness| loudness.gain()))
+    }
+
+    pub fn album_gain(&self) -> Option<f32> {
+        self.album_replay_gain
+            .map(|gain| 10f32.powf(gain / 20.0))
+            .or_else(|| self.gain())
+    }
+
+    pub fn segment(&self) -> Option<Segment> {
+        self.segment
+    }
+
+    pub fn continues(&self, previous: &Track) -> bool {
+        self.path == previous.path
+            && previous
+                .segment
+                .zip(self.segment)
+                .is_some_and(|(previous, segment)| previous.end <= segment.start)
     }
 
     pub fn duration(&self) -> Duration {
         self.duration
     }
+
+    pub fn added_at(&self) -> SystemTime {
+        self.added_at
+    }
+
+    pub fn is_stream(&self) -> bool {
+        self.is_stream
+    }
+
+    pub fn is_spoken_word(&self) -> bool {
+        let is_audiobook_file = self
+            .path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("m4b"));
+        is_audiobook_file
+            || self.genres.iter().any(|genre| {
+                let genre = genre.to_lowercase();
+                SPOKEN_WORD_GENRES
+                    .iter()
+                    .any(|spoken| genre.contains(spoken))
+            })
+    }
 }
 
 impl Track {
+    pub fn load_all(path: PathBuf, hidden_track: bool) -> anyhow::Result<Vec<Track>> {
+        Track::load(path).map(|track| track.split(hidden_track))
+    }
+
+    pub fn load_segment(path: PathBuf, segment: Option<Segment>) -> anyhow::Result<Track> {
+        let track = Track::load(path)?;
+        let Some(segment) = segment else {
+            return Ok(track);
+        };
+        track
+            .split(true)
+            .into_iter()
+            .find(|track| track.segment == Some(segment))
+            .ok_or_else(|| anyhow!("the cue sheet of the track has changed"))
+    }
+
+    pub fn split(self, hidden_track: bool) -> Vec<Track> {
+        if self.sections.len() < 2 {
+            return vec![self];
+        }
+        let hidden_track = self
+            .sections
+            .first()
+            .map(|section| section.start)
+            .filter(|&end| hidden_track && end >= HIDDEN_TRACK_MIN_LEN)
+            .map(|end| Track {
+                title: Some(t!("hidden-track")),
+                track_number: Some(0),
+                sections: vec![],
+                segment: Some(Segment {
+                    start: Duration::ZERO,
+                    end,
+                }),
+                duration: end,
+                ..self.clone()
+            });
+        let ends = self
+            .sections
+            .iter()
+            .skip(1)
+            .map(|section| section.start)
+            .chain([self.duration]);
+        let tracks = self
+            .sections
+            .iter()
+            .zip(ends)
+            .filter(|(section, end)| section.start < *end)
+            .map(|(section, end)| Track {
+                title: Some(
+                    section
+                        .title
+                        .clone()
+                        .unwrap_or_else(|| t!("cue-sheet-track", number = section.number)),
+                ),
+                track_number: Some(section.number),
+                sections: vec![],
+                segment: Some(Segment {
+                    start: section.start,
+                    end,
+                }),
+                duration: end - section.start,
+                ..self.clone()
+            });
+        hidden_track.into_iter().chain(tracks).collect()
+    }
+
     pub fn load(path: PathBuf) -> anyhow::Result<Track> {
-        let file = File::open(&path)?;
-        let mss = MediaSourceStream::new(Box::new(file), Default::default());
+        if let Some(url) = remote::url(&path)
+            && let Some(info) = radio::probe(url)?
+        {
+            return Ok(Track::stream(path, info));
+        }
+
+        let media_source: Box<dyn MediaSource> = match remote::url(&path) {
+            Some(url) => Box::new(RemoteSource::open(url)?),
+            None => Box::new(File::open(&path)?),
+        };
+        let mss = MediaSourceStream::new(media_source, Default::default());
         let hint = Hint::new();
         let mut probed =
             get_probe().format(&hint, mss, &Default::default(), &MetadataOptions::default())?;
+        let sample_rate = probed
+            .format
+            .default_track()
+            .and_then(|track| track.codec_params.sample_rate);
+        let headerless_mp3 = remote::url(&path).is_none() && is_headerless_mp3(&path);
+        let stream_duration = stream_duration(probed.format.as_ref()).filter(|_| !headerless_mp3);
+        let mut cue_starts = probed
+            .format
+            .cues()
+            .iter()
+            .map(|cue| {
+                let index_01 = match cue.points.as_slice() {
+                    [_, index_01, ..] | [index_01] => index_01.start_offset_ts,
+                    [] => 0,
+                };
+                (cue.index, cue.start_ts + index_01)
+            })
+            .collect::<Vec<_>>();
+        if cue_starts
+            .last()
+            .is_some_and(|(index, _)| matches!(index, 170 | 255))
+        {
+            cue_starts.pop();
+        }
         let metadata = probed.format.metadata();
         let Some(rev) = metadata.current() else {
             bail!("No metadata")
@@ -71,20 +349,277 @@ impl Track {
                 .map(|t| t.value.to_string())
         };
 
-        let file = File::open(&path)?;
-        let reader = BufReader::new(file);
-        let source = Decoder::new(reader)?;
-        let duration = source
-            .total_duration()
-            .ok_or(anyhow!("track has no total duration"))?;
+        let year = find_tag(StandardTagKey::Date)
+            .or_else(|| find_tag(StandardTagKey::ReleaseDate))
+            .or_else(|| find_tag(StandardTagKey::OriginalDate))
+            .and_then(|date| date.get(..4).map(str::to_string));
+
+        let find_number_tag =
+            |key| find_tag(key).and_then(|value| value.split('/').next()?.trim().parse().ok());
+
+        let cover = rev
+            .visuals()
+            .iter()
+            .find(|v| v.usage == Some(StandardVisualKey::FrontCover))
+            .or_else(|| rev.visuals().first())
+            .map(|v| shared_cover(&v.data));
+
+        let duration = match (stream_duration, remote::url(&path)) {
+            (Some(duration), _) => Some(duration),
+            (None, Some(url)) => {
+                let source = RemoteSource::open(url)?;
+                let byte_len = source.byte_len();
+                Decoder::builder()
+                    .with_data(source)
+                    .with_byte_len(byte_len)
+                    .build()?
+                    .total_duration()
+            }
+            (None, None) => match headerless_mp3.then(|| scan_duration(&path)) {
+                Some(Ok(duration)) => Some(duration),
+                scanned => {
+                    if let Some(Err(e)) = scanned {
+                        warn!("Failed to scan {}: {}", path.display(), e);
+                    }
+                    Decoder::new(BufReader::new(File::open(&path)?))?.total_duration()
+                }
+            },
+        }
+        .ok_or(anyhow!("track has no total duration"))?;
+
+        let bpm = find_tag(StandardTagKey::Bpm)
+            .and_then(|bpm| bpm.trim().parse::<f32>().ok())
+            .filter(|bpm| *bpm > 0.0)
+            .or_else(|| analysis_cache::bpm(&path));
+
+        let find_gain_tag = |key| {
+            find_tag(key).and_then(|gain| {
+                gain.trim()
+                    .trim_end_matches("dB")
+                    .trim()
+                    .parse::<f32>()
+                    .ok()
+            })
+        };
+        let replay_gain = find_gain_tag(StandardTagKey::ReplayGainTrackGain);
+        let album_replay_gain = find_gain_tag(StandardTagKey::ReplayGainAlbumGain);
+
+        let loudness = analysis_cache::loudness(&path);
+
+        let cue_titles = rev
+            .tags()
+            .iter()
+            .find(|tag| tag.key.eq_ignore_ascii_case("cuesheet"))
+            .map(|tag| cue_sheet_titles(&tag.value.to_string()))
+            .unwrap_or_default();
+        let sections = match sample_rate {
+            Some(sample_rate) if cue_starts.len() > 1 => cue_starts
+                .iter()
+                .map(|&(number, start)| Section {
+                    number,
+                    title: cue_titles.get(&number).cloned(),
+                    start: Duration::from_secs_f64(start as f64 / f64::from(sample_rate)),
+                })
+                .collect(),
+            _ => vec![],
+        };
+
+        let artists = tag_values(rev.tags(), StandardTagKey::Artist);
+        let genres = tag_values(rev.tags(), StandardTagKey::Genre);
+
+        let lyrics = fs::read_to_string(path.with_extension("lrc"))
+            .ok()
+            .or_else(|| find_tag(StandardTagKey::Lyrics))
+            .map(|lyrics| Lyrics::parse(&lyrics));
 
         Ok(Track {
             path,
             title: find_tag(StandardTagKey::TrackTitle),
             album: find_tag(StandardTagKey::Album),
-            artist: find_tag(StandardTagKey::Artist),
-            lyrics: find_tag(StandardTagKey::Lyrics),
+            album_artist: find_tag(StandardTagKey::AlbumArtist),
+            is_compilation: find_tag(StandardTagKey::Compilation)
+                .is_some_and(|value| matches!(value.trim(), "1" | "true")),
+            artist: join_values(&artists),
+            artists,
+            genres,
+            recording_mbid: find_tag(StandardTagKey::MusicBrainzRecordingId),
+            lyrics,
+            year,
+            track_number: find_number_tag(StandardTagKey::TrackNumber),
+            disc_number: find_number_tag(StandardTagKey::DiscNumber),
+            bpm,
+            replay_gain,
+            album_replay_gain,
+            loudness,
+            cover,
+            sections,
+            segment: None,
             duration,
+            added_at: SystemTime::now(),
+            is_stream: false,
         })
     }
+
+    pub fn unprobed_stream(path: PathBuf) -> Track {
+        Track::stream(path, StreamInfo { name: None })
+    }
+
+    fn stream(path: PathBuf, info: StreamInfo) -> Track {
+        Track {
+            path,
+            title: info.name,
+            album: None,
+            album_artist: None,
+            is_compilation: false,
+            artist: None,
+            artists: vec![],
+            genres: vec![],
+            recording_mbid: None,
+            lyrics: None,
+            year: None,
+            track_number: None,
+            disc_number: None,
+            bpm: None,
+            replay_gain: None,
+            album_replay_gain: None,
+            loudness: None,
+            cover: None,
+            sections: vec![],
+            segment: None,
+            duration: Duration::ZERO,
+            added_at: SystemTime::now(),
+            is_stream: true,
+        }
+    }
+}
+
+pub fn file_paths<'a>(tracks: impl IntoIterator<Item = &'a Track>) -> Vec<&'a Path> {
+    let mut paths = Vec::<&Path>::new();
+    let mut previous = None::<&Track>;
+    for track in tracks {
+        if !previous.is_some_and(|previous| track.continues(previous)) {
+            paths.push(&track.path);
+        }
+        previous = Some(track);
+    }
+    paths
+}
+
+pub fn tag_values(tags: &[Tag], key: StandardTagKey) -> Vec<String> {
+    let mut values = Vec::<String>::new();
+    for tag in tags.iter().filter(|tag| tag.std_key == Some(key)) {
+        for value in tag.value.to_string().split(['\0', ';']) {
+            let value = value.trim();
+            if !value.is_empty() && !values.iter().any(|v| v.eq_ignore_ascii_case(value)) {
+                values.push(value.to_string());
+            }
+        }
+    }
+    values
+}
+
+pub fn join_values(values: &[String]) -> Option<String> {
+    (!values.is_empty()).then(|| values.join("; "))
+}
+
+fn shared_cover(data: &[u8]) -> image::Handle {
+    let mut hasher = DefaultHasher::new();
+    data.hash(&mut hasher);
+    let key = (data.len(), hasher.finish());
+
+    let mut covers = COVERS.lock().unwrap_or_else(|e| e.into_inner());
+    let covers = covers.get_or_insert_default();
+    if covers.len() >= MAX_SHARED_COVERS && !covers.contains_key(&key) {
+        covers.clear();
+    }
+    covers
+        .entry(key)
+        .or_insert_with(|| image::Handle::from_bytes(data.to_vec()))
+        .clone()
+}
+
+fn cue_sheet_titles(cue_sheet: &str) -> HashMap<u32, String> {
+    let mut titles = HashMap::new();
+    let mut track = None;
+    for line in cue_sheet.lines().map(str::trim) {
+        if let Some(rest) = line.strip_prefix("TRACK ") {
+            track = rest.split_whitespace().next().and_then(|n| n.parse().ok());
+        } else if let Some(title) = line.strip_prefix("TITLE ")
+            && let Some(track) = track
+        {
+            titles.insert(track, title.trim().trim_matches('"').to_string());
+        }
+    }
+    titles
+}
+
+fn stream_duration(format: &dyn FormatReader) -> Option<Duration> {
+    let params = &format.default_track()?.codec_params;
+    let time = params.time_base?.calc_time(params.n_frames?);
+    Some(Duration::from_secs(time.seconds) + Duration::from_secs_f64(time.frac))
+}
+
+fn is_headerless_mp3(path: &Path) -> bool {
+    let is_mp3 = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("mp3"));
+    if !is_mp3 {
+        return false;
+    }
+    let Ok(mut file) = File::open(path) else {
+        return false;
+    };
+    let mut header = [0; 10];
+    if file.read_exact(&mut header).is_err() {
+        return false;
+    }
+    let mut offset = 0;
+    if header.starts_with(b"ID3") {
+        let size = header[6..10]
+            .iter()
+            .fold(0u64, |size, byte| size << 7 | u64::from(byte & 0x7f));
+        let footer = if header[5] & 0x10 != 0 { 10 } else { 0 };
+        offset = 10 + size + footer;
+    }
+
+    let mut first_frame = Vec::with_capacity(VBR_HEADER_SEARCH_LEN);
+    if file.seek(SeekFrom::Start(offset)).is_err()
+        || (&mut file)
+            .take(VBR_HEADER_SEARCH_LEN as u64)
+            .read_to_end(&mut first_frame)
+            .is_err()
+    {
+        return false;
+    }
+    ![b"Xing", b"Info", b"VBRI"]
+        .iter()
+        .any(|id| first_frame.windows(4).any(|window| window == *id))
+}
+
+fn scan_duration(path: &Path) -> anyhow::Result<Duration> {
+    let mss = MediaSourceStream::new(Box::new(File::open(path)?), Default::default());
+    let mut probed = get_probe().format(
+        &Hint::new(),
+        mss,
+        &Default::default(),
+        &MetadataOptions::default(),
+    )?;
+    let track = probed
+        .format
+        .default_track()
+        .ok_or(anyhow!("no audio track"))?;
+    let id = track.id;
+    let time_base = track
+        .codec_params
+        .time_base
+        .ok_or(anyhow!("no time base"))?;
+
+    let mut frames = 0;
+    while let Ok(packet) = probed.format.next_packet() {
+        if packet.track_id() == id {
+            frames += packet.dur;
+        }
+    }
+    let time = time_base.calc_time(frames);
+    Ok(Duration::from_secs(time.seconds) + Duration::from_secs_f64(time.frac))
 }
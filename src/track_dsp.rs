@@ -0,0 +1,51 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    dsp::DspSettings,
+    paths,
+    state::{self, Schema},
+};
+
+const SCHEMA: Schema = Schema {
+    migrations: &[state::unversioned],
+};
+
+pub struct TrackDspLibrary {
+    path: PathBuf,
+    settings: HashMap<PathBuf, DspSettings>,
+}
+
+impl TrackDspLibrary {
+    pub fn open() -> anyhow::Result<TrackDspLibrary> {
+        let path = paths::data_dir()?.join("track_dsp.json");
+        let settings = SCHEMA.load(&path)?.unwrap_or_default();
+        Ok(TrackDspLibrary { path, settings })
+    }
+
+    pub fn all(&self) -> &HashMap<PathBuf, DspSettings> {
+        &self.settings
+    }
+
+    pub fn get(&self, track: &Path) -> Option<DspSettings> {
+        self.settings.get(track).copied()
+    }
+
+    pub fn set(&mut self, track: &Path, settings: Option<DspSettings>) -> anyhow::Result<()> {
+        match settings {
+            Some(settings) => self.settings.insert(track.to_path_buf(), settings),
+            None => self.settings.remove(track),
+        };
+        SCHEMA.save(&self.path, &self.settings)
+    }
+
+    pub fn merge(
+        &mut self,
+        imported: impl IntoIterator<Item = (PathBuf, DspSettings)>,
+    ) -> anyhow::Result<()> {
+        self.settings.extend(imported);
+        SCHEMA.save(&self.path, &self.settings)
+    }
+}
@@ -0,0 +1,73 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    paths,
+    state::{self, Schema},
+};
+
+pub const MAX_RATING: u8 = 5;
+
+const SCHEMA: Schema = Schema {
+    migrations: &[state::unversioned],
+};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TrackStats {
+    pub rating: Option<u8>,
+    pub play_count: u32,
+}
+
+pub struct TrackStatsLibrary {
+    path: PathBuf,
+    stats: HashMap<PathBuf, TrackStats>,
+}
+
+impl TrackStatsLibrary {
+    pub fn open() -> anyhow::Result<TrackStatsLibrary> {
+        let path = paths::data_dir()?.join("track_stats.json");
+        let stats = SCHEMA.load(&path)?.unwrap_or_default();
+        Ok(TrackStatsLibrary { path, stats })
+    }
+
+    pub fn all(&self) -> &HashMap<PathBuf, TrackStats> {
+        &self.stats
+    }
+
+    pub fn get(&self, track: &Path) -> TrackStats {
+        self.stats.get(track).copied().unwrap_or_default()
+    }
+
+    pub fn tracks(&self) -> impl Iterator<Item = &Path> {
+        self.stats.keys().map(PathBuf::as_path)
+    }
+
+    pub fn add_play(&mut self, track: &Path) -> anyhow::Result<()> {
+        self.stats
+            .entry(track.to_path_buf())
+            .or_default()
+            .play_count += 1;
+        self.save()
+    }
+
+    pub fn merge(
+        &mut self,
+        imported: impl IntoIterator<Item = (PathBuf, TrackStats)>,
+    ) -> anyhow::Result<()> {
+        for (track, imported) in imported {
+            let stats = self.stats.entry(track).or_default();
+            stats.rating = imported.rating.or(stats.rating);
+            stats.play_count = stats.play_count.max(imported.play_count);
+        }
+        self.save()
+    }
+
+    fn save(&self) -> anyhow::Result<()> {
+        SCHEMA.save(&self.path, &self.stats)
+    }
+}
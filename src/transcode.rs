@@ -0,0 +1,131 @@
+use std::{
+    collections::HashSet,
+    fmt,
+    num::NonZero,
+    path::{Path, PathBuf},
+    process::Command,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+};
+
+use anyhow::{bail, Context};
+
+pub const BITRATES: [u32; 5] = [96, 128, 160, 192, 320];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Opus,
+    Mp3,
+    Flac,
+}
+
+impl Format {
+    pub const ALL: [Format; 3] = [Format::Opus, Format::Mp3, Format::Flac];
+    pub const LOSSY: [Format; 2] = [Format::Opus, Format::Mp3];
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Format::Opus => "opus",
+            Format::Mp3 => "mp3",
+            Format::Flac => "flac",
+        }
+    }
+
+    pub fn is_lossless(self) -> bool {
+        self == Format::Flac
+    }
+
+    fn codec_args(self, bitrate: u32) -> Vec<String> {
+        let codec = match self {
+            Format::Opus => "libopus",
+            Format::Mp3 => "libmp3lame",
+            Format::Flac => return vec!["-c:a".to_string(), "flac".to_string()],
+        };
+        vec![
+            "-c:a".to_string(),
+            codec.to_string(),
+            "-b:a".to_string(),
+            format!("{}k", bitrate),
+        ]
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Format::Opus => "Opus",
+            Format::Mp3 => "MP3",
+            Format::Flac => "FLAC",
+        })
+    }
+}
+
+pub fn is_lossless(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("flac") || ext.eq_ignore_ascii_case("wav"))
+}
+
+pub fn transcode(input: &Path, output: &Path, format: Format, bitrate: u32) -> anyhow::Result<()> {
+    if output.exists() {
+        bail!("{} already exists", output.display());
+    }
+    let result = Command::new("ffmpeg")
+        .args(["-n", "-loglevel", "error", "-i"])
+        .arg(input)
+        .args(["-map", "0:a", "-map_metadata", "0"])
+        .args(format.codec_args(bitrate))
+        .arg(output)
+        .output()
+        .context("failed to run ffmpeg, is it installed?")?;
+    if !result.status.success() {
+        bail!(
+            "ffmpeg failed to transcode {}: {}",
+            input.display(),
+            String::from_utf8_lossy(&result.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+pub fn transcode_all(
+    inputs: &[PathBuf],
+    destination: &Path,
+    format: Format,
+    bitrate: u32,
+    on_track: impl Fn(&Path, anyhow::Result<()>) + Sync,
+) {
+    let outputs = output_paths(inputs, destination, format);
+    let next = AtomicUsize::new(0);
+    let workers = thread::available_parallelism()
+        .map_or(2, NonZero::get)
+        .min(inputs.len());
+    thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let (Some(input), Some(output)) = (inputs.get(index), outputs.get(index)) else {
+                    break;
+                };
+                on_track(input, transcode(input, output, format, bitrate));
+            });
+        }
+    });
+}
+
+fn output_paths(inputs: &[PathBuf], destination: &Path, format: Format) -> Vec<PathBuf> {
+    let mut taken = HashSet::new();
+    inputs
+        .iter()
+        .map(|input| {
+            let stem = input.file_stem().unwrap_or_default().to_string_lossy();
+            let mut name = format!("{}.{}", stem, format.extension());
+            let mut copy = 1;
+            while !taken.insert(name.to_lowercase()) {
+                copy += 1;
+                name = format!("{} ({}).{}", stem, copy, format.extension());
+            }
+            destination.join(name)
+        })
+        .collect()
+}
@@ -0,0 +1,148 @@
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+use std::{
+    collections::HashMap,
+    sync::mpsc::{channel, Receiver, Sender},
+};
+
+#[cfg(any(target_os = "windows", target_os = "macos"))]
+use anyhow::anyhow;
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+use ksni::{blocking::TrayMethods, menu::StandardItem, MenuItem};
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+use tracing::error;
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+use zbus::{blocking::Connection, zvariant::Value};
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+use crate::i18n::t;
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+const ICON_NAME: &str = "multimedia-audio-player";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrayEvent {
+    Show,
+    Quit,
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub struct Tray {
+    handle: ksni::blocking::Handle<KantaTray>,
+    event_rx: Receiver<TrayEvent>,
+}
+
+#[cfg(any(target_os = "windows", target_os = "macos"))]
+pub struct Tray;
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+struct KantaTray {
+    events: Sender<TrayEvent>,
+    show_label: String,
+    quit_label: String,
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+impl Tray {
+    pub fn try_new() -> anyhow::Result<Tray> {
+        let (event_tx, event_rx) = channel();
+        let handle = KantaTray {
+            events: event_tx,
+            show_label: t!("show-window"),
+            quit_label: t!("quit"),
+        }
+        .spawn()?;
+        Ok(Tray { handle, event_rx })
+    }
+
+    pub fn receive_event(&self) -> Option<TrayEvent> {
+        self.event_rx.try_recv().ok()
+    }
+
+    pub fn notify(&self, summary: &str, body: &str) -> anyhow::Result<()> {
+        Connection::session()?.call_method(
+            Some("org.freedesktop.Notifications"),
+            "/org/freedesktop/Notifications",
+            Some("org.freedesktop.Notifications"),
+            "Notify",
+            &(
+                "Kanta",
+                0u32,
+                ICON_NAME,
+                summary,
+                body,
+                Vec::<&str>::new(),
+                HashMap::<&str, Value>::new(),
+                -1i32,
+            ),
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+impl Drop for Tray {
+    fn drop(&mut self) {
+        self.handle.shutdown();
+    }
+}
+
+#[cfg(any(target_os = "windows", target_os = "macos"))]
+impl Tray {
+    pub fn try_new() -> anyhow::Result<Tray> {
+        Err(anyhow!("Tray icons are not supported on this platform"))
+    }
+
+    pub fn receive_event(&self) -> Option<TrayEvent> {
+        None
+    }
+
+    pub fn notify(&self, _summary: &str, _body: &str) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+impl KantaTray {
+    fn send(&self, event: TrayEvent) {
+        if let Err(e) = self.events.send(event) {
+            error!("Failed to send tray event: {}", e);
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+impl ksni::Tray for KantaTray {
+    fn id(&self) -> String {
+        "kanta".to_string()
+    }
+
+    fn title(&self) -> String {
+        "Kanta".to_string()
+    }
+
+    fn icon_name(&self) -> String {
+        ICON_NAME.to_string()
+    }
+
+    fn activate(&mut self, _x: i32, _y: i32) {
+        self.send(TrayEvent::Show);
+    }
+
+    fn menu(&self) -> Vec<MenuItem<Self>> {
+        vec![
+            StandardItem {
+                label: self.show_label.clone(),
+                activate: Box::new(|tray: &mut KantaTray| tray.send(TrayEvent::Show)),
+                ..StandardItem::default()
+            }
+            .into(),
+            MenuItem::Separator,
+            StandardItem {
+                label: self.quit_label.clone(),
+                activate: Box::new(|tray: &mut KantaTray| tray.send(TrayEvent::Quit)),
+                ..StandardItem::default()
+            }
+            .into(),
+        ]
+    }
+}
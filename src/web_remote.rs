@@ -0,0 +1,259 @@
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::{Ipv4Addr, TcpListener, TcpStream, UdpSocket},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        mpsc::{self, Receiver, Sender},
+        Arc, Mutex,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use anyhow::{anyhow, bail};
+use serde::Serialize;
+use tracing::{debug, error, info};
+
+use crate::{hooks::Payload, i18n::t, player::ControlRequest};
+
+const PAGE: &str = include_str!("web_remote.html");
+const PAGE_KEYS: [&str; 6] = [
+    "previous-track",
+    "next-track",
+    "play",
+    "pause",
+    "volume",
+    "up-next",
+];
+const TIMEOUT: Duration = Duration::from_secs(5);
+const TOKEN_LEN: usize = 16;
+const MAX_HEADER_LEN: u64 = 8192;
+const MAX_CONNECTIONS: usize = 16;
+const REJECTION_DELAY: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Serialize)]
+pub struct RemoteState {
+    pub playback: Payload,
+    pub index: Option<usize>,
+    pub queue: Vec<QueueEntry>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct QueueEntry {
+    pub index: usize,
+    pub title: String,
+    pub artist: Option<String>,
+}
+
+pub struct WebRemote {
+    port: u16,
+    url: String,
+    state: Arc<Mutex<String>>,
+    requests: Receiver<ControlRequest>,
+    stopped: Arc<AtomicBool>,
+    accept: Option<JoinHandle<()>>,
+}
+
+impl WebRemote {
+    pub fn start(port: u16, token: &str) -> anyhow::Result<WebRemote> {
+        let listener = TcpListener::bind((Ipv4Addr::UNSPECIFIED, port))?;
+        let state = Arc::new(Mutex::new("{}".to_string()));
+        let stopped = Arc::new(AtomicBool::new(false));
+        let (sender, requests) = mpsc::channel();
+        let page = PAGE_KEYS.iter().fold(PAGE.to_string(), |page, key| {
+            page.replace(&format!("{{{{{}}}}}", key), &t!(key))
+        });
+
+        let server = Server {
+            token: token.to_string(),
+            page,
+            state: state.clone(),
+            sender,
+        };
+        let accept_stopped = stopped.clone();
+        let accept = thread::spawn(move || {
+            let server = Arc::new(server);
+            let connections = Arc::new(AtomicUsize::new(0));
+            for stream in listener.incoming() {
+                if accept_stopped.load(Ordering::Relaxed) {
+                    break;
+                }
+                let Ok(stream) = stream else {
+                    continue;
+                };
+                if connections.fetch_add(1, Ordering::Relaxed) >= MAX_CONNECTIONS {
+                    connections.fetch_sub(1, Ordering::Relaxed);
+                    debug!("Dropping a web remote connection, too many are open");
+                    continue;
+                }
+                let server = server.clone();
+                let connections = connections.clone();
+                thread::spawn(move || {
+                    if let Err(e) = server.handle(stream) {
+                        debug!("Failed to handle a web remote request: {}", e);
+                    }
+                    connections.fetch_sub(1, Ordering::Relaxed);
+                });
+            }
+        });
+
+        let url = format!(
+            "http://{}:{}/?token={}",
+            lan_address().unwrap_or_else(|| "localhost".to_string()),
+            port,
+            token
+        );
+        info!("Serving the web remote on port {}", port);
+        Ok(WebRemote {
+            port,
+            url,
+            state,
+            requests,
+            stopped,
+            accept: Some(accept),
+        })
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn receive_request(&self) -> Option<ControlRequest> {
+        self.requests.try_recv().ok()
+    }
+
+    pub fn set_state(&self, state: &RemoteState) {
+        match serde_json::to_string(state) {
+            Ok(state) => *self.state.lock().unwrap_or_else(|e| e.into_inner()) = state,
+            Err(e) => error!("Failed to encode the web remote state: {}", e),
+        }
+    }
+}
+
+impl Drop for WebRemote {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::Relaxed);
+        TcpStream::connect((Ipv4Addr::LOCALHOST, self.port)).ok();
+        if let Some(accept) = self.accept.take() {
+            accept.join().ok();
+        }
+    }
+}
+
+struct Server {
+    token: String,
+    page: String,
+    state: Arc<Mutex<String>>,
+    sender: Sender<ControlRequest>,
+}
+
+impl Server {
+    fn handle(&self, stream: TcpStream) -> anyhow::Result<()> {
+        stream.set_read_timeout(Some(TIMEOUT))?;
+        stream.set_write_timeout(Some(TIMEOUT))?;
+        let mut reader = BufReader::new(&stream).take(MAX_HEADER_LEN);
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line)?;
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line)? == 0 {
+                if reader.limit() == 0 {
+                    return respond(
+                        &stream,
+                        "431 Request Header Fields Too Large",
+                        "text/plain",
+                        "",
+                    );
+                }
+                break;
+            }
+            if line.trim_end().is_empty() {
+                break;
+            }
+        }
+
+        let mut parts = request_line.split_whitespace();
+        let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+            bail!("malformed request line: {}", request_line.trim_end());
+        };
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+        let param = |name: &str| {
+            query
+                .split('&')
+                .filter_map(|pair| pair.split_once('='))
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| value)
+        };
+        if param("token") != Some(self.token.as_str()) {
+            thread::sleep(REJECTION_DELAY);
+            return respond(&stream, "403 Forbidden", "text/plain", "Invalid link");
+        }
+
+        let value = || {
+            param("value")
+                .and_then(|value| value.parse::<f32>().ok())
+                .filter(|value| value.is_finite())
+        };
+        let request = match (method, path) {
+            ("GET", "/") => {
+                return respond(&stream, "200 OK", "text/html; charset=utf-8", &self.page);
+            }
+            ("GET", "/state") => {
+                let state = self.state.lock().unwrap_or_else(|e| e.into_inner()).clone();
+                return respond(&stream, "200 OK", "application/json", &state);
+            }
+            ("POST", "/play") => Some(ControlRequest::Play),
+            ("POST", "/pause") => Some(ControlRequest::Pause),
+            ("POST", "/next") => Some(ControlRequest::Next),
+            ("POST", "/previous") => Some(ControlRequest::Previous),
+            ("POST", "/volume") => {
+                value().map(|volume| ControlRequest::SetVolume(volume.clamp(0.0, 1.0)))
+            }
+            ("POST", "/seek") => value().map(|position| ControlRequest::Seek(position.max(0.0))),
+            ("POST", "/jump") => param("value")
+                .and_then(|index| index.parse().ok())
+                .map(ControlRequest::JumpTo),
+            _ => return respond(&stream, "404 Not Found", "text/plain", "Not found"),
+        };
+        match request {
+            Some(request) => {
+                self.sender.send(request).ok();
+                respond(&stream, "204 No Content", "text/plain", "")
+            }
+            None => respond(&stream, "400 Bad Request", "text/plain", "Missing value"),
+        }
+    }
+}
+
+pub fn generate_token() -> anyhow::Result<String> {
+    let mut bytes = [0; TOKEN_LEN];
+    getrandom::fill(&mut bytes).map_err(|e| anyhow!("failed to generate a token: {}", e))?;
+    Ok(bytes.iter().map(|byte| format!("{:02x}", byte)).collect())
+}
+
+pub fn is_valid_token(token: &str) -> bool {
+    token.len() == TOKEN_LEN * 2 && token.bytes().all(|byte| byte.is_ascii_hexdigit())
+}
+
+fn respond(
+    mut stream: &TcpStream,
+    status: &str,
+    content_type: &str,
+    body: &str,
+) -> anyhow::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    )?;
+    Ok(())
+}
+
+fn lan_address() -> Option<String> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).ok()?;
+    socket.connect((Ipv4Addr::new(192, 0, 2, 1), 9)).ok()?;
+    Some(socket.local_addr().ok()?.ip().to_string())
+}